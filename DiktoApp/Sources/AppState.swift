@@ -2,7 +2,9 @@ import AppKit
 import AVFoundation
 import Carbon
 import Foundation
+import IOKit.hid
 import SwiftUI
+import UserNotifications
 
 extension Notification.Name {
     static let diktoHotKeyPressed = Notification.Name("diktoHotKeyPressed")
@@ -74,6 +76,158 @@ func probeAccessibilityPermission() -> Bool {
     return true
 }
 
+// MARK: - Output Events
+
+/// What happened to a finished transcript on its way to the focused app.
+enum OutputEvent: Equatable {
+    case pasted
+    /// Auto-paste is off; the text is only on the clipboard.
+    case copied
+    /// Auto-paste didn't happen. The text stays on the clipboard so the user
+    /// can paste it manually.
+    case pasteFailed(reason: String)
+}
+
+// MARK: - Automation Permissions
+
+enum PermissionStatus {
+    case granted
+    case denied
+    /// The user hasn't been asked yet.
+    case notDetermined
+}
+
+/// One permission auto-paste depends on, with what to do when it's missing.
+struct AutomationPermission: Identifiable {
+    let id: String
+    let name: String
+    let status: PermissionStatus
+    /// What breaks without it, shown next to the status.
+    let purpose: String
+    /// Deep link to the matching System Settings pane.
+    let settingsURL: URL?
+
+    var isGranted: Bool { status == .granted }
+
+    /// Instruction for the user, or nil when nothing needs doing.
+    var action: String? {
+        switch status {
+        case .granted:
+            return nil
+        case .notDetermined:
+            return "Allow Dikto when macOS asks, or enable it under \(name) in System Settings."
+        case .denied:
+            return "Enable Dikto under Privacy & Security → \(name) in System Settings."
+        }
+    }
+
+    func openSettings() {
+        if let settingsURL {
+            NSWorkspace.shared.open(settingsURL)
+        }
+    }
+}
+
+/// Probe the permissions auto-paste needs. Accessibility covers sending the
+/// ⌘V keystroke (without it the paste fails silently); Input Monitoring is
+/// needed by some apps' secure fields and by keyboard shortcuts that
+/// listen for key events.
+func checkAutomationPermissions() -> [AutomationPermission] {
+    let accessibility: PermissionStatus =
+        probeAccessibilityPermission() && CGPreflightPostEventAccess() ? .granted : .denied
+
+    let inputMonitoring: PermissionStatus
+    switch IOHIDCheckAccess(kIOHIDRequestTypeListenEvent) {
+    case kIOHIDAccessTypeGranted:
+        inputMonitoring = .granted
+    case kIOHIDAccessTypeDenied:
+        inputMonitoring = .denied
+    default:
+        inputMonitoring = .notDetermined
+    }
+
+    return [
+        AutomationPermission(
+            id: "accessibility",
+            name: "Accessibility",
+            status: accessibility,
+            purpose: "Pastes transcribed text into the active app.",
+            settingsURL: URL(string: "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility")
+        ),
+        AutomationPermission(
+            id: "input-monitoring",
+            name: "Input Monitoring",
+            status: inputMonitoring,
+            purpose: "Lets shortcuts and paste work while secure fields or other key listeners are active.",
+            settingsURL: URL(string: "x-apple.systempreferences:com.apple.preference.security?Privacy_ListenEvent")
+        ),
+    ]
+}
+
+/// Context hint for the recognizer: the focused text field's contents (nearest
+/// the cursor) or, failing that, the frontmost app's name.
+func currentContextHint() -> String {
+    let appName = NSWorkspace.shared.frontmostApplication?.localizedName ?? ""
+    guard AXIsProcessTrusted() else { return appName }
+
+    let systemWide = AXUIElementCreateSystemWide()
+    var focused: AnyObject?
+    guard AXUIElementCopyAttributeValue(
+        systemWide,
+        kAXFocusedUIElementAttribute as CFString,
+        &focused
+    ) == .success, let element = focused else { return appName }
+
+    var value: AnyObject?
+    guard AXUIElementCopyAttributeValue(
+        element as! AXUIElement,
+        kAXValueAttribute as CFString,
+        &value
+    ) == .success, let text = value as? String, !text.isEmpty else { return appName }
+
+    // The core keeps only the tail; avoid sending whole documents over FFI
+    return String(text.suffix(2000))
+}
+
+/// The few characters before the caret in the focused text field, for the
+/// output stage's smart spacing. Empty when unknown or at the field start.
+func textBeforeCaret(maxLength: Int = 8) -> String {
+    guard AXIsProcessTrusted() else { return "" }
+
+    let systemWide = AXUIElementCreateSystemWide()
+    var focused: AnyObject?
+    guard AXUIElementCopyAttributeValue(
+        systemWide,
+        kAXFocusedUIElementAttribute as CFString,
+        &focused
+    ) == .success, let focused else { return "" }
+    let element = focused as! AXUIElement
+
+    var rangeValue: AnyObject?
+    var range = CFRange()
+    guard AXUIElementCopyAttributeValue(
+        element,
+        kAXSelectedTextRangeAttribute as CFString,
+        &rangeValue
+    ) == .success, let rangeValue,
+        AXValueGetValue(rangeValue as! AXValue, .cfRange, &range),
+        range.location > 0 else { return "" }
+
+    var value: AnyObject?
+    guard AXUIElementCopyAttributeValue(
+        element,
+        kAXValueAttribute as CFString,
+        &value
+    ) == .success, let text = value as? String else { return "" }
+
+    // AX ranges count UTF-16 units
+    let utf16 = text.utf16
+    let end = min(range.location, utf16.count)
+    let start = max(0, end - maxLength)
+    let slice = utf16[utf16.index(utf16.startIndex, offsetBy: start)..<utf16.index(utf16.startIndex, offsetBy: end)]
+    return String(slice) ?? ""
+}
+
 /// Callback that bridges UniFFI transcription events to AppState.
 final class AppCallback: TranscriptionCallback, @unchecked Sendable {
     private weak var appState: AppState?
@@ -93,11 +247,73 @@ final class AppCallback: TranscriptionCallback, @unchecked Sendable {
         DispatchQueue.main.async { [weak self] in
             self?.appState?.partialText = text
             self?.appState?.updateOverlay()
+            self?.appState?.pasteSegmentIfIncremental(text)
         }
     }
 
     func onSilence() {}
 
+    func onWordConfidence(words: [WordConfidence]) {
+        DispatchQueue.main.async { [weak self] in
+            self?.appState?.lastWordConfidence = words
+        }
+    }
+
+    func onOverlapWarning(warning: OverlapWarning) {
+        DispatchQueue.main.async { [weak self] in
+            self?.appState?.overlapWarnings.append(warning)
+        }
+    }
+
+    func onAudioInterrupted(reason: String) {
+        DispatchQueue.main.async { [weak self] in
+            self?.appState?.partialText = "Microphone interrupted, reconnecting..."
+            self?.appState?.updateOverlay()
+        }
+    }
+
+    func onNoAudioDetected() {
+        DispatchQueue.main.async { [weak self] in
+            self?.appState?.partialText = "No sound from the microphone. Is it muted?"
+            self?.appState?.updateOverlay()
+        }
+    }
+
+    func onTimeRemaining(seconds: UInt32) {
+        DispatchQueue.main.async { [weak self] in
+            guard let appState = self?.appState else { return }
+            if appState.secondsRemaining == nil {
+                NSSound(named: "Tink")?.play()
+            }
+            appState.secondsRemaining = seconds
+            appState.updateOverlay()
+        }
+    }
+
+    func onReady() {
+        DispatchQueue.main.async { [weak self] in
+            self?.appState?.modelInMemory = true
+        }
+    }
+
+    func onDryRun(actions: [OutputAction]) {
+        for action in actions {
+            NSLog("[Dikto] Dry run skipped: \(action)")
+        }
+    }
+
+    func onSummary(summary: String) {
+        DispatchQueue.main.async { [weak self] in
+            self?.appState?.lastSummary = summary
+        }
+    }
+
+    func onLanguageChange(language: String) {
+        DispatchQueue.main.async { [weak self] in
+            self?.appState?.detectedLanguage = language
+        }
+    }
+
     func onError(error: String) {
         DispatchQueue.main.async { [weak self] in
             self?.appState?.lastError = error
@@ -122,11 +338,13 @@ final class AppCallback: TranscriptionCallback, @unchecked Sendable {
                 appState.overlayController.hide()
                 appState.handleTranscriptionDone(text)
                 appState.scheduleIdleUnload()
-            case let .error(message):
+            case let .error(message, code):
+                NSLog("[Dikto] Session error: \(message)")
                 appState.isRecording = false
                 appState.isProcessing = false
                 appState.overlayController.hide()
-                appState.lastError = message
+                appState.lastError = localizedErrorMessage(
+                    code: code, locale: Locale.preferredLanguages.first ?? "en")
                 if appState.modelInMemory { appState.scheduleIdleUnload() }
             }
         }
@@ -184,6 +402,14 @@ final class AppState: ObservableObject {
     @Published var partialText = ""
     @Published var finalText = ""
     @Published var lastError: String?
+    @Published var lastOutputEvent: OutputEvent?
+    @Published var detectedLanguage: String?
+    @Published var lastWordConfidence: [WordConfidence] = []
+    @Published var overlapWarnings: [OverlapWarning] = []
+    /// TL;DR of the last long transcript, when a summarizer is configured.
+    @Published var lastSummary: String?
+    /// Countdown before max_duration ends the session, in its last seconds.
+    @Published var secondsRemaining: UInt32?
     @Published var models: [ModelInfoRecord] = []
     @Published var config: DiktoConfig?
     @Published var modelAvailable = false
@@ -197,6 +423,17 @@ final class AppState: ObservableObject {
     private var engine: DiktoEngine?
     private var sessionHandle: SessionHandle?
     private var activeCallback: AppCallback?
+    /// Incremental paste: whether this session's segments were pasted as
+    /// they came, chunks still waiting to be pasted, and the text to leave
+    /// on the clipboard once they are.
+    private var pastedIncrementally = false
+    /// Language of the current session, for CJK and right-to-left output.
+    private var sessionLanguage = ""
+    /// Whether the current session is a dry run, for the output stage.
+    private var sessionDryRun = false
+    private var pendingChunks: [String] = []
+    private var pastingChunks = false
+    private var clipboardAfterChunks: String?
     var activeDownloadCallback: DownloadCallback?
     private var hotKeyRef: EventHotKeyRef?
     private var pressedHandlerRef: EventHandlerRef?
@@ -210,8 +447,23 @@ final class AppState: ObservableObject {
     private static let idleUnloadInterval: TimeInterval = 300  // 5 minutes
 
     init() {
+        // Nobody reads a menu bar app's stderr; keep logs where Console.app finds them
+        let logFile = FileManager.default.homeDirectoryForCurrentUser
+            .appendingPathComponent("Library/Logs/Dikto/dikto.log")
+        do {
+            try initLogging(options: LogOptions(
+                level: "info", filePath: logFile.path, format: .pretty, stderr: false))
+        } catch {
+            NSLog("[Dikto] Logging unavailable: \(error)")
+        }
         loadEngine()
-        let micOK = AVCaptureDevice.authorizationStatus(for: .audio) == .authorized
+        let micStatus = AVCaptureDevice.authorizationStatus(for: .audio)
+        let micOK = micStatus == .authorized
+        switch micStatus {
+        case .authorized: engine?.setMicrophonePermission(permission: .granted)
+        case .denied, .restricted: engine?.setMicrophonePermission(permission: .denied)
+        default: engine?.setMicrophonePermission(permission: .unknown)
+        }
         let axOK = probeAccessibilityPermission()
         accessibilityGranted = axOK
 
@@ -371,7 +623,11 @@ final class AppState: ObservableObject {
     private func handleHotKeyReleased() {
         guard currentMode == .hold else { return }
         guard isRecording else { return }
-        stopRecording()
+        // Close the mic on key-up itself, off the main thread since it waits
+        // for the capture thread to let go of it
+        guard let handle = sessionHandle else { return }
+        sessionHandle = nil
+        Task.detached { _ = handle.stopCaptureNow() }
     }
 
     deinit {
@@ -400,6 +656,11 @@ final class AppState: ObservableObject {
         refreshConfig()
         refreshLanguages()
         NSLog("[Dikto] Engine ready. Model available on disk: \(modelAvailable)")
+        // Finish a download the last run was quit during
+        if let name = engine.interruptedDownloads().first {
+            NSLog("[Dikto] Restarting interrupted download of \(name)")
+            downloadModel(name: name)
+        }
     }
 
     func refreshModels() {
@@ -460,17 +721,21 @@ final class AppState: ObservableObject {
 
     private func proceedWithRecording(engine: DiktoEngine) {
         cancelIdleUnload()
-        let cfg = engine.getConfig()
-        let listenConfig = ListenConfig(
-            language: cfg.language,
-            maxDuration: cfg.maxDuration,
-            silenceDurationMs: cfg.silenceDurationMs,
-            speechThreshold: cfg.speechThreshold
-        )
+        let listenConfig = engine.listenConfigFor(target: currentOutputTarget())
+        sessionLanguage = listenConfig.language
+        sessionDryRun = listenConfig.dryRun
+        engine.setContextHint(text: currentContextHint())
 
         partialText = ""
         finalText = ""
         lastError = nil
+        lastOutputEvent = nil
+        detectedLanguage = nil
+        lastWordConfidence = []
+        overlapWarnings = []
+        lastSummary = nil
+        secondsRemaining = nil
+        pastedIncrementally = false
         isRecording = true  // Set immediately to prevent double-start during lazy load
 
         let callback = AppCallback(appState: self)
@@ -494,7 +759,11 @@ final class AppState: ObservableObject {
 
     func updateOverlay() {
         if isRecording {
-            overlayController.show(text: partialText, isProcessing: isProcessing)
+            var text = partialText
+            if let seconds = secondsRemaining, !isProcessing {
+                text += text.isEmpty ? "Stopping in \(seconds)s" : " · stopping in \(seconds)s"
+            }
+            overlayController.show(text: text, isProcessing: isProcessing)
         }
     }
 
@@ -507,46 +776,246 @@ final class AppState: ObservableObject {
         // Auto-copy / auto-paste
         let cfg = config ?? engine?.getConfig()
         let wantCopy = cfg?.autoCopy ?? true
-        let wantPaste = cfg?.autoPaste ?? true
+        var wantPaste = cfg?.autoPaste ?? true
+        let typing = cfg?.output.typingMode ?? false
+
+        // Per-app formatting and per-sink routing from the core's output stage
+        var output = cleaned
+        var clipboardText = cleaned
+        var richText: (format: ClipboardFormat, text: String)?
+        if let engine {
+            let texts = TranscriptTexts(transcript: cleaned, summary: lastSummary)
+            let plan = engine.routeOutput(texts: texts, target: currentOutputTarget())
+            if plan.dryRun {
+                NSLog("[Dikto] Dry run: would \(plan.autoPaste ? "paste" : "copy") \(plan.text.count) chars")
+                pastedIncrementally = false
+                return
+            }
+            output = plan.text
+            clipboardText = plan.clipboardText
+            wantPaste = plan.autoPaste
+            if let rich = plan.richText {
+                richText = (plan.clipboardFormat, rich)
+            }
+        }
+        // Segments already went in one by one; only copy the whole text
+        if pastedIncrementally {
+            pastedIncrementally = false
+            if wantCopy {
+                if pastingChunks {
+                    clipboardAfterChunks = clipboardText
+                } else {
+                    copyToClipboard(clipboardText, rich: richText)
+                }
+                lastOutputEvent = .copied
+            }
+            return
+        }
+
+        // Pasting goes through the clipboard, so a different paste text is
+        // put there first and the clipboard text restored afterwards
+        let splitPaste = wantPaste && !typing && output != clipboardText
 
-        if wantCopy || wantPaste {
-            NSPasteboard.general.clearContents()
-            NSPasteboard.general.setString(cleaned, forType: .string)
-            NSLog("[Dikto] Copied to clipboard")
+        if splitPaste {
+            copyToClipboard(output)
+        } else if wantCopy || (wantPaste && !typing) {
+            copyToClipboard(clipboardText, rich: richText)
         }
 
         if wantPaste {
-            let axOK = probeAccessibilityPermission()
+            let accessibility = checkAutomationPermissions().first { $0.id == "accessibility" }
+            let axOK = accessibility?.isGranted ?? false
             accessibilityGranted = axOK
             if axOK {
-                DispatchQueue.main.asyncAfter(deadline: .now() + 0.2) {
-                    self.simulatePaste()
+                let delay = Double(cfg?.output.pasteDelayMs ?? 200) / 1000
+                let keystrokeDelayMs = cfg?.output.keystrokeDelayMs ?? 5
+                DispatchQueue.main.asyncAfter(deadline: .now() + delay) {
+                    if typing {
+                        self.typeText(output, keystrokeDelayMs: keystrokeDelayMs)
+                    } else if let reason = self.simulatePaste() {
+                        self.reportPasteFailed(reason: reason)
+                    } else {
+                        self.lastOutputEvent = .pasted
+                    }
+                    if splitPaste && wantCopy {
+                        DispatchQueue.main.asyncAfter(deadline: .now() + delay) {
+                            self.copyToClipboard(clipboardText, rich: richText)
+                        }
+                    }
                 }
             } else {
-                lastError = "Accessibility permission lost — text copied to clipboard. Re-grant in Settings."
+                if typing { copyToClipboard(output, rich: richText) }
+                reportPasteFailed(reason: accessibility?.action ?? "Re-grant Accessibility in Settings.")
                 selectedSettingsTab = .permissions
                 SettingsWindowController.shared.show(appState: self)
             }
+        } else if wantCopy {
+            lastOutputEvent = .copied
+        }
+    }
+
+    /// In continuous mode with incremental paste, paste a committed segment
+    /// a few words at a time instead of waiting for the session to end.
+    func pasteSegmentIfIncremental(_ text: String) {
+        guard let engine else { return }
+        let cfg = config ?? engine.getConfig()
+        guard cfg.continuousMode, cfg.output.incrementalPaste, cfg.autoPaste,
+              keystrokeBlocker() == nil
+        else { return }
+        let chunks = engine.prepareSegmentOutput(text: text, target: currentOutputTarget())
+        guard !chunks.isEmpty else { return }
+        pastedIncrementally = true
+        if cfg.output.typingMode {
+            typeText(chunks.joined(), keystrokeDelayMs: cfg.output.keystrokeDelayMs)
+        } else {
+            pendingChunks += chunks
+            if !pastingChunks {
+                pastingChunks = true
+                pasteNextChunk(delay: Double(cfg.output.pasteDelayMs) / 1000)
+            }
+        }
+    }
+
+    /// Paste the queued chunks one at a time through the clipboard.
+    private func pasteNextChunk(delay: Double) {
+        guard !pendingChunks.isEmpty else {
+            pastingChunks = false
+            if let text = clipboardAfterChunks {
+                clipboardAfterChunks = nil
+                copyToClipboard(text)
+            }
+            return
+        }
+        copyToClipboard(pendingChunks.removeFirst())
+        DispatchQueue.main.asyncAfter(deadline: .now() + delay) {
+            if let reason = self.simulatePaste() {
+                self.pendingChunks = []
+                self.pastingChunks = false
+                self.reportPasteFailed(reason: reason)
+                return
+            }
+            self.lastOutputEvent = .pasted
+            // Let the app read the clipboard before it changes again
+            DispatchQueue.main.asyncAfter(deadline: .now() + delay) {
+                self.pasteNextChunk(delay: delay)
+            }
         }
     }
 
-    private func simulatePaste() {
+    /// The focused app, caret context, and session language, for per-app
+    /// profiles and script-specific output.
+    private func currentOutputTarget() -> OutputTarget {
+        let app = NSWorkspace.shared.frontmostApplication
+        return OutputTarget(
+            bundleId: app?.bundleIdentifier ?? "",
+            appName: app?.localizedName ?? "",
+            unixSecs: UInt64(Date().timeIntervalSince1970),
+            utcOffsetSecs: Int32(TimeZone.current.secondsFromGMT()),
+            precedingText: textBeforeCaret(),
+            language: detectedLanguage ?? sessionLanguage,
+            dryRun: sessionDryRun
+        )
+    }
+
+    private func copyToClipboard(_ text: String, rich: (format: ClipboardFormat, text: String)? = nil) {
+        let pasteboard = NSPasteboard.general
+        pasteboard.clearContents()
+        switch rich {
+        case let (.markdown, markdown)?:
+            // Markdown is plain text; note apps that know the type keep it as such
+            pasteboard.setString(markdown, forType: .string)
+            pasteboard.setString(markdown, forType: NSPasteboard.PasteboardType("net.daringfireball.markdown"))
+        case let (.rtf, rtf)?:
+            pasteboard.setString(text, forType: .string)
+            if let data = rtf.data(using: .ascii) {
+                pasteboard.setData(data, forType: .rtf)
+            }
+        default:
+            pasteboard.setString(text, forType: .string)
+        }
+        NSLog("[Dikto] Copied to clipboard")
+    }
+
+    /// Why synthetic keystrokes can't reach the focused app right now, or nil.
+    private func keystrokeBlocker() -> String? {
         guard probeAccessibilityPermission() else {
             accessibilityGranted = false
-            if lastError == nil {
-                lastError = "Accessibility permission lost — text copied to clipboard. Re-grant in Settings."
-            }
             selectedSettingsTab = .permissions
             SettingsWindowController.shared.show(appState: self)
-            return
+            return "Accessibility permission lost. Re-grant it in Settings."
+        }
+        // Password fields and some terminals turn on secure input, which
+        // drops synthetic keystrokes without any error
+        if IsSecureEventInputEnabled() {
+            return "Secure input is on in the active app (e.g. a password field)."
+        }
+        return nil
+    }
+
+    /// Send ⌘V to the focused app. Returns why the paste can't happen, or
+    /// nil once the keystroke is posted.
+    private func simulatePaste() -> String? {
+        if let blocker = keystrokeBlocker() {
+            return blocker
         }
         let src = CGEventSource(stateID: .hidSystemState)
-        let keyDown = CGEvent(keyboardEventSource: src, virtualKey: 0x09, keyDown: true) // 'v'
-        let keyUp = CGEvent(keyboardEventSource: src, virtualKey: 0x09, keyDown: false)
-        keyDown?.flags = .maskCommand
-        keyUp?.flags = .maskCommand
-        keyDown?.post(tap: .cghidEventTap)
-        keyUp?.post(tap: .cghidEventTap)
+        guard let keyDown = CGEvent(keyboardEventSource: src, virtualKey: 0x09, keyDown: true), // 'v'
+              let keyUp = CGEvent(keyboardEventSource: src, virtualKey: 0x09, keyDown: false)
+        else {
+            return "Could not create the paste keystroke."
+        }
+        keyDown.flags = .maskCommand
+        keyUp.flags = .maskCommand
+        keyDown.post(tap: .cghidEventTap)
+        keyUp.post(tap: .cghidEventTap)
+        return nil
+    }
+
+    /// Type `text` into the focused app one character at a time, pausing
+    /// `keystrokeDelayMs` between characters so slow apps keep up. Falls
+    /// back to the clipboard when keystrokes can't be sent.
+    private func typeText(_ text: String, keystrokeDelayMs: UInt32) {
+        if let blocker = keystrokeBlocker() {
+            copyToClipboard(text)
+            reportPasteFailed(reason: blocker)
+            return
+        }
+        DispatchQueue.global(qos: .userInitiated).async {
+            let src = CGEventSource(stateID: .hidSystemState)
+            for character in text {
+                let units = Array(String(character).utf16)
+                guard let keyDown = CGEvent(keyboardEventSource: src, virtualKey: 0, keyDown: true),
+                      let keyUp = CGEvent(keyboardEventSource: src, virtualKey: 0, keyDown: false)
+                else { continue }
+                keyDown.keyboardSetUnicodeString(stringLength: units.count, unicodeString: units)
+                keyUp.keyboardSetUnicodeString(stringLength: units.count, unicodeString: units)
+                keyDown.post(tap: .cghidEventTap)
+                keyUp.post(tap: .cghidEventTap)
+                if keystrokeDelayMs > 0 {
+                    usleep(keystrokeDelayMs * 1000)
+                }
+            }
+            DispatchQueue.main.async {
+                self.lastOutputEvent = .pasted
+            }
+        }
+    }
+
+    /// The transcript is on the clipboard but wasn't pasted: tell the user
+    /// to paste it themselves, in the menu and as a notification.
+    private func reportPasteFailed(reason: String) {
+        NSLog("[Dikto] Paste failed: \(reason)")
+        lastOutputEvent = .pasteFailed(reason: reason)
+        lastError = "Text copied to clipboard but not pasted. \(reason) Press ⌘V to paste it."
+
+        let center = UNUserNotificationCenter.current()
+        center.requestAuthorization(options: [.alert]) { granted, _ in
+            guard granted else { return }
+            let content = UNMutableNotificationContent()
+            content.title = "Press ⌘V to paste"
+            content.body = "Dikto couldn't paste your text, so it's on the clipboard. \(reason)"
+            center.add(UNNotificationRequest(identifier: "dikto.paste-failed", content: content, trigger: nil))
+        }
     }
 
     func switchModel(name: String) {
@@ -306,6 +306,11 @@ struct OnboardingView: View {
                     .font(.subheadline)
                     .foregroundStyle(.secondary)
                     .lineLimit(1)
+                if model.ramMb > 0 {
+                    Text("Needs ~\(formatSize(model.ramMb)) RAM · ~\(String(format: "%.2g", model.relativeSpeed))x realtime on M1")
+                        .font(.caption)
+                        .foregroundStyle(.tertiary)
+                }
             }
 
             Spacer(minLength: Theme.Spacing.xxs)
@@ -7,6 +7,7 @@ struct PermissionsSettingsView: View {
     @State private var micStatus: AVAuthorizationStatus = AVCaptureDevice.authorizationStatus(for: .audio)
     @State private var axGranted: Bool = probeAccessibilityPermission()
     @State private var axTimer: Timer?
+    @State private var inputMonitoring: AutomationPermission? = checkAutomationPermissions().first { $0.id == "input-monitoring" }
 
     var body: some View {
         Form {
@@ -72,6 +73,44 @@ struct PermissionsSettingsView: View {
                 .padding(.vertical, Theme.Spacing.xxs)
                 .help("Required for auto-paste into other applications")
             }
+
+            if let inputMonitoring {
+                Section {
+                    HStack(spacing: Theme.Spacing.md) {
+                        Image(systemName: "keyboard")
+                            .font(.title2)
+                            .foregroundStyle(.blue)
+                            .frame(width: Theme.IconSize.lg)
+
+                        VStack(alignment: .leading, spacing: Theme.Spacing.xxxs) {
+                            HStack(spacing: Theme.Spacing.sm) {
+                                Text(inputMonitoring.name)
+                                    .fontWeight(.medium)
+                                StatusBadge(granted: inputMonitoring.isGranted)
+                            }
+                            Text(inputMonitoring.purpose)
+                                .font(Theme.Typography.caption)
+                                .foregroundStyle(.secondary)
+                            if let action = inputMonitoring.action {
+                                Text(action)
+                                    .font(Theme.Typography.caption)
+                                    .foregroundStyle(.tertiary)
+                            }
+                        }
+
+                        Spacer()
+
+                        if !inputMonitoring.isGranted {
+                            Button("Open System Settings") {
+                                inputMonitoring.openSettings()
+                            }
+                            .controlSize(.small)
+                        }
+                    }
+                    .padding(.vertical, Theme.Spacing.xxs)
+                    .help("Optional: helps paste reach apps with secure input")
+                }
+            }
         }
         .formStyle(.grouped)
         .animation(Theme.Animation.standard, value: micStatus == .authorized)
@@ -150,6 +189,7 @@ struct PermissionsSettingsView: View {
         let granted = probeAccessibilityPermission()
         axGranted = granted
         appState.accessibilityGranted = granted
+        inputMonitoring = checkAutomationPermissions().first { $0.id == "input-monitoring" }
     }
 
     // MARK: - Accessibility Polling
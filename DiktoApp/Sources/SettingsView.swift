@@ -112,6 +112,14 @@ struct GeneralSettingsView: View {
     @EnvironmentObject var appState: AppState
     @State private var autoCopy = true
     @State private var autoPaste = true
+    @State private var typingMode = false
+    @State private var pasteDelay: Double = 200
+    @State private var keystrokeDelay: Double = 5
+    @State private var smartSpacing = true
+    @State private var smartCapitalization = true
+    @State private var appendSpace = false
+    @State private var appendNewline = false
+    @State private var clipboardFormat: ClipboardFormat = .plain
     @State private var maxDuration: Double = 30
     @State private var silenceDuration: Double = 1500
     @State private var selectedLanguage = "en"
@@ -211,11 +219,62 @@ struct GeneralSettingsView: View {
                             saveSettings()
                         }
                         .help("Automatically paste transcribed text into the focused app")
+                    if autoPaste {
+                        Toggle("Type text instead of pasting", isOn: $typingMode)
+                            .onChange(of: typingMode) { guard loaded else { return }; saveSettings() }
+                            .help("Send keystrokes for apps that block paste; the clipboard is left alone")
+                        LabeledContent("Paste delay") {
+                            HStack(spacing: Theme.Spacing.sm) {
+                                Slider(value: $pasteDelay, in: 0...1000, step: 50)
+                                    .onChange(of: pasteDelay) { guard loaded else { return }; saveSettings() }
+                                    .frame(maxWidth: 160)
+                                Text("\(Int(pasteDelay))ms")
+                                    .monospacedDigit()
+                                    .foregroundStyle(.secondary)
+                                    .frame(width: 56, alignment: .trailing)
+                            }
+                        }
+                        .help("Raise this if apps like Electron editors miss the paste")
+                        if typingMode {
+                            LabeledContent("Keystroke pacing") {
+                                HStack(spacing: Theme.Spacing.sm) {
+                                    Slider(value: $keystrokeDelay, in: 0...50, step: 1)
+                                        .onChange(of: keystrokeDelay) { guard loaded else { return }; saveSettings() }
+                                        .frame(maxWidth: 160)
+                                    Text("\(Int(keystrokeDelay))ms")
+                                        .monospacedDigit()
+                                        .foregroundStyle(.secondary)
+                                        .frame(width: 56, alignment: .trailing)
+                                }
+                            }
+                            .help("Pause between typed characters so slow apps receive all of them")
+                        }
+                    }
                     Text("Requires Accessibility permission in System Settings")
                         .font(Theme.Typography.caption)
                         .foregroundStyle(.tertiary)
                 }
 
+                Section("Inserting text") {
+                    Toggle("Add a space after the previous word", isOn: $smartSpacing)
+                        .onChange(of: smartSpacing) { guard loaded else { return }; saveSettings() }
+                        .help("Checks the text before the cursor so mid-sentence dictation doesn't run into it")
+                    Toggle("Match capitalization to the sentence", isOn: $smartCapitalization)
+                        .onChange(of: smartCapitalization) { guard loaded else { return }; saveSettings() }
+                        .help("Lowercases the first word when dictating mid-sentence and capitalizes it after a period")
+                    Toggle("End with a space", isOn: $appendSpace)
+                        .onChange(of: appendSpace) { guard loaded else { return }; saveSettings() }
+                    Toggle("End with a new line", isOn: $appendNewline)
+                        .onChange(of: appendNewline) { guard loaded else { return }; saveSettings() }
+                    Picker("Clipboard format", selection: $clipboardFormat) {
+                        Text("Plain text").tag(ClipboardFormat.plain)
+                        Text("Markdown").tag(ClipboardFormat.markdown)
+                        Text("Rich text (RTF)").tag(ClipboardFormat.rtf)
+                    }
+                    .onChange(of: clipboardFormat) { guard loaded else { return }; saveSettings() }
+                    .help("Markdown and rich text keep paragraphs when pasting into note apps")
+                }
+
                 if appState.availableLanguages.count > 1 {
                     Section {
                         Picker("Language", selection: $selectedLanguage) {
@@ -326,6 +385,14 @@ struct GeneralSettingsView: View {
         guard let cfg = appState.config else { return }
         autoCopy = cfg.autoCopy
         autoPaste = cfg.autoPaste
+        typingMode = cfg.output.typingMode
+        pasteDelay = Double(cfg.output.pasteDelayMs)
+        keystrokeDelay = Double(cfg.output.keystrokeDelayMs)
+        smartSpacing = cfg.output.smartSpacing
+        smartCapitalization = cfg.output.smartCapitalization
+        appendSpace = cfg.output.appendSpace
+        appendNewline = cfg.output.appendNewline
+        clipboardFormat = cfg.output.clipboardFormat
         maxDuration = Double(cfg.maxDuration)
         silenceDuration = Double(cfg.silenceDurationMs)
         selectedLanguage = cfg.language
@@ -356,18 +423,23 @@ struct GeneralSettingsView: View {
     }
 
     private func saveSettings() {
-        guard let cfg = appState.config else { return }
-        let newConfig = DiktoConfig(
-            modelName: cfg.modelName,
-            language: selectedLanguage,
-            maxDuration: UInt32(maxDuration),
-            silenceDurationMs: UInt32(silenceDuration),
-            speechThreshold: cfg.speechThreshold,
-            globalShortcut: shortcutValue,
-            autoPaste: autoPaste,
-            autoCopy: autoCopy,
-            activationMode: activationMode
-        )
+        guard var newConfig = appState.config else { return }
+        // Mutate a copy so fields not shown here (advanced, etc.) are preserved
+        newConfig.language = selectedLanguage
+        newConfig.maxDuration = UInt32(maxDuration)
+        newConfig.silenceDurationMs = UInt32(silenceDuration)
+        newConfig.globalShortcut = shortcutValue
+        newConfig.autoPaste = autoPaste
+        newConfig.autoCopy = autoCopy
+        newConfig.output.typingMode = typingMode
+        newConfig.output.pasteDelayMs = UInt32(pasteDelay)
+        newConfig.output.keystrokeDelayMs = UInt32(keystrokeDelay)
+        newConfig.output.smartSpacing = smartSpacing
+        newConfig.output.smartCapitalization = smartCapitalization
+        newConfig.output.appendSpace = appendSpace
+        newConfig.output.appendNewline = appendNewline
+        newConfig.output.clipboardFormat = clipboardFormat
+        newConfig.activationMode = activationMode
         appState.updateConfig(newConfig)
     }
 }
@@ -446,12 +518,15 @@ struct ModelsSettingsView: View {
                                     .foregroundStyle(.secondary)
                             }
                         } else {
-                            Button("Download") {
+                            let resume = model.state == .partiallyDownloaded
+                            Button(resume ? "Resume" : "Download") {
                                 appState.downloadModel(name: model.name)
                             }
                             .controlSize(.small)
                             .disabled(!appState.downloadProgress.isEmpty)
-                            .help("Download this model to your device")
+                            .help(resume
+                                ? "Some files are missing or incomplete; download them again"
+                                : "Download this model to your device")
                         }
                     }
                     .padding(.vertical, Theme.Spacing.xxs)
@@ -474,7 +549,8 @@ struct ModelsSettingsView: View {
     }
 
     private func isActive(_ model: ModelInfoRecord) -> Bool {
-        model.name == appState.config?.modelName
+        guard let name = appState.config?.modelName else { return false }
+        return model.name == name || model.aliases.contains(name)
     }
 
     private func formatSize(_ mb: UInt32) -> String {
@@ -0,0 +1,15 @@
+use dikto_core::config::load_config;
+
+/// Run the check-config command: list settings in config.json that conflict
+/// with each other. Fails when there are any, so scripts can catch them.
+pub fn run_check_config() -> anyhow::Result<()> {
+    let warnings = load_config().lint();
+    if warnings.is_empty() {
+        println!("No conflicting settings.");
+        return Ok(());
+    }
+    for warning in &warnings {
+        eprintln!("  - {warning}");
+    }
+    anyhow::bail!("{} conflicting setting(s)", warnings.len())
+}
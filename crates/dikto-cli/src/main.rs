@@ -1,6 +1,15 @@
+mod check_config;
+mod replay;
+mod segment;
+mod self_test;
 mod setup;
+mod transcribe;
+mod vad_replay;
 
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use dikto_core::logging::LogOptions;
 
 #[derive(Parser)]
 #[command(
@@ -14,19 +23,130 @@ struct Cli {
     #[arg(long)]
     setup: bool,
 
-    /// Model to download (use with --setup). Default: parakeet-tdt-0.6b-v2
+    /// Model or alias to download (use with --setup). Default: "default"
     #[arg(long)]
     model: Option<String>,
+
+    /// Language pack to set up (use with --setup), e.g. "de": downloads its
+    /// model and dictates in that language
+    #[arg(long)]
+    language: Option<String>,
+
+    /// Log level or RUST_LOG-style directives. Default: $RUST_LOG, else "warn"
+    #[arg(long)]
+    log_level: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Re-run voice detection on a recording with different thresholds, e.g.
+    /// a WAV saved by the vad_debug setting
+    VadReplay {
+        /// 16/24/32-bit PCM or float WAV file
+        file: PathBuf,
+
+        /// Speech threshold to try (repeatable). Default: 0.2 0.35 0.5 0.65
+        #[arg(long = "threshold")]
+        thresholds: Vec<f32>,
+
+        /// Silence that ends an utterance, in ms
+        #[arg(long, default_value_t = 1500)]
+        silence_ms: u32,
+    },
+
+    /// Print the speech regions of a recording as JSON (start/end in ms),
+    /// e.g. to cut a dataset into utterances or check VAD settings
+    Segment {
+        /// 16/24/32-bit PCM or float WAV file
+        file: PathBuf,
+
+        /// Speech threshold. Default: the configured speech_threshold
+        #[arg(long)]
+        threshold: Option<f32>,
+
+        /// Silence that ends a region, in ms. Default: the configured
+        /// silence_duration_ms
+        #[arg(long)]
+        silence_ms: Option<u32>,
+    },
+
+    /// Transcribe WAV files with the configured model, skipping recordings
+    /// already transcribed (by audio, not file name), e.g. a folder of
+    /// voice memos that gets re-exported
+    Transcribe {
+        /// 16/24/32-bit PCM or float WAV files
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// ISO-639-1 code or "auto". Default: the configured language
+        #[arg(long)]
+        language: Option<String>,
+    },
+
+    /// Run a saved session's recording through voice detection and the
+    /// model again, with the settings it was recorded with, to reproduce
+    /// what the session produced
+    Replay {
+        /// WAV saved with the saved_sessions.save_audio setting
+        file: PathBuf,
+    },
+
+    /// List settings in config.json that conflict with each other, e.g.
+    /// after editing it by hand
+    CheckConfig,
+
+    /// Check that voice detection and the configured model work end to end
+    SelfTest {
+        /// WAV of recorded speech to transcribe. Default: synthetic voice,
+        /// which checks every stage but not the text
+        #[arg(long)]
+        sample: Option<PathBuf>,
+    },
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    let level = cli
+        .log_level
+        .clone()
+        .or_else(|| std::env::var("RUST_LOG").ok())
+        .unwrap_or_else(|| "warn".to_string());
+    dikto_core::logging::init(LogOptions {
+        level,
+        ..LogOptions::default()
+    })?;
 
     if cli.setup {
-        setup::run_setup(cli.model.as_deref()).await?;
+        setup::run_setup(cli.model.as_deref(), cli.language.as_deref()).await?;
         return Ok(());
     }
 
+    match &cli.command {
+        Some(Command::VadReplay {
+            file,
+            thresholds,
+            silence_ms,
+        }) => vad_replay::run_vad_replay(file, thresholds, *silence_ms)?,
+        Some(Command::Segment {
+            file,
+            threshold,
+            silence_ms,
+        }) => segment::run_segment(file, *threshold, *silence_ms)?,
+        Some(Command::Transcribe { files, language }) => {
+            let language = language
+                .clone()
+                .unwrap_or_else(|| dikto_core::config::load_config().language);
+            transcribe::run_transcribe(files, &language)?
+        }
+        Some(Command::Replay { file }) => replay::run_replay(file)?,
+        Some(Command::CheckConfig) => check_config::run_check_config()?,
+        Some(Command::SelfTest { sample }) => self_test::run_self_test(sample.as_deref())?,
+        None => {}
+    }
+
     Ok(())
 }
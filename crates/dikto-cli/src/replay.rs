@@ -0,0 +1,12 @@
+use std::path::Path;
+
+use dikto_engine::Engine;
+
+/// Run the replay command: print what a saved session transcribes to when
+/// run through the pipeline again.
+pub fn run_replay(file: &Path) -> anyhow::Result<()> {
+    let engine = Engine::new();
+    let result = engine.replay_session(file)?;
+    println!("{}", result.text);
+    Ok(())
+}
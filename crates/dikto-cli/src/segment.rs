@@ -0,0 +1,44 @@
+use std::path::Path;
+
+use dikto_core::config::load_config;
+use dikto_core::vad::VadConfig;
+use dikto_core::vad_debug::{self, SpeechSegment};
+use dikto_core::wav;
+use serde::Serialize;
+
+/// What `dikto segment` prints.
+#[derive(Serialize)]
+struct SegmentReport<'a> {
+    file: &'a str,
+    duration_ms: u64,
+    speech_threshold: f32,
+    silence_duration_ms: u32,
+    segments: Vec<SpeechSegment>,
+}
+
+/// Run the segment command: find the speech in a recording with the VAD
+/// and print its regions as JSON. Unset options come from the user's config.
+pub fn run_segment(
+    file: &Path,
+    threshold: Option<f32>,
+    silence_ms: Option<u32>,
+) -> anyhow::Result<()> {
+    let samples = wav::read_wav(file)?;
+    let config = load_config();
+    let vad_config = VadConfig {
+        speech_threshold: threshold.unwrap_or(config.speech_threshold),
+        silence_duration_ms: silence_ms.unwrap_or(config.silence_duration_ms),
+        ..VadConfig::default()
+    };
+
+    let probabilities = vad_debug::chunk_probabilities(&samples)?;
+    let report = SegmentReport {
+        file: &file.to_string_lossy(),
+        duration_ms: samples.len() as u64 * 1000 / wav::TARGET_SAMPLE_RATE as u64,
+        speech_threshold: vad_config.speech_threshold,
+        silence_duration_ms: vad_config.silence_duration_ms,
+        segments: vad_debug::speech_regions(&probabilities, vad_config)?,
+    };
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
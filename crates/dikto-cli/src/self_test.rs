@@ -0,0 +1,32 @@
+use std::path::Path;
+
+use dikto_engine::Engine;
+
+/// Run the self-test command: put a sample through VAD and the configured
+/// model and print what each stage did. Fails when any stage failed.
+pub fn run_self_test(sample: Option<&Path>) -> anyhow::Result<()> {
+    let engine = Engine::new();
+    let report = engine.self_test(sample)?;
+
+    println!("model:      {}", report.model_name);
+    println!(
+        "sample:     {}",
+        match sample {
+            Some(path) => path.display().to_string(),
+            None => "synthetic voice (pass --sample for a real check)".to_string(),
+        }
+    );
+    println!("vad speech: {} ms", report.vad_speech_ms);
+    println!("load:       {} ms", report.load_ms);
+    println!("transcribe: {} ms", report.transcribe_ms);
+    println!("transcript: {:?}", report.transcript);
+
+    if report.passed {
+        println!("\nSelf-test passed.");
+        return Ok(());
+    }
+    for problem in &report.problems {
+        eprintln!("  - {problem}");
+    }
+    anyhow::bail!("Self-test failed")
+}
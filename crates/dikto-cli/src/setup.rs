@@ -2,8 +2,10 @@ use dikto_core::config::{self, DiktoConfig};
 use dikto_core::models;
 
 /// Run the setup command: download a model and create config.
-/// If `model_name` is None, downloads the default model (parakeet-tdt-0.6b-v2).
-pub async fn run_setup(model_name: Option<&str>) -> anyhow::Result<()> {
+/// If `model_name` is None, downloads the model behind the "default" alias,
+/// or the model of the language pack for `language` and switches the config
+/// to that pack.
+pub async fn run_setup(model_name: Option<&str>, language: Option<&str>) -> anyhow::Result<()> {
     eprintln!("Dikto Setup");
     eprintln!("===========\n");
 
@@ -25,8 +27,23 @@ pub async fn run_setup(model_name: Option<&str>) -> anyhow::Result<()> {
         eprintln!("Config already exists at {}", config_path.display());
     }
 
+    let pack = match language {
+        Some(code) => match models::find_language_pack(code) {
+            Some(pack) => Some(pack),
+            None => {
+                eprintln!("No language pack for '{code}'\n");
+                eprintln!("Available language packs:");
+                for pack in models::LANGUAGE_PACKS {
+                    eprintln!("  {:<4} {:<10} ({})", pack.language, pack.name, pack.model);
+                }
+                anyhow::bail!("Invalid language: {code}");
+            }
+        },
+        None => None,
+    };
+
     // Resolve model name
-    let model_name = model_name.unwrap_or("parakeet-tdt-0.6b-v2");
+    let model_name = model_name.or(pack.map(|p| p.model)).unwrap_or("default");
 
     // Validate model name
     let model = match models::find_model(model_name) {
@@ -48,6 +65,7 @@ pub async fn run_setup(model_name: Option<&str>) -> anyhow::Result<()> {
             anyhow::bail!("Invalid model name: {model_name}");
         }
     };
+    let model_name = model.name;
 
     // Download model if not present
     if models::is_model_downloaded(model_name) {
@@ -74,18 +92,31 @@ pub async fn run_setup(model_name: Option<&str>) -> anyhow::Result<()> {
         );
 
         let bar_clone = bar.clone();
-        let path = models::download_model(model_name, move |downloaded, total| {
-            if total > 0 {
-                bar_clone.set_length(total);
-            }
-            bar_clone.set_position(downloaded);
-        })
-        .await?;
+        let hf_cache = config::load_config()
+            .huggingface_cache
+            .then(config::huggingface_cache_dir)
+            .flatten();
+        let path =
+            models::download_model(model_name, hf_cache.as_deref(), move |downloaded, total| {
+                if total > 0 {
+                    bar_clone.set_length(total);
+                }
+                bar_clone.set_position(downloaded);
+            })
+            .await?;
 
         bar.finish_with_message("Download complete!");
         eprintln!("Model saved to {}", path.display());
     }
 
+    if let Some(pack) = pack {
+        let mut cfg = config::load_config();
+        cfg.model_name = model_name.to_string();
+        cfg.language = pack.language.to_string();
+        config::save_config(&cfg)?;
+        eprintln!("Switched to the {} language pack.", pack.name);
+    }
+
     eprintln!("\nSetup complete! You can now use dikto.");
     eprintln!("  Desktop app: open /Applications/Dikto.app");
 
@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+use dikto_engine::{Engine, FileTranscription};
+
+/// Run the transcribe command: print the transcript of each file, skipping
+/// recordings already transcribed. Fails when any file couldn't be read or
+/// transcribed.
+pub fn run_transcribe(files: &[PathBuf], language: &str) -> anyhow::Result<()> {
+    let engine = Engine::new();
+    let mut failed = 0;
+    for outcome in engine.transcribe_files(files, language)? {
+        match outcome {
+            FileTranscription::Transcribed { path, result, .. } => {
+                println!("{path}: {}", result.text);
+            }
+            FileTranscription::AlreadyTranscribed { path, .. } => {
+                println!("{path}: already transcribed, skipped");
+            }
+            FileTranscription::Failed { path, message } => {
+                eprintln!("{path}: {message}");
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("{failed} file(s) failed");
+    }
+    Ok(())
+}
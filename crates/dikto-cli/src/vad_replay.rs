@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use dikto_core::vad_debug;
+use dikto_core::wav;
+
+/// Thresholds tried when none are given: around the 0.35 default.
+pub const DEFAULT_THRESHOLDS: &[f32] = &[0.2, 0.35, 0.5, 0.65];
+
+/// Run the vad-replay command: re-run VAD over a recording with each
+/// threshold and print the utterances the pipeline would have cut.
+pub fn run_vad_replay(
+    file: &Path,
+    thresholds: &[f32],
+    silence_duration_ms: u32,
+) -> anyhow::Result<()> {
+    let samples = wav::read_wav(file)?;
+    let duration_secs = samples.len() as f32 / wav::TARGET_SAMPLE_RATE as f32;
+    let thresholds = if thresholds.is_empty() {
+        DEFAULT_THRESHOLDS
+    } else {
+        thresholds
+    };
+
+    eprintln!(
+        "{}: {duration_secs:.1}s, silence timeout {silence_duration_ms}ms\n",
+        file.display()
+    );
+    println!(
+        "{:>9}  {:>10}  {:>8}  segments",
+        "threshold", "utterances", "speech"
+    );
+    for result in vad_debug::replay(&samples, thresholds, silence_duration_ms)? {
+        let segments: Vec<String> = result
+            .segments
+            .iter()
+            .map(|s| {
+                format!(
+                    "{:.1}-{:.1}s",
+                    s.start_ms as f32 / 1000.0,
+                    s.end_ms as f32 / 1000.0
+                )
+            })
+            .collect();
+        println!(
+            "{:>9.2}  {:>10}  {:>7.1}s  {}",
+            result.speech_threshold,
+            result.segments.len(),
+            result.speech_ms() as f32 / 1000.0,
+            segments.join(" ")
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,80 @@
+// Benchmarks for the per-chunk work of a session: resampling device audio,
+// voice detection, and the text passes run on each final segment.
+//
+// Run with `make bench` or `cargo bench --package dikto-core`.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use dikto_core::audio::Resampler;
+use dikto_core::config::TextProcConfig;
+use dikto_core::textproc::{self, TextPipeline};
+use dikto_core::vad::{VadConfig, VadProcessor};
+
+/// One second of a 440 Hz tone at `rate`.
+fn tone(rate: u32) -> Vec<f32> {
+    (0..rate)
+        .map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / rate as f32).sin() * 0.3)
+        .collect()
+}
+
+fn resampler(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resampler");
+    for rate in [44_100, 48_000] {
+        let input = tone(rate);
+        group.throughput(Throughput::Elements(input.len() as u64));
+        group.bench_function(format!("{rate}_to_16000_1s"), |b| {
+            let mut resampler = Resampler::new(rate, 16_000);
+            let mut out = Vec::with_capacity(16_000);
+            b.iter(|| {
+                out.clear();
+                resampler.process(black_box(&input), &mut out);
+                black_box(out.len())
+            })
+        });
+    }
+    group.finish();
+}
+
+fn vad(c: &mut Criterion) {
+    let mut group = c.benchmark_group("vad");
+    let mut processor = VadProcessor::new(VadConfig::default()).unwrap();
+    let chunk: Vec<f32> = tone(16_000)[..processor.chunk_size()].to_vec();
+    group.throughput(Throughput::Elements(chunk.len() as u64));
+    group.bench_function("process_chunk", |b| {
+        b.iter(|| processor.process_chunk(black_box(&chunk)).unwrap())
+    });
+    // The state machine alone, without the model
+    group.bench_function("process_probability", |b| {
+        b.iter_batched(
+            || VadProcessor::new(VadConfig::default()).unwrap(),
+            |mut vad| {
+                for i in 0..100 {
+                    vad.process_probability(if i % 20 < 12 { 0.9 } else { 0.1 });
+                }
+                vad
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn text_passes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("text");
+    let segment = "so the quarterly numbers came in at 12500 units comma which is up \
+                   from last year period new paragraph next question mark";
+    group.bench_function("is_hallucination", |b| {
+        b.iter(|| textproc::is_hallucination(black_box(segment)))
+    });
+    let config = TextProcConfig::default();
+    group.bench_function("pipeline_build_en", |b| {
+        b.iter(|| TextPipeline::for_language(black_box("en"), &config))
+    });
+    let pipeline = TextPipeline::for_language("en", &config);
+    group.bench_function("pipeline_process_en", |b| {
+        b.iter(|| pipeline.process(black_box(segment)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, resampler, vad, text_passes);
+criterion_main!(benches);
@@ -0,0 +1,88 @@
+//! Records build metadata (git hash, linked backend versions) for `version_info()`.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Dependencies whose resolved versions are reported in `VersionInfo`,
+/// with the feature that links them. Deps left out of the build report
+/// "none".
+const TRACKED_DEPS: &[(&str, &str, &str)] = &[
+    ("whisper-rs", "DIKTO_WHISPER_RS_VERSION", "BACKEND_WHISPER"),
+    (
+        "whisper-rs-sys",
+        "DIKTO_WHISPER_RS_SYS_VERSION",
+        "BACKEND_WHISPER",
+    ),
+    ("ort", "DIKTO_ORT_VERSION", "NATIVE"),
+    (
+        "parakeet-rs",
+        "DIKTO_PARAKEET_RS_VERSION",
+        "BACKEND_PARAKEET",
+    ),
+];
+
+fn main() {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let workspace_root = Path::new(&manifest_dir).join("../..");
+
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .current_dir(&workspace_root)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=DIKTO_GIT_HASH={git_hash}");
+
+    let lock_path = workspace_root.join("Cargo.lock");
+    let lock = std::fs::read_to_string(&lock_path).unwrap_or_default();
+    for (name, var, feature) in TRACKED_DEPS {
+        let version = if std::env::var_os(format!("CARGO_FEATURE_{feature}")).is_none() {
+            "none".to_string()
+        } else {
+            locked_version(&lock, name).unwrap_or_else(|| "unknown".to_string())
+        };
+        println!("cargo:rustc-env={var}={version}");
+    }
+
+    println!(
+        "cargo:rustc-env=DIKTO_TARGET={}",
+        std::env::var("TARGET").unwrap_or_default()
+    );
+    println!("cargo:rerun-if-changed={}", lock_path.display());
+    // HEAD only changes on checkout; commits move the branch ref, which
+    // may be a loose file or a line in packed-refs
+    let git_dir = workspace_root.join(".git");
+    println!("cargo:rerun-if-changed={}", git_dir.join("HEAD").display());
+    println!(
+        "cargo:rerun-if-changed={}",
+        git_dir.join("packed-refs").display()
+    );
+    if let Some(branch_ref) = std::fs::read_to_string(git_dir.join("HEAD"))
+        .ok()
+        .and_then(|head| head.trim().strip_prefix("ref: ").map(str::to_string))
+    {
+        println!(
+            "cargo:rerun-if-changed={}",
+            git_dir.join(branch_ref).display()
+        );
+    }
+}
+
+/// Find the version of `name` in a Cargo.lock file.
+fn locked_version(lock: &str, name: &str) -> Option<String> {
+    let needle = format!("name = \"{name}\"");
+    let mut lines = lock.lines();
+    while let Some(line) = lines.next() {
+        if line.trim() == needle {
+            let version = lines.next()?.trim();
+            return version
+                .strip_prefix("version = \"")
+                .and_then(|v| v.strip_suffix('"'))
+                .map(str::to_string);
+        }
+    }
+    None
+}
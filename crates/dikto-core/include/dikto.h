@@ -0,0 +1,75 @@
+/*
+ * dikto.h — C ABI for libdikto_core (cdylib/staticlib).
+ *
+ * For hosts that can't use the UniFFI bindings, e.g. existing C++ apps.
+ * Link against libdikto_core and include this header.
+ *
+ * Conventions:
+ *   - All strings are UTF-8 and NUL-terminated.
+ *   - Strings returned by dikto_* functions are owned by the caller and must
+ *     be released with dikto_string_free().
+ *   - Functions that fail return NULL; dikto_last_error() then describes the
+ *     failure on the calling thread.
+ *   - Callbacks run on a background thread and must be safe to call from
+ *     any thread. The text argument is only valid during the call.
+ */
+#ifndef DIKTO_H
+#define DIKTO_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct DiktoEngine DiktoEngine;
+typedef struct DiktoSession DiktoSession;
+
+typedef void (*DiktoTextCallback)(void *user_data, const char *text);
+
+typedef struct DiktoCallbacks {
+    /* Passed unchanged to every callback. */
+    void *user_data;
+    /* Status updates while recording, e.g. "Recording... (2.0s)". */
+    DiktoTextCallback on_partial;
+    /* One finished utterance. */
+    DiktoTextCallback on_final_segment;
+    /* The session ended; text is the full transcript. */
+    DiktoTextCallback on_done;
+    /* The session failed; text is the message. */
+    DiktoTextCallback on_error;
+} DiktoCallbacks;
+
+/* Message from the last failed call on this thread, or NULL. Valid until the
+ * next failing call on the same thread. Do not free. */
+const char *dikto_last_error(void);
+
+/* Free a string returned by this library. NULL is ignored. */
+void dikto_string_free(char *text);
+
+/* Create an engine with the user's config. No model is loaded yet. */
+DiktoEngine *dikto_engine_new(void);
+
+/* Destroy an engine. Stop and free its sessions first. NULL is ignored. */
+void dikto_engine_free(DiktoEngine *engine);
+
+/* Transcribe a WAV file (16/24/32-bit PCM or 32-bit float, any rate) with
+ * the configured model, loading it if needed. language is an ISO-639-1 code
+ * or "auto". Returns the text, or NULL on failure. Blocks until done. */
+char *dikto_transcribe_file(const DiktoEngine *engine, const char *path,
+                            const char *language);
+
+/* Start listening on the microphone with the configured settings. The
+ * callbacks struct is copied. Returns NULL on failure. */
+const DiktoSession *dikto_listen(const DiktoEngine *engine,
+                                 const DiktoCallbacks *callbacks);
+
+/* Stop a session. on_done fires once the final text is ready. */
+void dikto_session_stop(const DiktoSession *session);
+
+/* Free a session handle. Does not stop the session. NULL is ignored. */
+void dikto_session_free(const DiktoSession *session);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* DIKTO_H */
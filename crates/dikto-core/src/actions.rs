@@ -0,0 +1,97 @@
+use std::path::{Path, PathBuf};
+
+use crate::config::{data_dir, ConfigError};
+use crate::storage;
+
+/// A task spoken in a transcript, e.g. "remind me to call Sam".
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct ActionItem {
+    /// The task itself, without the phrase that introduced it.
+    pub text: String,
+    /// The phrase that marked it, lowercase ("remind me to").
+    pub trigger: String,
+}
+
+/// Phrases that introduce a task, longest first so "action item" wins
+/// over shorter overlaps. Matched at word boundaries, ignoring case.
+const TRIGGERS: &[&str] = &[
+    "don't forget to",
+    "do not forget to",
+    "remember to",
+    "remind me to",
+    "make sure to",
+    "action item",
+    "follow up with",
+    "follow up on",
+    "we need to",
+    "i need to",
+    "to-do",
+    "todo",
+];
+
+/// Find the tasks in a transcript: each sentence containing a trigger
+/// phrase gives one item, the rest of the sentence after the phrase.
+pub fn extract_action_items(text: &str) -> Vec<ActionItem> {
+    text.split(['.', '!', '?', '\n'])
+        .filter_map(|sentence| {
+            // ASCII lowercasing keeps byte offsets valid in `sentence`
+            let lower = sentence.to_ascii_lowercase();
+            let (start, trigger) = TRIGGERS
+                .iter()
+                .filter_map(|t| find_phrase(&lower, t).map(|i| (i, *t)))
+                .min_by_key(|(i, t)| (*i, std::cmp::Reverse(t.len())))?;
+            let mut task = &sentence[start + trigger.len()..];
+            // "follow up with" keeps its object as part of the task
+            if trigger.starts_with("follow up") {
+                task = &sentence[start..];
+            }
+            let task = task
+                .trim_start_matches([' ', ':', ',', '-'])
+                .trim_end_matches([' ', ',', ';'])
+                .trim();
+            (!task.is_empty()).then(|| ActionItem {
+                text: capitalize(task),
+                trigger: trigger.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Byte offset of `phrase` in `text` as whole words.
+fn find_phrase(text: &str, phrase: &str) -> Option<usize> {
+    let boundary = |c: Option<char>| c.is_none_or(|c| !c.is_alphanumeric() && c != '\'');
+    text.match_indices(phrase).map(|(i, _)| i).find(|&i| {
+        boundary(text[..i].chars().next_back()) && boundary(text[i + phrase.len()..].chars().next())
+    })
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Where `tasks_file` points: absolute paths as they are, others under the
+/// data directory.
+pub fn tasks_path(tasks_file: &str) -> Result<PathBuf, ConfigError> {
+    let path = Path::new(tasks_file);
+    if path.is_absolute() {
+        Ok(path.to_path_buf())
+    } else {
+        Ok(data_dir()?.join(path))
+    }
+}
+
+/// Append items to a Markdown task list, one "- [ ] " line each.
+pub fn append_to_tasks_file(path: &Path, items: &[ActionItem]) -> std::io::Result<()> {
+    if items.is_empty() {
+        return Ok(());
+    }
+    let lines: String = items
+        .iter()
+        .map(|item| format!("- [ ] {}\n", item.text))
+        .collect();
+    storage::append_locked(path, lines.as_bytes())
+}
@@ -1,15 +1,26 @@
+#[cfg(feature = "audio-capture")]
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+#[cfg(feature = "audio-capture")]
 use ringbuf::traits::{Consumer, Observer, Producer, Split};
+#[cfg(feature = "audio-capture")]
 use ringbuf::{HeapCons, HeapProd, HeapRb};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
 use thiserror::Error;
-use tracing::{error, info};
+#[cfg(feature = "audio-capture")]
+use tracing::error;
+use tracing::info;
 
 #[derive(Debug, Error)]
 pub enum AudioError {
     #[error("No input device available")]
     NoInputDevice,
+    /// The OS refused microphone access (macOS privacy settings).
+    #[error("Microphone access denied")]
+    PermissionDenied,
     #[error("No supported input config")]
     NoSupportedConfig,
     #[error("Failed to build stream: {0}")]
@@ -20,6 +31,278 @@ pub enum AudioError {
     Device(String),
 }
 
+/// Microphone permission as the host's OS reports it, e.g. macOS
+/// `AVCaptureDevice.authorizationStatus`. cpal can't tell denied access
+/// from a quiet device, so hosts that know should say.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, uniffi::Enum)]
+pub enum MicrophonePermission {
+    #[default]
+    Unknown,
+    Granted,
+    Denied,
+}
+
+static MICROPHONE_PERMISSION: AtomicU8 = AtomicU8::new(0);
+
+/// Record the host's microphone permission for later captures.
+pub fn set_microphone_permission(permission: MicrophonePermission) {
+    let value = match permission {
+        MicrophonePermission::Unknown => 0,
+        MicrophonePermission::Granted => 1,
+        MicrophonePermission::Denied => 2,
+    };
+    MICROPHONE_PERMISSION.store(value, Ordering::Relaxed);
+}
+
+/// The permission last set with `set_microphone_permission`.
+pub fn microphone_permission() -> MicrophonePermission {
+    match MICROPHONE_PERMISSION.load(Ordering::Relaxed) {
+        1 => MicrophonePermission::Granted,
+        2 => MicrophonePermission::Denied,
+        _ => MicrophonePermission::Unknown,
+    }
+}
+
+/// How long a stream may deliver no samples, or only exact zeros, before
+/// it is taken for denied access.
+pub const PERMISSION_PROBE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Spots the stream macOS delivers when microphone access was denied: the
+/// device opens fine, then sends nothing or only exact zeros. Real
+/// microphones always pick up some noise, so a second of that at the start
+/// of a session means the permission is missing.
+#[derive(Debug, Clone)]
+pub struct PermissionProbe {
+    window_samples: usize,
+    zero_samples: usize,
+    finished: bool,
+}
+
+impl PermissionProbe {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            window_samples: (sample_rate as f32 * PERMISSION_PROBE_WINDOW.as_secs_f32()) as usize,
+            zero_samples: 0,
+            finished: false,
+        }
+    }
+
+    /// Look at the next samples of the stream. Returns true once a full
+    /// window has been exactly zero.
+    pub fn observe(&mut self, samples: &[f32]) -> bool {
+        if self.finished {
+            return false;
+        }
+        if samples.iter().any(|s| *s != 0.0) {
+            self.finished = true;
+            return false;
+        }
+        self.zero_samples += samples.len();
+        self.zero_samples >= self.window_samples
+    }
+
+    /// True when nothing at all has arrived `elapsed` after the stream opened.
+    pub fn stalled(&self, elapsed: Duration) -> bool {
+        !self.finished && self.zero_samples == 0 && elapsed >= PERMISSION_PROBE_WINDOW
+    }
+
+    /// The stream carried real sound, so the probe is no longer needed.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// How long a stream may deliver only exact zeros before the microphone is
+/// reported as muted.
+pub const NO_AUDIO_WINDOW: Duration = Duration::from_secs(2);
+
+/// Spots a microphone that is muted in hardware or by the OS: it keeps
+/// delivering samples, but every one is exactly zero. Reports each such
+/// stretch once; real sound starts watching for the next one.
+#[derive(Debug, Clone)]
+pub struct MuteDetector {
+    window_samples: usize,
+    zero_samples: usize,
+    reported: bool,
+}
+
+impl MuteDetector {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            window_samples: (sample_rate as f32 * NO_AUDIO_WINDOW.as_secs_f32()) as usize,
+            zero_samples: 0,
+            reported: false,
+        }
+    }
+
+    /// Look at the next samples of the stream. Returns true when the current
+    /// run of exact zeros first reaches `NO_AUDIO_WINDOW`.
+    pub fn observe(&mut self, samples: &[f32]) -> bool {
+        match samples.iter().rposition(|s| *s != 0.0) {
+            Some(last_sound) => {
+                self.zero_samples = samples.len() - last_sound - 1;
+                self.reported = false;
+            }
+            None => self.zero_samples += samples.len(),
+        }
+        if self.reported || self.zero_samples < self.window_samples {
+            return false;
+        }
+        self.reported = true;
+        true
+    }
+}
+
+/// Linear-interpolation resampler for mono device audio. Devices rarely
+/// run at 16kHz (WASAPI shared mode is usually 48kHz), and their callbacks
+/// hand over arbitrary chunk sizes, so the position and last sample carry
+/// over between calls: chunked input resamples the same as one long buffer.
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    /// Input samples per output sample.
+    ratio: f64,
+    /// Position of the next output sample, counted from `previous`.
+    position: f64,
+    /// Last sample of the previous chunk.
+    previous: Option<f32>,
+}
+
+impl Resampler {
+    pub fn new(from_rate: u32, to_rate: u32) -> Self {
+        Self {
+            ratio: from_rate as f64 / to_rate.max(1) as f64,
+            position: 0.0,
+            previous: None,
+        }
+    }
+
+    /// Whether input passes through unchanged.
+    pub fn is_passthrough(&self) -> bool {
+        self.ratio == 1.0
+    }
+
+    /// Resample the next chunk of input, appending to `out`.
+    pub fn process(&mut self, input: &[f32], out: &mut Vec<f32>) {
+        if self.is_passthrough() {
+            out.extend_from_slice(input);
+            return;
+        }
+        let Some(&last) = input.last() else {
+            return;
+        };
+        let previous = self.previous;
+        let offset = previous.is_some() as usize;
+        let at = |i: usize| match (i, previous) {
+            (0, Some(p)) => p,
+            _ => input[i - offset],
+        };
+        let len = input.len() + offset;
+        while (self.position as usize) + 1 < len {
+            let idx = self.position as usize;
+            let frac = (self.position - idx as f64) as f32;
+            out.push(at(idx) * (1.0 - frac) + at(idx + 1) * frac);
+            self.position += self.ratio;
+        }
+        // The last sample becomes index 0 of the next call
+        self.position -= (len - 1) as f64;
+        self.previous = Some(last);
+    }
+}
+
+/// Device streams open in this process.
+static OPEN_STREAMS: AtomicUsize = AtomicUsize::new(0);
+
+/// Whether any session has the microphone open right now, for the host's
+/// mic-in-use indicator.
+pub fn is_microphone_open() -> bool {
+    OPEN_STREAMS.load(Ordering::Acquire) > 0
+}
+
+/// Lets another thread shut a session's microphone. cpal streams must be
+/// dropped on the thread that opened them, so `halt` stops the stream from
+/// delivering samples, wakes that thread, and waits for it to drop the stream.
+#[derive(Debug, Default)]
+pub struct CaptureControl {
+    halted: AtomicBool,
+    stream: Mutex<StreamState>,
+    closed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct StreamState {
+    /// Open device streams; briefly two while a failed device is reopened.
+    open: usize,
+    /// Thread that opened the stream and reads from it.
+    reader: Option<std::thread::Thread>,
+}
+
+impl CaptureControl {
+    /// Stop capture and wait up to `timeout` for the stream to be dropped.
+    /// Returns whether the microphone is closed.
+    pub fn halt(&self, timeout: Duration) -> bool {
+        self.halted.store(true, Ordering::Release);
+        let Ok(state) = self.stream.lock() else {
+            return false;
+        };
+        if let Some(reader) = &state.reader {
+            reader.unpark();
+        }
+        match self
+            .closed
+            .wait_timeout_while(state, timeout, |s| s.open > 0)
+        {
+            Ok((state, _)) => state.open == 0,
+            Err(_) => false,
+        }
+    }
+
+    /// Whether `halt` was called.
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::Acquire)
+    }
+
+    /// Whether a device stream is open.
+    pub fn is_open(&self) -> bool {
+        self.stream.lock().is_ok_and(|s| s.open > 0)
+    }
+
+    /// Count a device stream as open, from the thread that reads it, until
+    /// the returned marker is dropped.
+    pub fn stream_opened(self: &Arc<Self>) -> OpenStream {
+        OPEN_STREAMS.fetch_add(1, Ordering::AcqRel);
+        if let Ok(mut state) = self.stream.lock() {
+            state.open += 1;
+            state.reader = Some(std::thread::current());
+        }
+        OpenStream {
+            control: self.clone(),
+        }
+    }
+
+    fn closed(&self) {
+        OPEN_STREAMS.fetch_sub(1, Ordering::AcqRel);
+        if let Ok(mut state) = self.stream.lock() {
+            state.open = state.open.saturating_sub(1);
+            if state.open == 0 {
+                state.reader = None;
+            }
+        }
+        self.closed.notify_all();
+    }
+}
+
+/// A device stream counted as open. Owners drop it right after the stream,
+/// so the count also comes down when a panic unwinds the session thread.
+pub struct OpenStream {
+    control: Arc<CaptureControl>,
+}
+
+impl Drop for OpenStream {
+    fn drop(&mut self) {
+        self.control.closed();
+    }
+}
+
 /// Configuration for audio capture.
 #[derive(Debug, Clone)]
 pub struct AudioCaptureConfig {
@@ -40,15 +323,34 @@ impl Default for AudioCaptureConfig {
 }
 
 /// Handle to a running audio capture session.
+#[cfg(feature = "audio-capture")]
 pub struct AudioCapture {
     _stream: cpal::Stream,
     consumer: HeapCons<f32>,
     running: Arc<AtomicBool>,
+    config: AudioCaptureConfig,
+    /// Set by the stream's error callback, e.g. when a USB mic is unplugged.
+    failure: Arc<Mutex<Option<String>>>,
+    control: Arc<CaptureControl>,
+    /// Declared after the stream so it is dropped after it.
+    _open: OpenStream,
 }
 
+#[cfg(feature = "audio-capture")]
 impl AudioCapture {
     /// Start capturing audio from the default input device.
     pub fn start(config: AudioCaptureConfig) -> Result<Self, AudioError> {
+        Self::start_with_control(config, Arc::default())
+    }
+
+    /// Start capturing, letting `control` halt the stream from another thread.
+    pub fn start_with_control(
+        config: AudioCaptureConfig,
+        control: Arc<CaptureControl>,
+    ) -> Result<Self, AudioError> {
+        if microphone_permission() == MicrophonePermission::Denied {
+            return Err(AudioError::PermissionDenied);
+        }
         let host = cpal::default_host();
         let device = host
             .default_input_device()
@@ -61,10 +363,10 @@ impl AudioCapture {
             .default_input_config()
             .map_err(|_| AudioError::NoSupportedConfig)?;
 
-        let device_sample_rate = supported_config.sample_rate().0;
-        let device_channels = supported_config.channels();
         info!(
-            "Device config: {device_sample_rate}Hz, {device_channels}ch, {:?}",
+            "Device config: {}Hz, {}ch, {:?}",
+            supported_config.sample_rate().0,
+            supported_config.channels(),
             supported_config.sample_format()
         );
 
@@ -73,22 +375,29 @@ impl AudioCapture {
 
         let running = Arc::new(AtomicBool::new(true));
         let running_clone = running.clone();
+        let failure = Arc::new(Mutex::new(None));
 
         let target_rate = config.target_sample_rate;
 
+        // The audio thread unparks the starting thread whenever it pushes
+        // samples, so `wait_for_samples` can block instead of busy-polling.
+        let waiter = std::thread::current();
+
         let stream = build_stream(
             &device,
             &supported_config,
             producer,
             running_clone,
             target_rate,
-            device_channels,
-            device_sample_rate,
+            waiter,
+            failure.clone(),
+            control.clone(),
         )?;
 
         stream
             .play()
             .map_err(|e| AudioError::StreamPlay(e.to_string()))?;
+        let open = control.stream_opened();
 
         info!("Audio capture started");
 
@@ -96,22 +405,52 @@ impl AudioCapture {
             _stream: stream,
             consumer,
             running,
+            config,
+            failure,
+            control,
+            _open: open,
         })
     }
 
+    /// Take the error that stopped the stream, if it failed since the last call.
+    pub fn take_failure(&self) -> Option<String> {
+        self.failure.lock().ok().and_then(|mut f| f.take())
+    }
+
     /// Read available samples from the ring buffer.
     /// Returns a Vec of f32 samples at the target sample rate (16kHz mono).
+    /// Allocates per call; hot loops should prefer `read_into`.
     pub fn read_samples(&mut self) -> Vec<f32> {
         let available = self.consumer.occupied_len();
         if available == 0 {
             return Vec::new();
         }
         let mut buf = vec![0.0f32; available];
-        let read = self.consumer.pop_slice(&mut buf);
+        let read = self.read_into(&mut buf);
         buf.truncate(read);
         buf
     }
 
+    /// Read up to `buf.len()` samples into a caller-owned scratch buffer.
+    /// Returns the number of samples written (16kHz mono). Does not allocate.
+    pub fn read_into(&mut self, buf: &mut [f32]) -> usize {
+        self.consumer.pop_slice(buf)
+    }
+
+    /// Number of samples currently waiting in the ring buffer.
+    pub fn available(&self) -> usize {
+        self.consumer.occupied_len()
+    }
+
+    /// Block until samples are available or `timeout` elapses.
+    /// Must be called from the thread that started the capture; spurious
+    /// early returns are possible, so callers should re-check `available()`.
+    pub fn wait_for_samples(&self, timeout: Duration) {
+        if self.consumer.occupied_len() == 0 {
+            std::thread::park_timeout(timeout);
+        }
+    }
+
     /// Check if the capture is still running.
     pub fn is_running(&self) -> bool {
         self.running.load(Ordering::Relaxed)
@@ -124,6 +463,7 @@ impl AudioCapture {
     }
 }
 
+#[cfg(feature = "audio-capture")]
 impl Drop for AudioCapture {
     fn drop(&mut self) {
         self.stop();
@@ -131,21 +471,25 @@ impl Drop for AudioCapture {
 }
 
 /// Build a cpal input stream that writes resampled mono samples into the ring buffer.
+#[cfg(feature = "audio-capture")]
 fn build_stream(
     device: &cpal::Device,
     config: &cpal::SupportedStreamConfig,
     mut producer: HeapProd<f32>,
     running: Arc<AtomicBool>,
     target_rate: u32,
-    channels: u16,
-    device_rate: u32,
+    waiter: std::thread::Thread,
+    failure: Arc<Mutex<Option<String>>>,
+    control: Arc<CaptureControl>,
 ) -> Result<cpal::Stream, AudioError> {
     let sample_format = config.sample_format();
+    let channels = config.channels();
+    let device_rate = config.sample_rate().0;
     let stream_config: cpal::StreamConfig = config.clone().into();
 
-    // Resampling state: we use linear interpolation for downsampling
-    let ratio = device_rate as f64 / target_rate as f64;
-    let mut resample_pos: f64 = 0.0;
+    let mut resampler = Resampler::new(device_rate, target_rate);
+    let mut resampled = Vec::new();
+    let error_waiter = waiter.clone();
 
     macro_rules! build_input_stream {
         ($sample_type:ty, $to_f32:expr) => {{
@@ -153,7 +497,7 @@ fn build_stream(
                 .build_input_stream(
                     &stream_config,
                     move |data: &[$sample_type], _: &cpal::InputCallbackInfo| {
-                        if !running.load(Ordering::Relaxed) {
+                        if !running.load(Ordering::Relaxed) || control.is_halted() {
                             return;
                         }
 
@@ -166,29 +510,21 @@ fn build_stream(
                             })
                             .collect();
 
-                        // Resample to target rate using linear interpolation
-                        if device_rate == target_rate {
-                            // No resampling needed
+                        if resampler.is_passthrough() {
                             let _ = producer.push_slice(&mono);
                         } else {
-                            let mut resampled = Vec::new();
-                            while (resample_pos as usize) < mono.len().saturating_sub(1) {
-                                let idx = resample_pos as usize;
-                                let frac = resample_pos - idx as f64;
-                                let sample =
-                                    mono[idx] * (1.0 - frac as f32) + mono[idx + 1] * frac as f32;
-                                resampled.push(sample);
-                                resample_pos += ratio;
-                            }
-                            resample_pos -= mono.len() as f64;
-                            if resample_pos < 0.0 {
-                                resample_pos = 0.0;
-                            }
+                            resampled.clear();
+                            resampler.process(&mono, &mut resampled);
                             let _ = producer.push_slice(&resampled);
                         }
+                        waiter.unpark();
                     },
                     move |err| {
                         error!("Audio input error: {err}");
+                        if let Ok(mut failure) = failure.lock() {
+                            *failure = Some(err.to_string());
+                        }
+                        error_waiter.unpark();
                     },
                     None,
                 )
@@ -204,6 +540,10 @@ fn build_stream(
         cpal::SampleFormat::U16 => {
             build_input_stream!(u16, |s: u16| (s as f32 / u16::MAX as f32) * 2.0 - 1.0)
         }
+        // Some WASAPI drivers only offer 32-bit integer capture
+        cpal::SampleFormat::I32 => {
+            build_input_stream!(i32, |s: i32| s as f32 / i32::MAX as f32)
+        }
         _ => {
             return Err(AudioError::StreamBuild(format!(
                 "Unsupported sample format: {sample_format:?}"
@@ -213,3 +553,190 @@ fn build_stream(
 
     Ok(stream)
 }
+
+/// Audio pushed by the host instead of captured from a device, e.g. an
+/// Android keyboard that owns the microphone. Holds 16kHz mono samples; when
+/// the host outpaces the pipeline the oldest samples are dropped.
+pub struct FeedBuffer {
+    samples: Mutex<VecDeque<f32>>,
+    ready: Condvar,
+    capacity: usize,
+}
+
+impl FeedBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: Mutex::new(VecDeque::with_capacity(capacity)),
+            ready: Condvar::new(),
+            capacity,
+        }
+    }
+
+    /// Append host samples and wake the pipeline.
+    pub fn push(&self, samples: &[f32]) {
+        let Ok(mut buffer) = self.samples.lock() else {
+            return;
+        };
+        buffer.extend(samples);
+        let excess = buffer.len().saturating_sub(self.capacity);
+        buffer.drain(..excess);
+        self.ready.notify_all();
+    }
+
+    /// Read up to `buf.len()` samples. Returns the number written.
+    pub fn read_into(&self, buf: &mut [f32]) -> usize {
+        let Ok(mut buffer) = self.samples.lock() else {
+            return 0;
+        };
+        let read = buf.len().min(buffer.len());
+        for (slot, sample) in buf.iter_mut().zip(buffer.drain(..read)) {
+            *slot = sample;
+        }
+        read
+    }
+
+    /// Number of samples waiting to be read.
+    pub fn available(&self) -> usize {
+        self.samples.lock().map(|b| b.len()).unwrap_or(0)
+    }
+
+    /// Block until samples are available or `timeout` elapses.
+    pub fn wait_for_samples(&self, timeout: Duration) {
+        let Ok(buffer) = self.samples.lock() else {
+            return;
+        };
+        let _ = self
+            .ready
+            .wait_timeout_while(buffer, timeout, |b| b.is_empty());
+    }
+}
+
+/// Names of the input devices the default host can see. Empty in builds
+/// without device audio.
+pub fn input_device_names() -> Vec<String> {
+    #[cfg(feature = "audio-capture")]
+    {
+        cpal::default_host()
+            .input_devices()
+            .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+            .unwrap_or_default()
+    }
+    #[cfg(not(feature = "audio-capture"))]
+    {
+        Vec::new()
+    }
+}
+
+/// Samples supplied by an app embedding the engine in place of the
+/// microphone, e.g. a network stream or a test signal.
+pub trait AudioSource: Send {
+    /// Read up to `buf.len()` 16kHz mono samples. Returns the number written;
+    /// 0 means none are available yet.
+    fn read_into(&mut self, buf: &mut [f32]) -> usize;
+
+    /// Block until samples are available or `timeout` elapses. The default
+    /// just sleeps for `timeout`.
+    fn wait_for_samples(&self, timeout: Duration) {
+        std::thread::sleep(timeout);
+    }
+}
+
+/// Where a session's audio comes from.
+pub enum AudioInput {
+    /// The default input device, via cpal.
+    #[cfg(feature = "audio-capture")]
+    Device(AudioCapture),
+    /// Samples the host pushes through `SessionHandle::feed_audio`.
+    Host(std::sync::Arc<FeedBuffer>),
+    /// An app-supplied source (see `DiktoEngineBuilder::audio_source`).
+    Custom(Box<dyn AudioSource>),
+}
+
+impl AudioInput {
+    /// Open the default input device, which `control` can halt. Fails in
+    /// builds without device audio, where hosts must feed samples themselves.
+    pub fn device(
+        config: AudioCaptureConfig,
+        control: Arc<CaptureControl>,
+    ) -> Result<Self, AudioError> {
+        #[cfg(feature = "audio-capture")]
+        {
+            AudioCapture::start_with_control(config, control).map(AudioInput::Device)
+        }
+        #[cfg(not(feature = "audio-capture"))]
+        {
+            let _ = (config, control);
+            Err(AudioError::NoInputDevice)
+        }
+    }
+
+    /// Read up to `buf.len()` 16kHz mono samples. Returns the number written.
+    pub fn read_into(&mut self, buf: &mut [f32]) -> usize {
+        match self {
+            #[cfg(feature = "audio-capture")]
+            AudioInput::Device(capture) => capture.read_into(buf),
+            AudioInput::Host(feed) => feed.read_into(buf),
+            AudioInput::Custom(source) => source.read_into(buf),
+        }
+    }
+
+    /// Block until samples are available or `timeout` elapses.
+    pub fn wait_for_samples(&self, timeout: Duration) {
+        match self {
+            #[cfg(feature = "audio-capture")]
+            AudioInput::Device(capture) => capture.wait_for_samples(timeout),
+            AudioInput::Host(feed) => feed.wait_for_samples(timeout),
+            AudioInput::Custom(source) => source.wait_for_samples(timeout),
+        }
+    }
+
+    /// Take the error that stopped the device stream, if it failed since the
+    /// last call. Host-fed and custom input never fail.
+    pub fn take_failure(&self) -> Option<String> {
+        match self {
+            #[cfg(feature = "audio-capture")]
+            AudioInput::Device(capture) => capture.take_failure(),
+            AudioInput::Host(_) | AudioInput::Custom(_) => None,
+        }
+    }
+
+    /// Reopen the default input device after a failure, with the original
+    /// capture settings. Host-fed and custom input are left as is.
+    pub fn reopen(&mut self) -> Result<(), AudioError> {
+        match self {
+            #[cfg(feature = "audio-capture")]
+            AudioInput::Device(capture) => {
+                capture.stop();
+                let (config, control) = (capture.config.clone(), capture.control.clone());
+                *capture = AudioCapture::start_with_control(config, control)?;
+                Ok(())
+            }
+            AudioInput::Host(_) | AudioInput::Custom(_) => Ok(()),
+        }
+    }
+
+    /// A probe for denied microphone access, for device input on macOS
+    /// unless the host reported the permission as granted.
+    pub fn permission_probe(&self) -> Option<PermissionProbe> {
+        match self {
+            #[cfg(feature = "audio-capture")]
+            AudioInput::Device(capture)
+                if cfg!(target_os = "macos")
+                    && microphone_permission() != MicrophonePermission::Granted =>
+            {
+                Some(PermissionProbe::new(capture.config.target_sample_rate))
+            }
+            _ => None,
+        }
+    }
+
+    /// Stop capturing. Host-fed and custom input have nothing to release.
+    pub fn stop(&self) {
+        match self {
+            #[cfg(feature = "audio-capture")]
+            AudioInput::Device(capture) => capture.stop(),
+            AudioInput::Host(_) => info!("Host audio feed closed"),
+            AudioInput::Custom(_) => info!("Custom audio source closed"),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+/// Build and version information for About dialogs and bug reports.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct VersionInfo {
+    /// dikto-core crate version.
+    pub version: String,
+    /// Short git commit hash of the build, or "unknown".
+    pub git_hash: String,
+    /// Enabled cargo features (e.g. "metal", "coreml").
+    pub features: Vec<String>,
+    /// Target triple the library was built for.
+    pub target: String,
+    /// Linked whisper-rs version, or "none" without backend-whisper.
+    pub whisper_rs_version: String,
+    /// Linked whisper-rs-sys version (pins the bundled whisper.cpp), or
+    /// "none" without backend-whisper.
+    pub whisper_cpp_sys_version: String,
+    /// Linked ONNX Runtime binding (ort) version, or "none" without native.
+    pub ort_version: String,
+    /// Linked parakeet-rs version, or "none" without backend-parakeet.
+    pub parakeet_rs_version: String,
+}
+
+/// Collect build information recorded at compile time by build.rs.
+pub fn version_info() -> VersionInfo {
+    VersionInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_hash: env!("DIKTO_GIT_HASH").to_string(),
+        features: enabled_features(),
+        target: env!("DIKTO_TARGET").to_string(),
+        whisper_rs_version: env!("DIKTO_WHISPER_RS_VERSION").to_string(),
+        whisper_cpp_sys_version: env!("DIKTO_WHISPER_RS_SYS_VERSION").to_string(),
+        ort_version: env!("DIKTO_ORT_VERSION").to_string(),
+        parakeet_rs_version: env!("DIKTO_PARAKEET_RS_VERSION").to_string(),
+    }
+}
+
+/// Cargo features this build was compiled with.
+pub fn enabled_features() -> Vec<String> {
+    let mut features = Vec::new();
+    if cfg!(feature = "native") {
+        features.push("native".to_string());
+    }
+    if cfg!(feature = "metal") {
+        features.push("metal".to_string());
+    }
+    if cfg!(feature = "coreml") {
+        features.push("coreml".to_string());
+    }
+    if cfg!(feature = "backend-whisper") {
+        features.push("backend-whisper".to_string());
+    }
+    if cfg!(feature = "backend-parakeet") {
+        features.push("backend-parakeet".to_string());
+    }
+    if cfg!(feature = "audio-capture") {
+        features.push("audio-capture".to_string());
+    }
+    features
+}
@@ -0,0 +1,249 @@
+//! Plain C ABI for integrators who can't use the UniFFI bindings, e.g. C++
+//! apps. Declared in `include/dikto.h`; keep the two in sync.
+//!
+//! Strings returned to C are owned by the caller and must be released with
+//! `dikto_string_free`. Functions that fail return NULL and leave
+//! a message for `dikto_last_error` on the calling thread.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::sync::Arc;
+
+use crate::output::OutputAction;
+use crate::{
+    DiktoEngine, OverlapWarning, RecordingState, SessionHandle, TranscriptionCallback,
+    WordConfidence,
+};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: impl Into<String>) {
+    let message = CString::new(message.into().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+fn to_c_string(text: &str) -> *mut c_char {
+    CString::new(text.replace('\0', " "))
+        .map(CString::into_raw)
+        .unwrap_or(std::ptr::null_mut())
+}
+
+/// Borrow a C string argument, recording an error for NULL or invalid UTF-8.
+///
+/// # Safety
+/// `ptr` must be NULL or a valid NUL-terminated string.
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Option<&'a str> {
+    if ptr.is_null() {
+        set_last_error(format!("{name} is NULL"));
+        return None;
+    }
+    match CStr::from_ptr(ptr).to_str() {
+        Ok(s) => Some(s),
+        Err(_) => {
+            set_last_error(format!("{name} is not valid UTF-8"));
+            None
+        }
+    }
+}
+
+/// Message from the last failed call on this thread, or NULL. Valid until
+/// the next failing call on the same thread; do not free.
+#[no_mangle]
+pub extern "C" fn dikto_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| {
+        e.borrow()
+            .as_ref()
+            .map(|s| s.as_ptr())
+            .unwrap_or(std::ptr::null())
+    })
+}
+
+/// Free a string returned by this library. NULL is ignored.
+///
+/// # Safety
+/// `text` must be NULL or a pointer returned by a `dikto_*` function that
+/// has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn dikto_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
+
+/// Create an engine with the user's config. No model is loaded yet.
+#[no_mangle]
+pub extern "C" fn dikto_engine_new() -> *mut DiktoEngine {
+    Box::into_raw(Box::new(DiktoEngine::new()))
+}
+
+/// Destroy an engine. NULL is ignored.
+///
+/// # Safety
+/// `engine` must be NULL or a pointer from `dikto_engine_new` with no
+/// active sessions, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn dikto_engine_free(engine: *mut DiktoEngine) {
+    if !engine.is_null() {
+        drop(Box::from_raw(engine));
+    }
+}
+
+/// Transcribe a WAV file with the configured model, loading it if needed.
+/// `language` is an ISO-639-1 code or "auto". Returns the text, or NULL on
+/// failure.
+///
+/// # Safety
+/// `engine` must come from `dikto_engine_new`; `path` and `language` must be
+/// NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn dikto_transcribe_file(
+    engine: *const DiktoEngine,
+    path: *const c_char,
+    language: *const c_char,
+) -> *mut c_char {
+    let Some(engine) = engine.as_ref() else {
+        set_last_error("engine is NULL");
+        return std::ptr::null_mut();
+    };
+    let (Some(path), Some(language)) = (str_arg(path, "path"), str_arg(language, "language"))
+    else {
+        return std::ptr::null_mut();
+    };
+    match engine.transcribe_file(path.to_string(), language.to_string()) {
+        Ok(result) => to_c_string(&result.text),
+        Err(e) => {
+            set_last_error(e.to_string());
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Callbacks for `dikto_listen`. They run on a background thread, receive
+/// `user_data` unchanged, and borrow `text` only for the duration of the
+/// call. Any of them may be NULL.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DiktoCallbacks {
+    pub user_data: *mut c_void,
+    /// Status updates while recording, e.g. "Recording... (2.0s)".
+    pub on_partial: Option<unsafe extern "C" fn(*mut c_void, *const c_char)>,
+    /// One finished utterance.
+    pub on_final_segment: Option<unsafe extern "C" fn(*mut c_void, *const c_char)>,
+    /// The session ended; `text` is the full transcript.
+    pub on_done: Option<unsafe extern "C" fn(*mut c_void, *const c_char)>,
+    /// The session failed; `text` is the message.
+    pub on_error: Option<unsafe extern "C" fn(*mut c_void, *const c_char)>,
+}
+
+/// Adapts C function pointers to TranscriptionCallback.
+struct CCallbacks(DiktoCallbacks);
+
+// The header requires callbacks and user_data to be usable from any thread
+unsafe impl Send for CCallbacks {}
+unsafe impl Sync for CCallbacks {}
+
+impl CCallbacks {
+    fn call(&self, f: Option<unsafe extern "C" fn(*mut c_void, *const c_char)>, text: &str) {
+        let Some(f) = f else { return };
+        let Ok(text) = CString::new(text.replace('\0', " ")) else {
+            return;
+        };
+        unsafe { f(self.0.user_data, text.as_ptr()) }
+    }
+}
+
+impl TranscriptionCallback for CCallbacks {
+    fn on_partial(&self, text: String) {
+        self.call(self.0.on_partial, &text);
+    }
+
+    fn on_final_segment(&self, text: String) {
+        self.call(self.0.on_final_segment, &text);
+    }
+
+    fn on_silence(&self) {}
+
+    fn on_error(&self, error: String) {
+        self.call(self.0.on_error, &error);
+    }
+
+    fn on_state_change(&self, state: RecordingState) {
+        match state {
+            RecordingState::Done { text } => self.call(self.0.on_done, &text),
+            RecordingState::Error { message, .. } => self.call(self.0.on_error, &message),
+            RecordingState::Listening | RecordingState::Processing => {}
+        }
+    }
+
+    fn on_language_change(&self, _language: String) {}
+
+    fn on_word_confidence(&self, _words: Vec<WordConfidence>) {}
+
+    fn on_overlap_warning(&self, _warning: OverlapWarning) {}
+
+    fn on_audio_interrupted(&self, _reason: String) {}
+
+    fn on_summary(&self, _summary: String) {}
+
+    fn on_no_audio_detected(&self) {}
+
+    fn on_time_remaining(&self, _seconds: u32) {}
+
+    fn on_ready(&self) {}
+
+    fn on_dry_run(&self, _actions: Vec<OutputAction>) {}
+}
+
+/// Start listening on the microphone with the configured settings. Returns
+/// a session to stop and free, or NULL on failure.
+///
+/// # Safety
+/// `engine` must come from `dikto_engine_new` and outlive the session;
+/// `callbacks` must point to a valid struct (it is copied).
+#[no_mangle]
+pub unsafe extern "C" fn dikto_listen(
+    engine: *const DiktoEngine,
+    callbacks: *const DiktoCallbacks,
+) -> *const SessionHandle {
+    let Some(engine) = engine.as_ref() else {
+        set_last_error("engine is NULL");
+        return std::ptr::null();
+    };
+    let Some(callbacks) = callbacks.as_ref() else {
+        set_last_error("callbacks is NULL");
+        return std::ptr::null();
+    };
+    let callback: Arc<dyn TranscriptionCallback> = Arc::new(CCallbacks(*callbacks));
+    match engine.start_listening(engine.listen_config(), callback) {
+        Ok(session) => Arc::into_raw(session),
+        Err(e) => {
+            set_last_error(e.to_string());
+            std::ptr::null()
+        }
+    }
+}
+
+/// Stop a session. `on_done` fires once the final text is ready. NULL is
+/// ignored.
+///
+/// # Safety
+/// `session` must be NULL or a pointer from `dikto_listen` not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn dikto_session_stop(session: *const SessionHandle) {
+    if let Some(session) = session.as_ref() {
+        session.stop();
+    }
+}
+
+/// Free a session handle. Does not stop it. NULL is ignored.
+///
+/// # Safety
+/// `session` must be NULL or a pointer from `dikto_listen` not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn dikto_session_free(session: *const SessionHandle) {
+    if !session.is_null() {
+        drop(Arc::from_raw(session));
+    }
+}
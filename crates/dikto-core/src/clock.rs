@@ -0,0 +1,93 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Source of the current time for session timing (max_duration, overlay
+/// throttling, the permission probe). Sessions take it from the engine, so
+/// tests can drive them with a `MockClock` instead of sleeping.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// The monotonic system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when told to.
+#[derive(Debug)]
+pub struct MockClock {
+    start: Instant,
+    offset: Mutex<Duration>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            offset: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    /// Move the clock forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        if let Ok(mut offset) = self.offset.lock() {
+            *offset += by;
+        }
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        let offset = self.offset.lock().map(|o| *o).unwrap_or_default();
+        self.start + offset
+    }
+}
+
+/// Wall-clock checks of one session: time since it started, for its
+/// deadline, and when the overlay may next be updated.
+pub struct SessionTimer {
+    clock: Arc<dyn Clock>,
+    started: Instant,
+    partial_interval: Duration,
+    last_partial: Instant,
+}
+
+impl SessionTimer {
+    /// Start timing now. The first partial is due `partial_interval` later.
+    pub fn start(clock: Arc<dyn Clock>, partial_interval: Duration) -> Self {
+        let started = clock.now();
+        Self {
+            clock,
+            started,
+            partial_interval,
+            last_partial: started,
+        }
+    }
+
+    /// Time since the session started.
+    pub fn elapsed(&self) -> Duration {
+        self.clock.now().saturating_duration_since(self.started)
+    }
+
+    /// Whether `partial_interval` has passed since the last partial. A
+    /// true answer counts as sending one now.
+    pub fn partial_due(&mut self) -> bool {
+        let now = self.clock.now();
+        if now.saturating_duration_since(self.last_partial) < self.partial_interval {
+            return false;
+        }
+        self.last_partial = now;
+        true
+    }
+}
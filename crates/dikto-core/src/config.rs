@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
 use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
 use tracing::warn;
 
 #[derive(Debug, thiserror::Error)]
@@ -22,6 +25,670 @@ pub enum ActivationMode {
     Hold,
 }
 
+/// Battery-aware operation. In low-power mode the pipeline polls less often,
+/// records with `low_power_model` when set, and hosts should skip warm-loading.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, uniffi::Enum)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum LowPowerMode {
+    /// Low-power while running on battery.
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+/// How each utterance's loudness is evened out before inference (see
+/// `level`). Quiet recordings transcribe worse than loud ones.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, uniffi::Enum)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum InputNormalization {
+    #[default]
+    Off,
+    /// Boost the loudest sample to -1 dBFS.
+    Peak,
+    /// Boost the overall level to -20 dBFS, without clipping peaks.
+    Rms,
+}
+
+/// Advanced pipeline tuning. Most users never need to touch these.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct AdvancedConfig {
+    /// Longest the pipeline waits for new audio before re-checking stop
+    /// conditions, in ms. The audio thread wakes the pipeline as soon as
+    /// samples arrive, so this only bounds idle wakeups.
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u32,
+    /// Minimum interval between "Recording..." partial updates, in ms.
+    #[serde(default = "default_partial_interval_ms")]
+    pub partial_interval_ms: u32,
+    /// Save each session's audio and per-chunk VAD decisions to
+    /// `<data_dir>/vad-debug/` for tuning with `dikto vad-replay`.
+    #[serde(default)]
+    pub vad_debug: bool,
+    /// Whisper beam search width. Above 1, each utterance is decoded with
+    /// beam search and up to `beam_size - 1` alternative readings are
+    /// offered, at the cost of one extra decoding pass each. 0 decodes
+    /// greedily.
+    #[serde(default)]
+    pub beam_size: u32,
+    /// Drop a Whisper segment whose no-speech probability is above this,
+    /// since it was most likely hallucinated from silence or noise
+    /// ("Thank you for watching."). 1.0 keeps every segment.
+    #[serde(default = "default_no_speech_threshold")]
+    pub no_speech_threshold: f32,
+    /// Bring quiet utterances up to a target level before inference.
+    #[serde(default)]
+    pub input_normalization: InputNormalization,
+    /// Leave an utterance alone when its loud and quiet parts differ by less
+    /// than this many dB, so a recording of only background noise isn't
+    /// amplified.
+    #[serde(default = "default_normalization_min_range_db")]
+    pub normalization_min_range_db: f32,
+    /// Give up on a session whose pipeline makes no progress for this many
+    /// seconds, so a hung or crashed session can't block new ones. 0
+    /// disables the timeout; a pipeline thread that exits without
+    /// finishing is always caught.
+    #[serde(default = "default_watchdog_timeout_secs")]
+    pub watchdog_timeout_secs: u32,
+    /// Shortest time between the starts of two sessions, in ms. Faster
+    /// starts fail with `DiktoError::RateLimited`.
+    #[serde(default = "default_min_session_interval_ms")]
+    pub min_session_interval_ms: u32,
+    /// Most sessions that may start in one minute. 0 allows any number.
+    #[serde(default = "default_max_sessions_per_minute")]
+    pub max_sessions_per_minute: u32,
+}
+
+fn default_poll_interval_ms() -> u32 {
+    50
+}
+
+fn default_partial_interval_ms() -> u32 {
+    500
+}
+
+fn default_no_speech_threshold() -> f32 {
+    0.6
+}
+
+fn default_normalization_min_range_db() -> f32 {
+    12.0
+}
+
+fn default_watchdog_timeout_secs() -> u32 {
+    300
+}
+
+fn default_min_session_interval_ms() -> u32 {
+    250
+}
+
+fn default_max_sessions_per_minute() -> u32 {
+    30
+}
+
+impl Default for AdvancedConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: default_poll_interval_ms(),
+            partial_interval_ms: default_partial_interval_ms(),
+            vad_debug: false,
+            beam_size: 0,
+            no_speech_threshold: default_no_speech_threshold(),
+            input_normalization: InputNormalization::Off,
+            normalization_min_range_db: default_normalization_min_range_db(),
+            watchdog_timeout_secs: default_watchdog_timeout_secs(),
+            min_session_interval_ms: default_min_session_interval_ms(),
+            max_sessions_per_minute: default_max_sessions_per_minute(),
+        }
+    }
+}
+
+impl AdvancedConfig {
+    /// Clamp timing fields to safe ranges.
+    pub fn validate(&mut self) {
+        self.poll_interval_ms = self.poll_interval_ms.clamp(1, 200);
+        self.partial_interval_ms = self.partial_interval_ms.clamp(50, 5000);
+        self.beam_size = self.beam_size.min(8);
+        self.no_speech_threshold = self.no_speech_threshold.clamp(0.0, 1.0);
+        self.normalization_min_range_db = self.normalization_min_range_db.clamp(0.0, 60.0);
+        if self.watchdog_timeout_secs != 0 {
+            self.watchdog_timeout_secs = self.watchdog_timeout_secs.clamp(30, 3600);
+        }
+        self.min_session_interval_ms = self.min_session_interval_ms.min(10_000);
+    }
+}
+
+/// Transcript post-processing settings (see `textproc`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct TextProcConfig {
+    /// Run the post-processing pipeline on final transcripts.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Replace spoken punctuation words ("comma", "new line") with marks.
+    #[serde(default)]
+    pub spoken_punctuation: bool,
+    /// Convert spoken math ("x squared over two") into LaTeX instead of prose.
+    #[serde(default)]
+    pub math_mode: bool,
+    /// Spell every utterance letter by letter ("alpha bravo seven" → "ab7").
+    #[serde(default)]
+    pub spelling_mode: bool,
+    /// Spell a single utterance when it starts with "spell out".
+    #[serde(default = "default_true")]
+    pub spelling_command: bool,
+    /// Per-language overrides of the built-in rules, keyed by language code.
+    #[serde(default)]
+    pub language_overrides: HashMap<String, LanguageRuleOverride>,
+    /// Pause between continuous-mode utterances, in ms, after which the
+    /// previous one is closed with a period. 0 disables.
+    #[serde(default = "default_sentence_break_ms")]
+    pub sentence_break_ms: u32,
+    /// Pause after which the next utterance starts a new paragraph. 0 disables.
+    #[serde(default = "default_paragraph_break_ms")]
+    pub paragraph_break_ms: u32,
+    /// Apply the language's prose rules: sentence case and punctuation
+    /// spacing. Off by default, since recognizers already case their text.
+    #[serde(default)]
+    pub prose_rules: bool,
+    /// With `prose_rules`, group long numbers with the language's thousands
+    /// separator ("1234567" → "1,234,567"). Off by default: ZIP codes,
+    /// phone numbers, and IDs look like numbers too.
+    #[serde(default)]
+    pub group_numbers: bool,
+}
+
+fn default_sentence_break_ms() -> u32 {
+    2000
+}
+
+fn default_paragraph_break_ms() -> u32 {
+    4000
+}
+
+impl Default for TextProcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            spoken_punctuation: false,
+            math_mode: false,
+            spelling_mode: false,
+            spelling_command: true,
+            language_overrides: HashMap::new(),
+            sentence_break_ms: default_sentence_break_ms(),
+            paragraph_break_ms: default_paragraph_break_ms(),
+            prose_rules: false,
+            group_numbers: false,
+        }
+    }
+}
+
+impl TextProcConfig {
+    /// These settings as changed by a session's preset.
+    pub fn with_preset(&self, preset: TextProcPreset) -> Self {
+        let prose = Self {
+            enabled: true,
+            math_mode: false,
+            spelling_mode: false,
+            prose_rules: true,
+            ..self.clone()
+        };
+        match preset {
+            TextProcPreset::Config => self.clone(),
+            TextProcPreset::Raw => Self {
+                enabled: false,
+                ..self.clone()
+            },
+            TextProcPreset::Prose => prose,
+            TextProcPreset::Code => Self {
+                spoken_punctuation: true,
+                prose_rules: false,
+                sentence_break_ms: 0,
+                paragraph_break_ms: 0,
+                ..prose
+            },
+        }
+    }
+}
+
+/// Post-processing for one session, chosen by the caller (see
+/// `ListenConfig::text_preset`) instead of the user's `text_processing`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, uniffi::Enum)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum TextProcPreset {
+    /// Whatever `text_processing` says.
+    #[default]
+    Config,
+    /// The recognizer's text untouched, with no breaks added between
+    /// utterances.
+    Raw,
+    /// Prose rules for the language, without math or spelling mode.
+    Prose,
+    /// Spoken punctuation only, so symbols can be dictated without
+    /// identifiers being capitalized or numbers grouped.
+    Code,
+}
+
+impl std::str::FromStr for TextProcPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "config" => Ok(Self::Config),
+            "raw" => Ok(Self::Raw),
+            "prose" => Ok(Self::Prose),
+            "code" => Ok(Self::Code),
+            _ => Err(format!(
+                "Unknown text preset '{s}' (expected config, raw, prose, or code)"
+            )),
+        }
+    }
+}
+
+/// How hosts deliver a finished transcript to the focused app.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct OutputConfig {
+    /// Wait between putting the text on the clipboard and sending the paste
+    /// keystroke, in ms. Some apps (Electron editors) miss the paste when
+    /// it arrives too soon.
+    #[serde(default = "default_paste_delay_ms")]
+    pub paste_delay_ms: u32,
+    /// Type the text as keystrokes instead of pasting it, leaving the
+    /// clipboard untouched. For apps that block paste.
+    #[serde(default)]
+    pub typing_mode: bool,
+    /// Pause between typed characters in typing mode, in ms, so slow apps
+    /// receive every character.
+    #[serde(default = "default_keystroke_delay_ms")]
+    pub keystroke_delay_ms: u32,
+    /// End every transcript with a space, ready for the next dictation.
+    #[serde(default)]
+    pub append_space: bool,
+    /// End every transcript with a line break.
+    #[serde(default)]
+    pub append_newline: bool,
+    /// Insert a space before the transcript when the text before the caret
+    /// ends in a word or punctuation. Needs the host to report that text.
+    #[serde(default = "default_true")]
+    pub smart_spacing: bool,
+    /// Capitalize the first word after a sentence end before the caret and
+    /// lowercase it mid-sentence. Needs the host to report that text.
+    #[serde(default = "default_true")]
+    pub smart_capitalization: bool,
+    /// How the transcript is put on the clipboard.
+    #[serde(default)]
+    pub clipboard_format: ClipboardFormat,
+    /// Formatting for specific apps, first match wins (see `output`).
+    #[serde(default)]
+    pub app_profiles: Vec<AppProfile>,
+    /// Which text each sink gets, e.g. the full transcript on the clipboard
+    /// and its summary pasted.
+    #[serde(default)]
+    pub routes: OutputRoutes,
+    /// In continuous mode, paste (or type) each final segment as it is
+    /// committed, a few words at a time, instead of everything at the end.
+    #[serde(default)]
+    pub incremental_paste: bool,
+    /// Words per chunk in incremental paste.
+    #[serde(default = "default_paste_chunk_words")]
+    pub paste_chunk_words: u32,
+    /// In Chinese and Japanese, turn ASCII punctuation into its full-width
+    /// form ("," → "，"). Spaces between CJK characters are dropped either way.
+    #[serde(default = "default_true")]
+    pub fullwidth_punctuation: bool,
+    /// In right-to-left languages, wrap the text in right-to-left marks so
+    /// punctuation at its ends stays put when pasted into a left-to-right
+    /// field.
+    #[serde(default = "default_true")]
+    pub rtl_marks: bool,
+    /// Default for `ListenConfig::dry_run`: run sessions as usual but skip
+    /// every output. Nothing is pasted, copied, saved, summarized, counted
+    /// in usage, or appended to the tasks file. What would have been done
+    /// is reported with `on_dry_run`, for trying out settings.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Clipboard representation of a transcript. Plain text is always offered
+/// alongside, for apps that don't take the richer one.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, uniffi::Enum)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum ClipboardFormat {
+    #[default]
+    Plain,
+    /// Paragraphs (from long pauses) separated by blank lines, with
+    /// characters that would start a heading or list escaped.
+    Markdown,
+    /// Rich Text Format, one `\par` per line.
+    Rtf,
+}
+
+/// A version of a finished transcript that a sink can take.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, uniffi::Enum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputSource {
+    /// The post-processed transcript.
+    #[default]
+    Transcript,
+    /// The session's summary (see `summary`), or the transcript when there
+    /// is none.
+    Summary,
+}
+
+/// What each sink receives. The default sends the transcript everywhere.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct OutputRoutes {
+    #[serde(default)]
+    pub clipboard: OutputSource,
+    #[serde(default)]
+    pub paste: OutputSource,
+    /// Also keep the recognizer's words, before text processing and
+    /// translation, in each history entry.
+    #[serde(default)]
+    pub history_raw: bool,
+}
+
+/// How transcripts are formatted and delivered in one app.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct AppProfile {
+    /// Bundle identifier ("com.tinyspeck.slackmacgap") or app name ("Slack"),
+    /// matched without regard to case.
+    pub app: String,
+    /// Overrides the global `auto_paste`; None keeps it.
+    #[serde(default)]
+    pub auto_paste: Option<bool>,
+    /// Join lines into one so chat apps don't send on each line break.
+    #[serde(default)]
+    pub plain_text: bool,
+    /// Drop sentence punctuation, for search boxes and terminals (see
+    /// `output::strip_punctuation`).
+    #[serde(default)]
+    pub strip_punctuation: bool,
+    /// Lowercase the whole text, undoing sentence case and capitalization.
+    #[serde(default)]
+    pub lowercase: bool,
+    #[serde(default)]
+    pub trailing_newline: bool,
+    /// Start the text with the local date and time ("2024-03-09 14:05 ").
+    #[serde(default)]
+    pub timestamp_prefix: bool,
+    /// Overrides the global `clipboard_format`; None keeps it.
+    #[serde(default)]
+    pub clipboard_format: Option<ClipboardFormat>,
+    /// Overrides `translation.target_language` for sessions started in this
+    /// app; an empty string turns translation off here. None keeps it.
+    #[serde(default)]
+    pub translate_to: Option<String>,
+}
+
+/// Translation of final segments before output (see `translate`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct TranslationConfig {
+    /// ISO-639-1 code to translate into. Empty turns translation off.
+    #[serde(default)]
+    pub target_language: String,
+    #[serde(default)]
+    pub engine: TranslationEngine,
+    /// Marian or NLLB ONNX export: a directory name under
+    /// `<models_dir>/translation/`, or an absolute path.
+    #[serde(default)]
+    pub model: String,
+    /// LibreTranslate-compatible endpoint for `TranslationEngine::Api`.
+    /// Transcripts are sent there, unlike anything else Dikto does.
+    #[serde(default)]
+    pub api_url: String,
+    #[serde(default)]
+    pub api_key: String,
+}
+
+/// Summaries of long transcripts (see `summarize`). Off until a command or
+/// API is set.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct SummaryConfig {
+    /// Summarize transcripts of at least this many words.
+    #[serde(default = "default_summary_min_words")]
+    pub min_words: u32,
+    /// Shell command that reads the transcript on stdin and prints the
+    /// summary, e.g. a local LLM runner.
+    #[serde(default)]
+    pub command: String,
+    /// HTTP endpoint sent `{"text": ...}` that answers `{"summary": ...}`,
+    /// used when `command` is empty. Transcripts are sent there.
+    #[serde(default)]
+    pub api_url: String,
+    /// Sent as a bearer token to `api_url`.
+    #[serde(default)]
+    pub api_key: String,
+    /// Longest to wait for a summary before delivering without one.
+    #[serde(default = "default_summary_timeout_secs")]
+    pub timeout_secs: u32,
+}
+
+fn default_summary_min_words() -> u32 {
+    300
+}
+
+fn default_summary_timeout_secs() -> u32 {
+    60
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            min_words: default_summary_min_words(),
+            command: String::new(),
+            api_url: String::new(),
+            api_key: String::new(),
+            timeout_secs: default_summary_timeout_secs(),
+        }
+    }
+}
+
+impl SummaryConfig {
+    /// Whether a summarizer is configured.
+    pub fn is_enabled(&self) -> bool {
+        !self.command.trim().is_empty() || !self.api_url.trim().is_empty()
+    }
+
+    /// Clamp the timeout to a safe range.
+    pub fn validate(&mut self) {
+        self.timeout_secs = self.timeout_secs.clamp(1, 600);
+    }
+}
+
+/// Extraction of spoken tasks ("remind me to ...") from transcripts (see
+/// `actions`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct ActionItemsConfig {
+    /// List the tasks in `TranscriptionResult::action_items`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Markdown file the tasks are appended to as "- [ ]" lines: an
+    /// absolute path, or a name under the data directory. Empty keeps them
+    /// in the result only.
+    #[serde(default)]
+    pub tasks_file: String,
+}
+
+/// Masking sensitive words in transcripts (see `redact`). Off until
+/// keywords are set.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct RedactionConfig {
+    /// Words or phrases to mask, matched as whole words ignoring case.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// What each match is replaced with.
+    #[serde(default = "default_redaction_mask")]
+    pub mask: String,
+}
+
+fn default_redaction_mask() -> String {
+    "[redacted]".to_string()
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            keywords: Vec::new(),
+            mask: default_redaction_mask(),
+        }
+    }
+}
+
+/// When hotkey presses are refused instead of starting a session (see
+/// `do_not_disturb`). Off by default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct DoNotDisturbConfig {
+    /// Local time windows as "HH:MM-HH:MM", e.g. "22:00-07:00".
+    #[serde(default)]
+    pub windows: Vec<String>,
+    /// Bundle identifiers or app names, matched without regard to case, in
+    /// front of which sessions don't start (e.g. "Keynote").
+    #[serde(default)]
+    pub apps: Vec<String>,
+    /// Refuse while the host reports screen sharing
+    /// (`DiktoEngine::set_screen_sharing`).
+    #[serde(default)]
+    pub while_screen_sharing: bool,
+}
+
+impl DoNotDisturbConfig {
+    /// Drop windows that aren't "HH:MM-HH:MM".
+    pub fn validate(&mut self) {
+        self.windows.retain(|w| {
+            let valid = crate::do_not_disturb::parse_window(w).is_some();
+            if !valid {
+                warn!("Invalid do-not-disturb window '{w}', ignoring it");
+            }
+            valid
+        });
+    }
+}
+
+/// Saving finished sessions to files (see `sessions`). Off by default.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct SavedSessionsConfig {
+    /// Write each transcript to a Markdown file.
+    #[serde(default)]
+    pub save_transcripts: bool,
+    /// Write each session's audio to a WAV file next to the transcript.
+    #[serde(default)]
+    pub save_audio: bool,
+    /// Folder the files go in: an absolute path, or a name under the data
+    /// directory.
+    #[serde(default = "default_sessions_dir")]
+    pub dir: String,
+    /// File name without extension. `{date}`, `{time}`, `{year}`,
+    /// `{month}`, `{app}` and `{first_words}` are filled in.
+    #[serde(default = "default_session_file_name_template")]
+    pub file_name_template: String,
+    /// Put each month's sessions in a YYYY-MM subfolder.
+    #[serde(default = "default_true")]
+    pub folder_by_month: bool,
+}
+
+fn default_sessions_dir() -> String {
+    "sessions".to_string()
+}
+
+fn default_session_file_name_template() -> String {
+    "{date}-{time}-{first_words}".to_string()
+}
+
+impl Default for SavedSessionsConfig {
+    fn default() -> Self {
+        Self {
+            save_transcripts: false,
+            save_audio: false,
+            dir: default_sessions_dir(),
+            file_name_template: default_session_file_name_template(),
+            folder_by_month: true,
+        }
+    }
+}
+
+impl SavedSessionsConfig {
+    /// Whether anything is saved.
+    pub fn is_enabled(&self) -> bool {
+        self.save_transcripts || self.save_audio
+    }
+}
+
+/// Where translation runs.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, uniffi::Enum)]
+#[serde(rename_all = "lowercase")]
+#[derive(Default)]
+pub enum TranslationEngine {
+    /// An ONNX model on this machine.
+    #[default]
+    Local,
+    /// The HTTP service at `api_url`.
+    Api,
+}
+
+fn default_paste_delay_ms() -> u32 {
+    200
+}
+
+fn default_keystroke_delay_ms() -> u32 {
+    5
+}
+
+fn default_paste_chunk_words() -> u32 {
+    8
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            paste_delay_ms: default_paste_delay_ms(),
+            typing_mode: false,
+            keystroke_delay_ms: default_keystroke_delay_ms(),
+            append_space: false,
+            append_newline: false,
+            smart_spacing: true,
+            smart_capitalization: true,
+            clipboard_format: ClipboardFormat::Plain,
+            app_profiles: Vec::new(),
+            routes: OutputRoutes::default(),
+            incremental_paste: false,
+            paste_chunk_words: default_paste_chunk_words(),
+            fullwidth_punctuation: true,
+            rtl_marks: true,
+            dry_run: false,
+        }
+    }
+}
+
+impl OutputConfig {
+    /// Clamp delays to safe ranges.
+    pub fn validate(&mut self) {
+        self.paste_delay_ms = self.paste_delay_ms.min(2000);
+        self.keystroke_delay_ms = self.keystroke_delay_ms.min(200);
+        self.paste_chunk_words = self.paste_chunk_words.clamp(1, 64);
+    }
+}
+
+/// User overrides for one language's post-processing rules. Unset fields
+/// keep the built-in behavior.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct LanguageRuleOverride {
+    /// Extra spoken phrases → punctuation. An empty value removes a built-in phrase.
+    #[serde(default)]
+    pub spoken_punctuation: HashMap<String, String>,
+    #[serde(default)]
+    pub capitalize_sentences: Option<bool>,
+    /// Thousands separator for long numbers ("" disables grouping).
+    #[serde(default)]
+    pub thousands_separator: Option<String>,
+    #[serde(default)]
+    pub space_before_punctuation: Option<bool>,
+}
+
 /// Valid modifier names for shortcut strings.
 const VALID_MODIFIERS: &[&str] = &["option", "command", "control", "shift"];
 
@@ -49,6 +716,14 @@ pub struct DiktoConfig {
     pub language: String,
     #[serde(default = "default_max_duration")]
     pub max_duration: u32,
+    /// Count down with `on_time_remaining` over the last this many seconds
+    /// before `max_duration` ends a session. 0 disables.
+    #[serde(default = "default_time_warning_secs")]
+    pub time_warning_secs: u32,
+    /// Let `max_duration` run only during silence: while the user keeps
+    /// talking the deadline moves out, up to the `max_duration` ceiling.
+    #[serde(default)]
+    pub extend_while_speaking: bool,
     #[serde(default = "default_silence_duration_ms")]
     pub silence_duration_ms: u32,
     #[serde(default = "default_speech_threshold")]
@@ -61,6 +736,78 @@ pub struct DiktoConfig {
     pub auto_copy: bool,
     #[serde(default)]
     pub activation_mode: ActivationMode,
+    /// Keep listening after each utterance until stopped, emitting one final
+    /// segment per utterance (meetings, long-form dictation).
+    #[serde(default)]
+    pub continuous_mode: bool,
+    /// Allow sessions up to an hour (meetings, lectures) by raising the
+    /// max_duration ceiling from 120s to 3600s. Long sessions are
+    /// transcribed in chunks so memory stays bounded.
+    #[serde(default)]
+    pub long_form: bool,
+    /// Transcribe and emit the speech so far whenever this many seconds have
+    /// been buffered, instead of waiting for a pause. 0 disables.
+    #[serde(default = "default_flush_threshold_secs")]
+    pub flush_threshold_secs: u32,
+    /// Keep a local transcript history (history.jsonl) for stats and lexicon.
+    /// Off unless the user opts in, since it stores the full text of every
+    /// dictation.
+    #[serde(default)]
+    pub save_history: bool,
+    /// Bias recognition toward recurring names and jargon learned from
+    /// history and corrections (Whisper only; requires save_history).
+    #[serde(default)]
+    pub personal_lexicon: bool,
+    #[serde(default)]
+    pub low_power_mode: LowPowerMode,
+    /// Smaller model to prefer while in low-power mode (used only if downloaded).
+    #[serde(default)]
+    pub low_power_model: Option<String>,
+    /// Whisper model file to load for `model_name` instead of the registry
+    /// copy, e.g. one managed by LM Studio or a whisper.cpp install: an
+    /// absolute path (symlinks are followed), a directory holding a
+    /// `ggml-*.bin`/`.gguf` file, or a file name inside the model's folder.
+    #[serde(default)]
+    pub model_path_override: Option<String>,
+    /// Reuse registry model files already in the Hugging Face hub cache
+    /// instead of downloading them again.
+    #[serde(default = "default_true")]
+    pub huggingface_cache: bool,
+    /// Model in use before the last switch, restored if the new model fails
+    /// its first load. Cleared once the new model loads.
+    #[serde(default)]
+    pub previous_model: Option<String>,
+    /// Folder for history, usage, saved sessions, and models instead of the
+    /// default data directory, e.g. to move them out of (or into) a cloud
+    /// drive. Must be an absolute path.
+    #[serde(default)]
+    pub data_dir: Option<String>,
+    /// Locale for text the engine writes itself, such as the "Loading
+    /// model..." partial, e.g. "de-DE". Empty means English.
+    #[serde(default)]
+    pub locale: String,
+    /// Starting a session while the previous one is still transcribing its
+    /// last words waits for it to finish instead of failing.
+    #[serde(default)]
+    pub queue_sessions: bool,
+    #[serde(default)]
+    pub advanced: AdvancedConfig,
+    #[serde(default)]
+    pub text_processing: TextProcConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub translation: TranslationConfig,
+    #[serde(default)]
+    pub summary: SummaryConfig,
+    #[serde(default)]
+    pub action_items: ActionItemsConfig,
+    #[serde(default)]
+    pub saved_sessions: SavedSessionsConfig,
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+    #[serde(default)]
+    pub do_not_disturb: DoNotDisturbConfig,
 }
 
 pub fn default_model_name() -> String {
@@ -71,10 +818,24 @@ fn default_language() -> String {
     "en".to_string()
 }
 
+/// Longest allowed max_duration, in seconds.
+pub const MAX_DURATION_SECS: u32 = 120;
+
+/// Longest allowed max_duration with `long_form` on, in seconds.
+pub const LONG_FORM_MAX_DURATION_SECS: u32 = 3600;
+
 fn default_max_duration() -> u32 {
     30
 }
 
+fn default_time_warning_secs() -> u32 {
+    5
+}
+
+fn default_flush_threshold_secs() -> u32 {
+    30
+}
+
 fn default_silence_duration_ms() -> u32 {
     1500
 }
@@ -97,22 +858,71 @@ impl Default for DiktoConfig {
             model_name: default_model_name(),
             language: default_language(),
             max_duration: default_max_duration(),
+            time_warning_secs: default_time_warning_secs(),
+            extend_while_speaking: false,
             silence_duration_ms: default_silence_duration_ms(),
             speech_threshold: default_speech_threshold(),
             global_shortcut: default_global_shortcut(),
             auto_paste: true,
             auto_copy: true,
             activation_mode: ActivationMode::Hold,
+            continuous_mode: false,
+            long_form: false,
+            flush_threshold_secs: default_flush_threshold_secs(),
+            save_history: false,
+            personal_lexicon: false,
+            low_power_mode: LowPowerMode::Auto,
+            low_power_model: None,
+            model_path_override: None,
+            huggingface_cache: true,
+            previous_model: None,
+            data_dir: None,
+            locale: String::new(),
+            queue_sessions: false,
+            advanced: AdvancedConfig::default(),
+            text_processing: TextProcConfig::default(),
+            output: OutputConfig::default(),
+            translation: TranslationConfig::default(),
+            summary: SummaryConfig::default(),
+            action_items: ActionItemsConfig::default(),
+            saved_sessions: SavedSessionsConfig::default(),
+            redaction: RedactionConfig::default(),
+            do_not_disturb: DoNotDisturbConfig::default(),
         }
     }
 }
 
 impl DiktoConfig {
+    /// Undo a model switch after the new model failed to load. Returns the
+    /// restored model, or None when there is nothing to go back to.
+    pub fn roll_back_model(&mut self) -> Option<String> {
+        let previous = self.previous_model.take()?;
+        if previous == self.model_name {
+            return None;
+        }
+        self.model_name = previous.clone();
+        self.model_path_override = None;
+        Some(previous)
+    }
+
     /// Clamp all numeric fields to safe ranges and validate shortcut and language.
     pub fn validate(&mut self) {
-        self.max_duration = self.max_duration.clamp(1, 120);
+        let ceiling = if self.long_form {
+            LONG_FORM_MAX_DURATION_SECS
+        } else {
+            MAX_DURATION_SECS
+        };
+        self.max_duration = self.max_duration.clamp(1, ceiling);
+        self.time_warning_secs = self.time_warning_secs.min(60);
+        if self.flush_threshold_secs != 0 {
+            self.flush_threshold_secs = self.flush_threshold_secs.clamp(5, 240);
+        }
         self.silence_duration_ms = self.silence_duration_ms.clamp(250, 10000);
         self.speech_threshold = self.speech_threshold.clamp(0.01, 0.99);
+        self.advanced.validate();
+        self.output.validate();
+        self.summary.validate();
+        self.do_not_disturb.validate();
 
         // Validate language code: must be 2-4 lowercase letters or "auto"
         if self.language != "auto" {
@@ -142,22 +952,203 @@ impl DiktoConfig {
     }
 }
 
-/// Returns the config directory path: ~/.config/dikto/
+/// A combination of settings that can't all take effect, as found by
+/// `DiktoConfig::lint`.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct ConfigWarning {
+    /// Dotted path of the setting that is ignored or has no effect, e.g.
+    /// "output.incremental_paste".
+    pub field: String,
+    pub message: String,
+}
+
+impl ConfigWarning {
+    fn new(field: &str, message: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl DiktoConfig {
+    /// Settings that conflict with each other. Unlike `validate`, nothing is
+    /// changed: each value is valid alone, but one of them won't do what
+    /// the user expects.
+    pub fn lint(&self) -> Vec<ConfigWarning> {
+        let mut warnings = Vec::new();
+        if self.continuous_mode && self.activation_mode == ActivationMode::Hold {
+            warnings.push(ConfigWarning::new(
+                "continuous_mode",
+                "continuous sessions end when the hold shortcut is released; \
+                 use toggle activation",
+            ));
+        }
+        if self.extend_while_speaking && self.continuous_mode {
+            warnings.push(ConfigWarning::new(
+                "extend_while_speaking",
+                "continuous sessions have no max_duration to extend",
+            ));
+        }
+        if self.time_warning_secs > 0 && self.time_warning_secs >= self.max_duration {
+            warnings.push(ConfigWarning::new(
+                "time_warning_secs",
+                "the countdown starts with the session; lower it below max_duration",
+            ));
+        }
+        if self.personal_lexicon && !self.save_history {
+            warnings.push(ConfigWarning::new(
+                "personal_lexicon",
+                "the lexicon is learned from history, which save_history turns off",
+            ));
+        }
+        let target = self.translation.target_language.trim();
+        if !target.is_empty() && self.language != "auto" && target == self.language {
+            warnings.push(ConfigWarning::new(
+                "translation.target_language",
+                "same as language, so there is nothing to translate",
+            ));
+        }
+        if self.output.incremental_paste && !self.continuous_mode {
+            warnings.push(ConfigWarning::new(
+                "output.incremental_paste",
+                "segments are only pasted as they come in continuous mode",
+            ));
+        }
+        if self.output.incremental_paste && !self.auto_paste {
+            warnings.push(ConfigWarning::new(
+                "output.incremental_paste",
+                "auto_paste is off, so nothing is pasted",
+            ));
+        }
+        if self.output.append_space && self.output.append_newline {
+            warnings.push(ConfigWarning::new(
+                "output.append_space",
+                "append_newline is also on; the transcript ends in both",
+            ));
+        }
+        if !self.summary.is_enabled() {
+            if self.output.routes.paste == OutputSource::Summary {
+                warnings.push(ConfigWarning::new(
+                    "output.routes.paste",
+                    "no summarizer is set up, so the transcript is pasted",
+                ));
+            }
+            if self.output.routes.clipboard == OutputSource::Summary {
+                warnings.push(ConfigWarning::new(
+                    "output.routes.clipboard",
+                    "no summarizer is set up, so the transcript is copied",
+                ));
+            }
+        }
+        if self.output.routes.history_raw && !self.save_history {
+            warnings.push(ConfigWarning::new(
+                "output.routes.history_raw",
+                "save_history is off, so there is no history to keep it in",
+            ));
+        }
+        warnings
+    }
+}
+
+/// Directories dikto reads and writes. Sandboxed hosts (Mac App Store
+/// builds) implement this to point at their container or App Group
+/// directories, since `~/.config` and `~/.local/share` are off-limits there.
+#[uniffi::export(with_foreign)]
+pub trait PathProvider: Send + Sync {
+    /// Directory holding config.json.
+    fn config_dir(&self) -> String;
+    /// Directory for history, usage, lexicon, and corrections files.
+    fn data_dir(&self) -> String;
+    /// Directory for downloaded models. None means `<data_dir>/models`.
+    fn models_dir(&self) -> Option<String>;
+}
+
+static PATH_PROVIDER: RwLock<Option<Arc<dyn PathProvider>>> = RwLock::new(None);
+
+static DATA_DIR_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Install the provider used by every path function below, or `None` to go
+/// back to the home-directory defaults. Must be called before the engine
+/// loads its config.
+pub fn set_path_provider(provider: Option<Arc<dyn PathProvider>>) {
+    match PATH_PROVIDER.write() {
+        Ok(mut guard) => *guard = provider,
+        Err(e) => warn!("Path provider lock poisoned: {e}"),
+    }
+}
+
+fn path_provider() -> Option<Arc<dyn PathProvider>> {
+    PATH_PROVIDER.read().ok().and_then(|guard| guard.clone())
+}
+
+/// Move the data directory to `dir` (from `DiktoConfig::data_dir`), ahead
+/// of any PathProvider. None, empty, or relative paths go back to the
+/// usual location.
+pub fn set_data_dir_override(dir: Option<&str>) {
+    let dir = dir
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .filter(|d| {
+            let absolute = d.is_absolute();
+            if !absolute {
+                warn!("Ignoring relative data_dir '{}'", d.display());
+            }
+            absolute
+        });
+    match DATA_DIR_OVERRIDE.write() {
+        Ok(mut guard) => *guard = dir,
+        Err(e) => warn!("Data directory lock poisoned: {e}"),
+    }
+}
+
+/// Returns the config directory path: ~/.config/dikto/ (%APPDATA%\dikto
+/// on Windows) unless a PathProvider is installed.
 pub fn config_dir() -> Result<PathBuf, ConfigError> {
-    Ok(dirs::home_dir()
-        .ok_or(ConfigError::NoHomeDir)?
-        .join(".config/dikto"))
+    if let Some(provider) = path_provider() {
+        return Ok(PathBuf::from(provider.config_dir()));
+    }
+    #[cfg(windows)]
+    let dir = dirs::config_dir().map(|d| d.join("dikto"));
+    #[cfg(not(windows))]
+    let dir = dirs::home_dir().map(|h| h.join(".config/dikto"));
+    dir.ok_or(ConfigError::NoHomeDir)
 }
 
 /// Returns the data directory path: ~/.local/share/dikto/
+/// (%LOCALAPPDATA%\dikto on Windows) unless it was moved with
+/// `set_data_dir_override` or a PathProvider is installed.
 pub fn data_dir() -> Result<PathBuf, ConfigError> {
-    Ok(dirs::home_dir()
-        .ok_or(ConfigError::NoHomeDir)?
-        .join(".local/share/dikto"))
+    if let Some(dir) = DATA_DIR_OVERRIDE
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+    {
+        return Ok(dir);
+    }
+    if let Some(provider) = path_provider() {
+        return Ok(PathBuf::from(provider.data_dir()));
+    }
+    // Local rather than roaming: models and history are too big to roam
+    #[cfg(windows)]
+    let dir = dirs::data_local_dir().map(|d| d.join("dikto"));
+    #[cfg(not(windows))]
+    let dir = dirs::home_dir().map(|h| h.join(".local/share/dikto"));
+    dir.ok_or(ConfigError::NoHomeDir)
 }
 
 /// Returns the models directory path: ~/.local/share/dikto/models/
 pub fn models_dir() -> PathBuf {
+    if let Some(dir) = path_provider().and_then(|p| p.models_dir()) {
+        return PathBuf::from(dir);
+    }
     match data_dir() {
         Ok(d) => d.join("models"),
         Err(e) => {
@@ -167,6 +1158,18 @@ pub fn models_dir() -> PathBuf {
     }
 }
 
+/// Returns the Hugging Face hub cache: $HF_HUB_CACHE, else $HF_HOME/hub,
+/// else ~/.cache/huggingface/hub.
+pub fn huggingface_cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("HF_HUB_CACHE") {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(home) = std::env::var_os("HF_HOME") {
+        return Some(PathBuf::from(home).join("hub"));
+    }
+    dirs::home_dir().map(|h| h.join(".cache/huggingface/hub"))
+}
+
 /// Returns the config file path: ~/.config/dikto/config.json
 pub fn config_path() -> Result<PathBuf, ConfigError> {
     Ok(config_dir()?.join("config.json"))
@@ -232,24 +1235,22 @@ pub fn load_config() -> DiktoConfig {
     config
 }
 
-/// Save config to disk. Values are validated (clamped) before saving.
-/// Sets file permissions to 0600 (user read/write only).
+/// Save config to disk atomically. Values are validated (clamped) before
+/// saving. Sets file permissions to 0600 (user read/write only).
 pub fn save_config(config: &DiktoConfig) -> Result<(), std::io::Error> {
     let mut config = config.clone();
     config.validate();
     let path = config_path().map_err(|e| std::io::Error::other(e.to_string()))?;
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
     let json = serde_json::to_string_pretty(&config).map_err(std::io::Error::other)?;
-    std::fs::write(&path, &json)?;
-
-    // Set file permissions to user read/write only (0600)
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
-    }
-
-    Ok(())
+    crate::storage::write_atomic_with(&path, |out| {
+        // Set file permissions to user read/write only (0600) before the
+        // file takes the config's place
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            out.get_ref()
+                .set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+        out.write_all(json.as_bytes())
+    })
 }
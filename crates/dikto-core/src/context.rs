@@ -0,0 +1,53 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::textproc::tail_graphemes;
+
+/// Longest initial prompt passed to Whisper, in characters. Whisper's prompt
+/// window is 224 tokens; ~4 characters per token leaves some headroom.
+pub const MAX_HINT_CHARS: usize = 800;
+
+/// Normalize a host-supplied context hint (nearby document text or app name)
+/// into a Whisper initial prompt.
+///
+/// Whitespace is collapsed and, if the hint is too long, only the tail is
+/// kept — the text nearest the cursor is the best predictor of what comes
+/// next. Returns None for an empty hint.
+pub fn normalize_hint(text: &str) -> Option<String> {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.is_empty() {
+        return None;
+    }
+    Some(tail_chars(&collapsed, MAX_HINT_CHARS))
+}
+
+/// Combine personal lexicon terms and the context hint into one prompt.
+/// Terms come first so the hint — the most recent context — sits closest to
+/// the audio; the hint is trimmed to fit the remaining budget.
+pub fn initial_prompt(hint: Option<&str>, terms: &[String]) -> Option<String> {
+    let glossary = terms.join(", ");
+    let glossary = tail_chars(&glossary, MAX_HINT_CHARS);
+    let budget = MAX_HINT_CHARS.saturating_sub(glossary.graphemes(true).count() + 2);
+    let hint = hint
+        .map(|h| tail_chars(h, budget))
+        .filter(|h| !h.is_empty());
+
+    match (glossary.is_empty(), hint) {
+        (true, None) => None,
+        (true, Some(hint)) => Some(hint),
+        (false, None) => Some(format!("{glossary}.")),
+        (false, Some(hint)) => Some(format!("{glossary}. {hint}")),
+    }
+}
+
+/// Keep at most `max` trailing characters, starting at a word boundary.
+fn tail_chars(text: &str, max: usize) -> String {
+    let tail = tail_graphemes(text, max);
+    if tail.len() == text.len() {
+        return text.to_string();
+    }
+    // Don't start mid-word
+    match tail.find(' ') {
+        Some(i) => tail[i + 1..].to_string(),
+        None => tail.to_string(),
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::config::{data_dir, ConfigError};
+use crate::storage;
+
+/// Longest phrase (in words) considered for a replacement rule.
+const MAX_RULE_WORDS: usize = 4;
+
+/// One user edit of dictated text, stored as a line of corrections.jsonl.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CorrectionPair {
+    /// Seconds since the Unix epoch when the correction was reported.
+    pub timestamp: u64,
+    pub original: String,
+    pub corrected: String,
+}
+
+/// A replacement rule suggested by repeated identical corrections.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct ReplacementSuggestion {
+    /// Phrase as the recognizer produced it.
+    pub from: String,
+    /// Phrase the user replaced it with.
+    pub to: String,
+    /// Number of corrections that made this exact change.
+    pub count: u32,
+}
+
+/// Returns the corrections file path: ~/.local/share/dikto/corrections.jsonl
+pub fn corrections_path() -> Result<PathBuf, ConfigError> {
+    Ok(data_dir()?.join("corrections.jsonl"))
+}
+
+/// Append a pair to a corrections file, creating it if needed.
+pub fn append_pair(path: &Path, pair: &CorrectionPair) -> Result<(), std::io::Error> {
+    let line = serde_json::to_string(pair).map_err(std::io::Error::other)?;
+    storage::append_locked(path, format!("{line}\n").as_bytes())
+}
+
+/// Load all pairs from a corrections file, oldest first. Corrupt lines are skipped.
+pub fn load_pairs(path: &Path) -> Vec<CorrectionPair> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(pair) => Some(pair),
+            Err(e) => {
+                warn!("Skipping corrupt correction line: {e}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Strip surrounding punctuation so "Okafor," and "Okafor" compare equal.
+fn trim_word(word: &str) -> &str {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+}
+
+/// The changed span of a correction: the words between the longest common
+/// prefix and suffix. Returns None when nothing changed or the span is too
+/// long to be a reusable rule (a rewrite rather than a fix).
+pub fn changed_span(original: &str, corrected: &str) -> Option<(String, String)> {
+    let a: Vec<&str> = original.split_whitespace().map(trim_word).collect();
+    let b: Vec<&str> = corrected.split_whitespace().map(trim_word).collect();
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let max_suffix = a.len().min(b.len()) - prefix;
+    let suffix = a
+        .iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take(max_suffix)
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    let from = &a[prefix..a.len() - suffix];
+    let to = &b[prefix..b.len() - suffix];
+    if from.is_empty() || to.is_empty() || from.len() > MAX_RULE_WORDS || to.len() > MAX_RULE_WORDS
+    {
+        return None;
+    }
+    Some((from.join(" "), to.join(" ")))
+}
+
+/// Suggest replacement rules for changes the user made at least `min_count`
+/// times, most frequent first.
+pub fn suggest_replacements(
+    pairs: &[CorrectionPair],
+    min_count: u32,
+) -> Vec<ReplacementSuggestion> {
+    let mut counts: HashMap<(String, String), u32> = HashMap::new();
+    for pair in pairs {
+        if let Some(span) = changed_span(&pair.original, &pair.corrected) {
+            *counts.entry(span).or_default() += 1;
+        }
+    }
+
+    let mut suggestions: Vec<ReplacementSuggestion> = counts
+        .into_iter()
+        .filter(|(_, count)| *count >= min_count)
+        .map(|((from, to), count)| ReplacementSuggestion { from, to, count })
+        .collect();
+    suggestions.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.from.cmp(&b.from)));
+    suggestions
+}
@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+/// When a non-continuous session has to end.
+#[derive(Debug, Clone)]
+pub struct SessionDeadline {
+    max_duration: Duration,
+    /// Hard limit when speech extends the deadline; None when it doesn't.
+    cap: Option<Duration>,
+    /// Speech heard so far, which doesn't count toward `max_duration`.
+    speech: Duration,
+}
+
+impl SessionDeadline {
+    /// A fixed deadline `max_duration` after the start.
+    pub fn new(max_duration: Duration) -> Self {
+        Self {
+            max_duration,
+            cap: None,
+            speech: Duration::ZERO,
+        }
+    }
+
+    /// A deadline that only counts down while the user is silent, so
+    /// someone talking past `max_duration` isn't cut off, ending `cap`
+    /// after the start at the latest.
+    pub fn extending(max_duration: Duration, cap: Duration) -> Self {
+        Self {
+            cap: Some(cap.max(max_duration)),
+            ..Self::new(max_duration)
+        }
+    }
+
+    /// Note `duration` of active speech. Ignored by a fixed deadline.
+    pub fn add_speech(&mut self, duration: Duration) {
+        if self.cap.is_some() {
+            self.speech += duration;
+        }
+    }
+
+    /// Time left `elapsed` after the start; zero once the session must end.
+    pub fn remaining(&self, elapsed: Duration) -> Duration {
+        let counted = elapsed.saturating_sub(self.speech);
+        let left = self.max_duration.saturating_sub(counted);
+        match self.cap {
+            Some(cap) => left.min(cap.saturating_sub(elapsed)),
+            None => left,
+        }
+    }
+}
+
+/// Counts down the last seconds before `max_duration` ends a session, so
+/// the host can warn the user instead of cutting them off mid-sentence.
+#[derive(Debug, Clone)]
+pub struct Countdown {
+    warn_secs: u32,
+    /// Last number reported, so each second is reported once.
+    reported: Option<u32>,
+}
+
+impl Countdown {
+    /// A countdown over the last `warn_secs` of a session (0 disables).
+    pub fn new(warn_secs: u32) -> Self {
+        Self {
+            warn_secs,
+            reported: None,
+        }
+    }
+
+    /// Whole seconds left (rounded up) once `remaining` is in the warning
+    /// window, the first time each number comes up.
+    pub fn tick(&mut self, remaining: Duration) -> Option<u32> {
+        if self.warn_secs == 0 || remaining.is_zero() {
+            return None;
+        }
+        let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        let secs = u32::try_from(secs).unwrap_or(u32::MAX);
+        if secs > self.warn_secs || self.reported == Some(secs) {
+            return None;
+        }
+        self.reported = Some(secs);
+        Some(secs)
+    }
+}
@@ -0,0 +1,175 @@
+use serde_json::{json, Value};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use crate::config::DiktoConfig;
+use crate::zip::ZipWriter;
+use crate::{history, usage, vad_debug, ModelFileState, ModelInfoRecord};
+
+/// How many VAD debug traces go into a bundle.
+pub const MAX_LOGS: usize = 3;
+
+/// Config keys whose values are dropped from bundles, matched as substrings.
+const SECRET_KEYS: [&str; 4] = ["token", "secret", "password", "api_key"];
+
+/// Strip personal details from JSON in place: values of secret-looking keys
+/// become "[redacted]" and paths under `home` start with "~" instead.
+pub fn redact(value: &mut Value, home: Option<&str>) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if SECRET_KEYS.iter().any(|s| key.contains(s)) && !v.is_null() {
+                    *v = Value::String("[redacted]".to_string());
+                } else {
+                    redact(v, home);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| redact(v, home)),
+        Value::String(s) => {
+            if let Some(home) = home.filter(|h| !h.is_empty()) {
+                if let Some(rest) = s.strip_prefix(home) {
+                    if rest.is_empty() || rest.starts_with('/') {
+                        *s = format!("~{rest}");
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The config as JSON, redacted. Redaction keywords are left out entirely,
+/// since they are the words the user wants kept private.
+pub fn config_json(config: &DiktoConfig, home: Option<&str>) -> Value {
+    let mut value = serde_json::to_value(config).unwrap_or(Value::Null);
+    if let Some(redaction) = value.get_mut("redaction").and_then(Value::as_object_mut) {
+        redaction.remove("keywords");
+    }
+    redact(&mut value, home);
+    value
+}
+
+/// A model and the state of its files as JSON.
+pub fn model_json(model: &ModelInfoRecord) -> Value {
+    let files: Vec<Value> = model
+        .files
+        .iter()
+        .map(|f| {
+            let (state, bytes) = match f.state {
+                ModelFileState::Missing => ("missing", 0),
+                ModelFileState::Partial { bytes } => ("partial", bytes),
+                ModelFileState::Present { bytes } => ("present", bytes),
+            };
+            json!({
+                "filename": f.filename,
+                "expected_mb": f.size_mb,
+                "sha256": f.sha256,
+                "state": state,
+                "bytes": bytes,
+            })
+        })
+        .collect();
+    json!({
+        "name": model.name,
+        "backend": model.backend,
+        "state": format!("{:?}", model.state),
+        "files": files,
+    })
+}
+
+/// The newest `limit` VAD trace logs in `dir`, newest first.
+pub fn recent_logs(dir: &Path, limit: usize) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut logs: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|p| Some((p.metadata().ok()?.modified().ok()?, p)))
+        .collect();
+    logs.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.1.cmp(&a.1)));
+    logs.into_iter().take(limit).map(|(_, p)| p).collect()
+}
+
+/// Gather the files of a diagnostics bundle as (name in archive, contents).
+/// Transcripts are left out: the last session is described by its stats only.
+pub fn collect(
+    config: &DiktoConfig,
+    models: &[ModelInfoRecord],
+    devices: &[String],
+) -> Vec<(String, Vec<u8>)> {
+    let home = dirs::home_dir().map(|h| h.to_string_lossy().into_owned());
+    let home = home.as_deref();
+    let pretty = |value: &Value| serde_json::to_vec_pretty(value).unwrap_or_default();
+
+    let version = crate::build_info::version_info();
+    let mut files = vec![
+        (
+            "version.json".to_string(),
+            pretty(&json!({
+                "version": version.version,
+                "git_hash": version.git_hash,
+                "features": version.features,
+                "target": version.target,
+                "whisper_rs": version.whisper_rs_version,
+                "ort": version.ort_version,
+                "parakeet_rs": version.parakeet_rs_version,
+            })),
+        ),
+        (
+            "config.json".to_string(),
+            pretty(&config_json(config, home)),
+        ),
+    ];
+
+    let mut models = Value::Array(models.iter().map(model_json).collect());
+    redact(&mut models, home);
+    files.push(("models.json".to_string(), pretty(&models)));
+    files.push(("devices.json".to_string(), pretty(&json!(devices))));
+
+    if let Ok(path) = usage::usage_path() {
+        let counters = usage::UsageCounters::load_from(&path);
+        let value = serde_json::to_value(counters).unwrap_or(Value::Null);
+        files.push(("usage.json".to_string(), pretty(&value)));
+    }
+
+    let last_session = history::load_default_entries().pop().map(|entry| {
+        json!({
+            "timestamp": entry.timestamp,
+            "word_count": entry.word_count,
+            "speech_duration_secs": entry.speech_duration_secs,
+            "model_name": entry.model_name,
+            "language": entry.language,
+        })
+    });
+    files.push((
+        "last_session.json".to_string(),
+        pretty(&last_session.unwrap_or(Value::Null)),
+    ));
+
+    if let Ok(dir) = vad_debug::debug_dir() {
+        for log in recent_logs(&dir, MAX_LOGS) {
+            let (Some(name), Ok(contents)) = (log.file_name(), std::fs::read(&log)) else {
+                continue;
+            };
+            files.push((format!("logs/{}", name.to_string_lossy()), contents));
+        }
+    }
+    files
+}
+
+/// Write `files` to a zip archive at `path`, replacing any existing file.
+pub fn write_bundle(path: &Path, files: &[(String, Vec<u8>)]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut zip = ZipWriter::new(BufWriter::new(std::fs::File::create(path)?));
+    for (name, contents) in files {
+        zip.add(name, contents)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::config::DoNotDisturbConfig;
+
+/// Why do-not-disturb refused to start a session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason {
+    /// The local time is inside a window, given as configured.
+    Window(String),
+    /// A listed app is in front.
+    App(String),
+    /// The host reported that the screen is being shared.
+    ScreenSharing,
+}
+
+impl std::fmt::Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reason::Window(window) => write!(f, "quiet hours {window}"),
+            Reason::App(app) => write!(f, "{app} is in front"),
+            Reason::ScreenSharing => write!(f, "the screen is being shared"),
+        }
+    }
+}
+
+/// What the engine knows when a session is about to start.
+#[derive(Debug, Clone, Default)]
+pub struct Context<'a> {
+    /// Local time as minutes after midnight.
+    pub minute_of_day: u32,
+    pub bundle_id: &'a str,
+    pub app_name: &'a str,
+    pub screen_sharing: bool,
+}
+
+/// Minutes after midnight of a "HH:MM" time.
+fn parse_time(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+/// Start and end of a "HH:MM-HH:MM" window in minutes after midnight. The
+/// end is exclusive and may be before the start for windows that run past
+/// midnight ("22:00-07:00").
+pub fn parse_window(window: &str) -> Option<(u32, u32)> {
+    let (start, end) = window.split_once('-')?;
+    Some((parse_time(start)?, parse_time(end)?))
+}
+
+/// Whether `minute` after midnight falls in the window.
+pub fn in_window((start, end): (u32, u32), minute: u32) -> bool {
+    if start <= end {
+        start <= minute && minute < end
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+/// The first rule that keeps a session from starting, if any.
+pub fn check(config: &DoNotDisturbConfig, context: &Context) -> Option<Reason> {
+    if config.while_screen_sharing && context.screen_sharing {
+        return Some(Reason::ScreenSharing);
+    }
+    let app = config.apps.iter().map(|a| a.trim()).find(|a| {
+        !a.is_empty()
+            && (a.eq_ignore_ascii_case(context.bundle_id)
+                || a.eq_ignore_ascii_case(context.app_name))
+    });
+    if let Some(app) = app {
+        return Some(Reason::App(app.to_string()));
+    }
+    config
+        .windows
+        .iter()
+        .find(|w| parse_window(w).is_some_and(|w| in_window(w, context.minute_of_day)))
+        .map(|w| Reason::Window(w.trim().to_string()))
+}
@@ -1,47 +1,56 @@
-use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
 
 use tracing::{debug, info, warn};
 
+use crate::config::InputNormalization;
+use crate::level;
 use crate::models::ModelBackend;
+use crate::spool::AudioSpool;
+pub use crate::textproc::is_hallucination;
+#[cfg(feature = "backend-parakeet")]
+use crate::transcribe::ParakeetEngine;
+#[cfg(feature = "backend-whisper")]
+use crate::transcribe::WhisperEngine;
 use crate::transcribe::{
-    ParakeetEngine, TranscribeConfig, TranscribeError, TranscriptSegment, WhisperEngine,
+    rank_alternatives, Hypothesis, TranscribeError, TranscriptSegment, WordConfidence,
 };
+use crate::worker::InferenceWorker;
 
-/// Unified ASR engine wrapping both Parakeet and Whisper backends.
+/// Unified ASR engine wrapping the backends built in (see the
+/// `backend-*` features).
 pub enum AsrEngine {
+    #[cfg(feature = "backend-parakeet")]
     Parakeet(Box<ParakeetEngine>),
+    #[cfg(feature = "backend-whisper")]
     Whisper(WhisperEngine),
 }
 
-// SAFETY: AsrEngine wraps ParakeetEngine and WhisperEngine, both of which use
-// internal C/C++ state. All access to AsrEngine is guarded by a Mutex<Option<LoadedEngine>>
-// in DiktoEngineInner, ensuring exclusive access from a single thread at a time.
-unsafe impl Send for AsrEngine {}
-unsafe impl Sync for AsrEngine {}
-
 impl AsrEngine {
-    /// Load a model based on backend type.
+    /// Load a model based on backend type. Fails for a backend this build
+    /// was compiled without.
     pub fn load(backend: ModelBackend, model_dir: &Path) -> Result<Self, TranscribeError> {
         match backend {
+            #[cfg(feature = "backend-parakeet")]
             ModelBackend::Parakeet => Ok(AsrEngine::Parakeet(Box::new(ParakeetEngine::load(
                 model_dir,
             )?))),
+            #[cfg(feature = "backend-whisper")]
             ModelBackend::Whisper => Ok(AsrEngine::Whisper(WhisperEngine::load(model_dir)?)),
-        }
-    }
-
-    /// Create a new transcription session.
-    pub fn create_session(&self, config: TranscribeConfig) -> AsrSession {
-        AsrSession {
-            audio_buffer: Vec::new(),
-            language: config.language,
+            #[cfg(feature = "backend-parakeet")]
+            ModelBackend::OnnxNemo => Ok(AsrEngine::Parakeet(Box::new(
+                ParakeetEngine::load_onnx_nemo(model_dir)?,
+            ))),
+            #[allow(unreachable_patterns)]
+            other => Err(TranscribeError::ModelLoad(format!(
+                "{other:?} models are not supported by this build"
+            ))),
         }
     }
 }
 
 /// A loaded engine paired with the model name it was loaded from.
-pub struct LoadedEngine {
+/// Owned exclusively by the InferenceWorker thread.
+pub(crate) struct LoadedEngine {
     pub model_name: String,
     pub engine: AsrEngine,
 }
@@ -50,27 +59,207 @@ pub struct LoadedEngine {
 pub struct AsrSession {
     audio_buffer: Vec<f32>,
     language: String,
+    /// Initial prompt for vocabulary biasing (Whisper only).
+    prompt: Option<String>,
+    /// Beam width for decoding; 0 or 1 is greedy (Whisper only).
+    beam_size: u32,
+    /// Segments more likely than this to be silence are dropped (Whisper only).
+    no_speech_threshold: f32,
+    /// Level each buffer is brought to before inference.
+    normalization: InputNormalization,
+    /// Dynamic range below which a buffer is left at its level, in dB.
+    normalization_min_range_db: f32,
+    /// Position of the buffered audio's first sample in the session's audio
+    /// stream, so segment timestamps are session-relative.
+    time_offset_secs: f32,
+    /// VAD probabilities of the buffered speech, keyed by session-relative
+    /// time in seconds, for overlap detection.
+    vad_probabilities: Vec<(f32, f32)>,
+    /// Other readings of the last flushed buffer, best first.
+    alternatives: Vec<Hypothesis>,
+    /// Directory to spool older audio into, when spooling is enabled.
+    spool_dir: Option<PathBuf>,
+    /// Audio that came before `audio_buffer`, moved out of RAM.
+    spool: Option<AudioSpool>,
 }
 
+/// Buffered audio that triggers spooling, in samples (30s at 16kHz).
+pub const SPOOL_RAM_SAMPLES: usize = 30 * 16000;
+
+/// Most recent audio kept in RAM after spooling, in samples (10s at 16kHz).
+/// Covers the longest silence `trim_end` may cut.
+pub const SPOOL_KEEP_SAMPLES: usize = 10 * 16000;
+
+/// Spooled audio is read back and transcribed this many samples at a time
+/// (30s at 16kHz).
+pub const SPOOL_CHUNK_SAMPLES: usize = 30 * 16000;
+
 impl AsrSession {
-    /// Create a new session with the given language (for testing without an engine).
+    /// Create a new session with the given language.
     pub fn new(language: String) -> Self {
         Self {
             audio_buffer: Vec::new(),
             language,
+            prompt: None,
+            beam_size: 0,
+            no_speech_threshold: 1.0,
+            normalization: InputNormalization::Off,
+            normalization_min_range_db: 0.0,
+            time_offset_secs: 0.0,
+            vad_probabilities: Vec::new(),
+            alternatives: Vec::new(),
+            spool_dir: None,
+            spool: None,
         }
     }
 
+    /// Keep RAM flat on long sessions: once more than `SPOOL_RAM_SAMPLES`
+    /// are buffered, all but the last `SPOOL_KEEP_SAMPLES` move to a temp
+    /// file in `dir`, and `flush` reads them back in chunks.
+    pub fn enable_spooling(&mut self, dir: PathBuf) {
+        self.spool_dir = Some(dir);
+    }
+
+    /// Samples currently moved out of RAM.
+    pub fn spooled_samples(&self) -> usize {
+        self.spool.as_ref().map_or(0, AudioSpool::len)
+    }
+
+    /// Set where the next buffered utterance starts in the session's stream.
+    pub fn set_time_offset(&mut self, secs: f32) {
+        self.time_offset_secs = secs;
+    }
+
+    /// Set the initial prompt passed to the recognizer on each flush.
+    pub fn set_prompt(&mut self, prompt: Option<String>) {
+        self.prompt = prompt;
+    }
+
+    /// Set the beam width used on each flush. Above 1, the recognizer also
+    /// reports alternative readings (see `take_alternatives`).
+    pub fn set_beam_size(&mut self, beam_size: u32) {
+        self.beam_size = beam_size;
+    }
+
+    /// Drop segments whose no-speech probability is above `threshold` on
+    /// each flush. 1.0 (the default) keeps every segment.
+    pub fn set_no_speech_threshold(&mut self, threshold: f32) {
+        self.no_speech_threshold = threshold;
+    }
+
+    /// Normalize each buffer's level with `mode` before inference, unless
+    /// its dynamic range is under `min_range_db` (see `level`).
+    pub fn set_normalization(&mut self, mode: InputNormalization, min_range_db: f32) {
+        self.normalization = mode;
+        self.normalization_min_range_db = min_range_db;
+    }
+
     /// Feed audio samples (16kHz mono f32).
     pub fn feed_samples(&mut self, samples: &[f32]) -> Vec<TranscriptSegment> {
         self.audio_buffer.extend_from_slice(samples);
+        if self.audio_buffer.len() > SPOOL_RAM_SAMPLES {
+            self.spill();
+        }
         Vec::new()
     }
 
-    /// Run batch inference on the accumulated audio buffer.
+    /// Move all but the most recent audio to the spool file. Audio stays in
+    /// RAM if spooling is off or the file can't be written.
+    fn spill(&mut self) {
+        let Some(dir) = &self.spool_dir else {
+            return;
+        };
+        if self.spool.is_none() {
+            match AudioSpool::create(dir) {
+                Ok(spool) => {
+                    debug!("Spooling audio to {}", spool.path().display());
+                    self.spool = Some(spool);
+                }
+                Err(e) => {
+                    warn!("Audio spool unavailable, keeping audio in RAM: {e}");
+                    self.spool_dir = None;
+                    return;
+                }
+            }
+        }
+        let Some(spool) = self.spool.as_mut() else {
+            return;
+        };
+        let older = self.audio_buffer.len() - SPOOL_KEEP_SAMPLES;
+        match spool.append(&self.audio_buffer[..older]) {
+            Ok(()) => {
+                self.audio_buffer.drain(..older);
+            }
+            Err(e) => {
+                warn!("Failed to spool audio, keeping it in RAM: {e}");
+                self.spool_dir = None;
+            }
+        }
+    }
+
+    /// Record the VAD's speech probability for the chunk starting at
+    /// `at_secs` in the session's stream.
+    pub fn note_vad_probability(&mut self, at_secs: f32, probability: f32) {
+        self.vad_probabilities.push((at_secs, probability));
+    }
+
+    /// Drop up to `samples` from the end of the buffer, e.g. the trailing
+    /// silence that ended an utterance.
+    pub fn trim_end(&mut self, samples: usize) {
+        let keep = self.audio_buffer.len().saturating_sub(samples);
+        self.audio_buffer.truncate(keep);
+    }
+
+    /// Run batch inference on the accumulated audio buffer. Spooled audio is
+    /// read back and transcribed in `SPOOL_CHUNK_SAMPLES` chunks first.
     pub fn flush(
         &mut self,
-        engine: &Arc<Mutex<Option<LoadedEngine>>>,
+        worker: &InferenceWorker,
+    ) -> Result<Vec<TranscriptSegment>, TranscribeError> {
+        self.alternatives.clear();
+        let Some(mut spool) = self.spool.take().filter(|spool| !spool.is_empty()) else {
+            let segments = self.flush_buffer(worker);
+            self.vad_probabilities.clear();
+            return segments;
+        };
+
+        debug!(
+            "flush: {:.1}s of spooled audio",
+            spool.len() as f32 / 16000.0
+        );
+        let tail = std::mem::take(&mut self.audio_buffer);
+        let base_offset = self.time_offset_secs;
+        let mut segments = Vec::new();
+        let chunks = spool
+            .chunks(SPOOL_CHUNK_SAMPLES)
+            .map_err(|e| TranscribeError::Inference(format!("read audio spool: {e}")))?;
+        for chunk in chunks {
+            let chunk =
+                chunk.map_err(|e| TranscribeError::Inference(format!("read audio spool: {e}")))?;
+            let chunk_secs = chunk.len() as f32 / 16000.0;
+            self.audio_buffer = chunk;
+            segments.extend(self.flush_buffer(worker)?);
+            self.time_offset_secs += chunk_secs;
+        }
+        self.audio_buffer = tail;
+        segments.extend(self.flush_buffer(worker)?);
+        self.time_offset_secs = base_offset;
+        self.vad_probabilities.clear();
+
+        // Alternatives would only cover the last chunk, not the utterance
+        self.alternatives.clear();
+        if let Err(e) = spool.clear() {
+            warn!("Failed to clear audio spool: {e}");
+        } else {
+            self.spool = Some(spool);
+        }
+        Ok(segments)
+    }
+
+    /// Transcribe the in-RAM buffer, which starts at `time_offset_secs`.
+    fn flush_buffer(
+        &mut self,
+        worker: &InferenceWorker,
     ) -> Result<Vec<TranscriptSegment>, TranscribeError> {
         if self.audio_buffer.is_empty() {
             warn!("flush: buffer empty, skipping");
@@ -93,60 +282,212 @@ impl AsrSession {
             self.audio_buffer.truncate(MAX_SAMPLES);
         }
 
-        debug!("flush: acquiring engine lock...");
-        let mut guard = engine
-            .lock()
-            .map_err(|e| TranscribeError::Inference(format!("Lock poisoned: {e}")))?;
-
-        let loaded = guard.as_mut().ok_or(TranscribeError::NotLoaded)?;
-
-        debug!("flush: lock acquired, running inference...");
-
-        let start = std::time::Instant::now();
-        let text = match &mut loaded.engine {
-            AsrEngine::Parakeet(e) => e.transcribe(&self.audio_buffer)?,
-            AsrEngine::Whisper(e) => e.transcribe(&self.audio_buffer, &self.language)?,
-        };
-        debug!(
-            "flush: inference done in {:.1}s",
-            start.elapsed().as_secs_f32()
+        debug!("flush: submitting inference job...");
+        let mut samples = std::mem::take(&mut self.audio_buffer);
+        let gain = level::normalize(
+            &mut samples,
+            self.normalization,
+            self.normalization_min_range_db,
         );
-        self.audio_buffer.clear();
+        if gain > 1.0 {
+            debug!("flush: boosted input by {:.1} dB", 20.0 * gain.log10());
+        }
+        let start_secs = self.time_offset_secs;
+        let end_secs = start_secs + samples.len() as f32 / 16000.0;
+        // Keep the probabilities of later audio for the chunks still to come
+        let (vad_probabilities, later): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.vad_probabilities)
+                .into_iter()
+                .partition(|(at, _)| *at < end_secs);
+        self.vad_probabilities = later;
+        let transcription = worker.transcribe_with_beam(
+            samples,
+            &self.language,
+            self.prompt.clone(),
+            self.beam_size,
+        )?;
 
-        let text = text.trim().to_string();
+        let text = transcription.text.trim().to_string();
         if text.is_empty() || is_hallucination(&text) {
             return Ok(Vec::new());
         }
+        self.alternatives = rank_alternatives(&text, transcription.alternatives)
+            .into_iter()
+            .filter(|h| !is_hallucination(&h.text))
+            .collect();
 
-        Ok(vec![TranscriptSegment {
-            text,
-            is_final: true,
-        }])
+        if transcription.segments.is_empty() {
+            return Ok(vec![TranscriptSegment {
+                text,
+                is_final: true,
+                language: transcription.language,
+                words: Vec::new(),
+                start_secs: None,
+                end_secs: None,
+                possible_overlap: false,
+                no_speech_prob: None,
+            }]);
+        }
+
+        let offset = self.time_offset_secs;
+        let threshold = self.no_speech_threshold;
+        let segments: Vec<TranscriptSegment> = transcription
+            .segments
+            .into_iter()
+            .filter(|seg| !seg.text.is_empty() && !is_hallucination(&seg.text))
+            .filter(|seg| {
+                let silent = is_no_speech(seg.no_speech_prob, threshold);
+                if silent {
+                    debug!(
+                        "flush: dropping segment with no_speech_prob={:?}",
+                        seg.no_speech_prob
+                    );
+                }
+                !silent
+            })
+            .map(|seg| {
+                let start_secs = offset + seg.start_secs;
+                let end_secs = offset + seg.end_secs;
+                let probabilities: Vec<f32> = vad_probabilities
+                    .iter()
+                    .filter(|(at, _)| *at >= start_secs && *at < end_secs)
+                    .map(|(_, p)| *p)
+                    .collect();
+                TranscriptSegment {
+                    possible_overlap: is_possible_overlap(&probabilities, &seg.words),
+                    text: seg.text,
+                    is_final: true,
+                    language: transcription.language.clone(),
+                    words: seg.words,
+                    start_secs: Some(start_secs),
+                    end_secs: Some(end_secs),
+                    no_speech_prob: seg.no_speech_prob,
+                }
+            })
+            .collect();
+        if segments.is_empty() {
+            // Readings of silence aren't alternatives to anything
+            self.alternatives.clear();
+        }
+        Ok(segments)
     }
 
-    /// Get accumulated audio buffer length in seconds.
+    /// Take the other readings of the last flushed buffer, best first.
+    /// Empty when the backend gave none or the buffer had no speech.
+    pub fn take_alternatives(&mut self) -> Vec<Hypothesis> {
+        std::mem::take(&mut self.alternatives)
+    }
+
+    /// Get accumulated audio buffer length in seconds, spooled audio included.
     pub fn buffer_duration_secs(&self) -> f32 {
-        self.audio_buffer.len() as f32 / 16000.0
+        (self.spooled_samples() + self.audio_buffer.len()) as f32 / 16000.0
+    }
+}
+
+/// True if the recognizer judged a segment more likely silence than
+/// `threshold`. Segments without a probability are kept.
+pub fn is_no_speech(no_speech_prob: Option<f32>, threshold: f32) -> bool {
+    no_speech_prob.is_some_and(|p| p > threshold)
+}
+
+/// Mean VAD probability at or above which speech counts as saturated.
+pub const OVERLAP_VAD_SATURATION: f32 = 0.85;
+
+/// Mean word confidence below which a transcription counts as collapsed.
+pub const OVERLAP_CONFIDENCE_FLOOR: f32 = 0.4;
+
+/// True if the VAD was sure there was speech while the recognizer was unsure
+/// of the words, the usual signature of crosstalk. Needs a few words to judge.
+pub fn is_possible_overlap(vad_probabilities: &[f32], words: &[WordConfidence]) -> bool {
+    if vad_probabilities.is_empty() || words.len() < 3 {
+        return false;
     }
+    let mean_vad = vad_probabilities.iter().sum::<f32>() / vad_probabilities.len() as f32;
+    let mean_confidence = words.iter().map(|w| w.confidence).sum::<f32>() / words.len() as f32;
+    mean_vad >= OVERLAP_VAD_SATURATION && mean_confidence < OVERLAP_CONFIDENCE_FLOOR
+}
+
+/// Most words two overlapping inference windows are expected to share.
+/// Matching stops here so a phrase repeated further from the boundary
+/// isn't mistaken for the overlap.
+pub const MAX_OVERLAP_WORDS: usize = 16;
+
+/// Whether two words match ignoring case and punctuation, so "world." at
+/// the end of one window matches "World" at the start of the next.
+fn same_word(a: &str, b: &str) -> bool {
+    let normalize = |w: &str| -> String {
+        w.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    normalize(a) == normalize(b)
+}
+
+/// How many words at the start of `next` repeat the end of `previous`: the
+/// longest run, up to `MAX_OVERLAP_WORDS`, that is both a suffix of one and
+/// a prefix of the other.
+pub fn overlap_word_count(previous: &[&str], next: &[&str]) -> usize {
+    let max = MAX_OVERLAP_WORDS.min(previous.len()).min(next.len());
+    (1..=max)
+        .rev()
+        .find(|&k| {
+            previous[previous.len() - k..]
+                .iter()
+                .zip(&next[..k])
+                .all(|(a, b)| same_word(a, b))
+        })
+        .unwrap_or(0)
 }
 
-/// Returns true if the text looks like a known ASR hallucination token.
-pub fn is_hallucination(text: &str) -> bool {
-    let t = text.trim().to_lowercase();
-    let hallucinations = [
-        "[blank_audio]",
-        "[music]",
-        "[inaudible]",
-        "[silence]",
-        "[no speech]",
-        "[applause]",
-        "[laughter]",
-        "(music)",
-        "(silence)",
-        "(laughter)",
-        "(applause)",
-        "(no speech)",
-        "(blank audio)",
-    ];
-    hallucinations.contains(&t.as_str())
+/// Merge segments from overlapping inference windows, dropping the words at
+/// the start of each segment that repeat the end of the one before. Only
+/// segments that overlap in time are deduplicated; segments without timing
+/// are assumed to. A segment left empty is dropped and its end time carried
+/// over to the previous one.
+pub fn merge_overlapping_segments(segments: Vec<TranscriptSegment>) -> Vec<TranscriptSegment> {
+    let mut merged: Vec<TranscriptSegment> = Vec::with_capacity(segments.len());
+    for mut seg in segments {
+        let Some(prev) = merged.last_mut() else {
+            merged.push(seg);
+            continue;
+        };
+        let overlaps = match (prev.end_secs, seg.start_secs) {
+            (Some(end), Some(start)) => start < end,
+            _ => true,
+        };
+        let prev_words: Vec<&str> = prev.text.split_whitespace().collect();
+        let words: Vec<&str> = seg.text.split_whitespace().collect();
+        let shared = if overlaps {
+            overlap_word_count(&prev_words, &words)
+        } else {
+            0
+        };
+        if shared == 0 {
+            merged.push(seg);
+            continue;
+        }
+
+        debug!("merge: dropping {shared} repeated word(s) at segment boundary");
+        if shared == words.len() {
+            prev.end_secs = match (prev.end_secs, seg.end_secs) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, b) => a.or(b),
+            };
+            continue;
+        }
+        let text = words[shared..].join(" ");
+        // Word confidences line up with the text only when the backend
+        // split words the same way
+        if seg.words.len() == words.len() {
+            seg.words.drain(..shared);
+        }
+        seg.start_secs = match (seg.start_secs, prev.end_secs) {
+            (Some(start), Some(end)) => Some(start.max(end)),
+            (start, _) => start,
+        };
+        seg.text = text;
+        merged.push(seg);
+    }
+    merged
 }
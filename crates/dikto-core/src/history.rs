@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::config::{data_dir, ConfigError};
+use crate::storage;
+use crate::usage::{unix_now, utc_date};
+use crate::TranscriptionResult;
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// One finished dictation, stored locally as a line of history.jsonl.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct HistoryEntry {
+    /// Seconds since the Unix epoch when the session finished.
+    pub timestamp: u64,
+    pub text: String,
+    pub word_count: u32,
+    /// Seconds of audio fed to the recognizer.
+    pub speech_duration_secs: f32,
+    pub model_name: String,
+    pub language: String,
+    /// The recognizer's words before text processing, when
+    /// `OutputRoutes::history_raw` is on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_text: Option<String>,
+    /// `wav::fingerprint` of the file this came from, for transcriptions of
+    /// files rather than live dictation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_fingerprint: Option<String>,
+}
+
+/// Time window for dictation statistics.
+#[derive(Debug, Clone, Copy, PartialEq, uniffi::Enum)]
+pub enum StatsPeriod {
+    /// The current UTC calendar day.
+    Today,
+    /// The last 7 days.
+    Week,
+    /// The last 30 days.
+    Month,
+    AllTime,
+}
+
+/// Aggregated dictation statistics for a period.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct DictationStats {
+    pub words: u64,
+    pub sessions: u64,
+    pub speech_duration_secs: f64,
+    /// Words per minute of recognized speech (0 when there is no speech).
+    pub average_wpm: f64,
+    /// Consecutive UTC days with at least one session, ending today (or
+    /// yesterday, if nothing has been dictated yet today).
+    pub streak_days: u32,
+}
+
+/// Returns the history file path: ~/.local/share/dikto/history.jsonl
+pub fn history_path() -> Result<PathBuf, ConfigError> {
+    Ok(data_dir()?.join("history.jsonl"))
+}
+
+/// Count whitespace-separated words in a transcript.
+pub fn word_count(text: &str) -> u32 {
+    text.split_whitespace().count() as u32
+}
+
+/// Append an entry to a history file, creating it if needed.
+pub fn append_entry(path: &Path, entry: &HistoryEntry) -> Result<(), std::io::Error> {
+    let line = serde_json::to_string(entry).map_err(std::io::Error::other)?;
+    storage::append_locked(path, format!("{line}\n").as_bytes())
+}
+
+/// Load all entries from a history file, oldest first. Corrupt lines are skipped.
+pub fn load_entries(path: &Path) -> Vec<HistoryEntry> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                warn!("Skipping corrupt history line: {e}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Load entries from the default history file.
+pub fn load_default_entries() -> Vec<HistoryEntry> {
+    match history_path() {
+        Ok(path) => load_entries(&path),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Record a finished session in the default history file, with the raw
+/// transcript if `keep_raw`. Empty transcripts are skipped.
+pub fn record_session(
+    result: &TranscriptionResult,
+    model_name: &str,
+    language: &str,
+    keep_raw: bool,
+) {
+    record(result, model_name, language, keep_raw, None);
+}
+
+/// Record a transcribed file in the default history file under its audio
+/// fingerprint, so later batches can skip it. Unlike dictation, files with
+/// no speech are recorded too, or they'd be transcribed again every time.
+pub fn record_file(
+    result: &TranscriptionResult,
+    model_name: &str,
+    language: &str,
+    keep_raw: bool,
+    fingerprint: &str,
+) {
+    record(
+        result,
+        model_name,
+        language,
+        keep_raw,
+        Some(fingerprint.to_string()),
+    );
+}
+
+fn record(
+    result: &TranscriptionResult,
+    model_name: &str,
+    language: &str,
+    keep_raw: bool,
+    audio_fingerprint: Option<String>,
+) {
+    if result.text.trim().is_empty() && audio_fingerprint.is_none() {
+        return;
+    }
+    let entry = HistoryEntry {
+        timestamp: unix_now(),
+        text: result.text.clone(),
+        word_count: word_count(&result.text),
+        speech_duration_secs: result.speech_duration_secs,
+        model_name: model_name.to_string(),
+        language: language.to_string(),
+        raw_text: keep_raw.then(|| result.raw_text.clone()),
+        audio_fingerprint,
+    };
+    let outcome = history_path()
+        .map_err(|e| std::io::Error::other(e.to_string()))
+        .and_then(|path| append_entry(&path, &entry));
+    if let Err(e) = outcome {
+        warn!("Failed to write history: {e}");
+    }
+}
+
+/// Fingerprints of every file transcribed in `entries`.
+pub fn transcribed_fingerprints(entries: &[HistoryEntry]) -> HashSet<String> {
+    entries
+        .iter()
+        .filter_map(|e| e.audio_fingerprint.clone())
+        .collect()
+}
+
+/// Returns true if `entry` falls inside `period` relative to `now`.
+fn in_period(entry: &HistoryEntry, period: StatsPeriod, now: u64) -> bool {
+    match period {
+        StatsPeriod::Today => utc_date(entry.timestamp) == utc_date(now),
+        StatsPeriod::Week => entry.timestamp + 7 * SECS_PER_DAY > now,
+        StatsPeriod::Month => entry.timestamp + 30 * SECS_PER_DAY > now,
+        StatsPeriod::AllTime => true,
+    }
+}
+
+/// Aggregate statistics over `entries` for `period`, with `now` as the current time.
+pub fn dictation_stats(entries: &[HistoryEntry], period: StatsPeriod, now: u64) -> DictationStats {
+    let mut words = 0u64;
+    let mut sessions = 0u64;
+    let mut speech_duration_secs = 0f64;
+    for entry in entries.iter().filter(|e| in_period(e, period, now)) {
+        words += entry.word_count as u64;
+        sessions += 1;
+        speech_duration_secs += entry.speech_duration_secs as f64;
+    }
+
+    DictationStats {
+        words,
+        sessions,
+        speech_duration_secs,
+        average_wpm: if speech_duration_secs > 0.0 {
+            words as f64 / (speech_duration_secs / 60.0)
+        } else {
+            0.0
+        },
+        streak_days: streak_days(entries, now),
+    }
+}
+
+/// Consecutive days with dictation, counting back from today (or yesterday).
+pub fn streak_days(entries: &[HistoryEntry], now: u64) -> u32 {
+    let days: HashSet<String> = entries.iter().map(|e| utc_date(e.timestamp)).collect();
+    let mut day = now;
+    if !days.contains(&utc_date(day)) {
+        day = day.saturating_sub(SECS_PER_DAY);
+    }
+    let mut streak = 0;
+    while days.contains(&utc_date(day)) {
+        streak += 1;
+        if day < SECS_PER_DAY {
+            break;
+        }
+        day -= SECS_PER_DAY;
+    }
+    streak
+}
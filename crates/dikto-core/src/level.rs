@@ -0,0 +1,78 @@
+use crate::config::InputNormalization;
+
+/// Peak normalization raises the loudest sample to -1 dBFS.
+pub const PEAK_TARGET: f32 = 0.89;
+
+/// RMS normalization raises the overall level to -20 dBFS, as long as that
+/// doesn't push a peak past `PEAK_TARGET`.
+pub const RMS_TARGET: f32 = 0.1;
+
+/// Most gain applied to one utterance (30 dB).
+pub const MAX_GAIN: f32 = 31.6;
+
+/// Samples per frame when measuring dynamic range: 20 ms at 16kHz.
+const FRAME: usize = 320;
+
+/// Frame RMS below which a frame counts as digital silence.
+const SILENCE_RMS: f32 = 1e-5;
+
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// Spread between the loud and quiet parts of a recording in dB: the 95th
+/// percentile of 20 ms frame levels over the 10th. Speech over a quiet
+/// background scores 20 dB and more; steady noise or hum scores a few dB.
+/// Shorter than two frames scores 0.
+pub fn dynamic_range_db(samples: &[f32]) -> f32 {
+    let mut levels: Vec<f32> = samples.chunks_exact(FRAME).map(rms).collect();
+    if levels.len() < 2 {
+        return 0.0;
+    }
+    levels.sort_by(f32::total_cmp);
+    let at = |q: f32| levels[((levels.len() - 1) as f32 * q).round() as usize];
+    let loud = at(0.95);
+    let quiet = at(0.10).max(SILENCE_RMS);
+    if loud <= SILENCE_RMS {
+        return 0.0;
+    }
+    20.0 * (loud / quiet).log10()
+}
+
+/// Gain that brings `samples` to the target level for `mode`, or 1.0 when
+/// the recording is left alone: normalization is off, the recording is
+/// silent, its dynamic range is under `min_range_db` (most likely noise
+/// with no speech), or it is already loud enough. Quiet recordings are
+/// only ever boosted, by at most `MAX_GAIN`, and never past `PEAK_TARGET`.
+pub fn normalization_gain(samples: &[f32], mode: InputNormalization, min_range_db: f32) -> f32 {
+    let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    if mode == InputNormalization::Off || peak <= SILENCE_RMS {
+        return 1.0;
+    }
+    if dynamic_range_db(samples) < min_range_db {
+        return 1.0;
+    }
+    let gain = match mode {
+        InputNormalization::Off => 1.0,
+        InputNormalization::Peak => PEAK_TARGET / peak,
+        InputNormalization::Rms => {
+            (RMS_TARGET / rms(samples).max(SILENCE_RMS)).min(PEAK_TARGET / peak)
+        }
+    };
+    gain.clamp(1.0, MAX_GAIN)
+}
+
+/// Normalize `samples` in place (see `normalization_gain`). Returns the
+/// gain applied.
+pub fn normalize(samples: &mut [f32], mode: InputNormalization, min_range_db: f32) -> f32 {
+    let gain = normalization_gain(samples, mode, min_range_db);
+    if gain != 1.0 {
+        for s in samples.iter_mut() {
+            *s *= gain;
+        }
+    }
+    gain
+}
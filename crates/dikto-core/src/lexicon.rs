@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::config::{data_dir, ConfigError};
+use crate::history::HistoryEntry;
+use crate::storage;
+
+/// Most terms included in a recognizer prompt.
+pub const MAX_TERMS: usize = 40;
+
+/// A term must appear in at least this many history entries to count.
+const MIN_HISTORY_COUNT: u32 = 2;
+
+/// Only the most recent entries are scanned, so old jargon ages out.
+const MAX_HISTORY_ENTRIES: usize = 1000;
+
+/// Corrections are explicit user feedback and outweigh passive history.
+const CORRECTION_WEIGHT: u32 = 3;
+
+/// Personal lexicon state that can't be derived from history: terms the user
+/// introduced by correcting transcripts. Stored in lexicon.json.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Lexicon {
+    /// Corrected term → number of times it was introduced by a correction.
+    #[serde(default)]
+    pub corrections: BTreeMap<String, u32>,
+}
+
+impl Lexicon {
+    /// Load from a file. Missing or unreadable files yield an empty lexicon.
+    pub fn load_from(path: &Path) -> Self {
+        let Ok(contents) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Failed to parse lexicon at {}: {e}", path.display());
+            Self::default()
+        })
+    }
+
+    /// Save to a file atomically, creating the parent directory if needed.
+    pub fn save_to(&self, path: &Path) -> Result<(), std::io::Error> {
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        storage::write_atomic(path, json.as_bytes())
+    }
+
+    /// Learn the terms a user introduced when editing `original` into `corrected`.
+    pub fn record_correction(&mut self, original: &str, corrected: &str) {
+        for term in correction_terms(original, corrected) {
+            *self.corrections.entry(term).or_default() += 1;
+        }
+    }
+}
+
+/// Returns the lexicon path: ~/.local/share/dikto/lexicon.json
+pub fn lexicon_path() -> Result<PathBuf, ConfigError> {
+    Ok(data_dir()?.join("lexicon.json"))
+}
+
+/// Strip surrounding punctuation, keeping inner marks ("O'Brien", "gRPC-Web").
+fn trim_word(word: &str) -> &str {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+}
+
+/// True for words worth biasing toward: proper nouns and jargon such as
+/// "Kubernetes", "iOS", or "GPT4". Ordinary lowercase words are skipped.
+fn is_distinctive(word: &str, sentence_start: bool) -> bool {
+    if word.chars().count() < 2 || !word.chars().any(char::is_alphabetic) {
+        return false;
+    }
+    let mut chars = word.chars();
+    let first_upper = chars.next().is_some_and(char::is_uppercase);
+    let inner_upper = chars.any(char::is_uppercase);
+    let mixed_digits = word.chars().any(|c| c.is_ascii_digit());
+    // A capital at sentence start says nothing about the word
+    inner_upper || mixed_digits || (first_upper && !sentence_start)
+}
+
+/// Distinctive terms in a transcript, in order, without duplicates.
+pub fn candidate_terms(text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut terms = Vec::new();
+    let mut sentence_start = true;
+    for raw in text.split_whitespace() {
+        let word = trim_word(raw);
+        if is_distinctive(word, sentence_start) && seen.insert(word.to_string()) {
+            terms.push(word.to_string());
+        }
+        sentence_start = raw.ends_with(['.', '?', '!']);
+    }
+    terms
+}
+
+/// Words present in `corrected` but not in `original`.
+pub fn correction_terms(original: &str, corrected: &str) -> Vec<String> {
+    let before: HashSet<&str> = original.split_whitespace().map(trim_word).collect();
+    let mut seen = HashSet::new();
+    corrected
+        .split_whitespace()
+        .map(trim_word)
+        .filter(|w| w.chars().count() >= 2 && !before.contains(w))
+        .filter(|w| seen.insert(*w))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Rank lexicon terms from recent history and recorded corrections, most
+/// frequent first, capped at `max_terms`.
+pub fn build_terms(entries: &[HistoryEntry], lexicon: &Lexicon, max_terms: usize) -> Vec<String> {
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    let recent = entries.len().saturating_sub(MAX_HISTORY_ENTRIES);
+    for entry in &entries[recent..] {
+        for term in candidate_terms(&entry.text) {
+            *counts.entry(term).or_default() += 1;
+        }
+    }
+    counts.retain(|_, count| *count >= MIN_HISTORY_COUNT);
+    for (term, count) in &lexicon.corrections {
+        *counts.entry(term.clone()).or_default() += count * CORRECTION_WEIGHT;
+    }
+
+    let mut ranked: Vec<(String, u32)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(max_terms);
+    ranked.into_iter().map(|(term, _)| term).collect()
+}
+
+/// Lexicon terms from the default history and lexicon files.
+pub fn default_terms() -> Vec<String> {
+    let lexicon = lexicon_path()
+        .map(|p| Lexicon::load_from(&p))
+        .unwrap_or_default();
+    build_terms(&crate::history::load_default_entries(), &lexicon, MAX_TERMS)
+}
+
+/// Record a correction in the default lexicon file.
+pub fn record_correction(original: &str, corrected: &str) -> Result<(), std::io::Error> {
+    let path = lexicon_path().map_err(|e| std::io::Error::other(e.to_string()))?;
+    let _lock = storage::FileLock::acquire(&path)?;
+    let mut lexicon = Lexicon::load_from(&path);
+    lexicon.record_correction(original, corrected);
+    lexicon.save_to(&path)
+}
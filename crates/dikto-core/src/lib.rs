@@ -1,24 +1,121 @@
 uniffi::setup_scaffolding!();
 
+#[cfg(all(
+    feature = "native",
+    not(any(feature = "backend-whisper", feature = "backend-parakeet"))
+))]
+compile_error!("the native feature needs backend-whisper, backend-parakeet, or both");
+
+pub mod actions;
 pub mod audio;
+pub mod build_info;
+#[cfg(feature = "native")]
+pub mod capi;
+pub mod clock;
 pub mod config;
+pub mod context;
+pub mod corrections;
+pub mod countdown;
+#[cfg(feature = "native")]
+pub mod diagnostics;
+pub mod do_not_disturb;
+#[cfg(feature = "native")]
 pub mod engine;
+pub mod history;
+pub mod level;
+pub mod lexicon;
+pub mod logging;
+pub mod math_latex;
+pub mod messages;
+#[cfg(feature = "native")]
 pub mod models;
+pub mod output;
+pub mod paste;
+#[cfg(feature = "native")]
+pub mod power;
+pub mod rate_limit;
+#[cfg(feature = "native")]
+pub mod redact;
+#[cfg(feature = "native")]
+pub mod self_test;
+pub mod sessions;
+pub mod spelling;
+pub mod spool;
+pub mod state;
+pub mod storage;
+#[cfg(feature = "native")]
+pub mod summarize;
+pub mod sync;
+pub mod templates;
+pub mod textproc;
+#[cfg(feature = "native")]
 pub mod transcribe;
+#[cfg(feature = "native")]
+pub mod translate;
+pub mod usage;
+#[cfg(feature = "native")]
 pub mod vad;
-
-use audio::{AudioCapture, AudioCaptureConfig, AudioError};
-use config::DiktoConfig;
-use engine::{AsrEngine, AsrSession, LoadedEngine};
+#[cfg(feature = "native")]
+pub mod vad_debug;
+#[cfg(feature = "native")]
+pub mod watchdog;
+pub mod wav;
+#[cfg(feature = "native")]
+pub mod worker;
+pub mod zip;
+
+#[cfg(feature = "native")]
+use audio::{AudioCaptureConfig, AudioInput, AudioSource, MuteDetector};
+use audio::{AudioError, CaptureControl, FeedBuffer};
+#[cfg(feature = "native")]
+use clock::SessionTimer;
+#[cfg(feature = "native")]
+use config::{ActionItemsConfig, AdvancedConfig, SavedSessionsConfig, SummaryConfig};
+#[cfg(feature = "native")]
+use config::{ConfigWarning, TextProcConfig, TranslationConfig};
+use config::{DiktoConfig, TextProcPreset};
+#[cfg(feature = "native")]
+use countdown::{Countdown, SessionDeadline};
+#[cfg(feature = "native")]
+use engine::AsrSession;
+use messages::{ErrorCode, StatusMessage};
+#[cfg(feature = "native")]
 use models::{ModelBackend, ModelError};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+#[cfg(feature = "native")]
+use redact::Redactor;
+#[cfg(feature = "native")]
+use spool::AudioSpool;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+#[cfg(feature = "native")]
+use std::sync::{Mutex, OnceLock};
+#[cfg(feature = "native")]
+use textproc::TextPipeline;
 use thiserror::Error;
-use tracing::{debug, info, warn};
-use transcribe::{TranscribeConfig, TranscribeError};
-use vad::{VadConfig, VadError, VadEvent, VadProcessor};
+use tracing::warn;
+#[cfg(feature = "native")]
+use tracing::{debug, info};
+#[cfg(feature = "native")]
+use transcribe::{TranscribeError, TranscriptSegment, WordConfidence};
+#[cfg(feature = "native")]
+use translate::Translator;
+#[cfg(feature = "native")]
+use vad::{VadConfig, VadError, VadEvent, VadProcessor, VadState};
+#[cfg(feature = "native")]
+use vad_debug::VadTrace;
+#[cfg(feature = "native")]
+use worker::InferenceWorker;
+
+/// Longest a queued session waits for the previous one to finish.
+#[cfg(feature = "native")]
+const QUEUE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// How often a queued session checks whether it can start.
+#[cfg(feature = "native")]
+const QUEUE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(20);
 
 /// Old Whisper model names (v1) that should be auto-migrated to Parakeet.
+#[cfg(feature = "native")]
 const OLD_WHISPER_MODEL_NAMES: &[&str] = &["tiny.en", "base.en", "small.en", "medium.en"];
 
 /// Errors from the Dikto engine.
@@ -34,27 +131,59 @@ pub enum DiktoError {
     Model(String),
     #[error("No model loaded. Run: dikto --setup")]
     NoModel,
+    #[error(
+        "Microphone access denied. Allow it in System Settings > Privacy & Security > Microphone"
+    )]
+    PermissionDenied,
     #[error("Already recording")]
     AlreadyRecording,
+    #[error("Do not disturb: {0}")]
+    DoNotDisturb(String),
+    #[error("Too many sessions, try again in {retry_after_ms} ms")]
+    RateLimited { retry_after_ms: u64 },
     #[error("Config error: {0}")]
     Config(String),
 }
 
+impl DiktoError {
+    /// Stable code for showing a localized message (see `messages`).
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            DiktoError::Audio(_) => ErrorCode::Audio,
+            DiktoError::Vad(_) => ErrorCode::Vad,
+            DiktoError::Transcribe(_) => ErrorCode::Transcribe,
+            DiktoError::Model(_) => ErrorCode::Model,
+            DiktoError::NoModel => ErrorCode::NoModel,
+            DiktoError::PermissionDenied => ErrorCode::PermissionDenied,
+            DiktoError::AlreadyRecording => ErrorCode::AlreadyRecording,
+            DiktoError::DoNotDisturb(_) => ErrorCode::DoNotDisturb,
+            DiktoError::RateLimited { .. } => ErrorCode::RateLimited,
+            DiktoError::Config(_) => ErrorCode::Config,
+        }
+    }
+}
+
 impl From<AudioError> for DiktoError {
     fn from(e: AudioError) -> Self {
-        DiktoError::Audio(e.to_string())
+        match e {
+            AudioError::PermissionDenied => DiktoError::PermissionDenied,
+            e => DiktoError::Audio(e.to_string()),
+        }
     }
 }
+#[cfg(feature = "native")]
 impl From<VadError> for DiktoError {
     fn from(e: VadError) -> Self {
         DiktoError::Vad(e.to_string())
     }
 }
+#[cfg(feature = "native")]
 impl From<TranscribeError> for DiktoError {
     fn from(e: TranscribeError) -> Self {
         DiktoError::Transcribe(e.to_string())
     }
 }
+#[cfg(feature = "native")]
 impl From<ModelError> for DiktoError {
     fn from(e: ModelError) -> Self {
         DiktoError::Model(e.to_string())
@@ -66,11 +195,58 @@ impl From<ModelError> for DiktoError {
 pub enum RecordingState {
     Listening,
     Processing,
-    Done { text: String },
-    Error { message: String },
+    Done {
+        text: String,
+    },
+    /// `message` is the English description, for logs; hosts show
+    /// `messages::localized_error_message(code, locale)`.
+    Error {
+        message: String,
+        code: ErrorCode,
+    },
+}
+
+/// Final output of a recording session.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct TranscriptionResult {
+    pub text: String,
+    /// The recognizer's words before text processing, templates, and
+    /// translation (still redacted).
+    pub raw_text: String,
+    /// Seconds of audio fed to the recognizer (speech plus pre-roll).
+    pub speech_duration_secs: f32,
+    /// Other readings of the final utterance, best first, for a "did you
+    /// mean" picker. Empty when the decoder gave none.
+    pub alternatives: Vec<TranscriptAlternative>,
+    /// TL;DR of a long transcript, when `DiktoConfig::summary` is set up.
+    pub summary: Option<String>,
+    /// Tasks spoken in the transcript, when `DiktoConfig::action_items` is on.
+    pub action_items: Vec<actions::ActionItem>,
+}
+
+/// The whole transcript with its final utterance read differently.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct TranscriptAlternative {
+    /// Replacement for `TranscriptionResult::text`, post-processed the same way.
+    pub text: String,
+    /// Just the final utterance as this alternative reads it.
+    pub utterance: String,
+    /// 0.0–1.0; higher is more likely. Only comparable within one result.
+    pub score: f32,
+}
+
+/// A final segment that may be unreliable because several people spoke at
+/// once, so hosts can mark it for review.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct OverlapWarning {
+    pub text: String,
+    /// Seconds from the start of the session, when the backend reports timing.
+    pub start_secs: Option<f32>,
+    pub end_secs: Option<f32>,
 }
 
 /// Callbacks for transcription events.
+#[cfg(feature = "native")]
 #[uniffi::export(with_foreign)]
 pub trait TranscriptionCallback: Send + Sync {
     fn on_partial(&self, text: String);
@@ -78,6 +254,41 @@ pub trait TranscriptionCallback: Send + Sync {
     fn on_silence(&self);
     fn on_error(&self, error: String);
     fn on_state_change(&self, state: RecordingState);
+    /// The recognized language changed between utterances (auto-detect only).
+    fn on_language_change(&self, language: String);
+    /// Per-word confidence for the recognizer's raw output, so UIs can gray
+    /// out uncertain words. Batch backends report it with each final
+    /// segment; a streaming backend may report it with partials.
+    fn on_word_confidence(&self, words: Vec<WordConfidence>);
+    /// A final segment looks like crosstalk: the VAD heard clear speech but
+    /// the recognizer was unsure of most words.
+    fn on_overlap_warning(&self, warning: OverlapWarning);
+    /// The microphone stream failed mid-session (e.g. a USB glitch). Text
+    /// heard so far has been emitted; the session resumes with
+    /// `RecordingState::Listening` once the device is back, or finishes with
+    /// what it has if it doesn't come back.
+    fn on_audio_interrupted(&self, reason: String);
+    /// A summary of a long transcript, sent just before
+    /// `RecordingState::Done`. Only when a summarizer is configured.
+    fn on_summary(&self, summary: String);
+    /// The microphone has sent only exact zeros for `audio::NO_AUDIO_WINDOW`, so
+    /// it is probably muted. The session keeps listening, so unmuting it
+    /// still works.
+    fn on_no_audio_detected(&self);
+    /// `seconds` (whole, rounded up) are left before `max_duration` ends
+    /// the session, once per second during the last `time_warning_secs`.
+    /// Not sent in continuous mode, where the limit doesn't end the session.
+    fn on_time_remaining(&self, seconds: u32);
+    /// The model is loaded and speech is being transcribed. The microphone
+    /// opens as soon as the session starts, so words spoken while a model
+    /// is still loading are buffered, not lost; until this arrives a UI can
+    /// show "loading" instead of "listening".
+    fn on_ready(&self);
+    /// The outputs a dry run skipped, sent just before
+    /// `RecordingState::Done`. Only when `ListenConfig::dry_run` is on.
+    /// Includes the copy and paste the host would have done; hosts pass
+    /// the flag on in `OutputTarget::dry_run` so their plan skips them.
+    fn on_dry_run(&self, actions: Vec<output::OutputAction>);
 }
 
 /// Callbacks for model download progress.
@@ -93,8 +304,41 @@ pub trait DownloadProgressCallback: Send + Sync {
 pub struct ListenConfig {
     pub language: String,
     pub max_duration: u32,
+    /// Seconds before `max_duration` from which `on_time_remaining` counts
+    /// down. 0 disables.
+    pub time_warning_secs: u32,
+    /// Count `max_duration` only while the user is silent, up to the
+    /// 120s (3600s long-form) ceiling. Not used in continuous mode.
+    pub extend_while_speaking: bool,
     pub silence_duration_ms: u32,
     pub speech_threshold: f32,
+    /// Keep listening across utterances until stopped. `max_duration` then
+    /// caps each utterance instead of the whole session.
+    pub continuous: bool,
+    /// Transcribe the speech in chunks as it is recorded instead of all at
+    /// once, so hour-long sessions keep memory bounded.
+    pub long_form: bool,
+    /// Transcribe and emit the speech buffered so far whenever it reaches
+    /// this many seconds, without waiting for a pause. 0 disables.
+    pub flush_threshold_secs: u32,
+    /// Take audio from SessionHandle::feed_audio instead of the microphone,
+    /// for hosts that own audio capture (e.g. Android).
+    pub host_audio: bool,
+    /// Which post-processing stages run, e.g. `Raw` for callers that format
+    /// the text themselves. `Config` follows `text_processing`.
+    pub text_preset: TextProcPreset,
+    /// ISO-639-1 code to translate final segments into, with the translator
+    /// from `DiktoConfig::translation`. Empty leaves them as spoken.
+    pub translate_to: String,
+    /// Focused app when the session starts, for saved-session file names.
+    pub app_name: String,
+    /// Bundle identifier of the focused app, for do-not-disturb app rules.
+    pub bundle_id: String,
+    /// Offset of local time from UTC in seconds, for saved-session file names.
+    pub utc_offset_secs: i32,
+    /// Skip the session's outputs and report them with `on_dry_run`. See
+    /// `OutputConfig::dry_run`.
+    pub dry_run: bool,
 }
 
 impl Default for ListenConfig {
@@ -102,8 +346,20 @@ impl Default for ListenConfig {
         Self {
             language: "en".to_string(),
             max_duration: 30,
+            time_warning_secs: 5,
+            extend_while_speaking: false,
             silence_duration_ms: 1500,
             speech_threshold: 0.35,
+            continuous: false,
+            long_form: false,
+            flush_threshold_secs: 30,
+            host_audio: false,
+            text_preset: TextProcPreset::Config,
+            translate_to: String::new(),
+            app_name: String::new(),
+            bundle_id: String::new(),
+            utc_offset_secs: 0,
+            dry_run: false,
         }
     }
 }
@@ -113,23 +369,107 @@ impl From<&DiktoConfig> for ListenConfig {
         Self {
             language: cfg.language.clone(),
             max_duration: cfg.max_duration,
+            time_warning_secs: cfg.time_warning_secs,
+            extend_while_speaking: cfg.extend_while_speaking,
             silence_duration_ms: cfg.silence_duration_ms,
             speech_threshold: cfg.speech_threshold,
+            continuous: cfg.continuous_mode,
+            long_form: cfg.long_form,
+            flush_threshold_secs: cfg.flush_threshold_secs,
+            host_audio: false,
+            text_preset: TextProcPreset::Config,
+            translate_to: cfg.translation.target_language.clone(),
+            app_name: String::new(),
+            bundle_id: String::new(),
+            utc_offset_secs: 0,
+            dry_run: cfg.output.dry_run,
         }
     }
 }
 
+/// Longest `SessionHandle::stop_capture_now` waits for the microphone to close.
+const STOP_CAPTURE_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Figures the pipeline keeps current for its `SessionHandle`, so hosts can
+/// poll them instead of getting a callback for each change.
+#[derive(Default)]
+struct SessionStats {
+    /// When the session started listening; unset while it's queued.
+    started: std::sync::OnceLock<std::time::Instant>,
+    /// When the pipeline finished, so the elapsed time stops there.
+    finished: std::sync::OnceLock<std::time::Instant>,
+    speech_detected: AtomicBool,
+    /// `f32::to_bits` of the seconds of speech buffered.
+    buffered_secs: AtomicU32,
+}
+
+impl SessionStats {
+    fn update(&self, speech_detected: bool, buffered_secs: f32) {
+        self.speech_detected
+            .store(speech_detected, Ordering::Release);
+        self.buffered_secs
+            .store(buffered_secs.to_bits(), Ordering::Release);
+    }
+}
+
+/// Where a session is in its life, for `DiktoEngine::active_sessions`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum SessionState {
+    /// Waiting for the previous session to finish (`queue_sessions`).
+    Queued,
+    /// Listening, with the microphone (or host audio) open.
+    Recording,
+    /// Asked to stop; transcribing the last words.
+    Stopping,
+    /// Done, with its result delivered.
+    Finished,
+}
+
+/// Where a session's audio comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum SessionSource {
+    Microphone,
+    /// Pushed by the host through `SessionHandle::feed_audio`.
+    HostAudio,
+    /// The `AudioSource` the engine was built with.
+    Custom,
+}
+
+/// A session as listed by `DiktoEngine::active_sessions`.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct SessionSummary {
+    /// Unique for the engine's lifetime, in start order.
+    pub id: u64,
+    pub state: SessionState,
+    /// As in `SessionHandle::elapsed_secs`.
+    pub elapsed_secs: f64,
+    pub source: SessionSource,
+}
+
 /// Handle to stop a running recording session.
 #[derive(uniffi::Object)]
 pub struct SessionHandle {
+    id: u64,
+    source: SessionSource,
     stop_flag: Arc<AtomicBool>,
+    /// Present when the session was started with `host_audio`.
+    feed: Option<Arc<FeedBuffer>>,
+    capture: Arc<CaptureControl>,
+    stats: Arc<SessionStats>,
+    clock: Arc<dyn clock::Clock>,
 }
 
 impl SessionHandle {
     /// Create a SessionHandle for testing (not used by production code).
     pub fn new_for_test() -> Self {
         Self {
+            id: 0,
+            source: SessionSource::Microphone,
             stop_flag: Arc::new(AtomicBool::new(false)),
+            feed: None,
+            capture: Arc::default(),
+            stats: Arc::default(),
+            clock: Arc::new(clock::SystemClock),
         }
     }
 }
@@ -141,10 +481,134 @@ impl SessionHandle {
         self.stop_flag.store(true, Ordering::Release);
     }
 
+    /// Stop the session and close the microphone before returning, instead
+    /// of when the pipeline next checks in. The speech so far is still
+    /// transcribed. Returns false if the microphone was still open after
+    /// `STOP_CAPTURE_TIMEOUT`.
+    pub fn stop_capture_now(&self) -> bool {
+        self.stop();
+        self.capture.halt(STOP_CAPTURE_TIMEOUT)
+    }
+
     /// Check if the session is still active.
     pub fn is_active(&self) -> bool {
         !self.stop_flag.load(Ordering::Acquire)
     }
+
+    /// Push 16kHz mono samples into a `host_audio` session. Ignored for
+    /// sessions that capture from the microphone.
+    pub fn feed_audio(&self, samples: Vec<f32>) {
+        match &self.feed {
+            Some(feed) => feed.push(&samples),
+            None => warn!("feed_audio called on a microphone session, ignoring"),
+        }
+    }
+
+    /// Seconds since the session started, for a recording timer. 0 while
+    /// it is queued behind the previous session; stops counting when the
+    /// session finishes.
+    pub fn elapsed_secs(&self) -> f64 {
+        let Some(started) = self.stats.started.get() else {
+            return 0.0;
+        };
+        let end = self
+            .stats
+            .finished
+            .get()
+            .copied()
+            .unwrap_or_else(|| self.clock.now());
+        end.saturating_duration_since(*started).as_secs_f64()
+    }
+
+    /// Seconds of speech recorded for the current utterance and not yet
+    /// transcribed.
+    pub fn buffered_audio_secs(&self) -> f32 {
+        f32::from_bits(self.stats.buffered_secs.load(Ordering::Acquire))
+    }
+
+    /// Whether the current utterance has begun. False while the session is
+    /// still waiting for speech, and again after each utterance in
+    /// continuous mode.
+    pub fn speech_detected(&self) -> bool {
+        self.stats.speech_detected.load(Ordering::Acquire)
+    }
+
+    /// The session's id, state, running time, and audio source.
+    pub fn summary(&self) -> SessionSummary {
+        let state = if self.stats.finished.get().is_some() {
+            SessionState::Finished
+        } else if self.stats.started.get().is_none() {
+            SessionState::Queued
+        } else if self.is_active() {
+            SessionState::Recording
+        } else {
+            SessionState::Stopping
+        };
+        SessionSummary {
+            id: self.id,
+            state,
+            elapsed_secs: self.elapsed_secs(),
+            source: self.source,
+        }
+    }
+}
+
+/// Handle to cancel a `switch_model_with_download` in progress.
+#[derive(Default, uniffi::Object)]
+pub struct ModelSwitchHandle {
+    cancelled: AtomicBool,
+}
+
+#[uniffi::export]
+impl ModelSwitchHandle {
+    /// Cancel the switch. A running download stops and can be resumed
+    /// later; the config is left unchanged.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Whether cancel() was called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// Result of `DiktoEngine::self_test`.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct SelfTestReport {
+    /// True when every stage worked.
+    pub passed: bool,
+    pub model_name: String,
+    /// False when synthetic audio stood in for recorded speech, in which
+    /// case empty VAD or model output is not counted as a failure.
+    pub used_speech_sample: bool,
+    /// Speech VAD found in the sample, in ms.
+    pub vad_speech_ms: u64,
+    /// Time to load the model; near 0 if it was already loaded.
+    pub load_ms: u64,
+    pub transcribe_ms: u64,
+    pub transcript: String,
+    /// What went wrong, one entry per failed stage.
+    pub problems: Vec<String>,
+}
+
+/// What `DiktoEngine::transcribe_files` did with one file.
+#[derive(Debug, Clone, PartialEq, uniffi::Enum)]
+pub enum FileTranscription {
+    Transcribed {
+        path: String,
+        fingerprint: String,
+        result: TranscriptionResult,
+    },
+    /// Skipped: the same audio is in history, or earlier in the batch.
+    AlreadyTranscribed {
+        path: String,
+        fingerprint: String,
+    },
+    Failed {
+        path: String,
+        message: String,
+    },
 }
 
 /// Owned model info record for FFI.
@@ -154,7 +618,108 @@ pub struct ModelInfoRecord {
     pub size_mb: u32,
     pub description: String,
     pub is_downloaded: bool,
+    /// Finer-grained than `is_downloaded`: tells an interrupted or truncated
+    /// download apart from one never started.
+    pub state: ModelState,
     pub backend: String,
+    /// Stable aliases ("default", "multilingual", ...) that resolve to this model.
+    pub aliases: Vec<String>,
+    /// Approximate peak RAM while transcribing, in MB. 0 when unknown.
+    pub ram_mb: u32,
+    /// Approximate real-time factor on an M1; lower is faster. 0 when unknown.
+    pub relative_speed: f32,
+    /// Approximate average English WER (%); lower is more accurate. 0 when unknown.
+    pub wer: f32,
+    /// ISO-639-1 codes the model transcribes.
+    pub languages: Vec<String>,
+    /// Files making up the model and what is on disk for each.
+    pub files: Vec<ModelFileRecord>,
+}
+
+/// One file of a model, for FFI.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct ModelFileRecord {
+    pub filename: String,
+    /// Expected size. 0 for user models, which have no registry entry.
+    pub size_mb: u32,
+    /// Expected SHA-256 (hex). Empty when the file is verified by size only.
+    pub sha256: String,
+    pub state: ModelFileState,
+}
+
+/// How much of a model is on disk.
+#[derive(Debug, Clone, Copy, PartialEq, uniffi::Enum)]
+pub enum ModelState {
+    NotDownloaded,
+    /// Some files are missing, still downloading, or the wrong size.
+    PartiallyDownloaded,
+    Downloaded,
+}
+
+/// What is on disk for one model file.
+#[derive(Debug, Clone, Copy, PartialEq, uniffi::Enum)]
+pub enum ModelFileState {
+    Missing,
+    /// A download was interrupted or is running.
+    Partial {
+        bytes: u64,
+    },
+    Present {
+        bytes: u64,
+    },
+}
+
+/// Order for `list_models_filtered`. Models with unknown metadata sort last.
+#[derive(Debug, Clone, Copy, PartialEq, uniffi::Enum)]
+pub enum ModelSortKey {
+    /// Registry order, then user models by name.
+    Default,
+    Name,
+    /// Smallest download first.
+    Size,
+    /// Lowest WER first.
+    Accuracy,
+    /// Fastest first.
+    Speed,
+}
+
+/// Filter and sort model records for a picker. `backend` matches the
+/// record's backend label ("Parakeet", "Whisper", ...) ignoring case and
+/// `language` an ISO-639-1 code; `None` accepts any.
+pub fn filter_models(
+    models: Vec<ModelInfoRecord>,
+    backend: Option<String>,
+    language: Option<String>,
+    downloaded_only: bool,
+    sort_by: ModelSortKey,
+) -> Vec<ModelInfoRecord> {
+    let mut models: Vec<ModelInfoRecord> = models
+        .into_iter()
+        .filter(|m| !downloaded_only || m.is_downloaded)
+        .filter(|m| {
+            backend
+                .as_deref()
+                .is_none_or(|b| m.backend.eq_ignore_ascii_case(b))
+        })
+        .filter(|m| {
+            language
+                .as_deref()
+                .is_none_or(|l| m.languages.iter().any(|code| code == l))
+        })
+        .collect();
+
+    // 0 marks an unknown estimate
+    let known = |v: f32| if v > 0.0 { v } else { f32::INFINITY };
+    match sort_by {
+        ModelSortKey::Default => {}
+        ModelSortKey::Name => models.sort_by(|a, b| a.name.cmp(&b.name)),
+        ModelSortKey::Size => models.sort_by_key(|m| m.size_mb),
+        ModelSortKey::Accuracy => models.sort_by(|a, b| known(a.wer).total_cmp(&known(b.wer))),
+        ModelSortKey::Speed => {
+            models.sort_by(|a, b| known(a.relative_speed).total_cmp(&known(b.relative_speed)))
+        }
+    }
+    models
 }
 
 /// Language info record for FFI.
@@ -164,21 +729,260 @@ pub struct LanguageInfo {
     pub name: String,
 }
 
+/// A language pack (see `models::LanguagePack`) for FFI.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct LanguagePackRecord {
+    pub language: String,
+    pub name: String,
+    pub model: String,
+    pub model_state: ModelState,
+    /// Phrases spoken for punctuation ("komma", "neue zeile"), with the
+    /// user's `language_overrides` applied.
+    pub spoken_punctuation: Vec<String>,
+    /// Separator put into long numbers ("12.345"); empty for none.
+    pub thousands_separator: String,
+    /// The config dictates in this language with this pack's model.
+    pub active: bool,
+}
+
+/// Creates the audio source for each session of an engine built with
+/// `DiktoEngineBuilder::audio_source`.
+#[cfg(feature = "native")]
+pub type AudioSourceFactory = Arc<dyn Fn() -> Box<dyn AudioSource> + Send + Sync>;
+
+/// Builds a DiktoEngine for Rust apps that embed it, with the config, models
+/// directory, and audio supplied by the app instead of taken from the
+/// user's files and microphone. `DiktoEngine::new()` is `build()` with
+/// nothing set.
+///
+/// Paths are not per engine. A models directory or a config `data_dir`
+/// given here is installed for the whole process, and replaces the one set
+/// by any engine built before it. Engines built without them leave the
+/// current paths alone.
+#[cfg(feature = "native")]
+#[derive(Default)]
+pub struct DiktoEngineBuilder {
+    config: Option<DiktoConfig>,
+    models_dir: Option<std::path::PathBuf>,
+    audio_source: Option<AudioSourceFactory>,
+    clock: Option<Arc<dyn clock::Clock>>,
+    without_config_file: bool,
+}
+
+#[cfg(feature = "native")]
+impl DiktoEngineBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start from this config instead of config.json. It is validated the
+    /// same way a loaded config is.
+    pub fn config(mut self, config: DiktoConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Look for and download models in `dir`. Like `set_path_provider`, this
+    /// applies to the whole process.
+    pub fn models_dir(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.models_dir = Some(dir.into());
+        self
+    }
+
+    /// Take each session's audio from a source made by `make_source`
+    /// instead of the microphone. Sessions started with `host_audio` still
+    /// read from `SessionHandle::feed_audio`.
+    pub fn audio_source<F>(mut self, make_source: F) -> Self
+    where
+        F: Fn() -> Box<dyn AudioSource> + Send + Sync + 'static,
+    {
+        self.audio_source = Some(Arc::new(make_source));
+        self
+    }
+
+    /// Time sessions with `clock` instead of the system clock, e.g. a
+    /// `clock::MockClock` in tests that check max_duration.
+    pub fn clock(mut self, clock: Arc<dyn clock::Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Never read or write config.json: start from the injected config (or
+    /// the defaults) and keep later changes in memory.
+    pub fn without_config_file(mut self) -> Self {
+        self.without_config_file = true;
+        self
+    }
+
+    /// Build the engine. Sets the process-wide models directory and data
+    /// directory when they were given (see above).
+    pub fn build(self) -> DiktoEngine {
+        if let Some(models) = self.models_dir {
+            config::set_path_provider(Some(Arc::new(ModelsDirPaths::new(models))));
+        }
+        let config = match self.config {
+            Some(mut config) => {
+                config.validate();
+                config
+            }
+            None if self.without_config_file => DiktoConfig::default(),
+            None => load_user_config(),
+        };
+        if config.data_dir.is_some() {
+            config::set_data_dir_override(config.data_dir.as_deref());
+        }
+        let state = if self.without_config_file {
+            state::EngineState::default()
+        } else {
+            state::load_state()
+        };
+        let interrupted = models::interrupted_downloads();
+        if !interrupted.is_empty() {
+            let names: Vec<&str> = interrupted.iter().map(|m| m.name).collect();
+            info!("Interrupted downloads can be resumed: {}", names.join(", "));
+        }
+        DiktoEngine {
+            inner: Arc::new(Mutex::new(DiktoEngineInner {
+                worker: Arc::new(InferenceWorker::spawn()),
+                config,
+                recording: Arc::default(),
+                context_hint: None,
+                persist_config: !self.without_config_file,
+                audio_source: self.audio_source,
+                session_stop: None,
+                sessions: Vec::new(),
+                next_session_id: 1,
+                session_queued: Arc::new(AtomicBool::new(false)),
+                screen_sharing: false,
+                rate_limiter: rate_limit::SessionRateLimiter::default(),
+                state,
+                clock: self.clock.unwrap_or_else(|| Arc::new(clock::SystemClock)),
+            })),
+        }
+    }
+}
+
+/// Keeps the current config and data directories but moves the models.
+#[cfg(feature = "native")]
+struct ModelsDirPaths {
+    config: std::path::PathBuf,
+    data: std::path::PathBuf,
+    models: std::path::PathBuf,
+}
+
+#[cfg(feature = "native")]
+impl ModelsDirPaths {
+    fn new(models: std::path::PathBuf) -> Self {
+        let fallback = || std::path::PathBuf::from(".");
+        Self {
+            config: config::config_dir().unwrap_or_else(|_| fallback()),
+            data: config::data_dir().unwrap_or_else(|_| fallback()),
+            models,
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+impl config::PathProvider for ModelsDirPaths {
+    fn config_dir(&self) -> String {
+        self.config.to_string_lossy().into_owned()
+    }
+
+    fn data_dir(&self) -> String {
+        self.data.to_string_lossy().into_owned()
+    }
+
+    fn models_dir(&self) -> Option<String> {
+        Some(self.models.to_string_lossy().into_owned())
+    }
+}
+
+/// Load config.json, moving configs that still name an old v1 Whisper
+/// model to the Parakeet default.
+#[cfg(feature = "native")]
+fn load_user_config() -> DiktoConfig {
+    let mut config = config::load_config();
+    if OLD_WHISPER_MODEL_NAMES.contains(&config.model_name.as_str()) {
+        warn!(
+            "Migrating config from old Whisper model '{}' to Parakeet default",
+            config.model_name
+        );
+        config.model_name = config::default_model_name();
+        if let Err(e) = config::save_config(&config) {
+            warn!("Failed to save migrated config: {e}");
+        }
+    }
+    config
+}
+
 /// Inner state of DiktoEngine, behind a Mutex for UniFFI compatibility.
+#[cfg(feature = "native")]
 struct DiktoEngineInner {
-    /// Shared engine holder — None when no model is loaded in RAM.
+    /// Inference actor that owns the loaded model (if any).
     /// Arc allows sharing with pipeline threads for lazy loading.
-    engine: Arc<Mutex<Option<LoadedEngine>>>,
+    worker: Arc<InferenceWorker>,
     config: DiktoConfig,
-    recording: Arc<AtomicBool>,
+    recording: Arc<watchdog::RecordingFlag>,
+    /// Host-supplied context (nearby document text or app name), already
+    /// normalized for use as a Whisper initial prompt.
+    context_hint: Option<String>,
+    /// Write config and state changes to config.json and state.json (off
+    /// for `without_config_file`).
+    persist_config: bool,
+    /// Audio for sessions that don't use host audio, instead of the microphone.
+    audio_source: Option<AudioSourceFactory>,
+    /// Stop flag of the most recent session.
+    session_stop: Option<Arc<AtomicBool>>,
+    /// Running and queued sessions; finished ones are dropped on the next
+    /// start or listing.
+    sessions: Vec<Arc<SessionHandle>>,
+    /// Id for the next `SessionHandle`.
+    next_session_id: u64,
+    /// A session is waiting for the current one to finish.
+    session_queued: Arc<AtomicBool>,
+    /// The host reported that the screen is being shared.
+    screen_sharing: bool,
+    /// Recent session starts, for `advanced.max_sessions_per_minute`.
+    rate_limiter: rate_limit::SessionRateLimiter,
+    /// Remembered across launches, restored from state.json at build.
+    state: state::EngineState,
+    /// Times sessions and rate limiting.
+    clock: Arc<dyn clock::Clock>,
+}
+
+#[cfg(feature = "native")]
+impl DiktoEngineInner {
+    /// Save `config` to config.json, unless the engine keeps its config in
+    /// memory only.
+    fn persist(&self, config: &DiktoConfig) -> Result<(), DiktoError> {
+        if !self.persist_config {
+            return Ok(());
+        }
+        config::save_config(config).map_err(|e| DiktoError::Config(e.to_string()))
+    }
+
+    /// Save `state` to state.json, unless the engine keeps everything in
+    /// memory. Failures are only logged: the state is a convenience.
+    fn persist_state(&self) {
+        if !self.persist_config {
+            return;
+        }
+        if let Err(e) = state::save_state(&self.state) {
+            warn!("Failed to save engine state: {e}");
+        }
+    }
 }
 
 /// The main Dikto engine. Models are loaded lazily into RAM on first recording.
+#[cfg(feature = "native")]
 #[derive(uniffi::Object)]
 pub struct DiktoEngine {
-    inner: Mutex<DiktoEngineInner>,
+    /// Arc so a pipeline thread can roll back the config after a failed
+    /// lazy load.
+    inner: Arc<Mutex<DiktoEngineInner>>,
 }
 
+#[cfg(feature = "native")]
 #[uniffi::export]
 impl DiktoEngine {
     /// Create a new DiktoEngine. Does NOT load any model into RAM.
@@ -186,66 +990,43 @@ impl DiktoEngine {
     #[uniffi::constructor]
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        let mut config = config::load_config();
-
-        // Auto-migrate old v1 Whisper model names to Parakeet default
-        if OLD_WHISPER_MODEL_NAMES.contains(&config.model_name.as_str()) {
-            warn!(
-                "Migrating config from old Whisper model '{}' to Parakeet default",
-                config.model_name
-            );
-            config.model_name = config::default_model_name();
-            if let Err(e) = config::save_config(&config) {
-                warn!("Failed to save migrated config: {e}");
-            }
-        }
+        DiktoEngineBuilder::new().build()
+    }
 
-        Self {
-            inner: Mutex::new(DiktoEngineInner {
-                engine: Arc::new(Mutex::new(None)),
-                config,
-                recording: Arc::new(AtomicBool::new(false)),
-            }),
-        }
+    /// Create a DiktoEngine whose config, data, and models live where
+    /// `paths` says, e.g. App Group containers in a sandboxed build.
+    /// The provider stays installed for the life of the process.
+    #[uniffi::constructor]
+    pub fn with_path_provider(paths: Arc<dyn config::PathProvider>) -> Self {
+        config::set_path_provider(Some(paths));
+        Self::new()
     }
 
     /// Explicitly load the configured model into RAM.
     /// Optional — start_listening() will lazy-load if needed.
     pub fn load_model(&self) -> Result<(), DiktoError> {
-        let inner = self
+        let mut inner = self
             .inner
             .lock()
             .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
-        let model_name = inner.config.model_name.clone();
-        let model_info = models::find_model(&model_name).ok_or(DiktoError::NoModel)?;
-        let path = models::model_path(&model_name).ok_or(DiktoError::NoModel)?;
+        let model_name = models::canonical_model_name(&inner.config.model_name);
+        let (backend, path) = locate_model(&inner.config, &model_name)?;
 
-        if !models::is_model_downloaded(&model_name) {
-            return Err(DiktoError::NoModel);
-        }
-
-        let asr = AsrEngine::load(model_info.backend, &path)?;
-        *inner
-            .engine
-            .lock()
-            .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))? = Some(LoadedEngine {
-            model_name: model_name.clone(),
-            engine: asr,
-        });
+        let loaded = inner.worker.load(&model_name, backend, &path);
+        after_model_load(&mut inner, &model_name, loaded)?;
         info!("Model '{}' loaded and ready", model_name);
         Ok(())
     }
 
+    /// Async variant of load_model for hosts with async/await.
+    pub async fn load_model_async(self: Arc<Self>) -> Result<(), DiktoError> {
+        run_blocking(move || self.load_model()).await
+    }
+
     /// Unload the current model from RAM, freeing memory.
     pub fn unload_model(&self) {
         let Ok(inner) = self.inner.lock() else { return };
-        let was_loaded = inner
-            .engine
-            .lock()
-            .ok()
-            .and_then(|mut g| g.take())
-            .is_some();
-        if was_loaded {
+        if inner.worker.unload() {
             info!("Model unloaded from RAM");
         }
     }
@@ -258,24 +1039,26 @@ impl DiktoEngine {
             .lock()
             .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
 
-        if inner.recording.load(Ordering::Acquire) {
+        if inner.recording.is_set() {
             return Err(DiktoError::AlreadyRecording);
         }
 
         // Verify model exists and is downloaded
-        let _ = models::find_model(&model_name).ok_or(DiktoError::NoModel)?;
+        let _ = models::model_backend(&model_name).ok_or(DiktoError::NoModel)?;
         if !models::is_model_downloaded(&model_name) {
             return Err(DiktoError::NoModel);
         }
 
         // Unload old model from RAM
-        if let Ok(mut guard) = inner.engine.lock() {
-            *guard = None;
-        }
+        inner.worker.unload();
 
-        // Save new model choice
-        inner.config.model_name = model_name.clone();
-        config::save_config(&inner.config).map_err(|e| DiktoError::Config(e.to_string()))?;
+        // Save new model choice. An override names a file of the old model.
+        let previous = std::mem::replace(&mut inner.config.model_name, model_name.clone());
+        if models::canonical_model_name(&previous) != models::canonical_model_name(&model_name) {
+            inner.config.previous_model = Some(previous);
+        }
+        inner.config.model_path_override = None;
+        inner.persist(&inner.config)?;
         info!(
             "Switched to model '{}' (will load on next recording)",
             model_name
@@ -283,132 +1066,137 @@ impl DiktoEngine {
         Ok(())
     }
 
+    /// Switch to a model, downloading it first if needed, on a background
+    /// thread. `callback` receives download progress, then on_complete once
+    /// the files are verified, the config saved, and (with `preload`) the
+    /// model loaded into RAM, or on_error on failure or cancellation.
+    pub fn switch_model_with_download(
+        self: Arc<Self>,
+        model_name: String,
+        preload: bool,
+        callback: Arc<dyn DownloadProgressCallback>,
+    ) -> Result<Arc<ModelSwitchHandle>, DiktoError> {
+        let hf_cache = {
+            let inner = self
+                .inner
+                .lock()
+                .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
+            if inner.recording.is_set() {
+                return Err(DiktoError::AlreadyRecording);
+            }
+            huggingface_cache(&inner.config)
+        };
+        let _ = models::model_backend(&model_name)
+            .ok_or_else(|| DiktoError::Model(format!("Unknown model: {model_name}")))?;
+
+        let handle = Arc::new(ModelSwitchHandle::default());
+        let cancel = handle.clone();
+        std::thread::spawn(move || {
+            let result = download_and_switch(
+                &self,
+                &model_name,
+                preload,
+                hf_cache.as_deref(),
+                &cancel,
+                &callback,
+            );
+            match result {
+                Ok(()) => callback.on_complete(model_name),
+                Err(e) => callback.on_error(e.to_string()),
+            }
+        });
+        Ok(handle)
+    }
+
     /// Start listening and transcribing. Returns a handle to stop the session.
     /// Lazy-loads the model into RAM if not already loaded.
     /// The final result is delivered via the callback's on_state_change(Done { text }).
+    /// With `queue_sessions` on, a call made while the previous session is
+    /// still finishing up waits for it instead of failing with AlreadyRecording.
     pub fn start_listening(
         &self,
         listen_config: ListenConfig,
         callback: Arc<dyn TranscriptionCallback>,
     ) -> Result<Arc<SessionHandle>, DiktoError> {
-        let inner = self
-            .inner
-            .lock()
-            .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
-
-        if inner.recording.load(Ordering::Acquire) {
-            return Err(DiktoError::AlreadyRecording);
-        }
-
-        // Verify model is available on disk
-        let model_name = inner.config.model_name.clone();
-        let model_info = models::find_model(&model_name).ok_or(DiktoError::NoModel)?;
-        if !models::is_model_downloaded(&model_name) {
-            return Err(DiktoError::NoModel);
-        }
-
-        let engine_holder = inner.engine.clone();
-        let backend = model_info.backend;
-        let model_path = models::model_path(&model_name).ok_or(DiktoError::NoModel)?;
-
-        let stop_flag = Arc::new(AtomicBool::new(false));
+        let feed = listen_config.host_audio.then(|| {
+            Arc::new(FeedBuffer::new(
+                AudioCaptureConfig::default().buffer_capacity,
+            ))
+        });
+        let (id, source, clock) = {
+            let mut inner = self
+                .inner
+                .lock()
+                .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
+            let id = inner.next_session_id;
+            inner.next_session_id += 1;
+            let source = match (&feed, &inner.audio_source) {
+                (Some(_), _) => SessionSource::HostAudio,
+                (None, Some(_)) => SessionSource::Custom,
+                (None, None) => SessionSource::Microphone,
+            };
+            (id, source, inner.clock.clone())
+        };
         let handle = Arc::new(SessionHandle {
-            stop_flag: stop_flag.clone(),
+            id,
+            source,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            feed,
+            capture: Arc::default(),
+            stats: Arc::default(),
+            clock,
         });
+        match self.begin_session(listen_config.clone(), callback.clone(), &handle) {
+            Err(DiktoError::AlreadyRecording) if self.reserve_queue_slot() => {
+                debug!("Previous session still finishing, queueing the new one");
+                self.queue_session(listen_config, callback, handle.clone());
+            }
+            result => result?,
+        }
+        if let Ok(mut inner) = self.inner.lock() {
+            inner
+                .sessions
+                .retain(|s| s.summary().state != SessionState::Finished);
+            inner.sessions.push(handle.clone());
+        }
+        Ok(handle)
+    }
 
-        let recording = inner.recording.clone();
-        recording.store(true, Ordering::Release);
-
-        let max_duration = listen_config.max_duration;
-        let silence_duration_ms = listen_config.silence_duration_ms;
-        let speech_threshold = listen_config.speech_threshold;
-        let language = listen_config.language.clone();
-
-        drop(inner); // Release outer lock before spawning
-
-        std::thread::spawn(move || {
-            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                // Lazy-load model if needed
-                let needs_load = {
-                    let guard = engine_holder
-                        .lock()
-                        .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
-                    !matches!(&*guard, Some(loaded) if loaded.model_name == model_name)
-                };
-
-                if needs_load {
-                    callback.on_state_change(RecordingState::Processing);
-                    callback.on_partial("Loading model...".to_string());
-                    debug!("Lazy-loading model '{}'...", model_name);
-
-                    match AsrEngine::load(backend, &model_path) {
-                        Ok(asr) => {
-                            let mut guard = engine_holder
-                                .lock()
-                                .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
-                            *guard = Some(LoadedEngine {
-                                model_name: model_name.clone(),
-                                engine: asr,
-                            });
-                            debug!("Model '{}' loaded into RAM", model_name);
-                        }
-                        Err(e) => {
-                            recording.store(false, Ordering::Release);
-                            callback.on_state_change(RecordingState::Error {
-                                message: format!("Failed to load model: {e}"),
-                            });
-                            return Ok(());
-                        }
-                    }
-                }
-
-                // Create transcription session
-                let transcribe_config = TranscribeConfig { language };
-                let session = {
-                    let guard = engine_holder
-                        .lock()
-                        .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
-                    let loaded = guard.as_ref().ok_or(DiktoError::NoModel)?;
-                    loaded.engine.create_session(transcribe_config)
-                };
-
-                let result = run_pipeline(
-                    session,
-                    &engine_holder,
-                    stop_flag,
-                    callback.clone(),
-                    max_duration,
-                    silence_duration_ms,
-                    speech_threshold,
-                );
-
-                recording.store(false, Ordering::Release);
-
-                match &result {
-                    Ok(text) => {
-                        debug!("pipeline done, text_len={}", text.len());
-                        callback.on_state_change(RecordingState::Done { text: text.clone() });
-                    }
-                    Err(e) => {
-                        warn!("pipeline error: {e}");
-                        callback.on_state_change(RecordingState::Error {
-                            message: e.to_string(),
-                        });
-                    }
-                }
-
-                Ok::<(), DiktoError>(())
-            }));
-
-            if let Err(_panic) = result {
-                recording.store(false, Ordering::Release);
-                callback.on_state_change(RecordingState::Error {
-                    message: "Internal error (thread panic)".to_string(),
-                });
+    /// Sessions that are queued, recording, or transcribing their last
+    /// words, oldest first, e.g. for a recording indicator.
+    pub fn active_sessions(&self) -> Vec<SessionSummary> {
+        let Ok(mut inner) = self.inner.lock() else {
+            return Vec::new();
+        };
+        let mut summaries = Vec::new();
+        inner.sessions.retain(|s| {
+            let summary = s.summary();
+            let active = summary.state != SessionState::Finished;
+            if active {
+                summaries.push(summary);
             }
+            active
         });
+        summaries
+    }
 
-        Ok(handle)
+    /// Stop every session, queued ones included, and close the microphone
+    /// before returning, for a panic button. Speech already recorded is
+    /// still transcribed. Returns false if a microphone was still open
+    /// after `STOP_CAPTURE_TIMEOUT`.
+    pub fn stop_all(&self) -> bool {
+        let sessions = match self.inner.lock() {
+            Ok(inner) => inner.sessions.clone(),
+            Err(e) => {
+                warn!("stop_all: lock poisoned ({e})");
+                return false;
+            }
+        };
+        let mut closed = true;
+        for session in &sessions {
+            closed &= session.stop_capture_now();
+        }
+        closed
     }
 
     /// Get a copy of the current config.
@@ -422,34 +1210,191 @@ impl DiktoEngine {
         }
     }
 
-    /// Update config and save.
-    pub fn update_config(&self, config: DiktoConfig) -> Result<(), DiktoError> {
-        let mut inner = self
-            .inner
-            .lock()
-            .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
-        config::save_config(&config).map_err(|e| DiktoError::Config(e.to_string()))?;
-        inner.config = config;
-        Ok(())
+    /// Set context for upcoming dictation: the current document's nearby text
+    /// or the frontmost app's name. Whisper uses it as an initial prompt so
+    /// terminology matches the surrounding content. An empty string clears it.
+    pub fn set_context_hint(&self, text: String) {
+        match self.inner.lock() {
+            Ok(mut inner) => inner.context_hint = context::normalize_hint(&text),
+            Err(e) => warn!("set_context_hint: lock poisoned ({e})"),
+        }
     }
 
-    /// List available models with download status.
-    pub fn list_models(&self) -> Vec<ModelInfoRecord> {
-        models::list_models()
-            .into_iter()
-            .map(|(m, downloaded)| ModelInfoRecord {
-                name: m.name.to_string(),
-                size_mb: m.size_mb,
-                description: m.description.to_string(),
-                is_downloaded: downloaded,
-                backend: match m.backend {
-                    ModelBackend::Parakeet => "Parakeet".to_string(),
-                    ModelBackend::Whisper => "Whisper".to_string(),
-                },
-            })
+    /// Tell the engine whether the screen is being shared or recorded, so
+    /// `do_not_disturb.while_screen_sharing` can refuse new sessions.
+    pub fn set_screen_sharing(&self, active: bool) {
+        match self.inner.lock() {
+            Ok(mut inner) => inner.screen_sharing = active,
+            Err(e) => warn!("set_screen_sharing: lock poisoned ({e})"),
+        }
+    }
+
+    /// Tell the engine whether the OS allows microphone access, e.g. from
+    /// macOS `AVCaptureDevice.authorizationStatus`. With `Denied`, sessions
+    /// fail with `DiktoError::PermissionDenied` instead of recording
+    /// silence; with `Granted`, a silent stream is never taken for denied
+    /// access.
+    pub fn set_microphone_permission(&self, permission: audio::MicrophonePermission) {
+        audio::set_microphone_permission(permission);
+    }
+
+    /// Whether a session has the microphone open, for the host's mic-in-use
+    /// indicator. Goes false as soon as the session stops listening, before
+    /// the last words are transcribed.
+    pub fn is_microphone_open(&self) -> bool {
+        audio::is_microphone_open()
+    }
+
+    /// Format a final transcript for the app it's going to, using the
+    /// matching app profile, and say whether to paste it there.
+    pub fn prepare_output(&self, text: String, target: output::OutputTarget) -> output::OutputPlan {
+        let config = self.get_config();
+        output::prepare_output(&text, &target, &config)
+    }
+
+    /// Format a final segment for incremental paste and cut it into the
+    /// chunks to paste, in order. See `OutputConfig::incremental_paste`.
+    pub fn prepare_segment_output(
+        &self,
+        text: String,
+        target: output::OutputTarget,
+    ) -> Vec<String> {
+        let config = self.get_config();
+        output::prepare_segment_output(&text, &target, &config)
+    }
+
+    /// `prepare_output` with the transcript's summary too, so the routes in
+    /// `OutputConfig::routes` can send it to the clipboard or the paste.
+    pub fn route_output(
+        &self,
+        texts: output::TranscriptTexts,
+        target: output::OutputTarget,
+    ) -> output::OutputPlan {
+        let config = self.get_config();
+        output::route_output(&texts, &target, &config)
+    }
+
+    /// Listen settings derived from the current config.
+    pub fn listen_config(&self) -> ListenConfig {
+        ListenConfig::from(&self.get_config())
+    }
+
+    /// Listen settings for a session whose text goes to `target`, with that
+    /// app's profile applied (e.g. its translation language), the language
+    /// last dictated there, and its name and clock used for saved-session
+    /// file names.
+    pub fn listen_config_for(&self, target: output::OutputTarget) -> ListenConfig {
+        let config = self.get_config();
+        let mut listen = ListenConfig::from(&config);
+        listen.app_name = target.app_name.clone();
+        listen.bundle_id = target.bundle_id.clone();
+        listen.utc_offset_secs = target.utc_offset_secs;
+        let remembered = self.inner.lock().ok().and_then(|inner| {
+            let app = app_key(&target.bundle_id, &target.app_name);
+            inner.state.language_for_app(app).map(str::to_string)
+        });
+        if let Some(language) = remembered {
+            listen.language = language;
+        }
+        if let Some(profile) = output::find_profile(&config.output.app_profiles, &target) {
+            if let Some(language) = &profile.translate_to {
+                listen.translate_to = language.clone();
+            }
+        }
+        listen
+    }
+
+    /// What the engine remembered from earlier runs (see `state`), e.g.
+    /// downloads to restart after the app was quit during them.
+    pub fn saved_state(&self) -> state::EngineState {
+        match self.inner.lock() {
+            Ok(inner) => inner.state.clone(),
+            Err(e) => {
+                warn!("saved_state: lock poisoned ({e}), returning defaults");
+                state::EngineState::default()
+            }
+        }
+    }
+
+    /// Update config and save.
+    pub fn update_config(&self, config: DiktoConfig) -> Result<(), DiktoError> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
+        for warning in config.lint() {
+            warn!("Config: {warning}");
+        }
+        inner.persist(&config)?;
+        // The worker caches by model name, so a new file for the same name
+        // must be loaded from scratch
+        if config.model_path_override != inner.config.model_path_override {
+            inner.worker.unload();
+        }
+        if config.data_dir != inner.config.data_dir {
+            config::set_data_dir_override(config.data_dir.as_deref());
+        }
+        inner.config = config;
+        Ok(())
+    }
+
+    /// Settings in `config` that conflict with each other, for a settings
+    /// screen to show before saving. `update_config` logs the same warnings.
+    pub fn lint_config(&self, config: DiktoConfig) -> Vec<ConfigWarning> {
+        config.lint()
+    }
+
+    /// Preferences to share with the user's other machines, as a versioned
+    /// JSON document. Models, paths, and other device-local fields are left
+    /// out (see `sync::DEVICE_LOCAL_FIELDS`).
+    pub fn export_sync_blob(&self) -> String {
+        sync::export_settings(&self.get_config(), usage::unix_now())
+    }
+
+    /// Apply preferences exported on another machine and save them,
+    /// keeping this machine's device-local fields.
+    pub fn apply_sync_blob(&self, blob: String) -> Result<(), DiktoError> {
+        let config = sync::apply_settings(&self.get_config(), &blob)
+            .map_err(|e| DiktoError::Config(e.to_string()))?;
+        self.update_config(config)
+    }
+
+    /// List available models with download status.
+    pub fn list_models(&self) -> Vec<ModelInfoRecord> {
+        models::MODELS
+            .iter()
+            .map(|m| registry_model_record(m, models::model_state(m.name)))
+            .chain(models::list_custom_models().iter().map(custom_model_record))
             .collect()
     }
 
+    /// Details of one model by name or alias, including the state of each
+    /// of its files, or None if no such model exists.
+    pub fn model_info(&self, name: String) -> Option<ModelInfoRecord> {
+        match models::find_model(&name) {
+            Some(m) => Some(registry_model_record(m, models::model_state(m.name))),
+            None => models::find_custom_model(&name).map(|m| custom_model_record(&m)),
+        }
+    }
+
+    /// List models matching a backend label, a language code, and download
+    /// status, in the given order. See `filter_models`.
+    pub fn list_models_filtered(
+        &self,
+        backend: Option<String>,
+        language: Option<String>,
+        downloaded_only: bool,
+        sort_by: ModelSortKey,
+    ) -> Vec<ModelInfoRecord> {
+        filter_models(
+            self.list_models(),
+            backend,
+            language,
+            downloaded_only,
+            sort_by,
+        )
+    }
+
     /// Download a model with progress reporting via callback.
     pub fn download_model(
         &self,
@@ -461,6 +1406,13 @@ impl DiktoEngine {
             .ok_or_else(|| DiktoError::Model(format!("Unknown model: {model_name}")))?;
 
         let name = model_name.clone();
+        let hf_cache = self
+            .inner
+            .lock()
+            .ok()
+            .and_then(|inner| huggingface_cache(&inner.config));
+        let engine = self.inner.clone();
+        mark_download(&engine, &name, true);
         std::thread::spawn(move || {
             let rt = match tokio::runtime::Builder::new_current_thread()
                 .enable_all()
@@ -468,6 +1420,7 @@ impl DiktoEngine {
             {
                 Ok(rt) => rt,
                 Err(e) => {
+                    mark_download(&engine, &name, false);
                     callback.on_error(format!("Failed to create runtime: {e}"));
                     return;
                 }
@@ -475,11 +1428,13 @@ impl DiktoEngine {
 
             rt.block_on(async {
                 let cb = callback.clone();
-                match models::download_model(&name, move |downloaded, total| {
-                    cb.on_progress(downloaded, total);
-                })
-                .await
-                {
+                let result =
+                    models::download_model(&name, hf_cache.as_deref(), move |downloaded, total| {
+                        cb.on_progress(downloaded, total);
+                    })
+                    .await;
+                mark_download(&engine, &name, false);
+                match result {
                     Ok(_) => callback.on_complete(name),
                     Err(e) => callback.on_error(e.to_string()),
                 }
@@ -489,6 +1444,461 @@ impl DiktoEngine {
         Ok(())
     }
 
+    /// Language packs, each with its model's download state.
+    pub fn list_language_packs(&self) -> Vec<LanguagePackRecord> {
+        let config = self.get_config();
+        models::LANGUAGE_PACKS
+            .iter()
+            .map(|pack| language_pack_record(pack, &config))
+            .collect()
+    }
+
+    /// Download the model of the language pack for `language`, as
+    /// `download_model` does.
+    pub fn download_language_pack(
+        &self,
+        language: String,
+        callback: Arc<dyn DownloadProgressCallback>,
+    ) -> Result<(), DiktoError> {
+        let pack = find_language_pack(&language)?;
+        self.download_model(pack.model.to_string(), callback)
+    }
+
+    /// Dictate in `language` with its pack: switch to the pack's model (it
+    /// must be downloaded) and set the session language, which selects the
+    /// pack's punctuation words and number formatting.
+    pub fn switch_language_pack(&self, language: String) -> Result<(), DiktoError> {
+        let pack = find_language_pack(&language)?;
+        let current = models::canonical_model_name(&self.get_config().model_name);
+        if current != pack.model {
+            self.switch_model(pack.model.to_string())?;
+        }
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
+        inner.config.language = pack.language.to_string();
+        inner.persist(&inner.config)?;
+        info!("Switched to the {} language pack", pack.name);
+        Ok(())
+    }
+
+    /// Registry models whose download didn't finish last time, from partial
+    /// `.downloading` files and from downloads state.json still lists as
+    /// under way. Hosts can offer to finish them with
+    /// `resume_pending_downloads`.
+    pub fn interrupted_downloads(&self) -> Vec<String> {
+        let pending = match self.inner.lock() {
+            Ok(inner) => inner.state.pending_downloads.clone(),
+            Err(_) => Vec::new(),
+        };
+        let mut names: Vec<String> = pending
+            .into_iter()
+            .filter_map(|name| models::find_model(&name))
+            .filter(|m| !models::is_model_downloaded(m.name))
+            .map(|m| m.name.to_string())
+            .collect();
+        for model in models::interrupted_downloads() {
+            if !names.iter().any(|n| n == model.name) {
+                names.push(model.name.to_string());
+            }
+        }
+        names
+    }
+
+    /// Finish the `interrupted_downloads`, one after another on a
+    /// background thread, picking up from each partial file's last
+    /// checkpoint. `callback` receives progress for the model being
+    /// downloaded, then on_complete or on_error for each. Returns the
+    /// models being resumed, empty when there is nothing to do.
+    pub fn resume_pending_downloads(
+        &self,
+        callback: Arc<dyn DownloadProgressCallback>,
+    ) -> Vec<String> {
+        let names = self.interrupted_downloads();
+        if names.is_empty() {
+            return names;
+        }
+        info!("Resuming interrupted downloads: {}", names.join(", "));
+        let hf_cache = self
+            .inner
+            .lock()
+            .ok()
+            .and_then(|inner| huggingface_cache(&inner.config));
+        let engine = self.inner.clone();
+        let queue = names.clone();
+        std::thread::spawn(move || {
+            let rt = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(rt) => rt,
+                Err(e) => {
+                    callback.on_error(format!("Failed to create runtime: {e}"));
+                    return;
+                }
+            };
+            for name in queue {
+                mark_download(&engine, &name, true);
+                let cb = callback.clone();
+                let result = rt.block_on(models::download_model(
+                    &name,
+                    hf_cache.as_deref(),
+                    move |downloaded, total| cb.on_progress(downloaded, total),
+                ));
+                mark_download(&engine, &name, false);
+                match result {
+                    Ok(_) => callback.on_complete(name),
+                    Err(e) => callback.on_error(format!("{name}: {e}")),
+                }
+            }
+        });
+        names
+    }
+
+    /// Async variant of download_model. Resolves once the download finishes;
+    /// `progress` only receives on_progress.
+    pub async fn download_model_async(
+        &self,
+        model_name: String,
+        progress: Option<Arc<dyn DownloadProgressCallback>>,
+    ) -> Result<(), DiktoError> {
+        let _ = models::find_model(&model_name)
+            .ok_or_else(|| DiktoError::Model(format!("Unknown model: {model_name}")))?;
+
+        let hf_cache = self
+            .inner
+            .lock()
+            .ok()
+            .and_then(|inner| huggingface_cache(&inner.config));
+        let engine = self.inner.clone();
+        mark_download(&engine, &model_name, true);
+        run_blocking(move || {
+            // reqwest needs a tokio runtime, which the foreign executor doesn't provide
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| DiktoError::Model(format!("Failed to create runtime: {e}")));
+            let result = rt.and_then(|rt| {
+                rt.block_on(models::download_model(
+                    &model_name,
+                    hf_cache.as_deref(),
+                    move |downloaded, total| {
+                        if let Some(cb) = &progress {
+                            cb.on_progress(downloaded, total);
+                        }
+                    },
+                ))
+                .map_err(DiktoError::from)
+            });
+            mark_download(&engine, &model_name, false);
+            result.map(|_| ())
+        })
+        .await
+    }
+
+    /// Transcribe a WAV file with the configured model, loading it if needed.
+    /// `language` is an ISO-639-1 code or "auto". Text processing applies as
+    /// for dictation; the result is not saved to history.
+    pub fn transcribe_file(
+        &self,
+        path: String,
+        language: String,
+    ) -> Result<TranscriptionResult, DiktoError> {
+        let (worker, text_processing) = ensure_model_loaded(self)?;
+        let samples = wav::read_wav(std::path::Path::new(&path))
+            .map_err(|e| DiktoError::Audio(e.to_string()))?;
+        let config = self.get_config();
+        let redactor = Redactor::new(&config.redaction);
+        let mut result = transcribe_samples(
+            &worker,
+            &samples,
+            &language,
+            &text_processing,
+            &redactor,
+            &config.advanced,
+        )?;
+        add_action_items(&mut result, &config.action_items);
+        add_summary(&mut result, &config.summary);
+        Ok(result)
+    }
+
+    /// Async variant of transcribe_file.
+    pub async fn transcribe_file_async(
+        self: Arc<Self>,
+        path: String,
+        language: String,
+    ) -> Result<TranscriptionResult, DiktoError> {
+        run_blocking(move || self.transcribe_file(path, language)).await
+    }
+
+    /// Transcribe a batch of WAV files, e.g. a watched folder, skipping any
+    /// whose audio was already transcribed. Files are matched by
+    /// `wav::fingerprint`, so a recording re-exported under another name or
+    /// format is still skipped. Transcribed files are added to history when
+    /// `save_history` is on; otherwise only duplicates within the batch are
+    /// caught. One file failing doesn't stop the rest.
+    pub fn transcribe_files(
+        &self,
+        paths: Vec<String>,
+        language: String,
+    ) -> Result<Vec<FileTranscription>, DiktoError> {
+        let (worker, text_processing) = ensure_model_loaded(self)?;
+        let config = self.get_config();
+        let model_name = models::canonical_model_name(&config.model_name);
+        let redactor = Redactor::new(&config.redaction);
+        let mut seen = if config.save_history {
+            history::transcribed_fingerprints(&history::load_default_entries())
+        } else {
+            Default::default()
+        };
+
+        let mut outcomes = Vec::with_capacity(paths.len());
+        for path in paths {
+            let samples = match wav::read_wav(std::path::Path::new(&path)) {
+                Ok(samples) => samples,
+                Err(e) => {
+                    outcomes.push(FileTranscription::Failed {
+                        path,
+                        message: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            let fingerprint = wav::fingerprint(&samples);
+            if !seen.insert(fingerprint.clone()) {
+                info!("Skipping {path}: already transcribed");
+                outcomes.push(FileTranscription::AlreadyTranscribed { path, fingerprint });
+                continue;
+            }
+            match transcribe_samples(
+                &worker,
+                &samples,
+                &language,
+                &text_processing,
+                &redactor,
+                &config.advanced,
+            ) {
+                Ok(mut result) => {
+                    add_action_items(&mut result, &config.action_items);
+                    add_summary(&mut result, &config.summary);
+                    if config.save_history {
+                        history::record_file(
+                            &result,
+                            &model_name,
+                            &language,
+                            config.output.routes.history_raw,
+                            &fingerprint,
+                        );
+                    }
+                    outcomes.push(FileTranscription::Transcribed {
+                        path,
+                        fingerprint,
+                        result,
+                    });
+                }
+                Err(e) => {
+                    // Let a retry pick it up
+                    seen.remove(&fingerprint);
+                    outcomes.push(FileTranscription::Failed {
+                        path,
+                        message: e.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(outcomes)
+    }
+
+    /// Async variant of transcribe_files.
+    pub async fn transcribe_files_async(
+        self: Arc<Self>,
+        paths: Vec<String>,
+        language: String,
+    ) -> Result<Vec<FileTranscription>, DiktoError> {
+        run_blocking(move || self.transcribe_files(paths, language)).await
+    }
+
+    /// Run a saved session's recording through VAD and inference again, the
+    /// way the live session did, to reproduce reports like "it cut off my
+    /// sentence". Uses the settings saved next to the WAV when
+    /// `saved_sessions.save_audio` is on (see `sessions::snapshot_path`), or
+    /// the current config for a recording without them. Session time
+    /// follows the audio instead of the wall clock, so `max_duration` ends
+    /// a replay where it ended the session. The model the session was
+    /// recorded with is loaded, and the replay fails if it isn't
+    /// downloaded. Whisper's context prompt and the redaction keywords
+    /// aren't saved, so a replay runs without the prompt and with the
+    /// current keywords. Nothing is translated, pasted, saved, or added to
+    /// history.
+    pub fn replay_session(&self, path: String) -> Result<TranscriptionResult, DiktoError> {
+        let path = std::path::PathBuf::from(path);
+        let samples = wav::read_wav(&path).map_err(|e| DiktoError::Audio(e.to_string()))?;
+        let current = self.get_config();
+        let config = match sessions::read_snapshot(&path) {
+            Ok(Some(mut config)) => {
+                config.redaction.keywords = current.redaction.keywords.clone();
+                config
+            }
+            Ok(None) => {
+                info!("No settings saved with the recording, replaying with the current config");
+                current.clone()
+            }
+            Err(e) => return Err(DiktoError::Config(format!("Bad session settings: {e}"))),
+        };
+        let recorded_with = models::canonical_model_name(&config.model_name);
+        let (backend, model_path) = locate_model(&current, &recorded_with)?;
+        let worker = self
+            .inner
+            .lock()
+            .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?
+            .worker
+            .clone();
+        if worker.loaded_model().as_deref() != Some(recorded_with.as_str()) {
+            let loaded = worker.load(&recorded_with, backend, &model_path);
+            let mut inner = self
+                .inner
+                .lock()
+                .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
+            after_model_load(&mut inner, &recorded_with, loaded)?;
+        }
+
+        let clock = Arc::new(clock::MockClock::new());
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let source = ReplaySource {
+            samples,
+            position: 0,
+            clock: clock.clone(),
+            stop_flag: stop_flag.clone(),
+        };
+        let mut settings = PipelineSettings::from_config(&config, clock);
+        // A debug replay never sends transcripts to a translation service
+        settings.listen.translate_to.clear();
+        run_pipeline(
+            new_asr_session(&settings),
+            &worker,
+            AudioInput::Custom(Box::new(source)),
+            stop_flag,
+            Arc::new(NoopCallback),
+            &settings,
+        )
+    }
+
+    /// Async variant of replay_session.
+    pub async fn replay_session_async(
+        self: Arc<Self>,
+        path: String,
+    ) -> Result<TranscriptionResult, DiktoError> {
+        run_blocking(move || self.replay_session(path)).await
+    }
+
+    /// Check that the configured model works end to end: VAD over a speech
+    /// sample, loading the model, and transcribing it. `sample_path` is a
+    /// WAV of recorded speech (hosts can bundle one); without it a
+    /// synthetic voice is used, which exercises every stage but can't show
+    /// that the text is right. Failures are listed in the report.
+    pub fn self_test(&self, sample_path: Option<String>) -> Result<SelfTestReport, DiktoError> {
+        let (model_name, language, threshold, silence_ms) = {
+            let inner = self
+                .inner
+                .lock()
+                .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
+            (
+                models::canonical_model_name(&inner.config.model_name),
+                inner.config.language.clone(),
+                inner.config.speech_threshold,
+                inner.config.silence_duration_ms,
+            )
+        };
+        let mut report = SelfTestReport {
+            passed: false,
+            model_name,
+            used_speech_sample: sample_path.is_some(),
+            vad_speech_ms: 0,
+            load_ms: 0,
+            transcribe_ms: 0,
+            transcript: String::new(),
+            problems: Vec::new(),
+        };
+
+        let samples = match &sample_path {
+            Some(path) => match wav::read_wav(std::path::Path::new(path)) {
+                Ok(samples) => samples,
+                Err(e) => {
+                    report
+                        .problems
+                        .push(format!("Cannot read sample {path}: {e}"));
+                    return Ok(report);
+                }
+            },
+            None => self_test::synthetic_voice(0.5, 2.0),
+        };
+
+        match vad_debug::replay(&samples, &[threshold], silence_ms) {
+            Ok(results) => {
+                report.vad_speech_ms = results.first().map_or(0, |r| r.speech_ms());
+                if report.vad_speech_ms == 0 && report.used_speech_sample {
+                    report
+                        .problems
+                        .push("VAD found no speech in the sample".to_string());
+                }
+            }
+            Err(e) => report.problems.push(format!("VAD failed: {e}")),
+        }
+
+        let started = std::time::Instant::now();
+        let loaded = ensure_model_loaded(self);
+        report.load_ms = started.elapsed().as_millis() as u64;
+        let (worker, text_processing) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                report.problems.push(e.to_string());
+                return Ok(report);
+            }
+        };
+
+        let started = std::time::Instant::now();
+        match transcribe_samples(
+            &worker,
+            &samples,
+            &language,
+            &text_processing,
+            &Redactor::default(),
+            &self.get_config().advanced,
+        ) {
+            Ok(result) => {
+                report.transcript = result.text;
+                if report.transcript.trim().is_empty() && report.used_speech_sample {
+                    report
+                        .problems
+                        .push("The model returned no text for the sample".to_string());
+                }
+            }
+            Err(e) => report.problems.push(e.to_string()),
+        }
+        report.transcribe_ms = started.elapsed().as_millis() as u64;
+
+        report.passed = report.problems.is_empty();
+        Ok(report)
+    }
+
+    /// Write a zip at `path` for bug reports: version, config with personal
+    /// paths and secrets redacted, models with expected hashes and on-disk
+    /// sizes, input devices, usage counters, stats of the last session
+    /// (not its text), and the most recent VAD debug logs.
+    pub fn export_diagnostics(&self, path: String) -> Result<(), DiktoError> {
+        let config = self
+            .inner
+            .lock()
+            .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?
+            .config
+            .clone();
+        let files =
+            diagnostics::collect(&config, &self.list_models(), &audio::input_device_names());
+        diagnostics::write_bundle(std::path::Path::new(&path), &files)
+            .map_err(|e| DiktoError::Config(format!("Cannot write {path}: {e}")))
+    }
+
     /// Get available languages for the currently configured model.
     pub fn available_languages(&self) -> Vec<LanguageInfo> {
         let Ok(inner) = self.inner.lock() else {
@@ -500,7 +1910,7 @@ impl DiktoEngine {
         let model_name = &inner.config.model_name;
 
         match models::find_model(model_name) {
-            Some(m) if m.backend == ModelBackend::Parakeet && model_name.contains("-v3") => {
+            Some(m) if m.backend == ModelBackend::Parakeet && m.name.contains("-v3") => {
                 parakeet_v3_languages()
             }
             Some(m) if m.backend == ModelBackend::Parakeet => vec![LanguageInfo {
@@ -508,6 +1918,15 @@ impl DiktoEngine {
                 name: "English".to_string(),
             }],
             Some(m) if m.backend == ModelBackend::Whisper => whisper_languages(),
+            None => match models::find_custom_model(model_name) {
+                Some(m) if !m.metadata.languages.is_empty() => {
+                    languages_for_codes(&m.metadata.languages)
+                }
+                _ => vec![LanguageInfo {
+                    code: "en".to_string(),
+                    name: "English".to_string(),
+                }],
+            },
             _ => vec![LanguageInfo {
                 code: "en".to_string(),
                 name: "English".to_string(),
@@ -521,7 +1940,7 @@ impl DiktoEngine {
         let Ok(inner) = self.inner.lock() else {
             return false;
         };
-        models::is_model_downloaded(&inner.config.model_name)
+        locate_model(&inner.config, &inner.config.model_name).is_ok()
     }
 
     /// Check if a model is currently loaded in RAM.
@@ -529,16 +1948,13 @@ impl DiktoEngine {
         let Ok(inner) = self.inner.lock() else {
             return false;
         };
-        let Ok(engine_guard) = inner.engine.lock() else {
-            return false;
-        };
-        engine_guard.is_some()
+        inner.worker.loaded_model().is_some()
     }
 
     /// Check if currently recording.
     pub fn is_recording(&self) -> bool {
         match self.inner.lock() {
-            Ok(g) => g.recording.load(Ordering::Acquire),
+            Ok(g) => g.recording.is_set(),
             Err(e) => {
                 warn!("is_recording: lock poisoned ({e}), returning false");
                 false
@@ -546,145 +1962,1815 @@ impl DiktoEngine {
         }
     }
 
-    /// Get the models directory path (for debugging).
-    pub fn models_dir(&self) -> String {
-        config::models_dir().to_string_lossy().to_string()
+    /// Check if low-power behavior currently applies (config or on battery).
+    /// Hosts should skip background warm-loading while this is true.
+    pub fn is_low_power_active(&self) -> bool {
+        let Ok(inner) = self.inner.lock() else {
+            return false;
+        };
+        power::is_low_power(&inner.config.low_power_mode)
     }
-}
 
-/// The main recording + transcription pipeline, runs on a background thread.
-fn run_pipeline(
-    mut session: AsrSession,
-    engine: &Arc<Mutex<Option<LoadedEngine>>>,
-    stop_flag: Arc<AtomicBool>,
-    callback: Arc<dyn TranscriptionCallback>,
-    max_duration: u32,
-    silence_duration_ms: u32,
-    speech_threshold: f32,
-) -> Result<String, DiktoError> {
-    callback.on_state_change(RecordingState::Listening);
+    /// Get crate version, git hash, enabled features, and linked backend versions.
+    pub fn version_info(&self) -> build_info::VersionInfo {
+        build_info::version_info()
+    }
 
-    // Start audio capture
-    let mut capture = AudioCapture::start(AudioCaptureConfig::default())?;
+    /// Local-only usage counters (sessions per day, durations, model usage).
+    pub fn usage_stats(&self) -> usage::UsageStats {
+        let counters = match usage::usage_path() {
+            Ok(path) => usage::UsageCounters::load_from(&path),
+            Err(_) => usage::UsageCounters::default(),
+        };
+        counters.stats(&usage::utc_date(usage::unix_now()))
+    }
 
-    // Initialize VAD
-    let vad_config = VadConfig {
-        speech_threshold,
-        silence_duration_ms,
-        ..Default::default()
-    };
-    let mut vad = VadProcessor::new(vad_config)?;
-    let chunk_size = vad.chunk_size();
+    /// Delete all locally stored usage counters.
+    pub fn reset_usage_stats(&self) -> Result<(), DiktoError> {
+        let path = usage::usage_path().map_err(|e| DiktoError::Config(e.to_string()))?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(DiktoError::Config(e.to_string())),
+        }
+    }
 
-    let start_time = std::time::Instant::now();
-    let max_dur = std::time::Duration::from_secs(max_duration as u64);
+    /// Words dictated in the given period, from local history.
+    pub fn words_dictated(&self, period: history::StatsPeriod) -> u64 {
+        self.dictation_stats(period).words
+    }
 
-    let mut vad_buffer: Vec<f32> = Vec::new();
-    let mut speech_detected = false;
-    // Buffer ~1s of pre-speech audio so we don't lose the start of speech
-    let pre_speech_max = 16000usize; // 1 second at 16kHz
-    let mut pre_speech_buffer: Vec<f32> = Vec::new();
-    // Throttle overlay updates to every ~500ms
-    let mut last_partial_time = std::time::Instant::now();
+    /// Word count, speech time, average WPM, and streak for the given period.
+    pub fn dictation_stats(&self, period: history::StatsPeriod) -> history::DictationStats {
+        let entries = history::load_default_entries();
+        history::dictation_stats(&entries, period, usage::unix_now())
+    }
 
-    loop {
-        // Check stop conditions
-        if stop_flag.load(Ordering::Acquire) {
-            info!("Stop requested");
-            break;
-        }
-        if start_time.elapsed() >= max_dur {
-            info!("Max duration reached");
-            break;
+    /// Most recent history entries, newest first.
+    pub fn recent_history(&self, limit: u32) -> Vec<history::HistoryEntry> {
+        let mut entries = history::load_default_entries();
+        entries.reverse();
+        entries.truncate(limit as usize);
+        entries
+    }
+
+    /// Report a user edit of dictated text. Hosts call this when the user
+    /// changes pasted text shortly after dictation. The pair is stored for
+    /// replacement-rule suggestions, and words the user introduced are added
+    /// to the personal lexicon.
+    pub fn report_correction(&self, original: String, corrected: String) -> Result<(), DiktoError> {
+        if original.trim() == corrected.trim() {
+            return Ok(());
         }
+        let path =
+            corrections::corrections_path().map_err(|e| DiktoError::Config(e.to_string()))?;
+        let pair = corrections::CorrectionPair {
+            timestamp: usage::unix_now(),
+            original,
+            corrected,
+        };
+        corrections::append_pair(&path, &pair).map_err(|e| DiktoError::Config(e.to_string()))?;
+        lexicon::record_correction(&pair.original, &pair.corrected)
+            .map_err(|e| DiktoError::Config(e.to_string()))
+    }
 
-        // Read samples from mic
-        let samples = capture.read_samples();
-        if samples.is_empty() {
-            std::thread::sleep(std::time::Duration::from_millis(10));
-            continue;
+    /// Replacement rules suggested by corrections made at least twice.
+    pub fn replacement_suggestions(&self) -> Vec<corrections::ReplacementSuggestion> {
+        match corrections::corrections_path() {
+            Ok(path) => corrections::suggest_replacements(&corrections::load_pairs(&path), 2),
+            Err(_) => Vec::new(),
         }
+    }
 
-        // Feed to VAD in chunks
-        vad_buffer.extend_from_slice(&samples);
+    /// Current personal lexicon terms, most frequent first.
+    pub fn lexicon_terms(&self) -> Vec<String> {
+        lexicon::default_terms()
+    }
 
-        while vad_buffer.len() >= chunk_size {
-            let chunk: Vec<f32> = vad_buffer.drain(..chunk_size).collect();
+    /// Dictation templates from templates.json.
+    pub fn list_templates(&self) -> Vec<templates::Template> {
+        templates::load_default_templates()
+    }
 
-            match vad.process_chunk(&chunk)? {
-                VadEvent::SpeechStart => {
-                    speech_detected = true;
-                    debug!(
-                        "Speech detected, feeding {} pre-speech samples",
-                        pre_speech_buffer.len()
-                    );
-                    // Feed buffered pre-speech audio so transcription captures the start
-                    if !pre_speech_buffer.is_empty() {
-                        session.feed_samples(&pre_speech_buffer);
-                        pre_speech_buffer.clear();
-                    }
-                }
-                VadEvent::SpeechEnd => {
-                    if speech_detected {
-                        callback.on_silence();
-                        info!("Speech ended (silence detected)");
+    /// Add a template, or replace the one with the same name (ignoring case).
+    /// Takes effect from the next recording session.
+    pub fn save_template(&self, template: templates::Template) -> Result<(), DiktoError> {
+        if template.name.trim().is_empty() {
+            return Err(DiktoError::Config("Template name is empty".to_string()));
+        }
+        let path = templates::templates_path().map_err(|e| DiktoError::Config(e.to_string()))?;
+        let mut all = templates::load_templates(&path);
+        match all
+            .iter_mut()
+            .find(|t| t.name.eq_ignore_ascii_case(&template.name))
+        {
+            Some(existing) => *existing = template,
+            None => all.push(template),
+        }
+        templates::save_templates(&path, &all).map_err(|e| DiktoError::Config(e.to_string()))
+    }
 
-                        // Flush remaining audio — batch inference happens here
-                        callback.on_state_change(RecordingState::Processing);
-                        let final_segments = session.flush(engine)?;
-                        let text = final_segments
-                            .iter()
-                            .map(|s| s.text.as_str())
-                            .collect::<Vec<_>>()
-                            .join(" ");
-
-                        for seg in &final_segments {
-                            callback.on_final_segment(seg.text.clone());
-                        }
+    /// Delete a template by name. Returns false if there was none.
+    pub fn delete_template(&self, name: String) -> Result<bool, DiktoError> {
+        let path = templates::templates_path().map_err(|e| DiktoError::Config(e.to_string()))?;
+        let mut all = templates::load_templates(&path);
+        let before = all.len();
+        all.retain(|t| !t.name.eq_ignore_ascii_case(&name));
+        if all.len() == before {
+            return Ok(false);
+        }
+        templates::save_templates(&path, &all).map_err(|e| DiktoError::Config(e.to_string()))?;
+        Ok(true)
+    }
 
+    /// Delete the local transcript history.
+    pub fn clear_history(&self) -> Result<(), DiktoError> {
+        let path = history::history_path().map_err(|e| DiktoError::Config(e.to_string()))?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(DiktoError::Config(e.to_string())),
+        }
+    }
+
+    /// Get the models directory path (for debugging).
+    pub fn models_dir(&self) -> String {
+        config::models_dir().to_string_lossy().to_string()
+    }
+}
+
+#[cfg(feature = "native")]
+impl DiktoEngine {
+    /// Start a session on `handle`, or fail with AlreadyRecording while
+    /// another one holds the recording flag.
+    fn begin_session(
+        &self,
+        listen_config: ListenConfig,
+        callback: Arc<dyn TranscriptionCallback>,
+        handle: &Arc<SessionHandle>,
+    ) -> Result<(), DiktoError> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
+
+        let local_time = unix_now().saturating_add_signed(i64::from(listen_config.utc_offset_secs));
+        let context = do_not_disturb::Context {
+            minute_of_day: (local_time % 86_400 / 60) as u32,
+            bundle_id: &listen_config.bundle_id,
+            app_name: &listen_config.app_name,
+            screen_sharing: inner.screen_sharing,
+        };
+        if let Some(reason) = do_not_disturb::check(&inner.config.do_not_disturb, &context) {
+            info!("Not starting a session: {reason}");
+            return Err(DiktoError::DoNotDisturb(reason.to_string()));
+        }
+
+        if inner.recording.is_set() {
+            return Err(DiktoError::AlreadyRecording);
+        }
+
+        let now = inner.clock.now();
+        let advanced = &inner.config.advanced;
+        let (min_interval, max_per_minute) = (
+            std::time::Duration::from_millis(advanced.min_session_interval_ms as u64),
+            advanced.max_sessions_per_minute,
+        );
+        if let Err(wait) = inner.rate_limiter.check(now, min_interval, max_per_minute) {
+            warn!("Refusing session start, too many sessions ({wait:?} to go)");
+            return Err(DiktoError::RateLimited {
+                retry_after_ms: wait.as_millis() as u64,
+            });
+        }
+
+        // Verify model is available on disk
+        let low_power = power::is_low_power(&inner.config.low_power_mode);
+        let model_name = power::effective_model_name(&inner.config, low_power);
+        let (backend, model_path) = locate_model(&inner.config, &model_name)?;
+        let worker = inner.worker.clone();
+
+        let stop_flag = handle.stop_flag.clone();
+        let capture_control = handle.capture.clone();
+        let feed = handle.feed.clone();
+        let source = match (&feed, &inner.audio_source) {
+            (None, Some(make_source)) => Some(make_source()),
+            _ => None,
+        };
+
+        let recording = inner.recording.clone();
+        let session_id = handle.id;
+        if !recording.claim(session_id) {
+            return Err(DiktoError::AlreadyRecording);
+        }
+        inner.rate_limiter.record(now);
+        remember_app_language(&mut inner, &listen_config);
+        inner.session_stop = Some(stop_flag.clone());
+        let liveness = watchdog::Liveness::new();
+        let target = output::OutputTarget {
+            bundle_id: listen_config.bundle_id.clone(),
+            app_name: listen_config.app_name.clone(),
+            ..output::OutputTarget::default()
+        };
+
+        let mut settings = PipelineSettings {
+            summary: inner.config.summary.clone(),
+            action_items: inner.config.action_items.clone(),
+            session_audio: session_audio_spool(&inner.config.saved_sessions),
+            saved_sessions: inner.config.saved_sessions.clone(),
+            translation: inner.config.translation.clone(),
+            translator: OnceLock::new(),
+            redactor: Redactor::new(&inner.config.redaction),
+            advanced: power::effective_advanced(&inner.config.advanced, low_power),
+            text_processing: inner
+                .config
+                .text_processing
+                .with_preset(listen_config.text_preset),
+            // Raw output is the recognizer's words, so spoken template
+            // commands stay as text
+            templates: match listen_config.text_preset {
+                TextProcPreset::Raw => Vec::new(),
+                _ => templates::load_default_templates(),
+            },
+            listen: listen_config,
+            liveness: liveness.clone(),
+            clock: inner.clock.clone(),
+            snapshot: None,
+            stats: handle.stats.clone(),
+            auto_copy: inner.config.auto_copy,
+            auto_paste: output::auto_paste_for(&target, &inner.config),
+        };
+        let _ = handle.stats.started.set(inner.clock.now());
+        if settings.session_audio.is_some() {
+            settings.snapshot = Some(session_snapshot(&inner.config, &settings, &model_name));
+        }
+        let watchdog_timeout =
+            std::time::Duration::from_secs(settings.advanced.watchdog_timeout_secs as u64);
+        let save_history = inner.config.save_history;
+        let history_raw = inner.config.output.routes.history_raw;
+        let locale = inner.config.locale.clone();
+        let context_hint = inner.context_hint.clone();
+        // Only Whisper accepts a prompt; skip the history scan for Parakeet
+        let use_lexicon = inner.config.personal_lexicon
+            && inner.config.save_history
+            && backend == ModelBackend::Whisper;
+        if low_power {
+            debug!("Low-power mode active (model '{model_name}')");
+        }
+
+        drop(inner); // Release outer lock before spawning
+        let engine_inner = self.inner.clone();
+        let watch = (
+            liveness.clone(),
+            recording.clone(),
+            stop_flag.clone(),
+            callback.clone(),
+            capture_control.clone(),
+        );
+
+        let pipeline = std::thread::spawn(move || {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                // Open the input before the slow model load, so the capture
+                // buffer holds what the user says in the meantime
+                let capture = match open_input(feed, source, capture_control) {
+                    Ok(capture) => capture,
+                    Err(e) => {
+                        recording.release(session_id);
+                        callback.on_state_change(RecordingState::Error {
+                            message: e.to_string(),
+                            code: e.code(),
+                        });
+                        return Ok(());
+                    }
+                };
+
+                // Lazy-load model if needed
+                let needs_load = worker.loaded_model().as_deref() != Some(model_name.as_str());
+
+                if needs_load {
+                    callback.on_state_change(RecordingState::Processing);
+                    callback.on_partial(
+                        messages::status_message(StatusMessage::LoadingModel, &locale).to_string(),
+                    );
+                    debug!("Lazy-loading model '{}'...", model_name);
+
+                    settings.liveness.beat();
+                    let loaded = worker.load(&model_name, backend, &model_path);
+                    settings.liveness.beat();
+                    let result = match engine_inner.lock() {
+                        Ok(mut inner) => after_model_load(&mut inner, &model_name, loaded),
+                        Err(_) => loaded.map_err(DiktoError::from),
+                    };
+                    match result {
+                        Ok(()) => {
+                            debug!("Model '{}' loaded into RAM", model_name);
+                        }
+                        Err(e) => {
+                            if settings.liveness.is_abandoned() {
+                                return Ok(());
+                            }
+                            recording.release(session_id);
+                            callback.on_state_change(RecordingState::Error {
+                                message: e.to_string(),
+                                code: e.code(),
+                            });
+                            return Ok(());
+                        }
+                    }
+                }
+
+                // Create transcription session
+                let mut session = new_asr_session(&settings);
+                let terms = if use_lexicon {
+                    lexicon::default_terms()
+                } else {
+                    Vec::new()
+                };
+                session.set_prompt(context::initial_prompt(context_hint.as_deref(), &terms));
+                let session_start = settings.clock.now();
+                let started_at = unix_now();
+
+                let mut result = run_pipeline(
+                    session,
+                    &worker,
+                    capture,
+                    stop_flag,
+                    callback.clone(),
+                    &settings,
+                );
+
+                // The watchdog already reported this session; a new one may
+                // own the recording flag now
+                if settings.liveness.is_abandoned() {
+                    warn!("Abandoned session finished, dropping its result");
+                    return Ok(());
+                }
+                recording.release(session_id);
+
+                let dry_run = settings.listen.dry_run;
+                if let Ok(output) = &mut result {
+                    if dry_run {
+                        // Still list the tasks, just don't write them
+                        let extract_only = ActionItemsConfig {
+                            tasks_file: String::new(),
+                            ..settings.action_items.clone()
+                        };
+                        add_action_items(output, &extract_only);
+                    } else {
+                        add_action_items(output, &settings.action_items);
+                    }
+                    // A summary runs the user's command or sends the
+                    // transcript to their endpoint
+                    if !dry_run {
+                        add_summary(output, &settings.summary);
+                    }
+                    if let Some(summary) = &output.summary {
+                        callback.on_summary(summary.clone());
+                    }
+                    if !dry_run {
+                        save_session(output, &settings, started_at);
+                    }
+                }
+                match &result {
+                    Ok(output) => {
+                        debug!("pipeline done, text_len={}", output.text.len());
+                        let duration = settings
+                            .clock
+                            .now()
+                            .saturating_duration_since(session_start);
+                        if dry_run {
+                            callback.on_dry_run(dry_run_actions(output, &settings, save_history));
+                        } else {
+                            usage::record_session(&model_name, duration.as_secs_f64());
+                        }
+                        if !dry_run && save_history {
+                            history::record_session(
+                                output,
+                                &model_name,
+                                &settings.listen.language,
+                                history_raw,
+                            );
+                        }
+                        callback.on_state_change(RecordingState::Done {
+                            text: output.text.clone(),
+                        });
+                    }
+                    Err(e) => {
+                        warn!("pipeline error: {e}");
+                        callback.on_state_change(RecordingState::Error {
+                            message: e.to_string(),
+                            code: e.code(),
+                        });
+                    }
+                }
+
+                Ok::<(), DiktoError>(())
+            }));
+            let _ = settings.stats.finished.set(settings.clock.now());
+            settings.stats.update(false, 0.0);
+
+            if let Err(_panic) = result {
+                if settings.liveness.is_abandoned() {
+                    return;
+                }
+                recording.release(session_id);
+                callback.on_state_change(RecordingState::Error {
+                    message: "Internal error (thread panic)".to_string(),
+                    code: ErrorCode::Internal,
+                });
+            }
+        });
+
+        let (liveness, recording, stop_flag, callback, capture_control) = watch;
+        watchdog::spawn(
+            liveness,
+            pipeline,
+            recording,
+            session_id,
+            stop_flag,
+            watchdog_timeout,
+            move |stall| {
+                // The stuck thread still owns the stream; at least stop
+                // taking audio from it
+                capture_control.halt(std::time::Duration::ZERO);
+                callback.on_state_change(RecordingState::Error {
+                    message: stall.to_string(),
+                    code: ErrorCode::Internal,
+                });
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Claim the one queue slot, if queueing is on and the running session
+    /// has been asked to stop (it is transcribing its last words).
+    fn reserve_queue_slot(&self) -> bool {
+        let Ok(inner) = self.inner.lock() else {
+            return false;
+        };
+        inner.config.queue_sessions
+            && inner
+                .session_stop
+                .as_ref()
+                .is_some_and(|stop| stop.load(Ordering::Acquire))
+            && !inner.session_queued.swap(true, Ordering::AcqRel)
+    }
+
+    /// Start the session on `handle` once the running one finishes. If the
+    /// host stops it first, it ends with empty text.
+    fn queue_session(
+        &self,
+        listen_config: ListenConfig,
+        callback: Arc<dyn TranscriptionCallback>,
+        handle: Arc<SessionHandle>,
+    ) {
+        let engine = DiktoEngine {
+            inner: self.inner.clone(),
+        };
+        std::thread::spawn(move || {
+            let deadline = std::time::Instant::now() + QUEUE_TIMEOUT;
+            let started = loop {
+                if handle.stop_flag.load(Ordering::Acquire) {
+                    break None;
+                }
+                match engine.begin_session(listen_config.clone(), callback.clone(), &handle) {
+                    Err(DiktoError::AlreadyRecording) if std::time::Instant::now() < deadline => {
+                        std::thread::sleep(QUEUE_POLL_INTERVAL);
+                    }
+                    result => break Some(result),
+                }
+            };
+            if let Ok(inner) = engine.inner.lock() {
+                inner.session_queued.store(false, Ordering::Release);
+            }
+            if !matches!(started, Some(Ok(()))) {
+                let _ = handle.stats.finished.set(handle.clock.now());
+            }
+            match started {
+                Some(Ok(())) => {}
+                Some(Err(e)) => callback.on_state_change(RecordingState::Error {
+                    message: e.to_string(),
+                    code: e.code(),
+                }),
+                None => {
+                    debug!("Queued session cancelled before it started");
+                    callback.on_state_change(RecordingState::Done {
+                        text: String::new(),
+                    });
+                }
+            }
+        });
+    }
+}
+
+/// Run blocking engine work on its own thread and await the result, so the
+/// async exports never stall the foreign executor.
+#[cfg(feature = "native")]
+async fn run_blocking<T, F>(f: F) -> Result<T, DiktoError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, DiktoError> + Send + 'static,
+{
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(f());
+    });
+    rx.await
+        .map_err(|_| DiktoError::Config("Internal error (thread panic)".to_string()))?
+}
+
+/// Per-session settings resolved before the pipeline thread starts.
+#[cfg(feature = "native")]
+struct PipelineSettings {
+    listen: ListenConfig,
+    advanced: AdvancedConfig,
+    text_processing: TextProcConfig,
+    /// Snippets that "insert <name> template" expands.
+    templates: Vec<templates::Template>,
+    summary: SummaryConfig,
+    action_items: ActionItemsConfig,
+    saved_sessions: SavedSessionsConfig,
+    /// The session's audio, kept for saving when `saved_sessions.save_audio`
+    /// is on.
+    session_audio: Option<Mutex<AudioSpool>>,
+    translation: TranslationConfig,
+    /// Loaded on the first segment that needs translating; None if loading
+    /// failed.
+    translator: OnceLock<Option<Arc<dyn Translator>>>,
+    /// Masks keywords in final text before any sink sees it.
+    redactor: Redactor,
+    /// Beaten by the pipeline so the watchdog can tell it's alive.
+    liveness: Arc<watchdog::Liveness>,
+    clock: Arc<dyn clock::Clock>,
+    /// The settings the session runs with, saved next to its audio for
+    /// `DiktoEngine::replay_session`. Only kept when the audio is saved.
+    snapshot: Option<DiktoConfig>,
+    /// Whether the host copies and pastes the transcript, for the actions
+    /// a dry run reports.
+    auto_copy: bool,
+    auto_paste: bool,
+    /// Shared with the session's handle.
+    stats: Arc<SessionStats>,
+}
+
+#[cfg(feature = "native")]
+impl PipelineSettings {
+    /// Settings for a session outside `start_listening` (a replay or a
+    /// test), taken from `config` as is. Nothing is saved.
+    fn from_config(config: &DiktoConfig, clock: Arc<dyn clock::Clock>) -> Self {
+        Self {
+            listen: ListenConfig::from(config),
+            advanced: config.advanced.clone(),
+            text_processing: config.text_processing.clone(),
+            templates: templates::load_default_templates(),
+            summary: config.summary.clone(),
+            action_items: config.action_items.clone(),
+            saved_sessions: SavedSessionsConfig::default(),
+            session_audio: None,
+            translation: config.translation.clone(),
+            translator: OnceLock::new(),
+            redactor: Redactor::new(&config.redaction),
+            liveness: watchdog::Liveness::new(),
+            clock,
+            snapshot: None,
+            stats: Arc::default(),
+            auto_copy: config.auto_copy,
+            auto_paste: config.auto_paste,
+        }
+    }
+
+    /// Translate a final segment into `listen.translate_to` if it was spoken
+    /// in another language. Failures keep the original text.
+    fn translate(
+        &self,
+        text: &str,
+        language: &str,
+        callback: &Arc<dyn TranscriptionCallback>,
+    ) -> Option<String> {
+        let target = &self.listen.translate_to;
+        if text.is_empty() || !translate::needs_translation(language, target) {
+            return None;
+        }
+        let translator = self.translator.get_or_init(|| {
+            translate::translator_for(&self.translation)
+                .map_err(|e| {
+                    warn!("Translation unavailable: {e}");
+                    callback.on_error(format!("Translation unavailable: {e}"));
+                })
+                .ok()
+        });
+        match translator.as_ref()?.translate(text, language, target) {
+            Ok(translated) => Some(translated),
+            Err(e) => {
+                warn!("Translation failed, keeping {language} text: {e}");
+                None
+            }
+        }
+    }
+}
+
+/// Text and audio accumulated over a session's utterances.
+#[cfg(feature = "native")]
+#[derive(Default)]
+struct SessionTranscript {
+    /// Final segments, each with the pause before it in ms (0 within an utterance).
+    segments: Vec<(u32, String)>,
+    /// Pause before the utterance being recorded, taken by its first segment.
+    next_gap_ms: u32,
+    speech_duration_secs: f32,
+    /// Language of the most recent utterance, when the backend reported one.
+    language: Option<String>,
+    /// Template being filled by the following segments, with the pause
+    /// before the command that inserted it.
+    template: Option<(u32, templates::TemplateFill)>,
+    /// Index in `segments` where the last utterance starts.
+    last_utterance: usize,
+    /// Post-processed other readings of the last utterance, with scores.
+    alternatives: Vec<(String, f32)>,
+    /// Recognizer text of every final segment.
+    raw: Vec<String>,
+}
+
+#[cfg(feature = "native")]
+impl SessionTranscript {
+    fn push(&mut self, text: String) {
+        let gap_ms = std::mem::take(&mut self.next_gap_ms);
+        self.segments.push((gap_ms, text));
+    }
+
+    /// Keep a segment's recognizer text for `TranscriptionResult::raw_text`.
+    fn push_raw(&mut self, text: &str, redactor: &Redactor) {
+        let text = redactor.redact(text.trim());
+        if !text.is_empty() {
+            self.raw.push(text);
+        }
+    }
+
+    /// Add a final segment, expanding template commands and filling the
+    /// placeholders of an inserted template. Returns the text to report to
+    /// the host: the template so far while one is being filled.
+    fn push_dictated(&mut self, text: String, templates: &[templates::Template]) -> String {
+        if let Some((gap_ms, mut fill)) = self.template.take() {
+            fill.fill(&text);
+            let rendered = fill.render();
+            if fill.is_complete() {
+                self.segments.push((gap_ms, rendered.clone()));
+            } else {
+                self.template = Some((gap_ms, fill));
+            }
+            return rendered;
+        }
+
+        let template = templates::parse_command(&text)
+            .and_then(|name| templates::find_template(templates, &name));
+        let Some(template) = template else {
+            self.push(text.clone());
+            return text;
+        };
+        let fill = templates::TemplateFill::new(template.clone());
+        let rendered = fill.render();
+        if fill.is_complete() {
+            self.push(rendered.clone());
+        } else {
+            debug!("Filling template '{}'", template.name);
+            self.template = Some((std::mem::take(&mut self.next_gap_ms), fill));
+        }
+        rendered
+    }
+
+    fn into_result(mut self, text_processing: &TextProcConfig) -> TranscriptionResult {
+        // A template still being filled goes in as is, placeholders and all
+        if let Some((gap_ms, fill)) = self.template.take() {
+            self.segments.push((gap_ms, fill.render()));
+        }
+        let alternatives = self
+            .alternatives
+            .iter()
+            .filter_map(|(utterance, score)| {
+                let (gap_ms, _) = self.segments.get(self.last_utterance)?;
+                let mut segments = self.segments[..self.last_utterance].to_vec();
+                segments.push((*gap_ms, utterance.clone()));
+                Some(TranscriptAlternative {
+                    text: textproc::join_utterances(&segments, text_processing),
+                    utterance: utterance.clone(),
+                    score: *score,
+                })
+            })
+            .collect();
+        TranscriptionResult {
+            text: textproc::join_utterances(&self.segments, text_processing),
+            raw_text: self.raw.join(" "),
+            speech_duration_secs: self.speech_duration_secs,
+            alternatives,
+            summary: None,
+            action_items: Vec::new(),
+        }
+    }
+}
+
+/// Audio kept after the last speech chunk when trimming an utterance's
+/// trailing silence (200ms at 16kHz).
+#[cfg(feature = "native")]
+const SPEECH_END_PADDING_SAMPLES: usize = 3200;
+
+/// Speech buffered before a long-form session transcribes it mid-utterance
+/// when `flush_threshold_secs` is 0, in seconds. Keeps the buffer far below
+/// AsrSession's 4-minute cap.
+#[cfg(feature = "native")]
+const LONG_FORM_CHUNK_SECS: f32 = 60.0;
+
+/// A transcription session set up for the pipeline's language and
+/// advanced settings.
+#[cfg(feature = "native")]
+fn new_asr_session(settings: &PipelineSettings) -> AsrSession {
+    let mut session = AsrSession::new(settings.listen.language.clone());
+    session.set_beam_size(settings.advanced.beam_size);
+    session.set_no_speech_threshold(settings.advanced.no_speech_threshold);
+    session.set_normalization(
+        settings.advanced.input_normalization,
+        settings.advanced.normalization_min_range_db,
+    );
+    if settings.listen.long_form {
+        session.enable_spooling(std::env::temp_dir());
+    }
+    session
+}
+
+/// `config` with a session's listen settings and its effective model,
+/// advanced, and text-processing settings folded in, so
+/// `replay_session` can set up the same pipeline from it.
+#[cfg(feature = "native")]
+fn session_snapshot(
+    config: &DiktoConfig,
+    settings: &PipelineSettings,
+    model_name: &str,
+) -> DiktoConfig {
+    let listen = &settings.listen;
+    let mut snapshot = config.clone();
+    snapshot.model_name = model_name.to_string();
+    snapshot.language = listen.language.clone();
+    snapshot.max_duration = listen.max_duration;
+    snapshot.time_warning_secs = listen.time_warning_secs;
+    snapshot.extend_while_speaking = listen.extend_while_speaking;
+    snapshot.silence_duration_ms = listen.silence_duration_ms;
+    snapshot.speech_threshold = listen.speech_threshold;
+    snapshot.continuous_mode = listen.continuous;
+    snapshot.long_form = listen.long_form;
+    snapshot.flush_threshold_secs = listen.flush_threshold_secs;
+    snapshot.translation.target_language = listen.translate_to.clone();
+    snapshot.advanced = settings.advanced.clone();
+    snapshot.text_processing = settings.text_processing.clone();
+    // Saved recordings end up attached to bug reports, so leave out what
+    // diagnostics and sync keep private
+    snapshot.redaction.keywords.clear();
+    snapshot.translation.api_url.clear();
+    snapshot.translation.api_key.clear();
+    snapshot.summary.api_url.clear();
+    snapshot.summary.api_key.clear();
+    snapshot.summary.command.clear();
+    snapshot
+}
+
+/// A recording played into the pipeline by `replay_session` as fast as it
+/// reads, moving `clock` on by the audio's duration. Stops the session at
+/// the end of the recording.
+#[cfg(feature = "native")]
+struct ReplaySource {
+    samples: Vec<f32>,
+    position: usize,
+    clock: Arc<clock::MockClock>,
+    stop_flag: Arc<AtomicBool>,
+}
+
+#[cfg(feature = "native")]
+impl AudioSource for ReplaySource {
+    fn read_into(&mut self, buf: &mut [f32]) -> usize {
+        let rest = &self.samples[self.position..];
+        let n = rest.len().min(buf.len());
+        if n == 0 {
+            self.stop_flag.store(true, Ordering::Release);
+            return 0;
+        }
+        buf[..n].copy_from_slice(&rest[..n]);
+        self.position += n;
+        self.clock
+            .advance(std::time::Duration::from_secs_f64(n as f64 / 16000.0));
+        n
+    }
+
+    fn wait_for_samples(&self, _timeout: std::time::Duration) {}
+}
+
+/// Callbacks for a session nobody is watching, such as a replay.
+#[cfg(feature = "native")]
+struct NoopCallback;
+
+#[cfg(feature = "native")]
+impl TranscriptionCallback for NoopCallback {
+    fn on_partial(&self, _text: String) {}
+    fn on_final_segment(&self, _text: String) {}
+    fn on_silence(&self) {}
+    fn on_error(&self, _error: String) {}
+    fn on_state_change(&self, _state: RecordingState) {}
+    fn on_language_change(&self, _language: String) {}
+    fn on_word_confidence(&self, _words: Vec<WordConfidence>) {}
+    fn on_overlap_warning(&self, _warning: OverlapWarning) {}
+    fn on_audio_interrupted(&self, _reason: String) {}
+    fn on_summary(&self, _summary: String) {}
+    fn on_no_audio_detected(&self) {}
+    fn on_time_remaining(&self, _seconds: u32) {}
+    fn on_ready(&self) {}
+    fn on_dry_run(&self, _actions: Vec<output::OutputAction>) {}
+}
+
+/// Start audio capture, or wait for the host or app to supply samples.
+#[cfg(feature = "native")]
+fn open_input(
+    feed: Option<Arc<FeedBuffer>>,
+    source: Option<Box<dyn AudioSource>>,
+    capture_control: Arc<CaptureControl>,
+) -> Result<AudioInput, DiktoError> {
+    Ok(match (feed, source) {
+        (Some(feed), _) => AudioInput::Host(feed),
+        (None, Some(source)) => AudioInput::Custom(source),
+        (None, None) => AudioInput::device(AudioCaptureConfig::default(), capture_control)?,
+    })
+}
+
+/// The main recording + transcription pipeline, runs on a background thread.
+#[cfg(feature = "native")]
+fn run_pipeline(
+    mut session: AsrSession,
+    worker: &InferenceWorker,
+    mut capture: AudioInput,
+    stop_flag: Arc<AtomicBool>,
+    callback: Arc<dyn TranscriptionCallback>,
+    settings: &PipelineSettings,
+) -> Result<TranscriptionResult, DiktoError> {
+    let listen_config = &settings.listen;
+    let advanced = &settings.advanced;
+    callback.on_state_change(RecordingState::Listening);
+
+    let mut permission_probe = capture.permission_probe();
+    let mut mute_detector = MuteDetector::new(16000);
+
+    // Initialize VAD
+    let vad_config = VadConfig {
+        speech_threshold: listen_config.speech_threshold,
+        silence_duration_ms: listen_config.silence_duration_ms,
+        ..Default::default()
+    };
+    let mut vad = VadProcessor::new(vad_config)?;
+    let chunk_size = vad.chunk_size();
+    let mut trace = if advanced.vad_debug {
+        vad_debug::debug_dir()
+            .map_err(std::io::Error::other)
+            .and_then(|dir| VadTrace::create(&dir))
+            .inspect(|t| info!("Recording VAD trace to {}", t.wav_path().display()))
+            .map_err(|e| warn!("VAD debug trace unavailable: {e}"))
+            .ok()
+    } else {
+        None
+    };
+
+    let mut transcript = SessionTranscript::default();
+    let partial_interval = std::time::Duration::from_millis(advanced.partial_interval_ms as u64);
+    // Throttles overlay updates (default every ~500ms)
+    let mut timer = SessionTimer::start(settings.clock.clone(), partial_interval);
+    let max_dur = std::time::Duration::from_secs(listen_config.max_duration as u64);
+    let mut deadline = if listen_config.extend_while_speaking {
+        let cap = if listen_config.long_form {
+            config::LONG_FORM_MAX_DURATION_SECS
+        } else {
+            config::MAX_DURATION_SECS
+        };
+        SessionDeadline::extending(max_dur, std::time::Duration::from_secs(cap.into()))
+    } else {
+        SessionDeadline::new(max_dur)
+    };
+    let mut countdown = Countdown::new(listen_config.time_warning_secs);
+    let chunk_duration = std::time::Duration::from_secs_f32(chunk_size as f32 / 16000.0);
+    let poll_interval = std::time::Duration::from_millis(advanced.poll_interval_ms as u64);
+    // Commit long stretches of speech as they go, bounding latency and memory
+    let flush_threshold_secs = match listen_config.flush_threshold_secs {
+        0 if listen_config.long_form => Some(LONG_FORM_CHUNK_SECS),
+        0 => None,
+        secs => Some(secs as f32),
+    };
+
+    // Reusable buffers: the hot loop reads and chunks without allocating
+    let mut scratch = vec![0.0f32; chunk_size * 16];
+    let mut vad_buffer: Vec<f32> = Vec::with_capacity(scratch.len() + chunk_size);
+    let mut speech_detected = false;
+    // Buffer ~1s of pre-speech audio so we don't lose the start of speech
+    let pre_speech_max = 16000usize; // 1 second at 16kHz
+    let mut pre_speech_buffer: Vec<f32> = Vec::new();
+    // Samples read since capture started, for session-relative timestamps
+    let mut samples_read = 0usize;
+    // End of the previous utterance's speech, to size the pause before the next
+    let mut last_speech_end: Option<u64> = None;
+    callback.on_ready();
+
+    loop {
+        settings.liveness.beat();
+
+        // Check stop conditions
+        if stop_flag.load(Ordering::Acquire) {
+            info!("Stop requested");
+            break;
+        }
+        if listen_config.continuous {
+            if speech_detected && session.buffer_duration_secs() >= max_dur.as_secs_f32() {
+                info!("Max utterance duration reached");
+                flush_utterance(&mut session, worker, &callback, settings, &mut transcript)?;
+                // Still mid-speech: keep feeding the session, which now starts here
+                session.set_time_offset(samples_read as f32 / 16000.0);
+                callback.on_state_change(RecordingState::Listening);
+            }
+        } else {
+            let remaining = deadline.remaining(timer.elapsed());
+            if remaining.is_zero() {
+                info!("Max duration reached");
+                break;
+            }
+            if let Some(seconds) = countdown.tick(remaining) {
+                callback.on_time_remaining(seconds);
+            }
+        }
+        if speech_detected
+            && flush_threshold_secs.is_some_and(|secs| session.buffer_duration_secs() >= secs)
+        {
+            debug!("Flush threshold reached mid-utterance, committing it");
+            flush_utterance(&mut session, worker, &callback, settings, &mut transcript)?;
+            session.set_time_offset(samples_read as f32 / 16000.0);
+            callback.on_state_change(RecordingState::Listening);
+        }
+
+        if let Some(reason) = capture.take_failure() {
+            warn!("Audio input failed mid-session: {reason}");
+            callback.on_audio_interrupted(reason);
+            // Commit what was heard so far in case the device doesn't come back
+            if speech_detected {
+                flush_utterance(&mut session, worker, &callback, settings, &mut transcript)?;
+                session.set_time_offset(samples_read as f32 / 16000.0);
+            }
+            if !reopen_capture(&mut capture, &stop_flag) {
+                info!("Audio input did not come back, ending session");
+                break;
+            }
+            callback.on_state_change(RecordingState::Listening);
+            continue;
+        }
+
+        // Read samples from the mic or host feed into the scratch buffer
+        let read = capture.read_into(&mut scratch);
+        if read == 0 {
+            if permission_probe
+                .as_ref()
+                .is_some_and(|p| p.stalled(timer.elapsed()))
+            {
+                return Err(AudioError::PermissionDenied.into());
+            }
+            // Sleep until the audio thread signals new samples (or the poll interval)
+            capture.wait_for_samples(poll_interval);
+            continue;
+        }
+        let samples = &scratch[..read];
+        if let Some(probe) = &mut permission_probe {
+            if probe.observe(samples) {
+                return Err(AudioError::PermissionDenied.into());
+            }
+            if probe.is_finished() {
+                permission_probe = None;
+            }
+        }
+        if mute_detector.observe(samples) {
+            warn!("Audio input is all zeros, the microphone may be muted");
+            callback.on_no_audio_detected();
+        }
+        if let Some(Ok(mut spool)) = settings.session_audio.as_ref().map(|s| s.lock()) {
+            if let Err(e) = spool.append(samples) {
+                warn!("Failed to keep session audio: {e}");
+            }
+        }
+        let read_before = samples_read;
+        samples_read += read;
+
+        // Feed to VAD in chunks, borrowing slices instead of draining per chunk
+        vad_buffer.extend_from_slice(samples);
+        let mut consumed = 0;
+
+        while vad_buffer.len() - consumed >= chunk_size {
+            let chunk = &vad_buffer[consumed..consumed + chunk_size];
+            consumed += chunk_size;
+
+            let event = vad.process_chunk(chunk)?;
+            if let Some(trace) = trace.as_mut() {
+                trace.record(chunk, vad.last_probability(), &event, vad.state());
+            }
+            if vad.state() == VadState::Speaking
+                && vad.last_probability() > listen_config.speech_threshold
+            {
+                deadline.add_speech(chunk_duration);
+            }
+            if speech_detected || event == VadEvent::SpeechStart {
+                let chunk_start = vad.samples_processed() - chunk_size as u64;
+                session.note_vad_probability(chunk_start as f32 / 16000.0, vad.last_probability());
+            }
+
+            match event {
+                VadEvent::SpeechStart => {
+                    speech_detected = true;
+                    if let Some(end) = last_speech_end {
+                        let gap = vad.speech_start_sample().saturating_sub(end);
+                        transcript.next_gap_ms = (gap * 1000 / 16000) as u32;
+                    }
+                    // The session buffer starts with the pre-speech audio,
+                    // followed by this whole read
+                    let start = read_before.saturating_sub(pre_speech_buffer.len());
+                    session.set_time_offset(start as f32 / 16000.0);
+                    debug!(
+                        "Speech detected, feeding {} pre-speech samples",
+                        pre_speech_buffer.len()
+                    );
+                    // Feed buffered pre-speech audio so transcription captures the start
+                    if !pre_speech_buffer.is_empty() {
+                        session.feed_samples(&pre_speech_buffer);
+                        pre_speech_buffer.clear();
+                    }
+                }
+                VadEvent::SpeechEnd => {
+                    if speech_detected {
+                        callback.on_silence();
+                        info!("Speech ended (silence detected)");
+
+                        // The session holds audio up to the start of this read.
+                        // Drop the silence that ended the utterance, keeping a
+                        // little padding so trailing consonants survive.
+                        let trailing = (read_before as u64)
+                            .saturating_sub(vad.last_speech_end_sample())
+                            as usize;
+                        session.trim_end(trailing.saturating_sub(SPEECH_END_PADDING_SAMPLES));
+                        last_speech_end = Some(vad.last_speech_end_sample());
+
+                        // Flush remaining audio — batch inference happens here
+                        if listen_config.continuous {
+                            flush_utterance(
+                                &mut session,
+                                worker,
+                                &callback,
+                                settings,
+                                &mut transcript,
+                            )?;
+                            speech_detected = false;
+                            callback.on_state_change(RecordingState::Listening);
+                            continue;
+                        }
+                        // Close the microphone before the slow part
                         capture.stop();
-                        return Ok(text);
+                        drop(capture);
+                        flush_utterance(
+                            &mut session,
+                            worker,
+                            &callback,
+                            settings,
+                            &mut transcript,
+                        )?;
+                        return Ok(transcript.into_result(&settings.text_processing));
                     }
                 }
                 VadEvent::SpeechContinue | VadEvent::Silence => {}
             }
         }
+        vad_buffer.drain(..consumed);
 
         // Feed audio to transcription buffer or buffer pre-speech audio
         if speech_detected {
-            session.feed_samples(&samples);
+            session.feed_samples(samples);
 
             // Send "Recording..." status to overlay (throttled)
-            if last_partial_time.elapsed() >= std::time::Duration::from_millis(500) {
+            if timer.partial_due() {
                 let duration = session.buffer_duration_secs();
                 callback.on_partial(format!("Recording... ({duration:.1}s)"));
-                last_partial_time = std::time::Instant::now();
             }
         } else {
             // Ring-buffer pre-speech audio (keep last ~1s)
-            pre_speech_buffer.extend_from_slice(&samples);
+            pre_speech_buffer.extend_from_slice(samples);
             if pre_speech_buffer.len() > pre_speech_max {
                 let excess = pre_speech_buffer.len() - pre_speech_max;
                 pre_speech_buffer.drain(..excess);
             }
         }
+        settings
+            .stats
+            .update(speech_detected, session.buffer_duration_secs());
     }
 
-    // Flush on stop
+    // Close the microphone, then flush on stop
+    capture.stop();
+    drop(capture);
+    flush_utterance(&mut session, worker, &callback, settings, &mut transcript)?;
+    Ok(transcript.into_result(&settings.text_processing))
+}
+
+/// Times a failed input device is reopened before a session gives up on it.
+#[cfg(feature = "native")]
+const AUDIO_REOPEN_ATTEMPTS: u32 = 5;
+
+/// Wait between attempts to reopen a failed input device.
+#[cfg(feature = "native")]
+const AUDIO_REOPEN_BACKOFF: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Try to reopen a failed input device, giving up early if the session is
+/// stopped. Returns whether capture is running again.
+#[cfg(feature = "native")]
+fn reopen_capture(capture: &mut AudioInput, stop_flag: &AtomicBool) -> bool {
+    for attempt in 1..=AUDIO_REOPEN_ATTEMPTS {
+        if stop_flag.load(Ordering::Acquire) {
+            return false;
+        }
+        match capture.reopen() {
+            Ok(()) => {
+                info!("Audio input reopened (attempt {attempt})");
+                return true;
+            }
+            Err(e) => warn!("Reopening audio input failed (attempt {attempt}): {e}"),
+        }
+        std::thread::sleep(AUDIO_REOPEN_BACKOFF);
+    }
+    false
+}
+
+/// Flush the buffered utterance through the inference worker, post-process
+/// it in its recognized language, and emit its final segments.
+#[cfg(feature = "native")]
+fn flush_utterance(
+    session: &mut AsrSession,
+    worker: &InferenceWorker,
+    callback: &Arc<dyn TranscriptionCallback>,
+    settings: &PipelineSettings,
+    transcript: &mut SessionTranscript,
+) -> Result<(), DiktoError> {
+    settings.liveness.beat();
     callback.on_state_change(RecordingState::Processing);
-    let final_segments = session.flush(engine)?;
-    let text = final_segments
-        .iter()
-        .map(|s| s.text.as_str())
-        .collect::<Vec<_>>()
-        .join(" ");
+    transcript.speech_duration_secs += session.buffer_duration_secs();
+
+    let segments = session.flush(worker)?;
+    let utterance_start = transcript.segments.len();
+    let language = segments
+        .first()
+        .and_then(|seg| seg.language.clone())
+        .unwrap_or_else(|| settings.listen.language.clone());
+    let mut dictated_as_is = transcript.template.is_none();
+    for seg in segments {
+        dictated_as_is &= emit_segment(seg, callback, settings, transcript);
+    }
 
-    for seg in &final_segments {
-        callback.on_final_segment(seg.text.clone());
+    // Alternatives only make sense for an utterance that went in as dictated,
+    // not one that filled or inserted a template
+    let alternatives = session.take_alternatives();
+    if transcript.segments.len() > utterance_start {
+        transcript.last_utterance = utterance_start;
+        transcript.alternatives = if dictated_as_is {
+            let pipeline = TextPipeline::for_language(&language, &settings.text_processing);
+            alternatives
+                .into_iter()
+                .map(|h| {
+                    (
+                        settings.redactor.redact(&pipeline.process(&h.text)),
+                        h.score,
+                    )
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
     }
+    Ok(())
+}
 
-    capture.stop();
-    Ok(text)
+/// Process one final segment and send it to the host: text processing,
+/// redaction, translation, and templates. Every callback gets redacted
+/// text. Returns false if what was reported isn't the segment as dictated
+/// (translated, or filling a template).
+#[cfg(feature = "native")]
+fn emit_segment(
+    seg: TranscriptSegment,
+    callback: &Arc<dyn TranscriptionCallback>,
+    settings: &PipelineSettings,
+    transcript: &mut SessionTranscript,
+) -> bool {
+    if let Some(detected) = &seg.language {
+        let previous = transcript
+            .language
+            .as_deref()
+            .unwrap_or(&settings.listen.language);
+        if detected != previous {
+            debug!("Utterance language: {previous} -> {detected}");
+            callback.on_language_change(detected.clone());
+        }
+        transcript.language = Some(detected.clone());
+    }
+
+    if !seg.words.is_empty() {
+        callback.on_word_confidence(settings.redactor.redact_words(&seg.words));
+    }
+
+    let mut as_is = true;
+    let language = seg.language.as_deref().unwrap_or(&settings.listen.language);
+    transcript.push_raw(&seg.text, &settings.redactor);
+    // Before translating, so a remote translator never gets a keyword
+    let mut text = settings.redactor.redact(
+        &TextPipeline::for_language(language, &settings.text_processing).process(&seg.text),
+    );
+    // Alternatives are readings of the spoken words, not the translation
+    if let Some(translated) = settings.translate(&text, language, callback) {
+        // Again, for keywords the translation kept as they were
+        text = settings.redactor.redact(&translated);
+        as_is = false;
+    }
+    if seg.possible_overlap {
+        debug!(
+            "Possible crosstalk in segment {:?}-{:?}",
+            seg.start_secs, seg.end_secs
+        );
+        callback.on_overlap_warning(OverlapWarning {
+            text: text.clone(),
+            start_secs: seg.start_secs,
+            end_secs: seg.end_secs,
+        });
+    }
+    let reported = transcript.push_dictated(text.clone(), &settings.templates);
+    as_is &= reported == text;
+    callback.on_final_segment(reported);
+    as_is
+}
+
+/// Run one recognized segment through `emit_segment` with `config`'s
+/// settings, sending the callbacks a live session would. For tests, which
+/// have no model to produce segments.
+#[cfg(feature = "native")]
+#[doc(hidden)]
+pub fn emit_segment_for_test(
+    config: &DiktoConfig,
+    segment: TranscriptSegment,
+    callback: Arc<dyn TranscriptionCallback>,
+) {
+    let settings = PipelineSettings::from_config(config, Arc::new(clock::SystemClock));
+    emit_segment(
+        segment,
+        &callback,
+        &settings,
+        &mut SessionTranscript::default(),
+    );
+}
+
+/// The settings a session under `config` would save next to its audio.
+/// For tests, like `emit_segment_for_test`.
+#[cfg(feature = "native")]
+#[doc(hidden)]
+pub fn session_snapshot_for_test(config: &DiktoConfig) -> DiktoConfig {
+    let settings = PipelineSettings::from_config(config, Arc::new(clock::SystemClock));
+    session_snapshot(config, &settings, &config.model_name)
+}
+
+/// The actions a dry-run session under `config` and `listen` would report
+/// for `text`. For tests, like `emit_segment_for_test`.
+#[cfg(feature = "native")]
+#[doc(hidden)]
+pub fn dry_run_actions_for_test(
+    config: &DiktoConfig,
+    listen: ListenConfig,
+    text: &str,
+) -> Vec<output::OutputAction> {
+    let target = output::OutputTarget {
+        bundle_id: listen.bundle_id.clone(),
+        app_name: listen.app_name.clone(),
+        ..output::OutputTarget::default()
+    };
+    let mut settings = PipelineSettings::from_config(config, Arc::new(clock::SystemClock));
+    settings.auto_paste = output::auto_paste_for(&target, config);
+    settings.listen = listen;
+    let result = TranscriptionResult {
+        text: text.to_string(),
+        raw_text: text.to_string(),
+        speech_duration_secs: 0.0,
+        alternatives: Vec::new(),
+        summary: None,
+        action_items: Vec::new(),
+    };
+    dry_run_actions(&result, &settings, config.save_history)
+}
+
+#[cfg(feature = "native")]
+impl From<models::FileState> for ModelFileState {
+    fn from(state: models::FileState) -> Self {
+        match state {
+            models::FileState::Missing => ModelFileState::Missing,
+            models::FileState::Partial { bytes } => ModelFileState::Partial { bytes },
+            models::FileState::Present { bytes } => ModelFileState::Present { bytes },
+        }
+    }
+}
+
+/// ModelInfoRecord for a registry model.
+#[cfg(feature = "native")]
+fn registry_model_record(m: &models::ModelInfo, state: ModelState) -> ModelInfoRecord {
+    let dir = config::models_dir().join(m.name);
+    ModelInfoRecord {
+        name: m.name.to_string(),
+        size_mb: m.size_mb,
+        description: m.description.to_string(),
+        is_downloaded: state == ModelState::Downloaded,
+        state,
+        backend: backend_label(m.backend),
+        aliases: models::aliases_for(m.name)
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+        ram_mb: m.ram_mb,
+        relative_speed: m.relative_speed,
+        wer: m.wer,
+        languages: m.languages.iter().map(|l| l.to_string()).collect(),
+        files: m
+            .files
+            .iter()
+            .map(|f| ModelFileRecord {
+                filename: f.filename.to_string(),
+                size_mb: f.size_mb,
+                sha256: f.sha256.to_string(),
+                state: models::file_state_in(&dir, f.filename).into(),
+            })
+            .collect(),
+    }
+}
+
+/// Language pack for `language`, or a Model error naming the ones there are.
+#[cfg(feature = "native")]
+fn find_language_pack(language: &str) -> Result<&'static models::LanguagePack, DiktoError> {
+    models::find_language_pack(language).ok_or_else(|| {
+        let available: Vec<&str> = models::LANGUAGE_PACKS.iter().map(|p| p.language).collect();
+        DiktoError::Model(format!(
+            "No language pack for '{language}'. Available: {}",
+            available.join(", ")
+        ))
+    })
+}
+
+/// LanguagePackRecord for `pack` as `config` would use it.
+#[cfg(feature = "native")]
+fn language_pack_record(pack: &models::LanguagePack, config: &DiktoConfig) -> LanguagePackRecord {
+    let mut rules = textproc::LanguageRules::for_language(pack.language);
+    if let Some(ov) = config.text_processing.language_overrides.get(pack.language) {
+        rules.apply_override(ov);
+    }
+    LanguagePackRecord {
+        language: pack.language.to_string(),
+        name: pack.name.to_string(),
+        model: pack.model.to_string(),
+        model_state: models::model_state(pack.model),
+        spoken_punctuation: rules
+            .spoken_punctuation
+            .into_iter()
+            .map(|(phrase, _)| phrase)
+            .collect(),
+        thousands_separator: rules.thousands_separator,
+        active: config.language == pack.language
+            && models::canonical_model_name(&config.model_name) == pack.model,
+    }
+}
+
+/// ModelInfoRecord for a user-converted model. Resource estimates are
+/// unknown and reported as 0.
+#[cfg(feature = "native")]
+fn custom_model_record(m: &models::CustomModel) -> ModelInfoRecord {
+    ModelInfoRecord {
+        name: m.name.clone(),
+        size_mb: m.size_mb(),
+        description: m.metadata.description.clone(),
+        is_downloaded: m.is_complete(),
+        state: m.state(),
+        backend: backend_label(ModelBackend::OnnxNemo),
+        aliases: Vec::new(),
+        ram_mb: 0,
+        relative_speed: 0.0,
+        wer: 0.0,
+        languages: if m.metadata.languages.is_empty() {
+            vec!["en".to_string()]
+        } else {
+            m.metadata.languages.clone()
+        },
+        files: m
+            .metadata
+            .files()
+            .iter()
+            .map(|f| ModelFileRecord {
+                filename: f.to_string(),
+                size_mb: 0,
+                sha256: String::new(),
+                state: models::file_state_in(&m.dir, f).into(),
+            })
+            .collect(),
+    }
+}
+
+/// Backend and on-disk location of `model_name`, honoring
+/// `model_path_override` when it is the configured Whisper model. Fails with
+/// NoModel when the model is unknown or its files aren't there.
+#[cfg(feature = "native")]
+fn locate_model(
+    config: &DiktoConfig,
+    model_name: &str,
+) -> Result<(ModelBackend, std::path::PathBuf), DiktoError> {
+    let model_name = models::canonical_model_name(model_name);
+    let backend = models::model_backend(&model_name).ok_or(DiktoError::NoModel)?;
+    if !backend.is_available() {
+        return Err(DiktoError::Model(format!(
+            "'{model_name}' needs {backend:?} support, which this build doesn't include"
+        )));
+    }
+    let model_dir = models::model_path(&model_name).ok_or(DiktoError::NoModel)?;
+
+    let is_configured = model_name == models::canonical_model_name(&config.model_name);
+    if let Some(override_path) = config.model_path_override.as_deref() {
+        if backend == ModelBackend::Whisper && is_configured {
+            let path = models::whisper_override_path(&model_dir, override_path);
+            if !models::has_whisper_model(&path) {
+                warn!(
+                    "model_path_override {} has no Whisper model",
+                    path.display()
+                );
+                return Err(DiktoError::NoModel);
+            }
+            return Ok((backend, path));
+        }
+    }
+
+    if !models::is_model_downloaded(&model_name) {
+        return Err(DiktoError::NoModel);
+    }
+    Ok((backend, model_dir))
+}
+
+/// Settle a model switch once `model_name` has been loaded. On success the
+/// switch is final; if the configured model fails to load, the config goes
+/// back to the previous model so later dictations work, and the error says
+/// so. Saves the config when it changes.
+#[cfg(feature = "native")]
+fn after_model_load(
+    inner: &mut DiktoEngineInner,
+    model_name: &str,
+    loaded: Result<(), TranscribeError>,
+) -> Result<(), DiktoError> {
+    let config = &mut inner.config;
+    let is_configured = model_name == models::canonical_model_name(&config.model_name);
+    let (changed, result) = match loaded {
+        Ok(()) => (
+            is_configured && config.previous_model.take().is_some(),
+            Ok(()),
+        ),
+        Err(e) => match is_configured.then(|| config.roll_back_model()).flatten() {
+            Some(previous) => {
+                warn!("Model '{model_name}' failed to load, switched back to '{previous}'");
+                (
+                    true,
+                    Err(DiktoError::Model(format!(
+                        "Failed to load model '{model_name}': {e}. Switched back to '{previous}'."
+                    ))),
+                )
+            }
+            None => (
+                false,
+                Err(DiktoError::Transcribe(format!("Failed to load model: {e}"))),
+            ),
+        },
+    };
+    if changed {
+        if let Err(e) = inner.persist(&inner.config) {
+            warn!("Failed to save config: {e}");
+        }
+    }
+    if result.is_ok() && inner.state.last_model.as_deref() != Some(model_name) {
+        inner.state.last_model = Some(model_name.to_string());
+        inner.persist_state();
+    }
+    result
+}
+
+/// Key for an app in `EngineState::app_languages`: its bundle identifier,
+/// or its name for hosts that don't report one.
+#[cfg(feature = "native")]
+fn app_key<'a>(bundle_id: &'a str, app_name: &'a str) -> &'a str {
+    if bundle_id.is_empty() {
+        app_name
+    } else {
+        bundle_id
+    }
+}
+
+/// Remember the language a session in an app was started with.
+#[cfg(feature = "native")]
+fn remember_app_language(inner: &mut DiktoEngineInner, listen_config: &ListenConfig) {
+    let app = app_key(&listen_config.bundle_id, &listen_config.app_name);
+    let before = inner.state.clone();
+    let configured = inner.config.language.clone();
+    inner
+        .state
+        .record_app_language(app, &listen_config.language, &configured);
+    if inner.state != before {
+        inner.persist_state();
+    }
+}
+
+/// Record in state.json whether a download of `model_name` is under way,
+/// so one cut short by quitting can be found at the next launch.
+#[cfg(feature = "native")]
+fn mark_download(inner: &Mutex<DiktoEngineInner>, model_name: &str, pending: bool) {
+    let Ok(mut inner) = inner.lock() else { return };
+    inner.state.set_download_pending(model_name, pending);
+    inner.persist_state();
+}
+
+/// Load the configured model into the worker unless it is already there.
+/// Returns the worker and the text processing settings to use with it.
+#[cfg(feature = "native")]
+fn ensure_model_loaded(
+    engine: &DiktoEngine,
+) -> Result<(Arc<InferenceWorker>, TextProcConfig), DiktoError> {
+    let (worker, model_name, backend, model_path, text_processing) = {
+        let inner = engine
+            .inner
+            .lock()
+            .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
+        let model_name = models::canonical_model_name(&inner.config.model_name);
+        let (backend, model_path) = locate_model(&inner.config, &model_name)?;
+        (
+            inner.worker.clone(),
+            model_name,
+            backend,
+            model_path,
+            inner.config.text_processing.clone(),
+        )
+    };
+    if worker.loaded_model().as_deref() != Some(model_name.as_str()) {
+        let loaded = worker.load(&model_name, backend, &model_path);
+        let mut inner = engine
+            .inner
+            .lock()
+            .map_err(|e| DiktoError::Config(format!("Lock poisoned: {e}")))?;
+        after_model_load(&mut inner, &model_name, loaded)?;
+    }
+    Ok((worker, text_processing))
+}
+
+/// List the tasks in a transcript and append them to the tasks file, when
+/// extraction is on.
+#[cfg(feature = "native")]
+fn add_action_items(result: &mut TranscriptionResult, config: &ActionItemsConfig) {
+    if !config.enabled {
+        return;
+    }
+    result.action_items = actions::extract_action_items(&result.text);
+    if result.action_items.is_empty() || config.tasks_file.trim().is_empty() {
+        return;
+    }
+    let appended = actions::tasks_path(config.tasks_file.trim())
+        .map_err(|e| e.to_string())
+        .and_then(|path| {
+            actions::append_to_tasks_file(&path, &result.action_items).map_err(|e| e.to_string())
+        });
+    if let Err(e) = appended {
+        warn!("Failed to append action items to tasks file: {e}");
+    }
+}
+
+/// Spool for the session's audio when saved sessions include it.
+#[cfg(feature = "native")]
+fn session_audio_spool(config: &SavedSessionsConfig) -> Option<Mutex<AudioSpool>> {
+    if !config.save_audio {
+        return None;
+    }
+    match AudioSpool::create(&std::env::temp_dir()) {
+        Ok(spool) => Some(Mutex::new(spool)),
+        Err(e) => {
+            warn!("Session audio won't be saved: {e}");
+            None
+        }
+    }
+}
+
+/// Seconds since the Unix epoch.
+#[cfg(feature = "native")]
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// The outputs a dry run skipped for `result`, in the order a real session
+/// does them.
+#[cfg(feature = "native")]
+fn dry_run_actions(
+    result: &TranscriptionResult,
+    settings: &PipelineSettings,
+    save_history: bool,
+) -> Vec<output::OutputAction> {
+    let mut skipped = Vec::new();
+    let tasks_file = settings.action_items.tasks_file.trim();
+    if !result.action_items.is_empty() && !tasks_file.is_empty() {
+        match actions::tasks_path(tasks_file) {
+            Ok(path) => skipped.push(output::OutputAction::AppendTasks {
+                path: path.display().to_string(),
+                items: result.action_items.len() as u32,
+            }),
+            Err(e) => warn!("Dry run: tasks file unusable: {e}"),
+        }
+    }
+    if settings.saved_sessions.is_enabled() {
+        match sessions::sessions_root(&settings.saved_sessions) {
+            Ok(dir) => skipped.push(output::OutputAction::SaveSession {
+                dir: dir.display().to_string(),
+            }),
+            Err(e) => warn!("Dry run: sessions folder unusable: {e}"),
+        }
+    }
+    if save_history {
+        skipped.push(output::OutputAction::RecordHistory);
+    }
+    let chars = result.text.chars().count() as u32;
+    if chars > 0 && settings.auto_paste {
+        skipped.push(output::OutputAction::Paste {
+            app: settings.listen.app_name.clone(),
+            chars,
+        });
+    }
+    if chars > 0 && settings.auto_copy {
+        skipped.push(output::OutputAction::Copy { chars });
+    }
+    skipped
+}
+
+/// Save a finished session's transcript and audio when saved sessions are
+/// on. Failures are logged; the result is delivered either way.
+#[cfg(feature = "native")]
+fn save_session(result: &TranscriptionResult, settings: &PipelineSettings, started_at: u64) {
+    let config = &settings.saved_sessions;
+    if !config.is_enabled() {
+        return;
+    }
+    let info = sessions::SessionInfo {
+        unix_secs: started_at,
+        utc_offset_secs: settings.listen.utc_offset_secs,
+        app: settings.listen.app_name.clone(),
+        text: result.text.clone(),
+    };
+    let mut audio = settings.session_audio.as_ref().and_then(|a| a.lock().ok());
+    let saved = sessions::sessions_root(config)
+        .map_err(|e| e.to_string())
+        .and_then(|root| {
+            sessions::save_session(
+                &root,
+                config,
+                &info,
+                result,
+                audio.as_deref_mut(),
+                settings.snapshot.as_ref(),
+            )
+            .map_err(|e| e.to_string())
+        });
+    match saved {
+        Ok(paths) => debug!("Saved session to {paths:?}"),
+        Err(e) => warn!("Failed to save session: {e}"),
+    }
+}
+
+/// Attach a summary to a long transcript when a summarizer is configured.
+/// A failed summary is logged and the transcript delivered without one.
+#[cfg(feature = "native")]
+fn add_summary(result: &mut TranscriptionResult, config: &SummaryConfig) {
+    if !summarize::should_summarize(&result.text, config) {
+        return;
+    }
+    match summarize::summarize(&result.text, config) {
+        Ok(summary) => result.summary = Some(summary),
+        Err(e) => warn!("Summary failed: {e}"),
+    }
+}
+
+/// Transcribe a whole recording of 16kHz mono samples with the loaded model.
+#[cfg(feature = "native")]
+fn transcribe_samples(
+    worker: &InferenceWorker,
+    samples: &[f32],
+    language: &str,
+    text_processing: &TextProcConfig,
+    redactor: &Redactor,
+    advanced: &AdvancedConfig,
+) -> Result<TranscriptionResult, DiktoError> {
+    // AsrSession::flush caps a buffer at 4 minutes, so feed long files in pieces
+    const CHUNK_SAMPLES: usize = 4 * 60 * wav::TARGET_SAMPLE_RATE as usize;
+    let mut session = AsrSession::new(language.to_string());
+    session.set_no_speech_threshold(advanced.no_speech_threshold);
+    session.set_normalization(
+        advanced.input_normalization,
+        advanced.normalization_min_range_db,
+    );
+    let mut transcript = SessionTranscript::default();
+    for chunk in samples.chunks(CHUNK_SAMPLES) {
+        session.feed_samples(chunk);
+        transcript.speech_duration_secs += session.buffer_duration_secs();
+        for seg in session.flush(worker)? {
+            let seg_language = seg.language.as_deref().unwrap_or(language);
+            transcript.push_raw(&seg.text, redactor);
+            let text = TextPipeline::for_language(seg_language, text_processing).process(&seg.text);
+            transcript.push(redactor.redact(&text));
+        }
+    }
+    Ok(transcript.into_result(text_processing))
+}
+
+/// Body of switch_model_with_download, run on its background thread.
+#[cfg(feature = "native")]
+fn download_and_switch(
+    engine: &DiktoEngine,
+    model_name: &str,
+    preload: bool,
+    hf_cache: Option<&std::path::Path>,
+    cancel: &ModelSwitchHandle,
+    callback: &Arc<dyn DownloadProgressCallback>,
+) -> Result<(), DiktoError> {
+    if !models::is_model_downloaded(model_name) {
+        // User models can't be downloaded
+        models::find_model(model_name).ok_or(DiktoError::NoModel)?;
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| DiktoError::Model(format!("Failed to create runtime: {e}")))?;
+        let cb = callback.clone();
+        mark_download(&engine.inner, model_name, true);
+        let downloaded = rt.block_on(models::download_model_cancellable(
+            model_name,
+            hf_cache,
+            &cancel.cancelled,
+            move |downloaded, total| cb.on_progress(downloaded, total),
+        ));
+        mark_download(&engine.inner, model_name, false);
+        downloaded?;
+        if !models::is_model_downloaded(model_name) {
+            return Err(DiktoError::Model(format!(
+                "Model '{model_name}' failed verification after download"
+            )));
+        }
+    }
+    if cancel.is_cancelled() {
+        return Err(models::ModelError::Cancelled.into());
+    }
+    engine.switch_model(model_name.to_string())?;
+    if preload {
+        engine.load_model()?;
+    }
+    Ok(())
+}
+
+/// Hugging Face hub cache to link downloads from, when enabled.
+#[cfg(feature = "native")]
+fn huggingface_cache(config: &DiktoConfig) -> Option<std::path::PathBuf> {
+    config
+        .huggingface_cache
+        .then(config::huggingface_cache_dir)
+        .flatten()
+}
+
+/// Display label for a backend in ModelInfoRecord.
+#[cfg(feature = "native")]
+fn backend_label(backend: ModelBackend) -> String {
+    match backend {
+        ModelBackend::Parakeet => "Parakeet",
+        ModelBackend::Whisper => "Whisper",
+        ModelBackend::OnnxNemo => "OnnxNemo",
+    }
+    .to_string()
+}
+
+/// LanguageInfo for language codes declared by a user model. Codes Whisper
+/// doesn't know are shown as-is.
+#[cfg(feature = "native")]
+fn languages_for_codes(codes: &[String]) -> Vec<LanguageInfo> {
+    let known = whisper_languages();
+    codes
+        .iter()
+        .map(|code| {
+            known
+                .iter()
+                .find(|l| &l.code == code)
+                .cloned()
+                .unwrap_or_else(|| LanguageInfo {
+                    code: code.clone(),
+                    name: code.clone(),
+                })
+        })
+        .collect()
 }
 
 /// Parakeet TDT v3 supported languages (25 European languages).
@@ -0,0 +1,111 @@
+use std::fs::OpenOptions;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::Mutex;
+
+use tracing::Subscriber;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+/// How log lines are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, uniffi::Enum)]
+pub enum LogFormat {
+    /// One human-readable line per event.
+    #[default]
+    Pretty,
+    /// One JSON object per line, for log collectors.
+    Json,
+}
+
+/// Where the engine's logs go and how much of them.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct LogOptions {
+    /// A level ("warn", "debug") or `RUST_LOG`-style directives
+    /// ("dikto_core=debug,info").
+    pub level: String,
+    /// File logs are appended to, created with its folder if needed.
+    pub file_path: Option<String>,
+    pub format: LogFormat,
+    /// Also write to stderr. Turn off when stderr carries a protocol, such
+    /// as an MCP server on stdio, or nobody reads it, as in a GUI app.
+    pub stderr: bool,
+}
+
+impl Default for LogOptions {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            file_path: None,
+            format: LogFormat::Pretty,
+            stderr: true,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+pub enum LogError {
+    #[error("Invalid log level: {0}")]
+    InvalidLevel(String),
+    #[error("Can't open log file: {0}")]
+    File(String),
+    #[error("Logging is already set up")]
+    AlreadyInitialized,
+}
+
+/// Install the process-wide tracing subscriber. Hosts call this once at
+/// startup, before creating an engine; later calls fail with
+/// `AlreadyInitialized`, as does a host that installed its own subscriber.
+pub fn init(options: LogOptions) -> Result<(), LogError> {
+    let filter =
+        EnvFilter::try_new(&options.level).map_err(|e| LogError::InvalidLevel(e.to_string()))?;
+    let file = match &options.file_path {
+        Some(path) => Some(
+            open_log_file(Path::new(path)).map_err(|e| LogError::File(format!("{path}: {e}")))?,
+        ),
+        None => None,
+    };
+
+    let stderr = options.stderr.then(|| {
+        sink(
+            options.format,
+            std::io::stderr,
+            std::io::stderr().is_terminal(),
+        )
+    });
+    let file = file.map(|f| sink(options.format, Mutex::new(f), false));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(stderr)
+        .with(file)
+        .try_init()
+        .map_err(|_| LogError::AlreadyInitialized)
+}
+
+/// `init` for hosts.
+#[uniffi::export]
+pub fn init_logging(options: LogOptions) -> Result<(), LogError> {
+    init(options)
+}
+
+fn open_log_file(path: &Path) -> std::io::Result<std::fs::File> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// A formatting layer writing to `writer`, with colors if `ansi`.
+fn sink<S, W>(format: LogFormat, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer().with_writer(writer);
+    match format {
+        LogFormat::Pretty => layer.with_ansi(ansi).boxed(),
+        LogFormat::Json => layer.json().boxed(),
+    }
+}
@@ -0,0 +1,240 @@
+use crate::textproc::PostProcessor;
+
+/// What a spoken math phrase means.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MathWord {
+    /// A symbol that stands on its own ("alpha" → `\alpha`).
+    Symbol(&'static str),
+    /// A binary operator written between operands ("plus" → `+`).
+    Infix(&'static str),
+    /// Attaches to the preceding operand ("squared" → `^2`).
+    Postfix(&'static str),
+    /// Exponent taken from the following operand ("to the power of").
+    Power,
+    /// Subscript taken from the following operand ("sub").
+    Subscript,
+    /// Square root of the following operand.
+    Sqrt,
+    /// Fraction of the surrounding operands ("over").
+    Over,
+}
+
+/// Spoken math vocabulary (English).
+const MATH_PHRASES: &[(&str, MathWord)] = &[
+    ("plus", MathWord::Infix("+")),
+    ("minus", MathWord::Infix("-")),
+    ("times", MathWord::Infix("\\cdot")),
+    ("multiplied by", MathWord::Infix("\\cdot")),
+    ("divided by", MathWord::Infix("\\div")),
+    ("equals", MathWord::Infix("=")),
+    ("is equal to", MathWord::Infix("=")),
+    ("not equal to", MathWord::Infix("\\neq")),
+    ("less than or equal to", MathWord::Infix("\\leq")),
+    ("greater than or equal to", MathWord::Infix("\\geq")),
+    ("less than", MathWord::Infix("<")),
+    ("greater than", MathWord::Infix(">")),
+    ("is not equal to", MathWord::Infix("\\neq")),
+    ("is less than or equal to", MathWord::Infix("\\leq")),
+    ("is greater than or equal to", MathWord::Infix("\\geq")),
+    ("is less than", MathWord::Infix("<")),
+    ("is greater than", MathWord::Infix(">")),
+    ("squared", MathWord::Postfix("^2")),
+    ("cubed", MathWord::Postfix("^3")),
+    ("factorial", MathWord::Postfix("!")),
+    ("prime", MathWord::Postfix("'")),
+    ("to the power of", MathWord::Power),
+    ("sub", MathWord::Subscript),
+    ("square root of", MathWord::Sqrt),
+    ("over", MathWord::Over),
+    ("infinity", MathWord::Symbol("\\infty")),
+    ("alpha", MathWord::Symbol("\\alpha")),
+    ("beta", MathWord::Symbol("\\beta")),
+    ("gamma", MathWord::Symbol("\\gamma")),
+    ("delta", MathWord::Symbol("\\delta")),
+    ("epsilon", MathWord::Symbol("\\epsilon")),
+    ("theta", MathWord::Symbol("\\theta")),
+    ("lambda", MathWord::Symbol("\\lambda")),
+    ("mu", MathWord::Symbol("\\mu")),
+    ("pi", MathWord::Symbol("\\pi")),
+    ("sigma", MathWord::Symbol("\\sigma")),
+    ("phi", MathWord::Symbol("\\phi")),
+    ("omega", MathWord::Symbol("\\omega")),
+];
+
+const UNITS: &[&str] = &[
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: &[&str] = &[
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// Converts spoken math into LaTeX, e.g. "x squared plus two over n" →
+/// `x^2 + \frac{2}{n}`. Words outside the math vocabulary pass through as
+/// operands, so variable names keep their original case.
+pub struct MathLatex {
+    /// (phrase words, meaning), longest phrases first.
+    phrases: Vec<(Vec<&'static str>, MathWord)>,
+}
+
+impl Default for MathLatex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MathLatex {
+    pub fn new() -> Self {
+        let mut phrases: Vec<(Vec<&'static str>, MathWord)> = MATH_PHRASES
+            .iter()
+            .map(|(phrase, word)| (phrase.split(' ').collect(), *word))
+            .collect();
+        phrases.sort_by_key(|(words, _)| std::cmp::Reverse(words.len()));
+        Self { phrases }
+    }
+
+    /// Match a math phrase at the start of `words` (already normalized).
+    fn match_at(&self, words: &[String]) -> Option<(usize, MathWord)> {
+        self.phrases.iter().find_map(|(phrase, word)| {
+            let matches =
+                phrase.len() <= words.len() && phrase.iter().zip(words).all(|(p, w)| p == w);
+            matches.then_some((phrase.len(), *word))
+        })
+    }
+}
+
+/// Parse a spoken number ("seven", "twenty one") at the start of `words`.
+fn number_at(words: &[String]) -> Option<(usize, u32)> {
+    let first = words.first()?;
+    if let Some(n) = UNITS.iter().position(|u| u == first) {
+        return Some((1, n as u32));
+    }
+    let tens = TENS.iter().position(|t| t == first)? as u32 * 10 + 20;
+    match words
+        .get(1)
+        .and_then(|w| UNITS[1..10].iter().position(|u| u == w))
+    {
+        Some(unit) => Some((2, tens + unit as u32 + 1)),
+        None => Some((1, tens)),
+    }
+}
+
+/// A resolved piece of the formula.
+enum Item {
+    Operand(String),
+    Infix(&'static str),
+    Over,
+}
+
+impl PostProcessor for MathLatex {
+    fn name(&self) -> &'static str {
+        "math_latex"
+    }
+
+    fn process(&self, text: &str) -> String {
+        let raw: Vec<&str> = text
+            .split_whitespace()
+            .map(|w| w.trim_matches(|c: char| matches!(c, ',' | '.' | '?' | '!' | ';' | ':')))
+            .filter(|w| !w.is_empty())
+            .collect();
+        let normalized: Vec<String> = raw.iter().map(|w| w.to_lowercase()).collect();
+
+        let mut items: Vec<Item> = Vec::new();
+        // Prefix operator waiting for its operand
+        let mut pending: Option<MathWord> = None;
+        let mut i = 0;
+        while i < raw.len() {
+            if let Some((len, n)) = number_at(&normalized[i..]) {
+                push_operand(&mut items, &mut pending, n.to_string());
+                i += len;
+            } else if let Some((len, word)) = self.match_at(&normalized[i..]) {
+                match word {
+                    MathWord::Symbol(s) => push_operand(&mut items, &mut pending, s.to_string()),
+                    MathWord::Infix(op) => items.push(Item::Infix(op)),
+                    MathWord::Postfix(suffix) => match items.last_mut() {
+                        Some(Item::Operand(prev)) => prev.push_str(suffix),
+                        _ => items.push(Item::Operand(suffix.to_string())),
+                    },
+                    MathWord::Power | MathWord::Subscript | MathWord::Sqrt => pending = Some(word),
+                    MathWord::Over => items.push(Item::Over),
+                }
+                i += len;
+            } else {
+                push_operand(&mut items, &mut pending, raw[i].to_string());
+                i += 1;
+            }
+        }
+
+        render(items)
+    }
+}
+
+/// Add an operand, applying a pending prefix operator to it.
+fn push_operand(items: &mut Vec<Item>, pending: &mut Option<MathWord>, operand: String) {
+    let marker = match pending.take() {
+        Some(MathWord::Sqrt) => {
+            items.push(Item::Operand(format!("\\sqrt{{{operand}}}")));
+            return;
+        }
+        Some(MathWord::Power) => "^",
+        Some(MathWord::Subscript) => "_",
+        _ => "",
+    };
+    match items.last_mut() {
+        Some(Item::Operand(prev)) if !marker.is_empty() => {
+            prev.push_str(&format!("{marker}{{{operand}}}"));
+        }
+        _ => items.push(Item::Operand(operand)),
+    }
+}
+
+/// Fold "a over b" into fractions and join everything with spaces.
+fn render(items: Vec<Item>) -> String {
+    let mut out: Vec<Item> = Vec::with_capacity(items.len());
+    let mut iter = items.into_iter().peekable();
+    while let Some(item) = iter.next() {
+        let Item::Over = item else {
+            out.push(item);
+            continue;
+        };
+        // A dangling "over" without operands on both sides stays a plain slash
+        if !matches!(
+            (out.last(), iter.peek()),
+            (Some(Item::Operand(_)), Some(Item::Operand(_)))
+        ) {
+            out.push(Item::Infix("/"));
+            continue;
+        }
+        if let (Some(Item::Operand(num)), Some(Item::Operand(den))) = (out.pop(), iter.next()) {
+            out.push(Item::Operand(format!("\\frac{{{num}}}{{{den}}}")));
+        }
+    }
+
+    out.iter()
+        .map(|item| match item {
+            Item::Operand(s) => s.as_str(),
+            Item::Infix(op) => op,
+            Item::Over => "/",
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
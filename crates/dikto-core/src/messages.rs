@@ -0,0 +1,135 @@
+/// Stable identifier for an error, so hosts can show their own message
+/// instead of the English, CLI-oriented `Display` text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum ErrorCode {
+    Audio,
+    Vad,
+    Transcribe,
+    Model,
+    NoModel,
+    /// The OS refused microphone access.
+    PermissionDenied,
+    AlreadyRecording,
+    /// A do-not-disturb rule kept the session from starting.
+    DoNotDisturb,
+    /// Sessions were started too quickly.
+    RateLimited,
+    Config,
+    /// A bug: the session thread panicked.
+    Internal,
+}
+
+/// Status shown while a session runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum StatusMessage {
+    LoadingModel,
+    Listening,
+    Processing,
+}
+
+/// Languages with a translated catalog. Anything else gets English.
+pub const CATALOG_LANGUAGES: &[&str] = &["en", "de", "es", "fr"];
+
+/// Catalog language for a locale such as "de-DE", "pt_BR.UTF-8", or "fr".
+pub fn catalog_language(locale: &str) -> &'static str {
+    let language = locale
+        .split(['-', '_', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    CATALOG_LANGUAGES
+        .iter()
+        .find(|l| **l == language)
+        .copied()
+        .unwrap_or("en")
+}
+
+/// A user-facing sentence for an error in the app, in `locale`'s language.
+pub fn error_message(code: ErrorCode, locale: &str) -> &'static str {
+    use ErrorCode::*;
+    match (catalog_language(locale), code) {
+        ("de", Audio) => "Das Mikrofon konnte nicht verwendet werden.",
+        ("de", Vad) => "Die Spracherkennung konnte nicht gestartet werden.",
+        ("de", Transcribe) => "Die Aufnahme konnte nicht transkribiert werden.",
+        ("de", Model) => "Das Modell konnte nicht geladen werden.",
+        ("de", NoModel) => "Kein Modell geladen. Lade in den Einstellungen eines herunter.",
+        ("de", PermissionDenied) => {
+            "Kein Zugriff auf das Mikrofon. Erlaube ihn in den Systemeinstellungen."
+        }
+        ("de", AlreadyRecording) => "Es läuft bereits eine Aufnahme.",
+        ("de", DoNotDisturb) => "„Nicht stören“ ist aktiv, die Aufnahme wurde nicht gestartet.",
+        ("de", RateLimited) => {
+            "Zu viele Aufnahmen kurz hintereinander. Versuche es gleich noch einmal."
+        }
+        ("de", Config) => "Die Einstellungen konnten nicht gespeichert werden.",
+        ("de", Internal) => "Ein interner Fehler ist aufgetreten.",
+        ("es", Audio) => "No se pudo usar el micrófono.",
+        ("es", Vad) => "No se pudo iniciar la detección de voz.",
+        ("es", Transcribe) => "No se pudo transcribir la grabación.",
+        ("es", Model) => "No se pudo cargar el modelo.",
+        ("es", NoModel) => "No hay ningún modelo cargado. Descarga uno en Ajustes.",
+        ("es", PermissionDenied) => {
+            "No hay acceso al micrófono. Permítelo en los Ajustes del Sistema."
+        }
+        ("es", AlreadyRecording) => "Ya hay una grabación en curso.",
+        ("es", DoNotDisturb) => "«No molestar» está activado; no se inició la grabación.",
+        ("es", RateLimited) => "Demasiadas grabaciones seguidas. Inténtalo de nuevo en un momento.",
+        ("es", Config) => "No se pudieron guardar los ajustes.",
+        ("es", Internal) => "Se produjo un error interno.",
+        ("fr", Audio) => "Impossible d'utiliser le micro.",
+        ("fr", Vad) => "Impossible de démarrer la détection de la voix.",
+        ("fr", Transcribe) => "Impossible de transcrire l'enregistrement.",
+        ("fr", Model) => "Impossible de charger le modèle.",
+        ("fr", NoModel) => "Aucun modèle chargé. Téléchargez-en un dans les Réglages.",
+        ("fr", PermissionDenied) => {
+            "Accès au micro refusé. Autorisez-le dans les Réglages Système."
+        }
+        ("fr", AlreadyRecording) => "Un enregistrement est déjà en cours.",
+        ("fr", DoNotDisturb) => "« Ne pas déranger » est actif, l'enregistrement n'a pas démarré.",
+        ("fr", RateLimited) => "Trop d'enregistrements à la suite. Réessayez dans un instant.",
+        ("fr", Config) => "Impossible d'enregistrer les réglages.",
+        ("fr", Internal) => "Une erreur interne s'est produite.",
+        (_, Audio) => "The microphone couldn't be used.",
+        (_, Vad) => "Voice detection couldn't start.",
+        (_, Transcribe) => "The recording couldn't be transcribed.",
+        (_, Model) => "The model couldn't be loaded.",
+        (_, NoModel) => "No model loaded. Download one in Settings.",
+        (_, PermissionDenied) => "Microphone access is denied. Allow it in System Settings.",
+        (_, AlreadyRecording) => "A recording is already in progress.",
+        (_, DoNotDisturb) => "Do not disturb is on, so recording didn't start.",
+        (_, RateLimited) => "Too many recordings in a row. Try again in a moment.",
+        (_, Config) => "The settings couldn't be saved.",
+        (_, Internal) => "An internal error occurred.",
+    }
+}
+
+/// Text for a session status in `locale`'s language.
+pub fn status_message(status: StatusMessage, locale: &str) -> &'static str {
+    use StatusMessage::*;
+    match (catalog_language(locale), status) {
+        ("de", LoadingModel) => "Modell wird geladen …",
+        ("de", Listening) => "Hört zu …",
+        ("de", Processing) => "Wird verarbeitet …",
+        ("es", LoadingModel) => "Cargando el modelo…",
+        ("es", Listening) => "Escuchando…",
+        ("es", Processing) => "Procesando…",
+        ("fr", LoadingModel) => "Chargement du modèle…",
+        ("fr", Listening) => "Écoute…",
+        ("fr", Processing) => "Traitement…",
+        (_, LoadingModel) => "Loading model...",
+        (_, Listening) => "Listening...",
+        (_, Processing) => "Processing...",
+    }
+}
+
+/// `error_message` for hosts.
+#[uniffi::export]
+pub fn localized_error_message(code: ErrorCode, locale: String) -> String {
+    error_message(code, &locale).to_string()
+}
+
+/// `status_message` for hosts.
+#[uniffi::export]
+pub fn localized_status_message(status: StatusMessage, locale: String) -> String {
+    status_message(status, &locale).to_string()
+}
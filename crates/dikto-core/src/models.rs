@@ -1,6 +1,10 @@
 use crate::config::models_dir;
+use crate::transcribe::is_whisper_model_file;
+pub use crate::ModelState;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use thiserror::Error;
 use tracing::{info, warn};
 
@@ -14,6 +18,10 @@ pub enum ModelError {
     Io(#[from] std::io::Error),
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
+    #[error("Invalid model metadata: {0}")]
+    InvalidMetadata(String),
+    #[error("Download cancelled")]
+    Cancelled,
 }
 
 /// ASR backend type for a model.
@@ -21,6 +29,20 @@ pub enum ModelError {
 pub enum ModelBackend {
     Parakeet,
     Whisper,
+    /// User-converted NeMo TDT export described by a metadata.json.
+    OnnxNemo,
+}
+
+impl ModelBackend {
+    /// Whether this build can run the backend's models: Whisper needs the
+    /// `backend-whisper` feature, Parakeet and NeMo exports
+    /// `backend-parakeet`.
+    pub fn is_available(self) -> bool {
+        match self {
+            ModelBackend::Whisper => cfg!(feature = "backend-whisper"),
+            ModelBackend::Parakeet | ModelBackend::OnnxNemo => cfg!(feature = "backend-parakeet"),
+        }
+    }
 }
 
 /// A single file that is part of a model.
@@ -41,8 +63,33 @@ pub struct ModelInfo {
     pub description: &'static str,
     pub files: &'static [ModelFile],
     pub backend: ModelBackend,
+    /// Approximate peak RAM while transcribing, in MB.
+    pub ram_mb: u32,
+    /// Approximate real-time factor on an M1 (seconds of processing per
+    /// second of audio); lower is faster.
+    pub relative_speed: f32,
+    /// Approximate average English WER (%) on the Open ASR Leaderboard;
+    /// lower is more accurate.
+    pub wer: f32,
+    /// ISO-639-1 codes the model transcribes.
+    pub languages: &'static [&'static str],
 }
 
+/// Parakeet TDT v2 is English only.
+const ENGLISH_ONLY: &[&str] = &["en"];
+
+/// Parakeet TDT v3: 25 European languages.
+const PARAKEET_V3_LANGUAGES: &[&str] = &[
+    "en", "de", "es", "fr", "it", "pt", "nl", "pl", "ru", "uk", "cs", "ro", "hu", "el", "bg", "hr",
+    "sk", "sl", "lt", "lv", "et", "fi", "da", "sv", "no",
+];
+
+/// Whisper's best-supported languages; it accepts 99 in total.
+const WHISPER_LANGUAGES: &[&str] = &[
+    "en", "zh", "de", "es", "ru", "ko", "fr", "ja", "pt", "tr", "pl", "ca", "nl", "ar", "sv", "it",
+    "id", "hi", "fi", "vi", "he", "uk", "el", "ms", "cs", "ro", "da", "hu", "ta", "no", "th",
+];
+
 /// Hardcoded model registry.
 pub const MODELS: &[ModelInfo] = &[
     ModelInfo {
@@ -50,6 +97,10 @@ pub const MODELS: &[ModelInfo] = &[
         size_mb: 2520,
         description: "NVIDIA Parakeet TDT 0.6B v2 — high accuracy English ASR (1.69% WER)",
         backend: ModelBackend::Parakeet,
+        ram_mb: 3000,
+        relative_speed: 0.05,
+        wer: 6.05,
+        languages: ENGLISH_ONLY,
         files: &[
             ModelFile {
                 filename: "encoder-model.onnx",
@@ -82,6 +133,10 @@ pub const MODELS: &[ModelInfo] = &[
         size_mb: 2560,
         description: "NVIDIA Parakeet TDT 0.6B v3 — 25 EU languages, 6.34% avg WER",
         backend: ModelBackend::Parakeet,
+        ram_mb: 3100,
+        relative_speed: 0.06,
+        wer: 6.34,
+        languages: PARAKEET_V3_LANGUAGES,
         files: &[
             ModelFile {
                 filename: "encoder-model.onnx",
@@ -114,6 +169,10 @@ pub const MODELS: &[ModelInfo] = &[
         size_mb: 75,
         description: "Whisper Tiny — fast, 99 languages, ~75 MB",
         backend: ModelBackend::Whisper,
+        ram_mb: 250,
+        relative_speed: 0.02,
+        wer: 12.8,
+        languages: WHISPER_LANGUAGES,
         files: &[ModelFile {
             filename: "ggml-tiny.bin",
             url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin",
@@ -126,6 +185,10 @@ pub const MODELS: &[ModelInfo] = &[
         size_mb: 460,
         description: "Whisper Small — balanced accuracy & speed, 99 languages, ~460 MB",
         backend: ModelBackend::Whisper,
+        ram_mb: 850,
+        relative_speed: 0.08,
+        wer: 8.6,
+        languages: WHISPER_LANGUAGES,
         files: &[ModelFile {
             filename: "ggml-small.bin",
             url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin",
@@ -138,6 +201,10 @@ pub const MODELS: &[ModelInfo] = &[
         size_mb: 1600,
         description: "Whisper Large v3 Turbo — highest accuracy, 99 languages, ~1.6 GB",
         backend: ModelBackend::Whisper,
+        ram_mb: 2300,
+        relative_speed: 0.2,
+        wer: 7.8,
+        languages: WHISPER_LANGUAGES,
         files: &[ModelFile {
             filename: "ggml-large-v3-turbo.bin",
             url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo.bin",
@@ -145,11 +212,47 @@ pub const MODELS: &[ModelInfo] = &[
             sha256: "1fc70f774d38eb169993ac391eea357ef47c88757ef72ee5943879b7e8e2bc69",
         }],
     },
+    ModelInfo {
+        name: "whisper-large-v3-turbo-q5_0",
+        size_mb: 547,
+        description: "Whisper Large v3 Turbo Q5 — near-turbo accuracy in a third of the RAM, ~550 MB",
+        backend: ModelBackend::Whisper,
+        ram_mb: 1000,
+        relative_speed: 0.25,
+        wer: 8.1,
+        languages: WHISPER_LANGUAGES,
+        files: &[ModelFile {
+            filename: "ggml-large-v3-turbo-q5_0.bin",
+            url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo-q5_0.bin",
+            size_mb: 547,
+            sha256: "394221709cd5ad1f40c46e6031ca61bce88931e6e088c188294c6d5a55ffa7e2",
+        }],
+    },
+    ModelInfo {
+        name: "whisper-large-v3-turbo-q8_0",
+        size_mb: 834,
+        description: "Whisper Large v3 Turbo Q8 — turbo accuracy with half the RAM, ~830 MB",
+        backend: ModelBackend::Whisper,
+        ram_mb: 1300,
+        relative_speed: 0.22,
+        wer: 7.9,
+        languages: WHISPER_LANGUAGES,
+        files: &[ModelFile {
+            filename: "ggml-large-v3-turbo-q8_0.bin",
+            url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo-q8_0.bin",
+            size_mb: 834,
+            sha256: "317eb69c11673c9de1e1f0d459b253999804ec71ac4c23c17ecf5fbe24e259a1",
+        }],
+    },
     ModelInfo {
         name: "distil-whisper-large-v3",
         size_mb: 1520,
         description: "Distil-Whisper Large v3 — 6x faster Whisper, 99 languages, ~1.5 GB",
         backend: ModelBackend::Whisper,
+        ram_mb: 2200,
+        relative_speed: 0.12,
+        wer: 7.5,
+        languages: WHISPER_LANGUAGES,
         files: &[ModelFile {
             filename: "ggml-distil-large-v3.bin",
             url: "https://huggingface.co/distil-whisper/distil-large-v3-ggml/resolve/main/ggml-distil-large-v3.bin",
@@ -159,23 +262,221 @@ pub const MODELS: &[ModelInfo] = &[
     },
 ];
 
-/// Look up model info by name.
+/// Stable aliases for registry models, so configs and docs can name a role
+/// rather than a versioned model that changes when a new release ships.
+pub const MODEL_ALIASES: &[(&str, &str)] = &[
+    ("default", "parakeet-tdt-0.6b-v2"),
+    ("best-english", "parakeet-tdt-0.6b-v2"),
+    ("multilingual", "parakeet-tdt-0.6b-v3"),
+];
+
+/// Registry name an alias points to, if `name` is an alias.
+pub fn resolve_alias(name: &str) -> Option<&'static str> {
+    MODEL_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, target)| *target)
+}
+
+/// Aliases that point to a registry model.
+pub fn aliases_for(name: &str) -> Vec<&'static str> {
+    MODEL_ALIASES
+        .iter()
+        .filter(|(_, target)| *target == name)
+        .map(|(alias, _)| *alias)
+        .collect()
+}
+
+/// Everything needed to dictate in one language, chosen and switched as a
+/// unit: the recognizer, plus the spoken-punctuation words and number
+/// formatting that `textproc::LanguageRules` keeps for the language. The
+/// registry models write punctuation themselves, so there is no separate
+/// punctuation model to fetch.
+#[derive(Debug, Clone)]
+pub struct LanguagePack {
+    /// ISO-639-1 code, used as the session language.
+    pub language: &'static str,
+    pub name: &'static str,
+    /// Registry model that transcribes the language best for its size.
+    pub model: &'static str,
+}
+
+/// Languages with their own text rules, each with its recognizer.
+pub const LANGUAGE_PACKS: &[LanguagePack] = &[
+    LanguagePack {
+        language: "en",
+        name: "English",
+        model: "parakeet-tdt-0.6b-v2",
+    },
+    LanguagePack {
+        language: "de",
+        name: "German",
+        model: "parakeet-tdt-0.6b-v3",
+    },
+    LanguagePack {
+        language: "fr",
+        name: "French",
+        model: "parakeet-tdt-0.6b-v3",
+    },
+    LanguagePack {
+        language: "es",
+        name: "Spanish",
+        model: "parakeet-tdt-0.6b-v3",
+    },
+    LanguagePack {
+        language: "it",
+        name: "Italian",
+        model: "parakeet-tdt-0.6b-v3",
+    },
+    LanguagePack {
+        language: "tr",
+        name: "Turkish",
+        model: "whisper-large-v3-turbo-q5_0",
+    },
+];
+
+/// Language pack for an ISO-639-1 code.
+pub fn find_language_pack(language: &str) -> Option<&'static LanguagePack> {
+    LANGUAGE_PACKS.iter().find(|p| p.language == language)
+}
+
+/// Look up model info by name or alias.
 pub fn find_model(name: &str) -> Option<&'static ModelInfo> {
+    let name = resolve_alias(name).unwrap_or(name);
     MODELS.iter().find(|m| m.name == name)
 }
 
+/// Versioned name for a model: aliases resolve to their registry model,
+/// anything else is returned unchanged.
+pub fn canonical_model_name(name: &str) -> String {
+    resolve_alias(name).unwrap_or(name).to_string()
+}
+
+/// Backend for a registry or user-converted model.
+pub fn model_backend(name: &str) -> Option<ModelBackend> {
+    find_model(name)
+        .map(|m| m.backend)
+        .or_else(|| find_custom_model(name).map(|_| ModelBackend::OnnxNemo))
+}
+
 /// Get the local directory path for a model.
 pub fn model_path(name: &str) -> Option<PathBuf> {
-    find_model(name).map(|_| models_dir().join(name))
+    find_model(name)
+        .map(|m| models_dir().join(m.name))
+        .or_else(|| find_custom_model(name).map(|m| m.dir))
 }
 
-/// Check if all files of a model are downloaded.
+/// Where to load a Whisper model from given `model_path_override`: absolute
+/// paths are used as-is, anything else is taken relative to `model_dir`.
+pub fn whisper_override_path(model_dir: &Path, override_path: &str) -> PathBuf {
+    let path = Path::new(override_path);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        model_dir.join(path)
+    }
+}
+
+/// True when `path` is a file (or symlink to one) or a directory holding a
+/// `ggml-*.bin` or `.gguf` file. A file given explicitly may have any name.
+pub fn has_whisper_model(path: &Path) -> bool {
+    if path.is_file() {
+        return true;
+    }
+    std::fs::read_dir(path).is_ok_and(|entries| {
+        entries
+            .flatten()
+            .any(|e| is_whisper_model_file(&e.file_name().to_string_lossy()))
+    })
+}
+
+/// Check if all files of a model are downloaded with plausible sizes.
 pub fn is_model_downloaded(name: &str) -> bool {
-    let Some(model) = find_model(name) else {
-        return false;
-    };
-    let dir = models_dir().join(name);
-    model.files.iter().all(|f| dir.join(f.filename).exists())
+    model_state(name) == ModelState::Downloaded
+}
+
+/// Download state of a registry or user-converted model. Unknown names are
+/// NotDownloaded.
+pub fn model_state(name: &str) -> ModelState {
+    match find_model(name) {
+        Some(model) => model_state_in(&models_dir().join(model.name), model),
+        None => find_custom_model(name)
+            .map(|m| m.state())
+            .unwrap_or(ModelState::NotDownloaded),
+    }
+}
+
+/// Download state of `model` in `dir`, checking each file's size against
+/// the registry (no hashing, so this stays cheap enough for list_models).
+pub fn model_state_in(dir: &Path, model: &ModelInfo) -> ModelState {
+    let states: Vec<(&ModelFile, FileState)> = model
+        .files
+        .iter()
+        .map(|f| (f, file_state_in(dir, f.filename)))
+        .collect();
+    let complete = states.iter().all(|(f, state)| {
+        matches!(state, FileState::Present { bytes } if size_matches(*bytes, f.size_mb))
+    });
+    if complete {
+        ModelState::Downloaded
+    } else if states.iter().all(|(_, state)| *state == FileState::Missing) {
+        ModelState::NotDownloaded
+    } else {
+        ModelState::PartiallyDownloaded
+    }
+}
+
+/// Whether a file of `bytes` plausibly is the registry file of `expected_mb`.
+/// Registry sizes are rounded (and some are MB rather than MiB), so allow 10%
+/// or 1 MiB, whichever is larger; a truncated download is far outside that.
+pub fn size_matches(bytes: u64, expected_mb: u32) -> bool {
+    const MIB: u64 = 1024 * 1024;
+    let expected = expected_mb as u64 * MIB;
+    let tolerance = (expected / 10).max(MIB);
+    bytes.abs_diff(expected) <= tolerance
+}
+
+/// What is on disk for one file of a model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileState {
+    Missing,
+    /// An interrupted or running download left `bytes` in `<file>.downloading`.
+    Partial {
+        bytes: u64,
+    },
+    Present {
+        bytes: u64,
+    },
+}
+
+/// State of `filename` inside a model directory.
+pub fn file_state_in(dir: &Path, filename: &str) -> FileState {
+    if let Ok(meta) = std::fs::metadata(dir.join(filename)) {
+        return FileState::Present { bytes: meta.len() };
+    }
+    match std::fs::metadata(dir.join(format!("{filename}.downloading"))) {
+        Ok(meta) => FileState::Partial { bytes: meta.len() },
+        Err(_) => FileState::Missing,
+    }
+}
+
+/// Whether a download of `model` into `dir` was cut short: some file has a
+/// `.downloading` partial waiting to be resumed.
+pub fn has_partial_download_in(dir: &Path, model: &ModelInfo) -> bool {
+    model
+        .files
+        .iter()
+        .any(|f| matches!(file_state_in(dir, f.filename), FileState::Partial { .. }))
+}
+
+/// Registry models whose download was interrupted, e.g. by quitting the
+/// app, and left partial files behind.
+pub fn interrupted_downloads() -> Vec<&'static ModelInfo> {
+    let dir = models_dir();
+    MODELS
+        .iter()
+        .filter(|m| has_partial_download_in(&dir.join(m.name), m))
+        .collect()
 }
 
 /// List all models with their download status.
@@ -188,7 +489,27 @@ pub fn list_models() -> Vec<(ModelInfo, bool)> {
 
 /// Download a model with progress callback.
 /// `on_progress` receives (bytes_downloaded, total_bytes).
-pub async fn download_model<F>(name: &str, on_progress: F) -> Result<PathBuf, ModelError>
+/// When `hf_cache` is given, files already in that Hugging Face hub cache
+/// are linked instead of downloaded.
+pub async fn download_model<F>(
+    name: &str,
+    hf_cache: Option<&Path>,
+    on_progress: F,
+) -> Result<PathBuf, ModelError>
+where
+    F: Fn(u64, u64) + Send + 'static,
+{
+    download_model_cancellable(name, hf_cache, &AtomicBool::new(false), on_progress).await
+}
+
+/// download_model that stops with `ModelError::Cancelled` once `cancel` is
+/// set. The partial file keeps a checkpoint, so a later download resumes.
+pub async fn download_model_cancellable<F>(
+    name: &str,
+    hf_cache: Option<&Path>,
+    cancel: &AtomicBool,
+    on_progress: F,
+) -> Result<PathBuf, ModelError>
 where
     F: Fn(u64, u64) + Send + 'static,
 {
@@ -197,7 +518,7 @@ where
         ModelError::NotFound(name.to_string(), available)
     })?;
 
-    let dir = models_dir().join(name);
+    let dir = models_dir().join(model.name);
     std::fs::create_dir_all(&dir)?;
 
     // Calculate total size and already-downloaded bytes
@@ -209,15 +530,62 @@ where
     let mut cumulative_downloaded: u64 = 0;
 
     for file in model.files {
+        if cancel.load(Ordering::Acquire) {
+            return Err(ModelError::Cancelled);
+        }
         let dest = dir.join(file.filename);
 
-        if dest.exists() {
-            // Count existing file size towards progress
-            let existing_size = std::fs::metadata(&dest).map(|m| m.len()).unwrap_or(0);
-            cumulative_downloaded += existing_size;
-            on_progress(cumulative_downloaded, total_bytes);
-            info!("File {} already exists, skipping", file.filename);
-            continue;
+        if let Ok(meta) = std::fs::metadata(&dest) {
+            if size_matches(meta.len(), file.size_mb) {
+                // Count existing file size towards progress
+                cumulative_downloaded += meta.len();
+                on_progress(cumulative_downloaded, total_bytes);
+                info!("File {} already exists, skipping", file.filename);
+                continue;
+            }
+            warn!(
+                "File {} is {} bytes, expected ~{} MB; downloading again",
+                file.filename,
+                meta.len(),
+                file.size_mb
+            );
+            std::fs::remove_file(&dest)?;
+        }
+
+        let cached = match hf_cache.and_then(|cache| find_in_hf_cache(cache, file)) {
+            Some(cached) => {
+                let path = cached.clone();
+                let file_info = file.clone();
+                let hash_ok =
+                    tokio::task::spawn_blocking(move || cached_file_matches(&path, &file_info))
+                        .await
+                        .map_err(|e| {
+                            ModelError::DownloadFailed(format!("Hash task failed: {e}"))
+                        })?;
+                if !hash_ok {
+                    warn!(
+                        "{} in the Hugging Face cache doesn't match its SHA-256, downloading",
+                        cached.display()
+                    );
+                }
+                hash_ok.then_some(cached)
+            }
+            None => None,
+        };
+        if let Some(cached) = cached {
+            match link_file(&cached, &dest) {
+                Ok(()) => {
+                    cumulative_downloaded += std::fs::metadata(&dest).map(|m| m.len()).unwrap_or(0);
+                    on_progress(cumulative_downloaded, total_bytes);
+                    info!(
+                        "Using {} from the Hugging Face cache ({})",
+                        file.filename,
+                        cached.display()
+                    );
+                    continue;
+                }
+                Err(e) => warn!("Failed to link {}: {e}, downloading", cached.display()),
+            }
         }
 
         info!(
@@ -225,8 +593,30 @@ where
             file.filename, file.size_mb, file.url
         );
 
-        let response = reqwest::get(file.url).await?;
+        let temp_dest = dir.join(format!("{}.downloading", file.filename));
+
+        // Resume a partial left by an interrupted run, but only from a
+        // checkpoint whose prefix hash still matches what is on disk
+        let mut resume = {
+            let temp_path = temp_dest.clone();
+            tokio::task::spawn_blocking(move || verified_partial(&temp_path))
+                .await
+                .map_err(|e| ModelError::DownloadFailed(format!("Hash task failed: {e}")))?
+        };
 
+        let client = reqwest::Client::new();
+        let mut request = client.get(file.url);
+        if let Some((offset, _)) = &resume {
+            request = request.header(reqwest::header::RANGE, format!("bytes={offset}-"));
+        }
+        let mut response = request.send().await?;
+
+        if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            // The checkpoint is at or past the end of the file; start over
+            discard_partial(&temp_dest);
+            resume = None;
+            response = client.get(file.url).send().await?;
+        }
         if !response.status().is_success() {
             return Err(ModelError::DownloadFailed(format!(
                 "HTTP {} for {}",
@@ -235,22 +625,66 @@ where
             )));
         }
 
-        let temp_dest = dir.join(format!("{}.downloading", file.filename));
+        // A server that ignores the range sends the whole file again
+        let resume = resume.filter(|_| response.status() == reqwest::StatusCode::PARTIAL_CONTENT);
+        if resume.is_none() {
+            discard_partial(&temp_dest);
+        }
 
         // Use a closure to ensure temp file cleanup on any error
         let download_result: Result<(), ModelError> = async {
             use futures::StreamExt;
+            use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
             let mut stream = response.bytes_stream();
-            let mut out = tokio::fs::File::create(&temp_dest)
-                .await
-                .map_err(ModelError::Io)?;
+            let (mut written, mut hasher, mut out) = match resume {
+                Some((offset, hasher)) => {
+                    info!("Resuming {} at {} bytes", file.filename, offset);
+                    let mut out = tokio::fs::OpenOptions::new()
+                        .write(true)
+                        .open(&temp_dest)
+                        .await
+                        .map_err(ModelError::Io)?;
+                    // Drop anything written after the last checkpoint
+                    out.set_len(offset).await.map_err(ModelError::Io)?;
+                    out.seek(std::io::SeekFrom::End(0))
+                        .await
+                        .map_err(ModelError::Io)?;
+                    (offset, hasher, out)
+                }
+                None => {
+                    let out = tokio::fs::File::create(&temp_dest)
+                        .await
+                        .map_err(ModelError::Io)?;
+                    (0, Sha256::new(), out)
+                }
+            };
+            cumulative_downloaded += written;
+            on_progress(cumulative_downloaded, total_bytes);
 
-            use tokio::io::AsyncWriteExt;
+            let mut next_checkpoint = written + CHECKPOINT_INTERVAL_BYTES;
             while let Some(chunk) = stream.next().await {
+                if cancel.load(Ordering::Acquire) {
+                    out.flush().await.map_err(ModelError::Io)?;
+                    out.sync_data().await.map_err(ModelError::Io)?;
+                    write_checkpoint(&temp_dest, written, &hasher);
+                    info!("Download of {} cancelled", file.filename);
+                    return Err(ModelError::Cancelled);
+                }
                 let chunk = chunk?;
                 out.write_all(&chunk).await.map_err(ModelError::Io)?;
+                hasher.update(&chunk);
+                written += chunk.len() as u64;
                 cumulative_downloaded += chunk.len() as u64;
                 on_progress(cumulative_downloaded, total_bytes);
+
+                if written >= next_checkpoint {
+                    // The checkpoint must never claim bytes that aren't on disk
+                    out.flush().await.map_err(ModelError::Io)?;
+                    out.sync_data().await.map_err(ModelError::Io)?;
+                    write_checkpoint(&temp_dest, written, &hasher);
+                    next_checkpoint = written + CHECKPOINT_INTERVAL_BYTES;
+                }
             }
             out.flush().await.map_err(ModelError::Io)?;
             drop(out);
@@ -291,14 +725,18 @@ where
             tokio::fs::rename(&temp_dest, &dest)
                 .await
                 .map_err(ModelError::Io)?;
+            let _ = std::fs::remove_file(checkpoint_path(&temp_dest));
 
             Ok(())
         }
         .await;
 
-        // Clean up temp file on any error
+        // Keep the partial after a dropped connection so the next run can
+        // resume; anything else (failed verification, disk errors) discards it
         if let Err(e) = download_result {
-            let _ = tokio::fs::remove_file(&temp_dest).await;
+            if !matches!(e, ModelError::Http(_)) {
+                discard_partial(&temp_dest);
+            }
             return Err(e);
         }
 
@@ -313,6 +751,121 @@ where
     Ok(dir)
 }
 
+/// `owner/repo` of a file hosted on huggingface.co.
+pub fn huggingface_repo(url: &str) -> Option<&str> {
+    let path = url.strip_prefix("https://huggingface.co/")?;
+    let (repo, _) = path.split_once("/resolve/")?;
+    (repo.split('/').count() == 2).then_some(repo)
+}
+
+/// Find a registry file in a Hugging Face hub cache, which keeps files as
+/// `models--<owner>--<repo>/snapshots/<revision>/<filename>`. Only copies
+/// whose size matches the registry are returned.
+pub fn find_in_hf_cache(cache: &Path, file: &ModelFile) -> Option<PathBuf> {
+    let repo = huggingface_repo(file.url)?;
+    let snapshots = cache
+        .join(format!("models--{}", repo.replace('/', "--")))
+        .join("snapshots");
+    let mut candidates: Vec<PathBuf> = std::fs::read_dir(snapshots)
+        .ok()?
+        .flatten()
+        .map(|e| e.path().join(file.filename))
+        .filter(|p| {
+            std::fs::metadata(p).is_ok_and(|m| m.is_file() && size_matches(m.len(), file.size_mb))
+        })
+        .collect();
+    candidates.sort();
+    candidates.pop()
+}
+
+/// Whether a copy of `file` found in a cache can be used: it must match
+/// the registry's SHA-256, like a download. Files without a pinned hash
+/// were already size-checked by `find_in_hf_cache`.
+pub fn cached_file_matches(path: &Path, file: &ModelFile) -> bool {
+    file.sha256.is_empty() || verify_file_sha256(path, file.sha256)
+}
+
+/// Make `src` available at `dest` without copying. Snapshot entries are
+/// themselves symlinks into the cache's blobs, so link the blob directly.
+fn link_file(src: &Path, dest: &Path) -> std::io::Result<()> {
+    let target = src.canonicalize()?;
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(&target, dest)
+    }
+    #[cfg(not(unix))]
+    {
+        std::fs::hard_link(&target, dest)
+    }
+}
+
+/// Bytes downloaded between resume checkpoints.
+const CHECKPOINT_INTERVAL_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Progress record for a `.downloading` file: how many leading bytes are
+/// known to be intact, and their SHA-256.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DownloadCheckpoint {
+    pub len: u64,
+    pub sha256: String,
+}
+
+/// Sidecar holding the checkpoint of a partial download.
+pub fn checkpoint_path(partial: &Path) -> PathBuf {
+    let mut name = partial.as_os_str().to_os_string();
+    name.push(".checkpoint");
+    PathBuf::from(name)
+}
+
+/// Record that the first `len` bytes of `partial` hash to `hasher`'s state.
+fn write_checkpoint(partial: &Path, len: u64, hasher: &Sha256) {
+    let checkpoint = DownloadCheckpoint {
+        len,
+        sha256: format!("{:x}", hasher.clone().finalize()),
+    };
+    let result = serde_json::to_string(&checkpoint)
+        .map_err(std::io::Error::other)
+        .and_then(|json| std::fs::write(checkpoint_path(partial), json));
+    if let Err(e) = result {
+        warn!("Failed to write download checkpoint: {e}");
+    }
+}
+
+/// Remove a partial download and its checkpoint.
+fn discard_partial(partial: &Path) {
+    let _ = std::fs::remove_file(partial);
+    let _ = std::fs::remove_file(checkpoint_path(partial));
+}
+
+/// Check a partial download against its checkpoint. Returns the offset to
+/// resume from and the hasher state over the verified prefix, or None when
+/// there is no checkpoint, the file is shorter than the checkpoint claims
+/// (partials only ever grow), or the prefix hash no longer matches.
+pub fn verified_partial(partial: &Path) -> Option<(u64, Sha256)> {
+    let json = std::fs::read_to_string(checkpoint_path(partial)).ok()?;
+    let checkpoint: DownloadCheckpoint = serde_json::from_str(&json).ok()?;
+    let on_disk = std::fs::metadata(partial).ok()?.len();
+    if checkpoint.len == 0 || on_disk < checkpoint.len {
+        warn!(
+            "Partial {} is shorter than its checkpoint, restarting",
+            partial.display()
+        );
+        return None;
+    }
+
+    let file = std::fs::File::open(partial).ok()?;
+    let mut hasher = Sha256::new();
+    let copied = std::io::copy(&mut std::io::Read::take(file, checkpoint.len), &mut hasher).ok()?;
+    if copied != checkpoint.len || format!("{:x}", hasher.clone().finalize()) != checkpoint.sha256 {
+        warn!(
+            "Partial {} failed its checkpoint hash, restarting",
+            partial.display()
+        );
+        return None;
+    }
+    Some((checkpoint.len, hasher))
+}
+
 /// Verify the SHA-256 hash of a file.
 pub fn verify_file_sha256(path: &std::path::Path, expected_hex: &str) -> bool {
     let Ok(mut file) = std::fs::File::open(path) else {
@@ -328,12 +881,12 @@ pub fn verify_file_sha256(path: &std::path::Path, expected_hex: &str) -> bool {
 
 /// Delete a downloaded model (removes the entire model directory).
 pub fn delete_model(name: &str) -> Result<(), ModelError> {
-    let Some(_) = find_model(name) else {
+    if find_model(name).is_none() && find_custom_model(name).is_none() {
         let available = MODELS.iter().map(|m| m.name).collect::<Vec<_>>().join(", ");
         return Err(ModelError::NotFound(name.to_string(), available));
-    };
+    }
 
-    let dir = models_dir().join(name);
+    let dir = models_dir().join(canonical_model_name(name));
     if dir.exists() {
         std::fs::remove_dir_all(&dir)?;
         info!("Deleted model {} at {}", name, dir.display());
@@ -342,3 +895,211 @@ pub fn delete_model(name: &str) -> Result<(), ModelError> {
     }
     Ok(())
 }
+
+// ---------------------------------------------------------------------------
+// User-converted models
+// ---------------------------------------------------------------------------
+
+/// Descriptor that marks a folder in the models directory as a user model.
+pub const CUSTOM_METADATA_FILE: &str = "metadata.json";
+
+/// Backend identifier accepted in metadata.json.
+pub const ONNX_NEMO_BACKEND: &str = "onnx-nemo";
+
+/// File names ParakeetTDT expects in a model directory.
+const NEMO_ENCODER: &str = "encoder-model.onnx";
+const NEMO_DECODER: &str = "decoder_joint-model.onnx";
+const NEMO_VOCAB: &str = "vocab.txt";
+
+/// Subdirectory holding links under the expected names when metadata.json
+/// renames the encoder, decoder, or vocab.
+const STAGED_DIR: &str = ".staged";
+
+fn default_encoder() -> String {
+    NEMO_ENCODER.to_string()
+}
+
+fn default_decoder() -> String {
+    NEMO_DECODER.to_string()
+}
+
+fn default_vocab() -> String {
+    NEMO_VOCAB.to_string()
+}
+
+/// metadata.json of a user-converted NeMo TDT model, e.g.
+/// `{"backend": "onnx-nemo", "description": "Parakeet tuned on call audio"}`.
+/// File names default to the layout of the registry Parakeet exports.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct CustomModelMetadata {
+    pub backend: String,
+    #[serde(default)]
+    pub description: String,
+    /// Language codes the model understands. Empty means English only.
+    #[serde(default)]
+    pub languages: Vec<String>,
+    #[serde(default = "default_encoder")]
+    pub encoder: String,
+    #[serde(default = "default_decoder")]
+    pub decoder: String,
+    #[serde(default = "default_vocab")]
+    pub vocab: String,
+}
+
+impl CustomModelMetadata {
+    /// Read and validate metadata.json from a model directory.
+    pub fn load(dir: &Path) -> Result<Self, ModelError> {
+        let contents = std::fs::read_to_string(dir.join(CUSTOM_METADATA_FILE))?;
+        let metadata: Self = serde_json::from_str(&contents)
+            .map_err(|e| ModelError::InvalidMetadata(e.to_string()))?;
+        if metadata.backend != ONNX_NEMO_BACKEND {
+            return Err(ModelError::InvalidMetadata(format!(
+                "unsupported backend '{}', expected '{ONNX_NEMO_BACKEND}'",
+                metadata.backend
+            )));
+        }
+        for file in metadata.files() {
+            if Path::new(file).components().count() != 1 {
+                return Err(ModelError::InvalidMetadata(format!(
+                    "'{file}' must be a file name inside the model folder"
+                )));
+            }
+        }
+        Ok(metadata)
+    }
+
+    /// Encoder, decoder, and vocab file names.
+    pub fn files(&self) -> [&str; 3] {
+        [&self.encoder, &self.decoder, &self.vocab]
+    }
+}
+
+/// A user-converted model found in the models directory.
+#[derive(Debug, Clone)]
+pub struct CustomModel {
+    pub name: String,
+    pub dir: PathBuf,
+    pub metadata: CustomModelMetadata,
+}
+
+impl CustomModel {
+    /// True when the encoder, decoder, and vocab are all present.
+    pub fn is_complete(&self) -> bool {
+        self.metadata
+            .files()
+            .iter()
+            .all(|f| self.dir.join(f).is_file())
+    }
+
+    /// Downloaded when complete, PartiallyDownloaded when only some files
+    /// are present. User models have no expected sizes to check.
+    pub fn state(&self) -> ModelState {
+        if self.is_complete() {
+            ModelState::Downloaded
+        } else if self
+            .metadata
+            .files()
+            .iter()
+            .any(|f| self.dir.join(f).is_file())
+        {
+            ModelState::PartiallyDownloaded
+        } else {
+            ModelState::NotDownloaded
+        }
+    }
+
+    /// Total size of the files in the model folder.
+    pub fn size_mb(&self) -> u32 {
+        let bytes: u64 = std::fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|e| e.metadata().ok())
+                    .filter(|m| m.is_file())
+                    .map(|m| m.len())
+                    .sum()
+            })
+            .unwrap_or(0);
+        (bytes / (1024 * 1024)) as u32
+    }
+}
+
+/// Look up a user model by folder name under `root`. Registry names are
+/// never treated as user models.
+pub fn find_custom_model_in(root: &Path, name: &str) -> Option<CustomModel> {
+    if find_model(name).is_some() || Path::new(name).components().count() != 1 {
+        return None;
+    }
+    let dir = root.join(name);
+    if !dir.join(CUSTOM_METADATA_FILE).exists() {
+        return None;
+    }
+    match CustomModelMetadata::load(&dir) {
+        Ok(metadata) => Some(CustomModel {
+            name: name.to_string(),
+            dir,
+            metadata,
+        }),
+        Err(e) => {
+            warn!("Ignoring model folder {}: {e}", dir.display());
+            None
+        }
+    }
+}
+
+/// Look up a user model in the default models directory.
+pub fn find_custom_model(name: &str) -> Option<CustomModel> {
+    find_custom_model_in(&models_dir(), name)
+}
+
+/// All user models under `root`, sorted by name.
+pub fn list_custom_models_in(root: &Path) -> Vec<CustomModel> {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut found: Vec<CustomModel> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| find_custom_model_in(root, &e.file_name().to_string_lossy()))
+        .collect();
+    found.sort_by(|a, b| a.name.cmp(&b.name));
+    found
+}
+
+/// All user models in the default models directory.
+pub fn list_custom_models() -> Vec<CustomModel> {
+    list_custom_models_in(&models_dir())
+}
+
+/// Directory to hand to ParakeetTDT for a user model. When metadata.json
+/// renames any of the files, they are hard-linked under the expected names
+/// into a `.staged` subfolder, along with everything else in the folder
+/// (e.g. the encoder's external `.onnx.data` weights).
+pub fn stage_nemo_model(dir: &Path, metadata: &CustomModelMetadata) -> Result<PathBuf, ModelError> {
+    let expected = [NEMO_ENCODER, NEMO_DECODER, NEMO_VOCAB];
+    if metadata.files() == expected {
+        return Ok(dir.to_path_buf());
+    }
+
+    let staged = dir.join(STAGED_DIR);
+    if staged.exists() {
+        std::fs::remove_dir_all(&staged)?;
+    }
+    std::fs::create_dir_all(&staged)?;
+
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if path.is_file() && entry.file_name() != CUSTOM_METADATA_FILE {
+            std::fs::hard_link(&path, staged.join(entry.file_name()))?;
+        }
+    }
+    for (file, name) in metadata.files().iter().zip(expected) {
+        let link = staged.join(name);
+        if *file != name {
+            let _ = std::fs::remove_file(&link);
+            std::fs::hard_link(dir.join(file), &link)?;
+        }
+    }
+    info!("Staged user model files in {}", staged.display());
+    Ok(staged)
+}
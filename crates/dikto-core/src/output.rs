@@ -0,0 +1,496 @@
+use crate::config::{AppProfile, ClipboardFormat, DiktoConfig, OutputSource};
+use crate::usage::utc_date;
+
+/// Where a transcript is about to go, as reported by the host.
+#[derive(Debug, Clone, Default, PartialEq, uniffi::Record)]
+pub struct OutputTarget {
+    /// Bundle identifier of the focused app, e.g. "md.obsidian".
+    pub bundle_id: String,
+    /// Display name of the focused app, e.g. "Obsidian".
+    pub app_name: String,
+    /// Current time in seconds since the Unix epoch.
+    pub unix_secs: u64,
+    /// Offset of local time from UTC in seconds, for timestamps.
+    pub utc_offset_secs: i32,
+    /// Text just before the caret in the focused field (a few characters
+    /// are enough). Empty when unknown or at the start of the field.
+    pub preceding_text: String,
+    /// Language the transcript is in (ISO-639-1), e.g. the session's or the
+    /// detected one. Empty or "auto" means the configured language.
+    pub language: String,
+    /// The session's `ListenConfig::dry_run`: plan the output without
+    /// pasting or copying it.
+    pub dry_run: bool,
+}
+
+/// The versions of a finished transcript the sinks choose from.
+#[derive(Debug, Clone, Default, PartialEq, uniffi::Record)]
+pub struct TranscriptTexts {
+    /// The post-processed transcript, as in `RecordingState::Done`.
+    pub transcript: String,
+    /// The session's summary, from `on_summary`.
+    pub summary: Option<String>,
+}
+
+impl TranscriptTexts {
+    /// The text `source` names. A missing or empty summary falls back to
+    /// the transcript.
+    pub fn get(&self, source: OutputSource) -> &str {
+        match source {
+            OutputSource::Transcript => &self.transcript,
+            OutputSource::Summary => self
+                .summary
+                .as_deref()
+                .filter(|s| !s.trim().is_empty())
+                .unwrap_or(&self.transcript),
+        }
+    }
+}
+
+/// A transcript formatted for its target and how to deliver it.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct OutputPlan {
+    /// Text to paste or type.
+    pub text: String,
+    /// Text to leave on the clipboard. Differs from `text` only when the
+    /// routes send different sources to the two, in which case hosts that
+    /// paste through the clipboard put it back after pasting.
+    pub clipboard_text: String,
+    /// Paste (or type) into the focused app. When false the host only
+    /// copies the text, if auto-copy is on.
+    pub auto_paste: bool,
+    /// Profile that matched the target, if any.
+    pub profile: Option<String>,
+    /// Format of `rich_text`.
+    pub clipboard_format: ClipboardFormat,
+    /// `clipboard_text` in `clipboard_format`, for hosts to put on the
+    /// clipboard next to the plain text. None for `ClipboardFormat::Plain`.
+    pub rich_text: Option<String>,
+    /// The session is a dry run (`OutputTarget::dry_run`): the host shows
+    /// or logs the plan instead of pasting or copying anything.
+    pub dry_run: bool,
+}
+
+/// An output a dry run skipped, reported with `on_dry_run`.
+#[derive(Debug, Clone, PartialEq, uniffi::Enum)]
+pub enum OutputAction {
+    /// Append `items` action items to the tasks file at `path`.
+    AppendTasks { path: String, items: u32 },
+    /// Save the transcript (and audio, if configured) under `dir`.
+    SaveSession { dir: String },
+    /// Add the transcript to the history.
+    RecordHistory,
+    /// Put `chars` characters on the clipboard.
+    Copy { chars: u32 },
+    /// Paste `chars` characters into `app`.
+    Paste { app: String, chars: u32 },
+}
+
+/// Whether the transcript is pasted into `target` or only copied: the
+/// matching profile's `auto_paste`, else the config's.
+pub fn auto_paste_for(target: &OutputTarget, config: &DiktoConfig) -> bool {
+    find_profile(&config.output.app_profiles, target)
+        .and_then(|p| p.auto_paste)
+        .unwrap_or(config.auto_paste)
+}
+
+/// First profile whose `app` is the target's bundle identifier or name.
+pub fn find_profile<'a>(
+    profiles: &'a [AppProfile],
+    target: &OutputTarget,
+) -> Option<&'a AppProfile> {
+    profiles.iter().find(|p| {
+        let app = p.app.trim();
+        !app.is_empty()
+            && (app.eq_ignore_ascii_case(&target.bundle_id)
+                || app.eq_ignore_ascii_case(&target.app_name))
+    })
+}
+
+/// Local date and time as "YYYY-MM-DD HH:MM".
+pub fn local_timestamp(unix_secs: u64, utc_offset_secs: i32) -> String {
+    let local = unix_secs.saturating_add_signed(i64::from(utc_offset_secs));
+    let minutes = local % 86_400 / 60;
+    format!(
+        "{} {:02}:{:02}",
+        utc_date(local),
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+/// How a language's script is laid out, for spacing and punctuation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    /// Words separated by spaces, left to right.
+    Spaced,
+    /// Chinese and Japanese: no spaces between words, full-width punctuation.
+    Cjk,
+    /// Arabic, Hebrew, Persian, Urdu, and other right-to-left scripts.
+    Rtl,
+}
+
+/// The script of an ISO-639-1 language code. Korean is spaced.
+pub fn script_for_language(language: &str) -> Script {
+    match language {
+        "zh" | "ja" | "yue" => Script::Cjk,
+        "ar" | "fa" | "he" | "ps" | "sd" | "ur" | "yi" => Script::Rtl,
+        _ => Script::Spaced,
+    }
+}
+
+/// Han, kana, CJK punctuation, and full-width forms.
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3000}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FF00}'..='\u{FFEF}'
+        | '\u{20000}'..='\u{2FA1F}')
+}
+
+/// Drop spaces between two CJK characters, e.g. where utterances were
+/// joined with a space. Spaces next to Latin words and line breaks stay.
+pub fn remove_cjk_spaces(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != ' ' {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        let end = chars[i..]
+            .iter()
+            .position(|&c| c != ' ')
+            .map_or(chars.len(), |n| i + n);
+        let between_cjk = out.chars().next_back().is_some_and(is_cjk)
+            && chars.get(end).is_some_and(|&c| is_cjk(c));
+        if !between_cjk {
+            out.extend(&chars[i..end]);
+        }
+        i = end;
+    }
+    out
+}
+
+/// Turn ASCII punctuation into its full-width form ("，" "。" "？"). Marks
+/// between two ASCII letters or digits ("3.14", "12:30") are left alone.
+pub fn to_fullwidth_punctuation(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let ascii_word = |c: Option<&char>| c.is_some_and(char::is_ascii_alphanumeric);
+    let mut out = String::with_capacity(text.len() * 2);
+    for (i, &c) in chars.iter().enumerate() {
+        let wide = match c {
+            ',' => '，',
+            '.' => '。',
+            '?' => '？',
+            '!' => '！',
+            ':' => '：',
+            ';' => '；',
+            '(' => '（',
+            ')' => '）',
+            _ => c,
+        };
+        let inside_word = i > 0 && ascii_word(chars.get(i - 1)) && ascii_word(chars.get(i + 1));
+        out.push(if inside_word { c } else { wide });
+    }
+    out
+}
+
+/// Marks `strip_punctuation` drops.
+const SENTENCE_MARKS: &[char] = &[
+    '.', ',', '?', '!', ';', ':', '…', '¿', '¡', '。', '，', '、', '？', '！', '；', '：',
+];
+
+/// Drop sentence punctuation (". , ? ! ; :" and their full-width forms,
+/// "、", "…", "¿", "¡"). ASCII marks between two letters or digits ("3.14",
+/// "12:30", "example.com") are left alone.
+pub fn strip_punctuation(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let word = |c: Option<&char>| c.is_some_and(|c| c.is_alphanumeric());
+    let mut out = String::with_capacity(text.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let sentence_mark = SENTENCE_MARKS.contains(&c);
+        let inside_word = c.is_ascii() && i > 0 && word(chars.get(i - 1)) && word(chars.get(i + 1));
+        if !sentence_mark || inside_word {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Whether `text` inserted after `preceding` needs a space in between: the
+/// caret follows a word or punctuation, and the text doesn't start with
+/// punctuation that attaches to the previous word.
+pub fn needs_leading_space(preceding: &str, text: &str) -> bool {
+    const OPENING: &[char] = &['(', '[', '{', '"', '\'', '“', '‘', '/', '-'];
+    const ATTACHING: &[char] = &['.', ',', '!', '?', ';', ':', ')', ']', '}', '’', '”'];
+    let (Some(before), Some(first)) = (preceding.chars().next_back(), text.chars().next()) else {
+        return false;
+    };
+    !before.is_whitespace()
+        && !OPENING.contains(&before)
+        && !first.is_whitespace()
+        && !ATTACHING.contains(&first)
+}
+
+/// Fit the case of the transcript's first word to the text before the
+/// caret: capitalized after a sentence end or line break, lowercase after a
+/// word or comma. Unknown context leaves it alone, as do words that keep
+/// their case anywhere ("I", "NASA", "iPhone").
+pub fn match_case_to_context(preceding: &str, text: &str) -> String {
+    let trimmed = preceding.trim_end_matches([' ', '\t']);
+    let Some(before) = trimmed.chars().next_back() else {
+        return text.to_string();
+    };
+    let sentence_start = matches!(before, '.' | '!' | '?' | '\n' | '\r');
+
+    let word_len = text
+        .find(|c: char| c.is_whitespace() || (c.is_ascii_punctuation() && c != '\''))
+        .unwrap_or(text.len());
+    let (word, rest) = text.split_at(word_len);
+    let mut chars = word.chars();
+    let Some(first) = chars.next() else {
+        return text.to_string();
+    };
+    let keeps_case = word == "I" || word.starts_with("I'") || chars.clone().any(char::is_uppercase);
+
+    let first: String = if sentence_start {
+        first.to_uppercase().collect()
+    } else if before.is_alphanumeric() || matches!(before, ',' | ';' | ':') {
+        if keeps_case {
+            return text.to_string();
+        }
+        first.to_lowercase().collect()
+    } else {
+        return text.to_string();
+    };
+    format!("{first}{}{rest}", chars.as_str())
+}
+
+/// The output stage: format a final transcript for the app it goes to,
+/// after post-processing and before the host copies or pastes it.
+pub fn prepare_output(text: &str, target: &OutputTarget, config: &DiktoConfig) -> OutputPlan {
+    let texts = TranscriptTexts {
+        transcript: text.to_string(),
+        summary: None,
+    };
+    route_output(&texts, target, config)
+}
+
+/// `prepare_output` for a transcript with several versions: each sink gets
+/// the one `OutputConfig::routes` names, formatted for the target.
+pub fn route_output(
+    texts: &TranscriptTexts,
+    target: &OutputTarget,
+    config: &DiktoConfig,
+) -> OutputPlan {
+    let profile = find_profile(&config.output.app_profiles, target);
+    let routes = &config.output.routes;
+    let auto_paste = auto_paste_for(target, config);
+    let clipboard_format = profile
+        .and_then(|p| p.clipboard_format)
+        .unwrap_or(config.output.clipboard_format);
+
+    let text = format_for_target(texts.get(routes.paste), target, config, profile);
+    let clipboard_text = if routes.clipboard == routes.paste {
+        text.clone()
+    } else {
+        format_for_target(texts.get(routes.clipboard), target, config, profile)
+    };
+    let rich_text = match clipboard_format {
+        ClipboardFormat::Plain => None,
+        ClipboardFormat::Markdown => Some(to_markdown(&clipboard_text)),
+        ClipboardFormat::Rtf => Some(to_rtf(&clipboard_text)),
+    };
+    OutputPlan {
+        text,
+        clipboard_text,
+        auto_paste,
+        profile: profile.map(|p| p.app.clone()),
+        clipboard_format,
+        rich_text,
+        dry_run: target.dry_run,
+    }
+}
+
+/// The output stage for one final segment in incremental paste: the
+/// segment formatted like a whole transcript (against the text now before
+/// the caret), then cut into chunks of `paste_chunk_words` words to paste
+/// one after another. Empty in a dry run.
+pub fn prepare_segment_output(
+    segment: &str,
+    target: &OutputTarget,
+    config: &DiktoConfig,
+) -> Vec<String> {
+    if target.dry_run {
+        return Vec::new();
+    }
+    let profile = find_profile(&config.output.app_profiles, target);
+    let text = format_for_target(segment, target, config, profile);
+    word_chunks(&text, config.output.paste_chunk_words as usize)
+}
+
+/// Split `text` into pieces of up to `words_per_chunk` words that join
+/// back into exactly `text`. Whitespace stays with the word before it, so
+/// every piece after the first starts with a word.
+pub fn word_chunks(text: &str, words_per_chunk: usize) -> Vec<String> {
+    let words_per_chunk = words_per_chunk.max(1);
+    let mut chunks = Vec::new();
+    let (mut start, mut words, mut in_word) = (0, 0, false);
+    for (i, c) in text.char_indices() {
+        let word_char = !c.is_whitespace();
+        if word_char && !in_word {
+            if words == words_per_chunk {
+                chunks.push(text[start..i].to_string());
+                start = i;
+                words = 0;
+            }
+            words += 1;
+        }
+        in_word = word_char;
+    }
+    if start < text.len() {
+        chunks.push(text[start..].to_string());
+    }
+    chunks
+}
+
+/// Apply the language's script rules, the target's profile, and the spacing
+/// and casing settings to `text`.
+fn format_for_target(
+    text: &str,
+    target: &OutputTarget,
+    config: &DiktoConfig,
+    profile: Option<&AppProfile>,
+) -> String {
+    let output = &config.output;
+    let language = match target.language.as_str() {
+        "" | "auto" => config.language.as_str(),
+        language => language,
+    };
+    let script = script_for_language(language);
+    let mut out = text.trim().to_string();
+
+    match script {
+        Script::Cjk => {
+            if output.fullwidth_punctuation {
+                out = to_fullwidth_punctuation(&out);
+            }
+            out = remove_cjk_spaces(&out);
+        }
+        // Marks at both ends keep a trailing "." or "?" from jumping to
+        // the left end of the line in a left-to-right field
+        Script::Rtl if output.rtl_marks && !out.is_empty() => {
+            out = format!("\u{200F}{out}\u{200F}");
+        }
+        _ => {}
+    }
+    if output.smart_capitalization {
+        out = match_case_to_context(&target.preceding_text, &out);
+    }
+    if let Some(profile) = profile {
+        if profile.strip_punctuation {
+            out = strip_punctuation(&out);
+        }
+        if profile.lowercase {
+            out = out.to_lowercase();
+        }
+        if profile.plain_text {
+            out = out.split_whitespace().collect::<Vec<_>>().join(" ");
+        }
+        if profile.timestamp_prefix {
+            out = format!(
+                "{} {out}",
+                local_timestamp(target.unix_secs, target.utc_offset_secs)
+            );
+        }
+        if profile.trailing_newline {
+            out.push('\n');
+        }
+    }
+
+    // CJK text runs on without spaces
+    let spaced = script != Script::Cjk;
+    if spaced && output.smart_spacing && needs_leading_space(&target.preceding_text, &out) {
+        out.insert(0, ' ');
+    }
+    if output.append_newline && !out.ends_with('\n') {
+        out.push('\n');
+    } else if spaced && output.append_space && !out.ends_with(char::is_whitespace) {
+        out.push(' ');
+    }
+    out
+}
+
+/// Markdown for a transcript: each paragraph (separated by a blank line in
+/// the transcript) stays a paragraph, and a line that would otherwise read
+/// as a heading, quote, or list item has its marker escaped. Inline
+/// characters are left alone so LaTeX from math mode survives.
+pub fn to_markdown(text: &str) -> String {
+    let body = text.trim();
+    // Keep the spacing added around the transcript for the insertion point
+    let lead = &text[..text.len() - text.trim_start().len()];
+    let trail = &text[text.trim_end().len()..];
+    let mut out = String::new();
+    for paragraph in body.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        if !out.is_empty() {
+            out.push_str("\n\n");
+        }
+        let lines: Vec<String> = paragraph
+            .lines()
+            .map(|line| escape_block_marker(line.trim()))
+            .collect();
+        // Two trailing spaces keep single line breaks inside a paragraph
+        out.push_str(&lines.join("  \n"));
+    }
+    format!("{lead}{out}{trail}")
+}
+
+/// Escape a Markdown block marker at the start of a line.
+fn escape_block_marker(line: &str) -> String {
+    let starts_list = |marker: char| {
+        line.strip_prefix(marker)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    };
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let numbered = digits > 0 && matches!(line[digits..].chars().next(), Some('.' | ')'));
+    if line.starts_with(['#', '>']) || starts_list('-') || starts_list('+') || starts_list('*') {
+        format!("\\{line}")
+    } else if numbered {
+        format!("{}\\{}", &line[..digits], &line[digits..])
+    } else {
+        line.to_string()
+    }
+}
+
+/// An RTF document holding a transcript: special characters escaped,
+/// non-ASCII as `\u` escapes, and each line break a `\par`.
+pub fn to_rtf(text: &str) -> String {
+    let mut out = String::from("{\\rtf1\\ansi\\deff0{\\fonttbl{\\f0 Helvetica;}}\\f0 ");
+    let mut units = [0u16; 2];
+    for c in text.chars() {
+        match c {
+            '\\' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\par\n"),
+            '\r' => {}
+            '\t' => out.push_str("\\tab "),
+            c if c.is_ascii() => out.push(c),
+            // RTF takes UTF-16 code units as signed 16-bit numbers, each
+            // followed by a fallback character for old readers
+            c => {
+                for unit in c.encode_utf16(&mut units) {
+                    out.push_str(&format!("\\u{}?", *unit as i16));
+                }
+            }
+        }
+    }
+    out.push('}');
+    out
+}
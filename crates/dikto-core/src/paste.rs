@@ -0,0 +1,258 @@
+use std::time::Duration;
+
+use tracing::info;
+
+use crate::config::OutputConfig;
+
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+pub enum PasteError {
+    #[error("Pasting from the engine is not supported on this platform")]
+    Unsupported,
+    #[error("Can't set the clipboard: {0}")]
+    Clipboard(String),
+    #[error("Can't send keystrokes: {0}")]
+    Input(String),
+}
+
+/// Whether `deliver` works here. The macOS app pastes through its own
+/// accessibility code, so only Windows hosts use this module for now.
+pub fn is_supported() -> bool {
+    cfg!(windows)
+}
+
+/// Put `text` into the focused app as `output` says: typed as keystrokes in
+/// typing mode, otherwise placed on the clipboard as plain text and pasted
+/// with Ctrl+V after `paste_delay_ms`. Does nothing in a dry run.
+pub fn deliver(text: &str, output: &OutputConfig) -> Result<(), PasteError> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    if output.dry_run {
+        info!("Dry run: not pasting {} chars", text.chars().count());
+        return Ok(());
+    }
+    if output.typing_mode {
+        platform::type_text(
+            text,
+            Duration::from_millis(output.keystroke_delay_ms.into()),
+        )
+    } else {
+        platform::set_clipboard(text)?;
+        std::thread::sleep(Duration::from_millis(output.paste_delay_ms.into()));
+        platform::send_paste()
+    }
+}
+
+/// `deliver` for hosts, e.g. with an `OutputPlan`'s text once its
+/// `auto_paste` says to paste.
+#[uniffi::export]
+pub fn paste_text(text: String, output: OutputConfig) -> Result<(), PasteError> {
+    deliver(&text, &output)
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::ffi::c_void;
+    use std::time::Duration;
+
+    use super::PasteError;
+
+    type Handle = *mut c_void;
+
+    const CF_UNICODETEXT: u32 = 13;
+    const GMEM_MOVEABLE: u32 = 0x0002;
+    const INPUT_KEYBOARD: u32 = 1;
+    const KEYEVENTF_KEYUP: u32 = 0x0002;
+    const KEYEVENTF_UNICODE: u32 = 0x0004;
+    const VK_CONTROL: u16 = 0x11;
+    const VK_V: u16 = 0x56;
+    /// Other apps (clipboard managers) may hold the clipboard briefly.
+    const OPEN_ATTEMPTS: u32 = 10;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct KeybdInput {
+        w_vk: u16,
+        w_scan: u16,
+        dw_flags: u32,
+        time: u32,
+        dw_extra_info: usize,
+    }
+
+    /// Largest member of the INPUT union; only here for its size.
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct MouseInput {
+        dx: i32,
+        dy: i32,
+        mouse_data: u32,
+        dw_flags: u32,
+        time: u32,
+        dw_extra_info: usize,
+    }
+
+    #[repr(C)]
+    union InputUnion {
+        ki: KeybdInput,
+        _mi: MouseInput,
+    }
+
+    #[repr(C)]
+    struct Input {
+        kind: u32,
+        u: InputUnion,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn OpenClipboard(owner: Handle) -> i32;
+        fn EmptyClipboard() -> i32;
+        fn SetClipboardData(format: u32, mem: Handle) -> Handle;
+        fn CloseClipboard() -> i32;
+        fn SendInput(count: u32, inputs: *const Input, size: i32) -> u32;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GlobalAlloc(flags: u32, bytes: usize) -> Handle;
+        fn GlobalLock(mem: Handle) -> *mut c_void;
+        fn GlobalUnlock(mem: Handle) -> i32;
+        fn GlobalFree(mem: Handle) -> Handle;
+        fn GetLastError() -> u32;
+    }
+
+    fn key(vk: u16, scan: u16, flags: u32) -> Input {
+        Input {
+            kind: INPUT_KEYBOARD,
+            u: InputUnion {
+                ki: KeybdInput {
+                    w_vk: vk,
+                    w_scan: scan,
+                    dw_flags: flags,
+                    time: 0,
+                    dw_extra_info: 0,
+                },
+            },
+        }
+    }
+
+    fn send(inputs: &[Input]) -> Result<(), PasteError> {
+        // SAFETY: `inputs` is a live slice of correctly laid out INPUTs.
+        let sent = unsafe {
+            SendInput(
+                inputs.len() as u32,
+                inputs.as_ptr(),
+                std::mem::size_of::<Input>() as i32,
+            )
+        };
+        if sent as usize == inputs.len() {
+            return Ok(());
+        }
+        // Blocked by UIPI when the focused app runs elevated
+        // SAFETY: GetLastError only reads this thread's error code.
+        let error = unsafe { GetLastError() };
+        Err(PasteError::Input(format!("SendInput error {error}")))
+    }
+
+    /// Replace the clipboard with `text` as CF_UNICODETEXT.
+    pub fn set_clipboard(text: &str) -> Result<(), PasteError> {
+        let mut units: Vec<u16> = text.encode_utf16().collect();
+        units.push(0);
+        let bytes = units.len() * std::mem::size_of::<u16>();
+
+        // SAFETY: the clipboard is opened before and closed after use on
+        // every path. The global block is written only while locked, and is
+        // owned by the system once SetClipboardData succeeds; we free it
+        // ourselves otherwise.
+        unsafe {
+            let mut opened = false;
+            for _ in 0..OPEN_ATTEMPTS {
+                if OpenClipboard(std::ptr::null_mut()) != 0 {
+                    opened = true;
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            if !opened {
+                return Err(PasteError::Clipboard(format!(
+                    "OpenClipboard error {}",
+                    GetLastError()
+                )));
+            }
+            let result = (|| {
+                if EmptyClipboard() == 0 {
+                    return Err(format!("EmptyClipboard error {}", GetLastError()));
+                }
+                let mem = GlobalAlloc(GMEM_MOVEABLE, bytes);
+                if mem.is_null() {
+                    return Err(format!("GlobalAlloc error {}", GetLastError()));
+                }
+                let dest = GlobalLock(mem) as *mut u16;
+                if dest.is_null() {
+                    GlobalFree(mem);
+                    return Err(format!("GlobalLock error {}", GetLastError()));
+                }
+                std::ptr::copy_nonoverlapping(units.as_ptr(), dest, units.len());
+                GlobalUnlock(mem);
+                if SetClipboardData(CF_UNICODETEXT, mem).is_null() {
+                    let error = GetLastError();
+                    GlobalFree(mem);
+                    return Err(format!("SetClipboardData error {error}"));
+                }
+                Ok(())
+            })();
+            CloseClipboard();
+            result.map_err(PasteError::Clipboard)
+        }
+    }
+
+    /// Press and release Ctrl+V in the focused window.
+    pub fn send_paste() -> Result<(), PasteError> {
+        send(&[
+            key(VK_CONTROL, 0, 0),
+            key(VK_V, 0, 0),
+            key(VK_V, 0, KEYEVENTF_KEYUP),
+            key(VK_CONTROL, 0, KEYEVENTF_KEYUP),
+        ])
+    }
+
+    /// Type `text` as Unicode keystrokes, `delay` apart. A character outside
+    /// the BMP goes out as one surrogate pair.
+    pub fn type_text(text: &str, delay: Duration) -> Result<(), PasteError> {
+        let mut buf = [0u16; 2];
+        for (i, c) in text.chars().enumerate() {
+            if i > 0 && !delay.is_zero() {
+                std::thread::sleep(delay);
+            }
+            let units = c.encode_utf16(&mut buf);
+            let mut inputs = Vec::with_capacity(units.len() * 2);
+            for &unit in units.iter() {
+                inputs.push(key(0, unit, KEYEVENTF_UNICODE));
+            }
+            for &unit in units.iter() {
+                inputs.push(key(0, unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP));
+            }
+            send(&inputs)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use std::time::Duration;
+
+    use super::PasteError;
+
+    pub fn set_clipboard(_text: &str) -> Result<(), PasteError> {
+        Err(PasteError::Unsupported)
+    }
+
+    pub fn send_paste() -> Result<(), PasteError> {
+        Err(PasteError::Unsupported)
+    }
+
+    pub fn type_text(_text: &str, _delay: Duration) -> Result<(), PasteError> {
+        Err(PasteError::Unsupported)
+    }
+}
@@ -0,0 +1,119 @@
+use crate::config::{AdvancedConfig, DiktoConfig, LowPowerMode};
+use crate::models;
+
+/// Multiplier applied to the poll interval in low-power mode.
+const LOW_POWER_POLL_FACTOR: u32 = 4;
+/// Minimum partial-update interval in low-power mode, in ms.
+const LOW_POWER_PARTIAL_INTERVAL_MS: u32 = 1000;
+
+/// Returns true if the machine is currently running on battery power.
+/// Returns false when the power source cannot be determined (e.g. desktops,
+/// non-macOS platforms).
+pub fn on_battery() -> bool {
+    platform::on_battery().unwrap_or(false)
+}
+
+/// Resolve whether low-power behavior should apply for the given mode.
+pub fn is_low_power(mode: &LowPowerMode) -> bool {
+    match mode {
+        LowPowerMode::On => true,
+        LowPowerMode::Off => false,
+        LowPowerMode::Auto => on_battery(),
+    }
+}
+
+/// Pipeline timings to use, relaxed when low-power mode is active to cut
+/// wakeups during long sessions. Beam search is also turned off then, since
+/// each alternative costs a full extra decoding pass.
+pub fn effective_advanced(advanced: &AdvancedConfig, low_power: bool) -> AdvancedConfig {
+    if !low_power {
+        return advanced.clone();
+    }
+    let mut relaxed = advanced.clone();
+    relaxed.poll_interval_ms = advanced
+        .poll_interval_ms
+        .saturating_mul(LOW_POWER_POLL_FACTOR);
+    relaxed.partial_interval_ms = advanced
+        .partial_interval_ms
+        .max(LOW_POWER_PARTIAL_INTERVAL_MS);
+    relaxed.beam_size = 0;
+    relaxed.validate();
+    relaxed
+}
+
+/// Model to record with: the configured `low_power_model` when low-power mode
+/// is active and that model is downloaded, otherwise the regular model.
+/// Aliases are resolved, so the result is always a versioned model name.
+pub fn effective_model_name(config: &DiktoConfig, low_power: bool) -> String {
+    if low_power {
+        if let Some(name) = &config.low_power_model {
+            if models::is_model_downloaded(name) {
+                return models::canonical_model_name(name);
+            }
+        }
+    }
+    models::canonical_model_name(&config.model_name)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::{c_char, c_void, CStr};
+
+    type CFTypeRef = *const c_void;
+    type CFStringRef = *const c_void;
+
+    const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPSCopyPowerSourcesInfo() -> CFTypeRef;
+        fn IOPSGetProvidingPowerSourceType(snapshot: CFTypeRef) -> CFStringRef;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFRelease(cf: CFTypeRef);
+        fn CFStringGetCString(
+            string: CFStringRef,
+            buffer: *mut c_char,
+            buffer_size: isize,
+            encoding: u32,
+        ) -> u8;
+    }
+
+    /// Query IOKit for the providing power source ("AC Power" / "Battery Power").
+    pub fn on_battery() -> Option<bool> {
+        // SAFETY: IOPSCopyPowerSourcesInfo returns an owned CF object that we
+        // release below. The providing-source string follows the Get rule (not
+        // owned) and is only read while the snapshot is alive.
+        unsafe {
+            let snapshot = IOPSCopyPowerSourcesInfo();
+            if snapshot.is_null() {
+                return None;
+            }
+            let source = IOPSGetProvidingPowerSourceType(snapshot);
+            let mut buf = [0 as c_char; 64];
+            let result = if !source.is_null()
+                && CFStringGetCString(
+                    source,
+                    buf.as_mut_ptr(),
+                    buf.len() as isize,
+                    K_CF_STRING_ENCODING_UTF8,
+                ) != 0
+            {
+                Some(CStr::from_ptr(buf.as_ptr()).to_bytes() == b"Battery Power")
+            } else {
+                None
+            };
+            CFRelease(snapshot);
+            result
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    pub fn on_battery() -> Option<bool> {
+        None
+    }
+}
@@ -0,0 +1,48 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Span `max_per_minute` counts session starts over.
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Recent session starts, to keep a stuck hotkey or a runaway script from
+/// starting sessions back to back.
+#[derive(Debug, Default)]
+pub struct SessionRateLimiter {
+    starts: VecDeque<Instant>,
+}
+
+impl SessionRateLimiter {
+    /// Whether a session may start at `now`: at least `min_interval` after
+    /// the last start, and fewer than `max_per_minute` starts in the last
+    /// minute (0 allows any number). Err holds how long to wait.
+    pub fn check(
+        &mut self,
+        now: Instant,
+        min_interval: Duration,
+        max_per_minute: u32,
+    ) -> Result<(), Duration> {
+        while self
+            .starts
+            .front()
+            .is_some_and(|start| now.saturating_duration_since(*start) >= WINDOW)
+        {
+            self.starts.pop_front();
+        }
+        if let Some(last) = self.starts.back() {
+            let since = now.saturating_duration_since(*last);
+            if since < min_interval {
+                return Err(min_interval - since);
+            }
+        }
+        if max_per_minute > 0 && self.starts.len() >= max_per_minute as usize {
+            let oldest = self.starts[self.starts.len() - max_per_minute as usize];
+            return Err(WINDOW.saturating_sub(now.saturating_duration_since(oldest)));
+        }
+        Ok(())
+    }
+
+    /// Count a session that started at `now`.
+    pub fn record(&mut self, now: Instant) {
+        self.starts.push_back(now);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::config::RedactionConfig;
+use crate::transcribe::WordConfidence;
+
+/// Masks configured keywords in final text. Keywords match whole words,
+/// ignoring case; a phrase matches its words in a row with any spaces or
+/// punctuation between them, so "project falcon" also catches
+/// "Project-Falcon".
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    /// Each keyword as lowercase words.
+    keywords: Vec<Vec<String>>,
+    mask: String,
+}
+
+impl Redactor {
+    pub fn new(config: &RedactionConfig) -> Self {
+        let keywords = config
+            .keywords
+            .iter()
+            .map(|k| words(k).map(|(_, w)| w.to_lowercase()).collect::<Vec<_>>())
+            .filter(|k| !k.is_empty())
+            .collect();
+        Self {
+            keywords,
+            mask: config.mask.clone(),
+        }
+    }
+
+    /// Whether there is anything to mask.
+    pub fn is_active(&self) -> bool {
+        !self.keywords.is_empty()
+    }
+
+    /// `text` with every keyword replaced by the mask.
+    pub fn redact(&self, text: &str) -> String {
+        if !self.is_active() {
+            return text.to_string();
+        }
+        let mut out = String::with_capacity(text.len());
+        let mut copied = 0;
+        for (start, end) in self.matches(text) {
+            out.push_str(&text[copied..start]);
+            out.push_str(&self.mask);
+            copied = end;
+        }
+        out.push_str(&text[copied..]);
+        out
+    }
+
+    /// `words` with every word that is part of a keyword replaced by the
+    /// mask, for `on_word_confidence`. Confidences are kept.
+    pub fn redact_words(&self, words: &[WordConfidence]) -> Vec<WordConfidence> {
+        if !self.is_active() {
+            return words.to_vec();
+        }
+        let mut text = String::new();
+        let mut ranges = Vec::with_capacity(words.len());
+        for w in words {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            ranges.push((text.len(), text.len() + w.word.len()));
+            text.push_str(&w.word);
+        }
+        let matches = self.matches(&text);
+        words
+            .iter()
+            .zip(ranges)
+            .map(|(w, (start, end))| {
+                let masked = matches.iter().any(|&(s, e)| start < e && s < end);
+                WordConfidence {
+                    word: if masked {
+                        self.mask.clone()
+                    } else {
+                        w.word.clone()
+                    },
+                    confidence: w.confidence,
+                }
+            })
+            .collect()
+    }
+
+    /// Byte ranges of the keywords in `text`, in order.
+    fn matches(&self, text: &str) -> Vec<(usize, usize)> {
+        let spans: Vec<(usize, &str)> = words(text).collect();
+        let lower: Vec<String> = spans.iter().map(|(_, w)| w.to_lowercase()).collect();
+
+        let mut found = Vec::new();
+        let mut i = 0;
+        while i < spans.len() {
+            // Longest keyword first, so "falcon" doesn't cut "falcon one" short
+            let matched = self
+                .keywords
+                .iter()
+                .filter(|k| lower[i..].starts_with(k))
+                .map(|k| k.len())
+                .max();
+            let Some(len) = matched else {
+                i += 1;
+                continue;
+            };
+            let (start, _) = spans[i];
+            let (last_start, last) = spans[i + len - 1];
+            found.push((start, last_start + last.len()));
+            i += len;
+        }
+        found
+    }
+}
+
+/// Runs of letters and digits in `text`, with their byte offsets.
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(move |w| (w.as_ptr() as usize - text.as_ptr() as usize, w))
+}
@@ -0,0 +1,58 @@
+use std::f32::consts::PI;
+
+use crate::wav::TARGET_SAMPLE_RATE;
+
+/// Formant frequencies (Hz) and bandwidths of an open /a/ vowel.
+const FORMANTS: [(f32, f32); 3] = [(730.0, 90.0), (1090.0, 110.0), (2440.0, 170.0)];
+
+/// Syllables per second, roughly conversational pace.
+const SYLLABLE_RATE: f32 = 4.0;
+
+/// Voice-like audio at 16kHz for exercising the pipeline when no recorded
+/// speech sample is available: `lead_secs` of silence, `voiced_secs` of a
+/// harmonic /a/ vowel with a falling pitch and syllable-rate loudness, and
+/// `lead_secs` of silence again. It is not words, so models may return
+/// nothing for it; only recorded speech should be used to check the text.
+pub fn synthetic_voice(lead_secs: f32, voiced_secs: f32) -> Vec<f32> {
+    let rate = TARGET_SAMPLE_RATE as f32;
+    let lead = vec![0.0; (lead_secs * rate) as usize];
+    let voiced_len = (voiced_secs * rate) as usize;
+
+    let mut phase = 0.0f32;
+    let mut voiced = Vec::with_capacity(voiced_len);
+    for i in 0..voiced_len {
+        let t = i as f32 / rate;
+        // Pitch falls from 140 to 100 Hz, like the end of a statement
+        let f0 = 140.0 - 40.0 * t / voiced_secs.max(f32::EPSILON);
+        phase += 2.0 * PI * f0 / rate;
+
+        let mut sample = 0.0;
+        let mut harmonic = 1.0;
+        while harmonic * f0 < rate / 2.0 && harmonic <= 40.0 {
+            let freq = harmonic * f0;
+            let gain: f32 = FORMANTS
+                .iter()
+                .map(|&(center, bandwidth)| {
+                    let x = (freq - center) / bandwidth;
+                    1.0 / (1.0 + x * x)
+                })
+                .sum();
+            // Glottal source rolls off about 12 dB per octave
+            sample += gain / harmonic * (harmonic * phase).sin();
+            harmonic += 1.0;
+        }
+
+        let envelope = 0.5 - 0.5 * (2.0 * PI * SYLLABLE_RATE * t).cos();
+        voiced.push(sample * envelope);
+    }
+
+    let peak = voiced.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    if peak > 0.0 {
+        voiced.iter_mut().for_each(|s| *s *= 0.5 / peak);
+    }
+
+    let mut samples = lead.clone();
+    samples.extend(voiced);
+    samples.extend(lead);
+    samples
+}
@@ -0,0 +1,205 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::{data_dir, ConfigError, DiktoConfig, SavedSessionsConfig};
+use crate::spool::AudioSpool;
+use crate::storage;
+use crate::usage::utc_date;
+use crate::wav::{self, TARGET_SAMPLE_RATE};
+use crate::TranscriptionResult;
+
+/// Words of the transcript used by `{first_words}`.
+const FIRST_WORDS: usize = 5;
+
+/// What a saved session's file name is made from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionInfo {
+    /// When the session started, in seconds since the Unix epoch.
+    pub unix_secs: u64,
+    /// Offset of local time from UTC in seconds.
+    pub utc_offset_secs: i32,
+    /// App that was focused when the session started.
+    pub app: String,
+    pub text: String,
+}
+
+/// Directory saved sessions go under: an absolute `dir` as is, otherwise a
+/// folder in the data directory.
+pub fn sessions_root(config: &SavedSessionsConfig) -> Result<PathBuf, ConfigError> {
+    let dir = Path::new(config.dir.trim());
+    if dir.is_absolute() {
+        Ok(dir.to_path_buf())
+    } else {
+        Ok(data_dir()?.join(dir))
+    }
+}
+
+/// Fill in a file name template. `{date}` is YYYY-MM-DD, `{time}` HH-MM,
+/// `{year}` and `{month}` are numeric, `{app}` is the focused app, and
+/// `{first_words}` the start of the transcript in lowercase. Characters
+/// that aren't allowed in file names become dashes; an empty result is
+/// "session".
+pub fn render_file_name(template: &str, info: &SessionInfo) -> String {
+    let local = info
+        .unix_secs
+        .saturating_add_signed(i64::from(info.utc_offset_secs));
+    let date = utc_date(local);
+    let minutes = local % 86_400 / 60;
+    let time = format!("{:02}-{:02}", minutes / 60, minutes % 60);
+    let first_words: Vec<String> = info
+        .text
+        .split_whitespace()
+        .map(|w| {
+            w.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .take(FIRST_WORDS)
+        .collect();
+    let name = template
+        .replace("{date}", &date)
+        .replace("{time}", &time)
+        .replace("{year}", &date[..4])
+        .replace("{month}", &date[5..7])
+        .replace("{app}", &info.app)
+        .replace("{first_words}", &first_words.join("-"));
+    sanitize_file_name(&name)
+}
+
+/// Replace characters file systems reject and collapse runs of dashes.
+fn sanitize_file_name(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.chars() {
+        let c = if c.is_control() || r#"/\:*?"<>|"#.contains(c) {
+            '-'
+        } else {
+            c
+        };
+        if !(c == '-' && out.ends_with('-')) {
+            out.push(c);
+        }
+    }
+    let out = out.trim_matches(|c: char| c == '-' || c == '.' || c.is_whitespace());
+    if out.is_empty() {
+        "session".to_string()
+    } else {
+        out.to_string()
+    }
+}
+
+/// Folder for a session under `root`: a YYYY-MM subfolder when sessions are
+/// filed by month.
+pub fn session_dir(root: &Path, info: &SessionInfo, by_month: bool) -> PathBuf {
+    if !by_month {
+        return root.to_path_buf();
+    }
+    let local = info
+        .unix_secs
+        .saturating_add_signed(i64::from(info.utc_offset_secs));
+    root.join(&utc_date(local)[..7])
+}
+
+/// A stem in `dir` not used by any file with the given extensions,
+/// adding "-2", "-3", ... when needed.
+pub fn unique_stem(dir: &Path, stem: &str, extensions: &[&str]) -> String {
+    let taken = |s: &str| {
+        extensions
+            .iter()
+            .any(|ext| dir.join(format!("{s}.{ext}")).exists())
+    };
+    if !taken(stem) {
+        return stem.to_string();
+    }
+    (2..)
+        .map(|n| format!("{stem}-{n}"))
+        .find(|s| !taken(s))
+        .unwrap_or_else(|| stem.to_string())
+}
+
+/// The transcript as Markdown, with its summary and action items when present.
+pub fn transcript_markdown(result: &TranscriptionResult) -> String {
+    let mut out = result.text.trim().to_string();
+    out.push('\n');
+    if let Some(summary) = &result.summary {
+        out.push_str(&format!("\n## Summary\n\n{}\n", summary.trim()));
+    }
+    if !result.action_items.is_empty() {
+        out.push_str("\n## Action items\n\n");
+        for item in &result.action_items {
+            out.push_str(&format!("- [ ] {}\n", item.text));
+        }
+    }
+    out
+}
+
+/// Save a finished session under `root`: the transcript as `<name>.md`
+/// and, when given, its audio as `<name>.wav` with the settings it was
+/// recorded with as `<name>.json` (see `snapshot_path`). Returns the paths
+/// written.
+pub fn save_session(
+    root: &Path,
+    config: &SavedSessionsConfig,
+    info: &SessionInfo,
+    result: &TranscriptionResult,
+    audio: Option<&mut AudioSpool>,
+    snapshot: Option<&DiktoConfig>,
+) -> std::io::Result<Vec<PathBuf>> {
+    let dir = session_dir(root, info, config.folder_by_month);
+    std::fs::create_dir_all(&dir)?;
+    let stem = unique_stem(
+        &dir,
+        &render_file_name(&config.file_name_template, info),
+        &["md", "wav", "json"],
+    );
+
+    let mut written = Vec::new();
+    if config.save_transcripts && !result.text.trim().is_empty() {
+        let path = dir.join(format!("{stem}.md"));
+        storage::write_atomic(&path, transcript_markdown(result).as_bytes())?;
+        written.push(path);
+    }
+    if let Some(audio) = audio.filter(|a| !a.is_empty()) {
+        let path = dir.join(format!("{stem}.wav"));
+        write_spooled_wav(&path, audio)?;
+        let settings_path = snapshot_path(&path);
+        written.push(path);
+        if let Some(snapshot) = snapshot {
+            let json = serde_json::to_string_pretty(snapshot).map_err(std::io::Error::other)?;
+            storage::write_atomic(&settings_path, json.as_bytes())?;
+            written.push(settings_path);
+        }
+    }
+    Ok(written)
+}
+
+/// Where the settings a saved recording was made with are kept: next to
+/// it, as `<name>.json`.
+pub fn snapshot_path(wav_path: &Path) -> PathBuf {
+    wav_path.with_extension("json")
+}
+
+/// The settings saved with the recording at `wav_path`, or None if it has
+/// none (it was saved by an older version, or isn't a saved session).
+pub fn read_snapshot(wav_path: &Path) -> std::io::Result<Option<DiktoConfig>> {
+    let json = match std::fs::read_to_string(snapshot_path(wav_path)) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Write spooled 16kHz audio to a WAV file a chunk at a time.
+fn write_spooled_wav(path: &Path, audio: &mut AudioSpool) -> std::io::Result<()> {
+    storage::write_atomic_with(path, |out| {
+        out.write_all(&wav::wav_header(audio.len(), TARGET_SAMPLE_RATE))?;
+        for chunk in audio.chunks(TARGET_SAMPLE_RATE as usize * 10)? {
+            out.write_all(&wav::encode_pcm16(&chunk?))?;
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,162 @@
+use crate::textproc::PostProcessor;
+
+/// Spoken phrases (lowercase) that start a spelled utterance, e.g.
+/// "spell out alpha bravo seven".
+const COMMAND_PHRASES: &[&str] = &["spell out", "spelling mode"];
+
+/// Words that uppercase the next spelled character.
+const CAPITAL_WORDS: &[&str] = &["capital", "uppercase", "cap"];
+
+/// Spoken word → literal characters: NATO alphabet, letter names, digits,
+/// and the symbols common in serials and email addresses.
+const SPELLING_WORDS: &[(&str, &str)] = &[
+    ("alpha", "a"),
+    ("alfa", "a"),
+    ("bravo", "b"),
+    ("charlie", "c"),
+    ("delta", "d"),
+    ("echo", "e"),
+    ("foxtrot", "f"),
+    ("golf", "g"),
+    ("hotel", "h"),
+    ("india", "i"),
+    ("juliet", "j"),
+    ("juliett", "j"),
+    ("kilo", "k"),
+    ("lima", "l"),
+    ("mike", "m"),
+    ("november", "n"),
+    ("oscar", "o"),
+    ("papa", "p"),
+    ("quebec", "q"),
+    ("romeo", "r"),
+    ("sierra", "s"),
+    ("tango", "t"),
+    ("uniform", "u"),
+    ("victor", "v"),
+    ("whiskey", "w"),
+    ("x-ray", "x"),
+    ("xray", "x"),
+    ("yankee", "y"),
+    ("zulu", "z"),
+    ("bee", "b"),
+    ("cee", "c"),
+    ("see", "c"),
+    ("dee", "d"),
+    ("ef", "f"),
+    ("gee", "g"),
+    ("aitch", "h"),
+    ("jay", "j"),
+    ("kay", "k"),
+    ("el", "l"),
+    ("em", "m"),
+    ("en", "n"),
+    ("pee", "p"),
+    ("cue", "q"),
+    ("queue", "q"),
+    ("ar", "r"),
+    ("ess", "s"),
+    ("tee", "t"),
+    ("you", "u"),
+    ("vee", "v"),
+    ("ex", "x"),
+    ("why", "y"),
+    ("zed", "z"),
+    ("zee", "z"),
+    ("zero", "0"),
+    ("oh", "0"),
+    ("one", "1"),
+    ("two", "2"),
+    ("three", "3"),
+    ("four", "4"),
+    ("five", "5"),
+    ("six", "6"),
+    ("seven", "7"),
+    ("eight", "8"),
+    ("nine", "9"),
+    ("at", "@"),
+    ("dot", "."),
+    ("period", "."),
+    ("point", "."),
+    ("dash", "-"),
+    ("hyphen", "-"),
+    ("minus", "-"),
+    ("underscore", "_"),
+    ("slash", "/"),
+    ("plus", "+"),
+    ("hash", "#"),
+    ("space", " "),
+];
+
+/// Converts spelled-out input into the literal string, e.g.
+/// "alpha bravo seven" → "ab7". Words outside the spelling vocabulary are
+/// appended as-is, so "A B 7" from the recognizer also becomes "ab7".
+pub struct Spelling;
+
+/// Lowercase a word and strip punctuation the recognizer attached to it.
+fn normalize(word: &str) -> String {
+    word.trim_matches(|c: char| matches!(c, ',' | '.' | '?' | '!' | ';' | ':'))
+        .to_lowercase()
+}
+
+/// If `text` starts with a spelling command, return the words after it.
+pub fn strip_command(text: &str) -> Option<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    COMMAND_PHRASES.iter().find_map(|phrase| {
+        let len = phrase.split(' ').count();
+        let matches = words.len() > len
+            && phrase
+                .split(' ')
+                .zip(&words)
+                .all(|(p, w)| normalize(w) == p);
+        matches.then(|| words[len..].join(" "))
+    })
+}
+
+impl PostProcessor for Spelling {
+    fn name(&self) -> &'static str {
+        "spelling"
+    }
+
+    fn process(&self, text: &str) -> String {
+        let raw: Vec<&str> = text.split_whitespace().collect();
+        let mut out = String::new();
+        let mut capital = false;
+        let mut i = 0;
+        while i < raw.len() {
+            let word = normalize(raw[i]);
+            i += 1;
+            if word.is_empty() {
+                continue;
+            }
+            if CAPITAL_WORDS.contains(&word.as_str()) {
+                capital = true;
+                continue;
+            }
+
+            let piece = if word == "double"
+                && raw
+                    .get(i)
+                    .is_some_and(|next| matches!(normalize(next).as_str(), "u" | "you"))
+            {
+                i += 1;
+                "w".to_string()
+            } else if let Some((_, literal)) = SPELLING_WORDS.iter().find(|(w, _)| *w == word) {
+                literal.to_string()
+            } else if word.chars().count() == 1 {
+                word
+            } else {
+                raw[i - 1]
+                    .trim_matches(|c: char| matches!(c, ',' | '.' | '?' | '!' | ';' | ':'))
+                    .to_string()
+            };
+
+            if std::mem::take(&mut capital) {
+                out.push_str(&piece.to_uppercase());
+            } else {
+                out.push_str(&piece);
+            }
+        }
+        out
+    }
+}
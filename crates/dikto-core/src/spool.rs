@@ -0,0 +1,120 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tracing::warn;
+
+/// Bytes per spooled sample (f32, little-endian).
+const SAMPLE_BYTES: usize = 4;
+
+/// Distinguishes spools created by one process.
+static NEXT_SPOOL: AtomicU64 = AtomicU64::new(0);
+
+/// Audio moved out of RAM into a temporary file during a long session, read
+/// back in chunks for inference. The file is deleted when the spool is dropped.
+pub struct AudioSpool {
+    path: PathBuf,
+    file: File,
+    samples: usize,
+}
+
+impl AudioSpool {
+    /// Create an empty spool file in `dir`.
+    pub fn create(dir: &Path) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let n = NEXT_SPOOL.fetch_add(1, Ordering::Relaxed);
+        let path = dir.join(format!("dikto-spool-{}-{n}.f32", std::process::id()));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            file,
+            samples: 0,
+        })
+    }
+
+    /// Where the spooled audio is kept.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of spooled samples.
+    pub fn len(&self) -> usize {
+        self.samples
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples == 0
+    }
+
+    /// Append samples to the end of the spool.
+    pub fn append(&mut self, samples: &[f32]) -> std::io::Result<()> {
+        self.file
+            .seek(SeekFrom::Start((self.samples * SAMPLE_BYTES) as u64))?;
+        let mut writer = BufWriter::new(&mut self.file);
+        for sample in samples {
+            writer.write_all(&sample.to_le_bytes())?;
+        }
+        writer.flush()?;
+        self.samples += samples.len();
+        Ok(())
+    }
+
+    /// Read the spooled audio back in order, at most `chunk_samples` at a time.
+    pub fn chunks(&mut self, chunk_samples: usize) -> std::io::Result<SpoolChunks<'_>> {
+        self.file.seek(SeekFrom::Start(0))?;
+        Ok(SpoolChunks {
+            reader: BufReader::new(&mut self.file),
+            remaining: self.samples,
+            chunk_samples: chunk_samples.max(1),
+        })
+    }
+
+    /// Drop all spooled audio, keeping the file for reuse.
+    pub fn clear(&mut self) -> std::io::Result<()> {
+        self.file.set_len(0)?;
+        self.samples = 0;
+        Ok(())
+    }
+}
+
+impl Drop for AudioSpool {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            warn!("Failed to remove audio spool {}: {e}", self.path.display());
+        }
+    }
+}
+
+/// Iterator over the chunks of an `AudioSpool`.
+pub struct SpoolChunks<'a> {
+    reader: BufReader<&'a mut File>,
+    remaining: usize,
+    chunk_samples: usize,
+}
+
+impl Iterator for SpoolChunks<'_> {
+    type Item = std::io::Result<Vec<f32>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let len = self.remaining.min(self.chunk_samples);
+        let mut bytes = vec![0u8; len * SAMPLE_BYTES];
+        if let Err(e) = self.reader.read_exact(&mut bytes) {
+            self.remaining = 0;
+            return Some(Err(e));
+        }
+        self.remaining -= len;
+        Some(Ok(bytes
+            .chunks_exact(SAMPLE_BYTES)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()))
+    }
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::config::{data_dir, ConfigError};
+use crate::storage;
+
+/// What the engine remembers between launches that isn't a setting:
+/// the last model it loaded, the language last used in each app, and
+/// downloads that were started but never finished.
+///
+/// Kept in `~/.local/share/dikto/state.json`, apart from config.json, so
+/// it never shows up in the user's settings or a sync blob.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct EngineState {
+    /// Model most recently loaded into RAM, e.g. to warm-load at launch.
+    #[serde(default)]
+    pub last_model: Option<String>,
+    /// Language to dictate in per app (bundle identifier or app name),
+    /// kept only where it differs from the configured language.
+    #[serde(default)]
+    pub app_languages: HashMap<String, String>,
+    /// Models whose download started and hasn't completed or failed. At
+    /// launch these are the downloads the last run was quit during.
+    #[serde(default)]
+    pub pending_downloads: Vec<String>,
+}
+
+impl EngineState {
+    /// Load state from a file. Missing or unreadable files yield empty state.
+    pub fn load_from(path: &Path) -> Self {
+        let Ok(contents) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Failed to parse engine state at {}: {e}", path.display());
+            Self::default()
+        })
+    }
+
+    /// Save state to a file atomically, creating the parent directory if needed.
+    pub fn save_to(&self, path: &Path) -> Result<(), std::io::Error> {
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        storage::write_atomic(path, json.as_bytes())
+    }
+
+    /// Language last used in `app`, if it differed from the configured one.
+    pub fn language_for_app(&self, app: &str) -> Option<&str> {
+        self.app_languages.get(app).map(String::as_str)
+    }
+
+    /// Remember that `app` was dictated into in `language`. Going back to
+    /// `configured` forgets the app, so it follows later config changes.
+    pub fn record_app_language(&mut self, app: &str, language: &str, configured: &str) {
+        if app.is_empty() {
+            return;
+        }
+        if language == configured {
+            self.app_languages.remove(app);
+        } else {
+            self.app_languages
+                .insert(app.to_string(), language.to_string());
+        }
+    }
+
+    /// Mark a download of `model_name` as started (`pending`) or over.
+    pub fn set_download_pending(&mut self, model_name: &str, pending: bool) {
+        self.pending_downloads.retain(|name| name != model_name);
+        if pending {
+            self.pending_downloads.push(model_name.to_string());
+        }
+    }
+}
+
+/// Returns the engine state path: ~/.local/share/dikto/state.json
+pub fn state_path() -> Result<PathBuf, ConfigError> {
+    Ok(data_dir()?.join("state.json"))
+}
+
+/// Load the default state file.
+pub fn load_state() -> EngineState {
+    match state_path() {
+        Ok(path) => EngineState::load_from(&path),
+        Err(e) => {
+            warn!("Cannot read engine state: {e}");
+            EngineState::default()
+        }
+    }
+}
+
+/// Save `state` to the default state file.
+pub fn save_state(state: &EngineState) -> Result<(), std::io::Error> {
+    let path = state_path().map_err(|e| std::io::Error::other(e.to_string()))?;
+    let _lock = storage::FileLock::acquire(&path)?;
+    state.save_to(&path)
+}
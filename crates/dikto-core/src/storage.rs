@@ -0,0 +1,124 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+/// Longest to wait for another process's lock before giving up.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Locks older than this are left over from a crashed process.
+const STALE_LOCK: Duration = Duration::from_secs(30);
+
+/// Distinguishes temporary files created by one process.
+static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+
+/// Replace `path` with `contents` in one step: the data goes to a
+/// temporary file in the same folder, is flushed to disk, and is renamed
+/// over the old file. Readers and sync clients (iCloud Drive, Dropbox)
+/// see either the old file or the new one, never half of each.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    write_atomic_with(path, |out| out.write_all(contents))
+}
+
+/// Like `write_atomic`, for writers that stream the contents.
+pub fn write_atomic_with(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    std::fs::create_dir_all(dir)?;
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let n = NEXT_TEMP.fetch_add(1, Ordering::Relaxed);
+    let temp = dir.join(format!(".{name}.{}-{n}.tmp", std::process::id()));
+
+    let written = File::create(&temp).and_then(|file| {
+        let mut out = BufWriter::new(file);
+        write(&mut out)?;
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()
+    });
+    let result = written.and_then(|()| std::fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Append `contents` to `path` under its lock, creating the file if
+/// needed, and flush it to disk before the lock is released.
+pub fn append_locked(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let _lock = FileLock::acquire(path)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(contents)?;
+    file.sync_data()
+}
+
+/// Advisory lock on a data file, held as a `<name>.lock` file next to it
+/// so other Dikto processes (the app and the CLI, or two machines sharing
+/// a synced folder) take turns updating it. Released when dropped.
+pub struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Wait for the lock on `path`. Locks left by a crashed process are
+    /// broken after a while; a live holder that keeps it past
+    /// `LOCK_TIMEOUT` is an error.
+    pub fn acquire(path: &Path) -> std::io::Result<Self> {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".lock");
+        let lock_path = path.with_file_name(name);
+        let deadline = Instant::now() + LOCK_TIMEOUT;
+        loop {
+            match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&lock_path)
+            {
+                Ok(mut file) => {
+                    let _ = write!(file, "{}", std::process::id());
+                    return Ok(Self { path: lock_path });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if is_stale(&lock_path) {
+                        warn!("Breaking stale lock {}", lock_path.display());
+                        let _ = std::fs::remove_file(&lock_path);
+                        continue;
+                    }
+                    if Instant::now() >= deadline {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::TimedOut,
+                            format!("{} is locked by another process", path.display()),
+                        ));
+                    }
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            warn!("Failed to release lock {}: {e}", self.path.display());
+        }
+    }
+}
+
+fn is_stale(lock_path: &Path) -> bool {
+    std::fs::metadata(lock_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age > STALE_LOCK)
+}
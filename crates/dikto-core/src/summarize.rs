@@ -0,0 +1,126 @@
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::config::SummaryConfig;
+
+#[derive(Debug, Error)]
+pub enum SummarizeError {
+    #[error("Summary command failed: {0}")]
+    Command(String),
+    #[error("Summary API error: {0}")]
+    Api(String),
+    #[error("No summary after {0} seconds")]
+    Timeout(u32),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Whether `text` is long enough to summarize with `config`.
+pub fn should_summarize(text: &str, config: &SummaryConfig) -> bool {
+    config.is_enabled() && text.split_whitespace().count() >= config.min_words as usize
+}
+
+/// Summarize a transcript with the configured command, or the API when no
+/// command is set. Returns the summary trimmed; an empty one is an error.
+pub fn summarize(text: &str, config: &SummaryConfig) -> Result<String, SummarizeError> {
+    let timeout = Duration::from_secs(u64::from(config.timeout_secs));
+    let summary = if !config.command.trim().is_empty() {
+        run_command(&config.command, text, timeout)?
+    } else {
+        call_api(&config.api_url, &config.api_key, text, timeout)?
+    };
+    let summary = summary.trim().to_string();
+    if summary.is_empty() {
+        return Err(SummarizeError::Command("empty summary".to_string()));
+    }
+    Ok(summary)
+}
+
+/// Run `command` through the shell with `input` on stdin and return its
+/// stdout, killing it after `timeout`.
+fn run_command(command: &str, input: &str, timeout: Duration) -> Result<String, SummarizeError> {
+    #[cfg(windows)]
+    let (shell, flag) = ("cmd", "/C");
+    #[cfg(not(windows))]
+    let (shell, flag) = ("sh", "-c");
+    let mut child = Command::new(shell)
+        .args([flag, command])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    // Write and read on threads so a command that fills a pipe can't block us
+    let mut stdin = child.stdin.take();
+    let input = input.to_string();
+    let writer = std::thread::spawn(move || {
+        if let Some(stdin) = stdin.as_mut() {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+    });
+    let mut stdout = child.stdout.take();
+    let reader = std::thread::spawn(move || {
+        let mut out = String::new();
+        if let Some(stdout) = stdout.as_mut() {
+            let _ = stdout.read_to_string(&mut out);
+        }
+        out
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(SummarizeError::Timeout(timeout.as_secs() as u32));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    let _ = writer.join();
+    let output = reader.join().unwrap_or_default();
+    if !status.success() {
+        return Err(SummarizeError::Command(format!("exited with {status}")));
+    }
+    Ok(output)
+}
+
+#[derive(Deserialize)]
+struct ApiResponse {
+    summary: String,
+}
+
+fn call_api(
+    url: &str,
+    api_key: &str,
+    text: &str,
+    timeout: Duration,
+) -> Result<String, SummarizeError> {
+    let api_error = |e: reqwest::Error| SummarizeError::Api(e.to_string());
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let mut request = reqwest::Client::new()
+        .post(url.trim())
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(serde_json::json!({ "text": text }).to_string())
+        .timeout(timeout);
+    if !api_key.is_empty() {
+        request = request.bearer_auth(api_key);
+    }
+    let body = runtime
+        .block_on(async {
+            let response = request.send().await?.error_for_status()?;
+            response.text().await
+        })
+        .map_err(api_error)?;
+    let parsed: ApiResponse =
+        serde_json::from_str(&body).map_err(|e| SummarizeError::Api(e.to_string()))?;
+    Ok(parsed.summary)
+}
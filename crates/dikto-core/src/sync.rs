@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::config::DiktoConfig;
+
+/// Marks a JSON document as Dikto settings.
+const SYNC_FORMAT: &str = "dikto-settings";
+
+/// Version written by `export_settings`. Documents from a newer major
+/// version are refused; fields added within a version are ignored by older
+/// readers and keep their local values when missing.
+pub const SYNC_VERSION: u32 = 1;
+
+/// Config fields that describe this machine rather than the user's
+/// preferences: models, paths, hardware tuning, and the services
+/// transcripts are sent to along with their keys. They are never exported
+/// and never overwritten by a synced document, so secrets stay out of
+/// synced folders and another machine can't point this one's transcripts
+/// at a new server. Nested fields are written as "section.field".
+pub const DEVICE_LOCAL_FIELDS: &[&str] = &[
+    "model_name",
+    "low_power_mode",
+    "low_power_model",
+    "model_path_override",
+    "huggingface_cache",
+    "previous_model",
+    "data_dir",
+    "advanced",
+    "translation.model",
+    "translation.api_url",
+    "translation.api_key",
+    "summary.command",
+    "summary.api_url",
+    "summary.api_key",
+    "action_items.tasks_file",
+    "saved_sessions.dir",
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum SyncError {
+    #[error("Invalid settings document: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Not a Dikto settings document")]
+    NotSettings,
+    #[error("Settings document version {0} is newer than this version of Dikto supports")]
+    UnsupportedVersion(u32),
+}
+
+/// Settings shared between a user's machines, e.g. through iCloud
+/// key-value storage or a file in a synced folder.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SyncDocument {
+    pub format: String,
+    pub version: u32,
+    /// When the document was exported, in seconds since the Unix epoch.
+    /// Decides which side wins when two documents are merged.
+    pub updated_at: u64,
+    /// Config fields by name, as in config.json, minus device-local ones.
+    pub settings: Map<String, Value>,
+}
+
+impl SyncDocument {
+    /// Parse a document, checking its format and version.
+    pub fn parse(blob: &str) -> Result<Self, SyncError> {
+        let doc: SyncDocument = serde_json::from_str(blob)?;
+        if doc.format != SYNC_FORMAT {
+            return Err(SyncError::NotSettings);
+        }
+        if doc.version > SYNC_VERSION {
+            return Err(SyncError::UnsupportedVersion(doc.version));
+        }
+        Ok(doc)
+    }
+
+    fn to_blob(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// The shareable part of `config` as a settings document.
+pub fn export_settings(config: &DiktoConfig, updated_at: u64) -> String {
+    let mut settings = match serde_json::to_value(config) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    };
+    remove_device_local(&mut settings);
+    SyncDocument {
+        format: SYNC_FORMAT.to_string(),
+        version: SYNC_VERSION,
+        updated_at,
+        settings,
+    }
+    .to_blob()
+}
+
+/// `config` with the settings from a document applied. Device-local fields
+/// and fields the document doesn't have keep their current values.
+pub fn apply_settings(config: &DiktoConfig, blob: &str) -> Result<DiktoConfig, SyncError> {
+    let mut incoming = SyncDocument::parse(blob)?.settings;
+    remove_device_local(&mut incoming);
+    let mut merged = serde_json::to_value(config)?;
+    if let Value::Object(local) = &mut merged {
+        merge_into(local, incoming);
+    }
+    let mut config: DiktoConfig = serde_json::from_value(merged)?;
+    config.validate();
+    Ok(config)
+}
+
+/// Combine two settings documents, e.g. a local copy and the one found in
+/// the synced folder. Fields both have come from the newer document;
+/// fields only one has are kept.
+pub fn merge_documents(a: &str, b: &str) -> Result<String, SyncError> {
+    let a = SyncDocument::parse(a)?;
+    let b = SyncDocument::parse(b)?;
+    let (mut newer, older) = if b.updated_at >= a.updated_at {
+        (b, a)
+    } else {
+        (a, b)
+    };
+    let mut settings = older.settings;
+    merge_into(&mut settings, std::mem::take(&mut newer.settings));
+    newer.settings = settings;
+    newer.version = SYNC_VERSION;
+    Ok(newer.to_blob())
+}
+
+/// Overlay `incoming` on `target`, recursing into objects so a section
+/// with fewer fields doesn't erase the others.
+fn merge_into(target: &mut Map<String, Value>, incoming: Map<String, Value>) {
+    for (key, value) in incoming {
+        match (target.get_mut(&key), value) {
+            (Some(Value::Object(existing)), Value::Object(value)) => merge_into(existing, value),
+            (_, value) => {
+                target.insert(key, value);
+            }
+        }
+    }
+}
+
+fn remove_device_local(settings: &mut Map<String, Value>) {
+    for field in DEVICE_LOCAL_FIELDS {
+        match field.split_once('.') {
+            Some((section, name)) => {
+                if let Some(Value::Object(section)) = settings.get_mut(section) {
+                    section.remove(name);
+                }
+            }
+            None => {
+                settings.remove(*field);
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::config::{config_dir, ConfigError};
+use crate::storage;
+
+/// A reusable snippet inserted by saying "insert <name> template". Each
+/// `{{placeholder}}` in the body is filled by the next utterance, in order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, uniffi::Record)]
+pub struct Template {
+    pub name: String,
+    /// Snippet text, e.g. "Attendees: {{attendees}}\nNotes: {{notes}}".
+    pub body: String,
+}
+
+/// Returns the templates file path: ~/.config/dikto/templates.json
+pub fn templates_path() -> Result<PathBuf, ConfigError> {
+    Ok(config_dir()?.join("templates.json"))
+}
+
+/// Load templates from a file. A missing or corrupt file gives none.
+pub fn load_templates(path: &Path) -> Vec<Template> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    match serde_json::from_str(&contents) {
+        Ok(templates) => templates,
+        Err(e) => {
+            warn!("Ignoring corrupt templates file: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Load templates from the default file.
+pub fn load_default_templates() -> Vec<Template> {
+    match templates_path() {
+        Ok(path) => load_templates(&path),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Write templates to a file atomically, creating its directory if needed.
+pub fn save_templates(path: &Path, templates: &[Template]) -> Result<(), ConfigError> {
+    storage::write_atomic(path, serde_json::to_string_pretty(templates)?.as_bytes())?;
+    Ok(())
+}
+
+/// Placeholder names in the order they first appear in `body`.
+pub fn placeholders(body: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else { break };
+        let name = after[..end].trim();
+        if !name.is_empty() && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+/// Lowercase words with punctuation removed, for matching spoken names.
+fn spoken_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| {
+            w.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// The template name in a command like "Insert meeting notes template.",
+/// or None if the text isn't one.
+pub fn parse_command(text: &str) -> Option<String> {
+    let words = spoken_words(text);
+    match words.as_slice() {
+        [first, name @ .., last] if first == "insert" && last == "template" && !name.is_empty() => {
+            Some(name.join(" "))
+        }
+        _ => None,
+    }
+}
+
+/// Template whose name matches a spoken name, ignoring case, spaces, and
+/// punctuation, so "bug report" finds "Bug-Report".
+pub fn find_template<'a>(templates: &'a [Template], spoken: &str) -> Option<&'a Template> {
+    let spoken = spoken_words(spoken).concat();
+    templates
+        .iter()
+        .find(|t| !spoken.is_empty() && spoken_words(&t.name).concat() == spoken)
+}
+
+/// A template being filled in, one utterance per placeholder.
+#[derive(Debug, Clone)]
+pub struct TemplateFill {
+    template: Template,
+    names: Vec<String>,
+    values: Vec<String>,
+}
+
+impl TemplateFill {
+    pub fn new(template: Template) -> Self {
+        Self {
+            names: placeholders(&template.body),
+            template,
+            values: Vec::new(),
+        }
+    }
+
+    /// Placeholder the next utterance fills, or None when all are filled.
+    pub fn next_placeholder(&self) -> Option<&str> {
+        self.names.get(self.values.len()).map(String::as_str)
+    }
+
+    /// Fill the next placeholder. Ignored once complete.
+    pub fn fill(&mut self, text: &str) {
+        if !self.is_complete() {
+            self.values.push(text.trim().to_string());
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.values.len() >= self.names.len()
+    }
+
+    /// The body with filled placeholders substituted. Unfilled ones are
+    /// left as `{{name}}` so they're easy to find and complete by hand.
+    pub fn render(&self) -> String {
+        let mut out = String::with_capacity(self.template.body.len());
+        let mut rest = self.template.body.as_str();
+        while let Some(start) = rest.find("{{") {
+            let after = &rest[start + 2..];
+            let Some(end) = after.find("}}") else { break };
+            out.push_str(&rest[..start]);
+            let name = after[..end].trim();
+            match self.names.iter().position(|n| n == name) {
+                Some(i) if i < self.values.len() => out.push_str(&self.values[i]),
+                _ => out.push_str(&rest[start..start + 2 + end + 2]),
+            }
+            rest = &after[end + 2..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
@@ -0,0 +1,575 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::config::{LanguageRuleOverride, TextProcConfig};
+use crate::math_latex::MathLatex;
+use crate::spelling::{self, Spelling};
+
+/// A single post-processing stage applied to final transcript text.
+pub trait PostProcessor: Send + Sync {
+    /// Short identifier for logging and diagnostics.
+    fn name(&self) -> &'static str;
+    /// Transform the text.
+    fn process(&self, text: &str) -> String;
+}
+
+/// Per-language rule table for the built-in stages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageRules {
+    /// Spoken phrase (lowercase) → punctuation it stands for.
+    pub spoken_punctuation: Vec<(String, String)>,
+    /// Uppercase the first letter of each sentence.
+    pub capitalize_sentences: bool,
+    /// Use Turkic dotted/dotless i casing.
+    pub turkic_casing: bool,
+    /// Separator inserted into digit runs of 5+ digits ("" disables grouping).
+    pub thousands_separator: String,
+    /// Put a space before `? ! : ;` (French typography).
+    pub space_before_punctuation: bool,
+}
+
+fn phrases(table: &[(&str, &str)]) -> Vec<(String, String)> {
+    table
+        .iter()
+        .map(|(phrase, mark)| (phrase.to_string(), mark.to_string()))
+        .collect()
+}
+
+impl LanguageRules {
+    /// Built-in rules for a language code. Unknown codes (and "auto") get
+    /// English spoken-punctuation words and no number grouping.
+    pub fn for_language(language: &str) -> Self {
+        let base = Self {
+            spoken_punctuation: phrases(&[
+                ("comma", ","),
+                ("period", "."),
+                ("full stop", "."),
+                ("question mark", "?"),
+                ("exclamation mark", "!"),
+                ("exclamation point", "!"),
+                ("colon", ":"),
+                ("semicolon", ";"),
+                ("new line", "\n"),
+                ("new paragraph", "\n\n"),
+            ]),
+            capitalize_sentences: true,
+            turkic_casing: false,
+            thousands_separator: String::new(),
+            space_before_punctuation: false,
+        };
+
+        match language {
+            "en" => Self {
+                thousands_separator: ",".to_string(),
+                ..base
+            },
+            "de" => Self {
+                spoken_punctuation: phrases(&[
+                    ("komma", ","),
+                    ("punkt", "."),
+                    ("fragezeichen", "?"),
+                    ("ausrufezeichen", "!"),
+                    ("doppelpunkt", ":"),
+                    ("semikolon", ";"),
+                    ("neue zeile", "\n"),
+                    ("neuer absatz", "\n\n"),
+                ]),
+                thousands_separator: ".".to_string(),
+                ..base
+            },
+            "fr" => Self {
+                spoken_punctuation: phrases(&[
+                    ("virgule", ","),
+                    ("point d'interrogation", "?"),
+                    ("point d'exclamation", "!"),
+                    ("point virgule", ";"),
+                    ("deux points", ":"),
+                    ("point", "."),
+                    ("à la ligne", "\n"),
+                    ("nouveau paragraphe", "\n\n"),
+                ]),
+                thousands_separator: " ".to_string(),
+                space_before_punctuation: true,
+                ..base
+            },
+            "es" => Self {
+                spoken_punctuation: phrases(&[
+                    ("coma", ","),
+                    ("punto y coma", ";"),
+                    ("punto", "."),
+                    ("signo de interrogación", "?"),
+                    ("signo de exclamación", "!"),
+                    ("dos puntos", ":"),
+                    ("nueva línea", "\n"),
+                    ("nuevo párrafo", "\n\n"),
+                ]),
+                thousands_separator: ".".to_string(),
+                ..base
+            },
+            "it" => Self {
+                spoken_punctuation: phrases(&[
+                    ("virgola", ","),
+                    ("punto interrogativo", "?"),
+                    ("punto esclamativo", "!"),
+                    ("punto e virgola", ";"),
+                    ("due punti", ":"),
+                    ("punto", "."),
+                    ("a capo", "\n"),
+                    ("nuovo paragrafo", "\n\n"),
+                ]),
+                thousands_separator: ".".to_string(),
+                ..base
+            },
+            "tr" | "az" => Self {
+                turkic_casing: true,
+                thousands_separator: ".".to_string(),
+                ..base
+            },
+            _ => base,
+        }
+    }
+
+    /// Apply user overrides from config on top of the built-in rules.
+    pub fn apply_override(&mut self, ov: &LanguageRuleOverride) {
+        for (phrase, mark) in &ov.spoken_punctuation {
+            let phrase = phrase.to_lowercase();
+            self.spoken_punctuation.retain(|(p, _)| *p != phrase);
+            // An empty replacement removes a built-in phrase
+            if !mark.is_empty() {
+                self.spoken_punctuation.push((phrase, mark.clone()));
+            }
+        }
+        if let Some(v) = ov.capitalize_sentences {
+            self.capitalize_sentences = v;
+        }
+        if let Some(v) = &ov.thousands_separator {
+            self.thousands_separator = v.clone();
+        }
+        if let Some(v) = ov.space_before_punctuation {
+            self.space_before_punctuation = v;
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Stages
+// ---------------------------------------------------------------------------
+
+/// Replaces spoken punctuation words ("comma", "new line") with the marks.
+pub struct SpokenPunctuation {
+    /// (phrase words, mark), longest phrases first so "point virgule" wins over "point".
+    phrases: Vec<(Vec<String>, String)>,
+}
+
+impl SpokenPunctuation {
+    pub fn new(table: &[(String, String)]) -> Self {
+        let mut phrases: Vec<(Vec<String>, String)> = table
+            .iter()
+            .map(|(phrase, mark)| {
+                (
+                    phrase.split_whitespace().map(str::to_string).collect(),
+                    mark.clone(),
+                )
+            })
+            .filter(|(words, _): &(Vec<String>, String)| !words.is_empty())
+            .collect();
+        phrases.sort_by_key(|(words, _)| std::cmp::Reverse(words.len()));
+        Self { phrases }
+    }
+
+    /// Match a phrase at the start of `words`; returns (word count, mark).
+    fn match_at(&self, words: &[&str]) -> Option<(usize, &str)> {
+        self.phrases.iter().find_map(|(phrase, mark)| {
+            let matches = phrase.len() <= words.len()
+                && phrase
+                    .iter()
+                    .zip(words)
+                    .all(|(p, w)| normalize_word(w) == *p);
+            matches.then_some((phrase.len(), mark.as_str()))
+        })
+    }
+}
+
+/// Lowercase a word and strip punctuation the recognizer attached to it.
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| c.is_ascii_punctuation() && c != '\'')
+        .to_lowercase()
+}
+
+impl PostProcessor for SpokenPunctuation {
+    fn name(&self) -> &'static str {
+        "spoken_punctuation"
+    }
+
+    fn process(&self, text: &str) -> String {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let mut out = String::with_capacity(text.len());
+        let mut i = 0;
+        while i < words.len() {
+            if let Some((len, mark)) = self.match_at(&words[i..]) {
+                // Punctuation attaches to the previous word; drop any mark the
+                // recognizer already put there ("hello, comma" → "hello,")
+                while !mark.contains('\n') {
+                    let Some(last) = out.graphemes(true).next_back() else {
+                        break;
+                    };
+                    if !last.chars().all(|c| c.is_ascii_punctuation()) {
+                        break;
+                    }
+                    let keep = out.len() - last.len();
+                    out.truncate(keep);
+                }
+                out.push_str(mark);
+                i += len;
+                continue;
+            }
+            if !out.is_empty() && !out.ends_with('\n') {
+                out.push(' ');
+            }
+            out.push_str(words[i]);
+            i += 1;
+        }
+        out
+    }
+}
+
+/// Uppercases the first letter of each sentence.
+pub struct SentenceCase {
+    turkic: bool,
+}
+
+impl SentenceCase {
+    pub fn new(turkic: bool) -> Self {
+        Self { turkic }
+    }
+}
+
+impl PostProcessor for SentenceCase {
+    fn name(&self) -> &'static str {
+        "sentence_case"
+    }
+
+    fn process(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut at_sentence_start = true;
+        for g in text.graphemes(true) {
+            let mut chars = g.chars();
+            let Some(c) = chars.next() else {
+                continue;
+            };
+            if at_sentence_start && c.is_alphabetic() {
+                // Only the base letter changes; accents stay attached to it
+                if self.turkic && c == 'i' {
+                    out.push('İ');
+                } else {
+                    out.extend(c.to_uppercase());
+                }
+                out.push_str(chars.as_str());
+                at_sentence_start = false;
+                continue;
+            }
+            if matches!(g, "." | "?" | "!" | "\n" | "\r\n") {
+                at_sentence_start = true;
+            } else if !c.is_whitespace() && !matches!(c, '"' | '\'' | '(' | '«' | '¿' | '¡') {
+                at_sentence_start = false;
+            }
+            out.push_str(g);
+        }
+        out
+    }
+}
+
+/// Groups long digit runs with a thousands separator ("1234567" → "1,234,567").
+/// Runs shorter than 5 digits are left alone so years like "2024" survive,
+/// and so are runs that aren't quantities: phone-length runs of 10 or more
+/// digits, a leading zero, a "#" in front, or a word like "zip code" or
+/// "order number" before them.
+pub struct NumberGrouping {
+    separator: String,
+}
+
+impl NumberGrouping {
+    pub fn new(separator: &str) -> Self {
+        Self {
+            separator: separator.to_string(),
+        }
+    }
+
+    fn group(&self, digits: &str) -> String {
+        if digits.len() < 5 {
+            return digits.to_string();
+        }
+        let mut out = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push_str(&self.separator);
+            }
+            out.push(c);
+        }
+        out
+    }
+}
+
+impl PostProcessor for NumberGrouping {
+    fn name(&self) -> &'static str {
+        "number_grouping"
+    }
+
+    fn process(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut run = String::new();
+        let mut prev: Option<char> = None;
+        let mut graphemes = text.graphemes(true).peekable();
+        while let Some(g) = graphemes.next() {
+            // A digit with a mark on it ("1️⃣") is not part of a number
+            if g.len() == 1 && g.as_bytes()[0].is_ascii_digit() {
+                run.push_str(g);
+                continue;
+            }
+            let c = g.chars().next();
+            if !run.is_empty() {
+                let standalone = !is_attached(prev)
+                    && (!is_attached(c)
+                        // A sentence-ending "." or "," is fine; "12.5" is not
+                        || (matches!(g, "." | ",")
+                            && !graphemes
+                                .peek()
+                                .is_some_and(|n| n.starts_with(|c: char| c.is_ascii_digit()))));
+                let quantity = standalone && is_quantity(&run, &out);
+                out.push_str(&self.format_run(&run, quantity));
+                run.clear();
+            }
+            out.push_str(g);
+            prev = g.chars().next_back();
+        }
+        let quantity = !is_attached(prev) && is_quantity(&run, &out);
+        out.push_str(&self.format_run(&run, quantity));
+        out
+    }
+}
+
+/// Words after which a number names something rather than counts it, as in
+/// "zip code 94103" or "order number 123456".
+const IDENTIFIER_WORDS: &[&str] = &[
+    "account",
+    "call",
+    "card",
+    "code",
+    "ext",
+    "extension",
+    "flight",
+    "id",
+    "invoice",
+    "no",
+    "number",
+    "order",
+    "phone",
+    "pin",
+    "postcode",
+    "ref",
+    "reference",
+    "room",
+    "serial",
+    "ticket",
+    "tracking",
+    "zip",
+];
+
+/// Whether the digit run `digits`, following `before`, reads as a quantity.
+fn is_quantity(digits: &str, before: &str) -> bool {
+    if digits.len() >= 10 || digits.starts_with('0') || before.ends_with('#') {
+        return false;
+    }
+    let word = before
+        .split_whitespace()
+        .next_back()
+        .unwrap_or("")
+        .trim_end_matches([':', '.', '#'])
+        .to_lowercase();
+    !IDENTIFIER_WORDS.contains(&word.as_str())
+}
+
+/// True if `c` glues a digit run to a decimal, grouped number, or identifier.
+fn is_attached(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_alphanumeric() || ".,_".contains(c))
+}
+
+impl NumberGrouping {
+    fn format_run(&self, digits: &str, standalone: bool) -> String {
+        if standalone {
+            self.group(digits)
+        } else {
+            digits.to_string()
+        }
+    }
+}
+
+/// Puts a space before `? ! : ;` (French typography).
+pub struct PunctuationSpacing;
+
+impl PostProcessor for PunctuationSpacing {
+    fn name(&self) -> &'static str {
+        "punctuation_spacing"
+    }
+
+    fn process(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len() + 8);
+        for g in text.graphemes(true) {
+            if matches!(g, "?" | "!" | ":" | ";") && !out.is_empty() && !out.ends_with(' ') {
+                out.push(' ');
+            }
+            out.push_str(g);
+        }
+        out
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Pipeline
+// ---------------------------------------------------------------------------
+
+/// Ordered list of post-processing stages for a session.
+#[derive(Default)]
+pub struct TextPipeline {
+    stages: Vec<Box<dyn PostProcessor>>,
+    /// Spell out utterances that start with a spelling command.
+    spelling_command: bool,
+}
+
+impl TextPipeline {
+    /// An empty pipeline that returns text unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a stage.
+    pub fn push(&mut self, stage: Box<dyn PostProcessor>) {
+        self.stages.push(stage);
+    }
+
+    /// Build the pipeline for a session language from config.
+    pub fn for_language(language: &str, config: &TextProcConfig) -> Self {
+        let mut pipeline = Self::new();
+        if !config.enabled {
+            return pipeline;
+        }
+        if config.spelling_mode {
+            pipeline.push(Box::new(Spelling));
+            return pipeline;
+        }
+        pipeline.spelling_command = config.spelling_command;
+        // Math mode replaces prose rules: capitalization and punctuation
+        // words would corrupt formulas
+        if config.math_mode {
+            pipeline.push(Box::new(MathLatex::new()));
+            return pipeline;
+        }
+
+        let mut rules = LanguageRules::for_language(language);
+        if let Some(ov) = config.language_overrides.get(language) {
+            rules.apply_override(ov);
+        }
+
+        if config.spoken_punctuation && !rules.spoken_punctuation.is_empty() {
+            pipeline.push(Box::new(SpokenPunctuation::new(&rules.spoken_punctuation)));
+        }
+        if !config.prose_rules {
+            return pipeline;
+        }
+        if config.group_numbers && !rules.thousands_separator.is_empty() {
+            pipeline.push(Box::new(NumberGrouping::new(&rules.thousands_separator)));
+        }
+        if rules.capitalize_sentences {
+            pipeline.push(Box::new(SentenceCase::new(rules.turkic_casing)));
+        }
+        if rules.space_before_punctuation {
+            pipeline.push(Box::new(PunctuationSpacing));
+        }
+        pipeline
+    }
+
+    /// Run all stages in order. A spelling command at the start of the
+    /// utterance bypasses the stages for that utterance.
+    pub fn process(&self, text: &str) -> String {
+        if self.spelling_command {
+            if let Some(rest) = spelling::strip_command(text) {
+                return Spelling.process(&rest);
+            }
+        }
+        self.stages
+            .iter()
+            .fold(text.to_string(), |acc, stage| stage.process(&acc))
+    }
+
+    /// Names of the active stages, in order.
+    pub fn stage_names(&self) -> Vec<&'static str> {
+        self.stages.iter().map(|s| s.name()).collect()
+    }
+}
+
+/// Join final segments, each paired with the pause before it in ms, into
+/// one transcript. Long pauses close the previous sentence or start a new
+/// paragraph per `sentence_break_ms` and `paragraph_break_ms`.
+pub fn join_utterances(segments: &[(u32, String)], config: &TextProcConfig) -> String {
+    let reached =
+        |gap_ms: u32, threshold: u32| config.enabled && threshold > 0 && gap_ms >= threshold;
+    // A period would corrupt LaTeX or spelled-out text
+    let punctuate = !config.math_mode && !config.spelling_mode;
+
+    let mut out = String::new();
+    for (gap_ms, text) in segments {
+        if text.is_empty() {
+            continue;
+        }
+        if !out.is_empty() {
+            let paragraph = reached(*gap_ms, config.paragraph_break_ms);
+            let sentence = paragraph || reached(*gap_ms, config.sentence_break_ms);
+            if sentence && punctuate && !out.ends_with(['.', '!', '?', '…', ':', ';']) {
+                out.push('.');
+            }
+            out.push_str(if paragraph { "\n\n" } else { " " });
+        }
+        out.push_str(text);
+    }
+    out
+}
+
+/// The first `max` user-perceived characters (grapheme clusters) of `text`,
+/// so an accented letter or emoji is never cut in half.
+pub fn truncate_graphemes(text: &str, max: usize) -> &str {
+    match text.grapheme_indices(true).nth(max) {
+        Some((i, _)) => &text[..i],
+        None => text,
+    }
+}
+
+/// The last `max` grapheme clusters of `text`.
+pub fn tail_graphemes(text: &str, max: usize) -> &str {
+    if max == 0 {
+        return "";
+    }
+    match text.grapheme_indices(true).rev().nth(max - 1) {
+        Some((i, _)) => &text[i..],
+        None => text,
+    }
+}
+
+/// Returns true if the text looks like a known ASR hallucination token.
+pub fn is_hallucination(text: &str) -> bool {
+    let t = text.trim().to_lowercase();
+    let hallucinations = [
+        "[blank_audio]",
+        "[music]",
+        "[inaudible]",
+        "[silence]",
+        "[no speech]",
+        "[applause]",
+        "[laughter]",
+        "(music)",
+        "(silence)",
+        "(laughter)",
+        "(applause)",
+        "(no speech)",
+        "(blank audio)",
+    ];
+    hallucinations.contains(&t.as_str())
+}
@@ -2,8 +2,15 @@ use std::path::Path;
 use thiserror::Error;
 use tracing::info;
 
+#[cfg(feature = "backend-parakeet")]
 use parakeet_rs::{ParakeetTDT, Transcriber};
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+#[cfg(feature = "backend-whisper")]
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+};
+
+#[cfg(feature = "backend-parakeet")]
+use crate::models::{stage_nemo_model, CustomModelMetadata};
 
 #[derive(Debug, Error)]
 pub enum TranscribeError {
@@ -35,19 +42,189 @@ impl Default for TranscribeConfig {
 pub struct TranscriptSegment {
     pub text: String,
     pub is_final: bool,
+    /// Language the segment was recognized in, when the backend reports it
+    /// (Whisper auto-detection). None for backends without detection.
+    pub language: Option<String>,
+    /// Per-word recognizer confidence, when the backend reports it.
+    pub words: Vec<WordConfidence>,
+    /// Start and end of the segment in seconds from the start of the
+    /// session's audio stream, when the backend reports timing.
+    pub start_secs: Option<f32>,
+    pub end_secs: Option<f32>,
+    /// The VAD heard clear speech but the recognizer was unsure of most
+    /// words, which usually means two people talking at once.
+    pub possible_overlap: bool,
+    /// Whisper's probability that the segment's audio held no speech.
+    /// None for backends that don't report it.
+    pub no_speech_prob: Option<f32>,
+}
+
+/// A recognized word with the recognizer's confidence in it.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct WordConfidence {
+    pub word: String,
+    /// 0.0–1.0; the lowest token probability within the word, so a single
+    /// uncertain sub-word marks the whole word uncertain.
+    pub confidence: f32,
+}
+
+/// Result of one batch inference call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transcription {
+    pub text: String,
+    /// Detected (or requested) language code, if the backend knows it.
+    pub language: Option<String>,
+    /// Timed segments covering `text` (empty if the backend reports no timing).
+    pub segments: Vec<TimedSegment>,
+    /// Other readings of the whole buffer, best first, when the decoder
+    /// produces them. Never includes `text` itself.
+    pub alternatives: Vec<Hypothesis>,
+}
+
+/// One decoder hypothesis for a buffer of audio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hypothesis {
+    pub text: String,
+    /// 0.0–1.0; higher is more likely. Only comparable within one buffer.
+    pub score: f32,
+}
+
+/// Most alternatives kept per utterance.
+pub const MAX_ALTERNATIVES: usize = 4;
+
+/// Clean up raw hypotheses for a "did you mean" picker: trimmed, without
+/// empty ones, ones matching `best` or each other (ignoring case and
+/// punctuation), best score first, at most `MAX_ALTERNATIVES`.
+pub fn rank_alternatives(best: &str, hypotheses: Vec<Hypothesis>) -> Vec<Hypothesis> {
+    fn key(text: &str) -> String {
+        text.chars()
+            .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    }
+
+    let mut hypotheses: Vec<Hypothesis> = hypotheses
+        .into_iter()
+        .map(|h| Hypothesis {
+            text: h.text.trim().to_string(),
+            score: h.score,
+        })
+        .filter(|h| !key(&h.text).is_empty())
+        .collect();
+    hypotheses.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    let mut seen = vec![key(best)];
+    let mut ranked = Vec::new();
+    for h in hypotheses {
+        let k = key(&h.text);
+        if !seen.contains(&k) {
+            seen.push(k);
+            ranked.push(h);
+        }
+        if ranked.len() == MAX_ALTERNATIVES {
+            break;
+        }
+    }
+    ranked
+}
+
+/// Score a decoded hypothesis from its tokens' probabilities: their
+/// geometric mean, so long and short readings compare fairly. Special tokens
+/// are ignored; no tokens scores 0.0.
+pub fn hypothesis_score(tokens: &[(String, f32)]) -> f32 {
+    let probs: Vec<f32> = tokens
+        .iter()
+        .filter(|(text, _)| !text.starts_with("[_") && !text.starts_with("<|"))
+        .map(|(_, prob)| prob.clamp(f32::MIN_POSITIVE, 1.0))
+        .collect();
+    if probs.is_empty() {
+        return 0.0;
+    }
+    let mean_log = probs.iter().map(|p| p.ln()).sum::<f32>() / probs.len() as f32;
+    mean_log.exp()
+}
+
+/// A stretch of recognized text with timing relative to the transcribed buffer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedSegment {
+    pub text: String,
+    pub start_secs: f32,
+    pub end_secs: f32,
+    /// Per-word confidence (empty for backends without token probabilities).
+    pub words: Vec<WordConfidence>,
+    /// Probability that the audio held no speech (Whisper only).
+    pub no_speech_prob: Option<f32>,
+}
+
+/// Group timed sub-word tokens `(text, start_secs, end_secs)` into
+/// sentence-level segments, splitting after `.`, `?`, and `!`. A token
+/// starting with whitespace or `▁` (SentencePiece) begins a new word.
+pub fn segments_from_timed_tokens(tokens: &[(String, f32, f32)]) -> Vec<TimedSegment> {
+    let mut segments = Vec::new();
+    let mut current: Option<TimedSegment> = None;
+    for (token, start, end) in tokens {
+        let starts_word = token.starts_with(|c: char| c.is_whitespace() || c == '▁');
+        let piece = token.trim_matches(|c: char| c.is_whitespace() || c == '▁');
+        if piece.is_empty() {
+            continue;
+        }
+        let seg = current.get_or_insert_with(|| TimedSegment {
+            text: String::new(),
+            start_secs: *start,
+            end_secs: *end,
+            words: Vec::new(),
+            no_speech_prob: None,
+        });
+        if starts_word && !seg.text.is_empty() {
+            seg.text.push(' ');
+        }
+        seg.text.push_str(piece);
+        seg.end_secs = *end;
+        if piece.ends_with(['.', '?', '!']) {
+            segments.extend(current.take());
+        }
+    }
+    segments.extend(current);
+    segments
+}
+
+/// Group sub-word tokens into words. A token starting with whitespace begins
+/// a new word; special tokens (`[_BEG_]`, `<|endoftext|>`) are skipped.
+pub fn group_word_confidence(tokens: &[(String, f32)]) -> Vec<WordConfidence> {
+    let mut words: Vec<WordConfidence> = Vec::new();
+    for (text, prob) in tokens {
+        if text.starts_with("[_") || text.starts_with("<|") {
+            continue;
+        }
+        let starts_word = text.starts_with(char::is_whitespace) || words.is_empty();
+        let piece = text.trim();
+        if piece.is_empty() {
+            continue;
+        }
+        match words.last_mut() {
+            Some(last) if !starts_word => {
+                last.word.push_str(piece);
+                last.confidence = last.confidence.min(*prob);
+            }
+            _ => words.push(WordConfidence {
+                word: piece.to_string(),
+                confidence: prob.clamp(0.0, 1.0),
+            }),
+        }
+    }
+    words
 }
 
 /// Parakeet TDT engine that keeps the model loaded in memory.
+#[cfg(feature = "backend-parakeet")]
 pub struct ParakeetEngine {
     model: ParakeetTDT,
 }
 
-// SAFETY: ParakeetTDT uses ort::Session internally which isn't Send/Sync by default.
-// All access is guarded by the Mutex<Option<LoadedEngine>> in DiktoEngineInner,
-// ensuring only one thread accesses the ParakeetEngine at a time.
-unsafe impl Send for ParakeetEngine {}
-unsafe impl Sync for ParakeetEngine {}
-
+#[cfg(feature = "backend-parakeet")]
 impl ParakeetEngine {
     /// Load a Parakeet TDT model from a directory.
     /// The directory must contain encoder-model.onnx, decoder_joint-model.onnx, and vocab.txt.
@@ -62,15 +239,36 @@ impl ParakeetEngine {
         Ok(Self { model })
     }
 
+    /// Load a user-converted NeMo TDT export described by metadata.json.
+    pub fn load_onnx_nemo(model_dir: &Path) -> Result<Self, TranscribeError> {
+        let metadata = CustomModelMetadata::load(model_dir)
+            .map_err(|e| TranscribeError::ModelLoad(e.to_string()))?;
+        let staged = stage_nemo_model(model_dir, &metadata)
+            .map_err(|e| TranscribeError::ModelLoad(e.to_string()))?;
+        Self::load(&staged)
+    }
+
     /// Run batch inference on audio samples.
-    /// Returns the transcribed text.
-    pub fn transcribe(&mut self, samples: &[f32]) -> Result<String, TranscribeError> {
+    /// Returns the transcribed text with sentence segments timed from the
+    /// TDT token durations.
+    pub fn transcribe(&mut self, samples: &[f32]) -> Result<Transcription, TranscribeError> {
         let result = self
             .model
             .transcribe_samples(samples.to_vec(), 16000, 1, None)
             .map_err(|e| TranscribeError::Inference(e.to_string()))?;
 
-        Ok(result.text)
+        let tokens: Vec<(String, f32, f32)> = result
+            .tokens
+            .iter()
+            .map(|t| (t.text.clone(), t.start, t.end))
+            .collect();
+
+        Ok(Transcription {
+            text: result.text,
+            language: None,
+            segments: segments_from_timed_tokens(&tokens),
+            alternatives: Vec::new(),
+        })
     }
 }
 
@@ -78,23 +276,26 @@ impl ParakeetEngine {
 // Whisper engine (whisper.cpp via whisper-rs)
 // ---------------------------------------------------------------------------
 
+/// True for files whisper.cpp can load: `ggml-*.bin` or any `.gguf`.
+pub fn is_whisper_model_file(name: &str) -> bool {
+    (name.starts_with("ggml-") && name.ends_with(".bin")) || name.ends_with(".gguf")
+}
+
 /// Whisper engine that keeps the model loaded in memory.
+#[cfg(feature = "backend-whisper")]
 pub struct WhisperEngine {
     ctx: WhisperContext,
 }
 
-// SAFETY: WhisperContext wraps a raw C pointer to the whisper.cpp context.
-// All access is guarded by the Mutex<Option<LoadedEngine>> in DiktoEngineInner,
-// ensuring only one thread accesses the WhisperEngine at a time.
-unsafe impl Send for WhisperEngine {}
-unsafe impl Sync for WhisperEngine {}
-
+#[cfg(feature = "backend-whisper")]
 impl WhisperEngine {
-    /// Load a Whisper GGML model from a directory.
-    /// Looks for a known `.bin` filename from the model registry, falling back
-    /// to searching for any `.bin` file.
-    pub fn load(model_dir: &Path) -> Result<Self, TranscribeError> {
-        Self::load_with_filename(model_dir, None)
+    /// Load a Whisper GGML/GGUF model from a file (or a symlink to one) or
+    /// from a directory, searching it for any `ggml-*.bin` or `.gguf` file.
+    pub fn load(model_path: &Path) -> Result<Self, TranscribeError> {
+        if model_path.is_file() {
+            return Self::load_file(model_path);
+        }
+        Self::load_with_filename(model_path, None)
     }
 
     /// Load a Whisper model, optionally specifying the expected filename.
@@ -117,23 +318,30 @@ impl WhisperEngine {
                 )));
             }
         } else {
-            // Fallback: search for known ggml-*.bin filenames
-            std::fs::read_dir(model_dir)
+            // Fallback: search for ggml-*.bin or *.gguf files, preferring GGUF
+            let mut candidates: Vec<String> = std::fs::read_dir(model_dir)
                 .map_err(|e| TranscribeError::ModelLoad(e.to_string()))?
                 .filter_map(|entry| entry.ok())
-                .find(|entry| {
-                    let name = entry.file_name();
-                    let name = name.to_string_lossy();
-                    name.starts_with("ggml-") && name.ends_with(".bin")
-                })
-                .map(|entry| entry.path())
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .filter(|name| is_whisper_model_file(name))
+                .collect();
+            candidates.sort_by_key(|name| (!name.ends_with(".gguf"), name.clone()));
+            candidates
+                .first()
+                .map(|name| model_dir.join(name))
                 .ok_or_else(|| {
                     TranscribeError::ModelLoad(
-                        "No ggml-*.bin file found in model directory".to_string(),
+                        "No ggml-*.bin or .gguf file found in model directory".to_string(),
                     )
                 })?
         };
 
+        Self::load_file(&bin_path)
+    }
+
+    /// Load a specific GGML/GGUF model file.
+    fn load_file(bin_path: &Path) -> Result<Self, TranscribeError> {
+        info!("Loading Whisper model file {}", bin_path.display());
         let bin_path_str = bin_path
             .to_str()
             .ok_or_else(|| TranscribeError::ModelLoad("Invalid UTF-8 in model path".into()))?;
@@ -149,43 +357,152 @@ impl WhisperEngine {
     /// Run batch inference on audio samples.
     /// `language` should be an ISO-639-1 code (e.g. "en", "es") or "auto".
     pub fn transcribe(&self, samples: &[f32], language: &str) -> Result<String, TranscribeError> {
+        Ok(self.transcribe_detect(samples, language, None, 0)?.text)
+    }
+
+    /// Like `transcribe`, but also reports the language. With "auto", Whisper
+    /// detects the language of this buffer, so each utterance is tagged
+    /// independently. `prompt` is passed as Whisper's initial prompt to bias
+    /// vocabulary and style toward the surrounding context. A `beam_size`
+    /// above 1 decodes with beam search and adds up to `beam_size - 1`
+    /// alternatives, each from an extra sampled pass over the buffer.
+    pub fn transcribe_detect(
+        &self,
+        samples: &[f32],
+        language: &str,
+        prompt: Option<&str>,
+        beam_size: u32,
+    ) -> Result<Transcription, TranscribeError> {
         let mut state = self
             .ctx
             .create_state()
             .map_err(|e| TranscribeError::Inference(format!("create state: {e}")))?;
 
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        let strategy = if beam_size > 1 {
+            SamplingStrategy::BeamSearch {
+                beam_size: beam_size as i32,
+                patience: -1.0,
+            }
+        } else {
+            SamplingStrategy::Greedy { best_of: 1 }
+        };
+        let params = decode_params(strategy, language, prompt);
+        state
+            .full(params, samples)
+            .map_err(|e| TranscribeError::Inference(format!("whisper inference: {e}")))?;
+        let (text, segments, _) = read_segments(&state)?;
 
-        if language == "auto" {
-            params.set_language(None);
+        let language = if language == "auto" {
+            state
+                .full_lang_id_from_state()
+                .ok()
+                .and_then(whisper_rs::get_lang_str)
+                .map(str::to_string)
         } else {
-            params.set_language(Some(language));
+            Some(language.to_string())
+        };
+
+        // whisper.cpp keeps only the winning beam, so the runners-up come
+        // from sampling at rising temperatures in the detected language
+        let passes = (beam_size.saturating_sub(1) as usize).min(MAX_ALTERNATIVES);
+        let mut alternatives = Vec::with_capacity(passes);
+        for pass in 1..=passes {
+            let mut params = decode_params(
+                SamplingStrategy::Greedy { best_of: 1 },
+                language.as_deref().unwrap_or("auto"),
+                prompt,
+            );
+            params.set_temperature(0.2 * pass as f32);
+            params.set_temperature_inc(0.0);
+            state
+                .full(params, samples)
+                .map_err(|e| TranscribeError::Inference(format!("whisper inference: {e}")))?;
+            let (text, _, tokens) = read_segments(&state)?;
+            alternatives.push(Hypothesis {
+                text,
+                score: hypothesis_score(&tokens),
+            });
         }
 
-        // Disable token timestamps for speed
-        params.set_token_timestamps(false);
-        // Single-segment mode
-        params.set_single_segment(false);
-        params.set_print_special(false);
-        params.set_print_progress(false);
-        params.set_print_realtime(false);
-        params.set_print_timestamps(false);
+        Ok(Transcription {
+            text,
+            language,
+            segments,
+            alternatives,
+        })
+    }
+}
 
-        state
-            .full(params, samples)
-            .map_err(|e| TranscribeError::Inference(format!("whisper inference: {e}")))?;
+/// Whisper decoding parameters shared by every pass over a buffer.
+#[cfg(feature = "backend-whisper")]
+fn decode_params<'a, 'b>(
+    strategy: SamplingStrategy,
+    language: &'b str,
+    prompt: Option<&str>,
+) -> FullParams<'a, 'b> {
+    let mut params = FullParams::new(strategy);
 
-        let n_segments = state
-            .full_n_segments()
-            .map_err(|e| TranscribeError::Inference(format!("get segments: {e}")))?;
+    if language == "auto" {
+        params.set_language(None);
+    } else {
+        params.set_language(Some(language));
+    }
+
+    if let Some(prompt) = prompt {
+        params.set_initial_prompt(prompt);
+    }
+
+    // Disable token timestamps for speed
+    params.set_token_timestamps(false);
+    // Single-segment mode
+    params.set_single_segment(false);
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    params
+}
 
-        let mut text = String::new();
-        for i in 0..n_segments {
-            if let Ok(seg) = state.full_get_segment_text(i) {
-                text.push_str(&seg);
+/// Text, timed segments, and every token with its probability from the
+/// state's last decoding pass.
+#[cfg(feature = "backend-whisper")]
+fn read_segments(
+    state: &WhisperState,
+) -> Result<(String, Vec<TimedSegment>, Vec<(String, f32)>), TranscribeError> {
+    let n_segments = state
+        .full_n_segments()
+        .map_err(|e| TranscribeError::Inference(format!("get segments: {e}")))?;
+
+    let mut text = String::new();
+    let mut segments = Vec::new();
+    let mut all_tokens = Vec::new();
+    for i in 0..n_segments {
+        let Ok(seg_text) = state.full_get_segment_text(i) else {
+            continue;
+        };
+        text.push_str(&seg_text);
+
+        let mut tokens: Vec<(String, f32)> = Vec::new();
+        let n_tokens = state.full_n_tokens(i).unwrap_or(0);
+        for t in 0..n_tokens {
+            if let (Ok(token), Ok(prob)) = (
+                state.full_get_token_text(i, t),
+                state.full_get_token_prob(i, t),
+            ) {
+                tokens.push((token, prob));
             }
         }
-
-        Ok(text)
+        // Segment times are in centiseconds
+        let t0 = state.full_get_segment_t0(i).unwrap_or(0);
+        let t1 = state.full_get_segment_t1(i).unwrap_or(t0);
+        segments.push(TimedSegment {
+            text: seg_text.trim().to_string(),
+            start_secs: t0 as f32 / 100.0,
+            end_secs: t1 as f32 / 100.0,
+            words: group_word_confidence(&tokens),
+            no_speech_prob: state.full_get_segment_no_speech_prob(i).ok(),
+        });
+        all_tokens.extend(tokens);
     }
+    Ok((text, segments, all_tokens))
 }
@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use ort::session::Session;
+use ort::value::Tensor;
+use serde::Deserialize;
+use thiserror::Error;
+use tracing::info;
+
+use crate::config::{models_dir, TranslationConfig, TranslationEngine};
+
+#[derive(Debug, Error)]
+pub enum TranslateError {
+    #[error("Translation model not found at {0}")]
+    ModelNotFound(PathBuf),
+    #[error("Invalid translation model: {0}")]
+    InvalidModel(String),
+    #[error("Can't translate into '{0}' with this model")]
+    UnsupportedLanguage(String),
+    #[error("ONNX error: {0}")]
+    Onnx(String),
+    #[error("Translation API error: {0}")]
+    Api(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<ort::Error> for TranslateError {
+    fn from(e: ort::Error) -> Self {
+        TranslateError::Onnx(e.to_string())
+    }
+}
+
+/// Translates final segments between languages given as ISO-639-1 codes.
+pub trait Translator: Send + Sync {
+    fn translate(&self, text: &str, source: &str, target: &str) -> Result<String, TranslateError>;
+}
+
+/// Whether a segment recognized in `source` needs translating into
+/// `target`. Region suffixes are ignored ("en-US" is "en").
+pub fn needs_translation(source: &str, target: &str) -> bool {
+    let base = |code: &str| code.split(['-', '_']).next().unwrap_or("").to_lowercase();
+    !target.is_empty() && base(source) != base(target)
+}
+
+/// Directory of the local model named in `config`: an absolute path, or a
+/// name under `<models_dir>/translation/`.
+pub fn model_path(config: &TranslationConfig) -> PathBuf {
+    let model = Path::new(&config.model);
+    if model.is_absolute() {
+        model.to_path_buf()
+    } else {
+        models_dir().join("translation").join(model)
+    }
+}
+
+/// The translator last built, kept so sessions don't reload the model.
+static CACHED: Mutex<Option<(TranslationConfig, Arc<dyn Translator>)>> = Mutex::new(None);
+
+/// Translator for `config`, reusing the previous one when the settings
+/// that pick it are unchanged.
+pub fn translator_for(config: &TranslationConfig) -> Result<Arc<dyn Translator>, TranslateError> {
+    let mut cached = CACHED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((key, translator)) = cached.as_ref() {
+        if same_translator(key, config) {
+            return Ok(translator.clone());
+        }
+    }
+    let translator: Arc<dyn Translator> = match config.engine {
+        TranslationEngine::Local => Arc::new(OnnxTranslator::load(&model_path(config))?),
+        TranslationEngine::Api => Arc::new(ApiTranslator::new(&config.api_url, &config.api_key)?),
+    };
+    *cached = Some((config.clone(), translator.clone()));
+    Ok(translator)
+}
+
+fn same_translator(a: &TranslationConfig, b: &TranslationConfig) -> bool {
+    a.engine == b.engine && a.model == b.model && a.api_url == b.api_url && a.api_key == b.api_key
+}
+
+// ---------------------------------------------------------------------------
+// Local ONNX models
+// ---------------------------------------------------------------------------
+
+/// The parts of a Hugging Face config.json the decoder needs.
+#[derive(Deserialize)]
+struct ModelConfig {
+    model_type: String,
+    decoder_start_token_id: i64,
+    eos_token_id: i64,
+    pad_token_id: i64,
+}
+
+/// tokenizer.json, for exports that ship it instead of vocab.json.
+#[derive(Deserialize)]
+struct TokenizerFile {
+    model: TokenizerModel,
+}
+
+#[derive(Deserialize)]
+struct TokenizerModel {
+    vocab: HashMap<String, i64>,
+}
+
+/// Marker SentencePiece puts before each word.
+const WORD_START: char = '▁';
+
+/// Marian (opus-mt) or NLLB model exported to ONNX with Hugging Face
+/// Optimum: `encoder_model.onnx`, `decoder_model.onnx`, `config.json`, and
+/// `vocab.json` or `tokenizer.json`. Text is split into the longest pieces
+/// in the vocabulary, which matches SentencePiece on common words; rare
+/// words may split differently, which these models tolerate.
+pub struct OnnxTranslator {
+    encoder: Mutex<Session>,
+    decoder: Mutex<Session>,
+    config: ModelConfig,
+    vocab: HashMap<String, i64>,
+    pieces: HashMap<i64, String>,
+    longest_piece: usize,
+}
+
+impl OnnxTranslator {
+    pub fn load(dir: &Path) -> Result<Self, TranslateError> {
+        if !dir.join("encoder_model.onnx").exists() {
+            return Err(TranslateError::ModelNotFound(dir.to_path_buf()));
+        }
+        let invalid = |e: serde_json::Error| TranslateError::InvalidModel(e.to_string());
+        let config: ModelConfig =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("config.json"))?)
+                .map_err(invalid)?;
+        let vocab: HashMap<String, i64> = if dir.join("vocab.json").exists() {
+            serde_json::from_str(&std::fs::read_to_string(dir.join("vocab.json"))?)
+                .map_err(invalid)?
+        } else {
+            let file: TokenizerFile =
+                serde_json::from_str(&std::fs::read_to_string(dir.join("tokenizer.json"))?)
+                    .map_err(invalid)?;
+            file.model.vocab
+        };
+
+        let encoder = Session::builder()?.commit_from_file(dir.join("encoder_model.onnx"))?;
+        let decoder = Session::builder()?.commit_from_file(dir.join("decoder_model.onnx"))?;
+        info!(
+            "Loaded {} translation model from {}",
+            config.model_type,
+            dir.display()
+        );
+        Ok(Self {
+            encoder: Mutex::new(encoder),
+            decoder: Mutex::new(decoder),
+            pieces: vocab.iter().map(|(p, id)| (*id, p.clone())).collect(),
+            longest_piece: vocab.keys().map(|p| p.chars().count()).max().unwrap_or(1),
+            vocab,
+            config,
+        })
+    }
+
+    fn is_nllb(&self) -> bool {
+        self.config.model_type == "m2m_100"
+    }
+
+    /// NLLB's token for a language, e.g. "fra_Latn" for "fr".
+    fn language_token(&self, language: &str) -> Result<i64, TranslateError> {
+        nllb_language(language)
+            .and_then(|code| self.vocab.get(code).copied())
+            .ok_or_else(|| TranslateError::UnsupportedLanguage(language.to_string()))
+    }
+
+    /// Split text into vocabulary ids, longest piece first.
+    fn encode(&self, text: &str) -> Vec<i64> {
+        let unk = self.vocab.get("<unk>").copied();
+        let mut ids = Vec::new();
+        for word in text.split_whitespace() {
+            let chars: Vec<char> = std::iter::once(WORD_START).chain(word.chars()).collect();
+            let mut start = 0;
+            while start < chars.len() {
+                let longest = (start + 1..=chars.len().min(start + self.longest_piece))
+                    .rev()
+                    .find_map(|end| {
+                        let piece: String = chars[start..end].iter().collect();
+                        self.vocab.get(&piece).map(|id| (end, *id))
+                    });
+                match longest {
+                    Some((end, id)) => {
+                        ids.push(id);
+                        start = end;
+                    }
+                    None => {
+                        ids.extend(unk);
+                        start += 1;
+                    }
+                }
+            }
+        }
+        ids
+    }
+
+    /// Join pieces back into text, dropping special tokens.
+    fn decode(&self, ids: &[i64]) -> String {
+        let text: String = ids
+            .iter()
+            .filter_map(|id| self.pieces.get(id))
+            .filter(|p| !(p.starts_with('<') && p.ends_with('>')) && nllb_token(p).is_none())
+            .map(String::as_str)
+            .collect();
+        text.replace(WORD_START, " ").trim().to_string()
+    }
+
+    fn encoder_states(&self, input_ids: &[i64]) -> Result<(Vec<i64>, Vec<f32>), TranslateError> {
+        let n = input_ids.len();
+        let mut encoder = self.encoder.lock().unwrap_or_else(|e| e.into_inner());
+        let outputs = encoder.run(ort::inputs![
+            "input_ids" => Tensor::from_array(([1, n], input_ids.to_vec()))?,
+            "attention_mask" => Tensor::from_array(([1, n], vec![1i64; n]))?,
+        ])?;
+        let (shape, states) = outputs["last_hidden_state"].try_extract_tensor::<f32>()?;
+        Ok((shape.to_vec(), states.to_vec()))
+    }
+
+    /// Greedy decoding: feed the tokens so far and take the likeliest next
+    /// one until end of sentence.
+    fn generate(
+        &self,
+        input_len: usize,
+        states: (Vec<i64>, Vec<f32>),
+        mut tokens: Vec<i64>,
+    ) -> Result<Vec<i64>, TranslateError> {
+        let (state_shape, states) = states;
+        let state_shape: Vec<usize> = state_shape.iter().map(|&d| d as usize).collect();
+        let prefix = tokens.len();
+        let max_len = prefix + input_len * 2 + 10;
+        let mut decoder = self.decoder.lock().unwrap_or_else(|e| e.into_inner());
+        while tokens.len() < max_len {
+            let t = tokens.len();
+            let outputs = decoder.run(ort::inputs![
+                "input_ids" => Tensor::from_array(([1, t], tokens.clone()))?,
+                "encoder_attention_mask" => Tensor::from_array(([1, input_len], vec![1i64; input_len]))?,
+                "encoder_hidden_states" => Tensor::from_array((state_shape.clone(), states.clone()))?,
+            ])?;
+            let (shape, logits) = outputs["logits"].try_extract_tensor::<f32>()?;
+            let vocab = *shape.last().unwrap_or(&0) as usize;
+            let last = &logits[(t - 1) * vocab..t * vocab];
+            let next = last
+                .iter()
+                .enumerate()
+                .filter(|(id, _)| *id as i64 != self.config.pad_token_id)
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(id, _)| id as i64)
+                .unwrap_or(self.config.eos_token_id);
+            if next == self.config.eos_token_id {
+                break;
+            }
+            tokens.push(next);
+        }
+        Ok(tokens.split_off(prefix))
+    }
+}
+
+impl Translator for OnnxTranslator {
+    fn translate(&self, text: &str, source: &str, target: &str) -> Result<String, TranslateError> {
+        let mut input_ids = self.encode(text);
+        if input_ids.is_empty() {
+            return Ok(String::new());
+        }
+        input_ids.push(self.config.eos_token_id);
+        // NLLB names both languages with tokens; a Marian model is trained
+        // for one language pair, so they are implied
+        let mut prefix = vec![self.config.decoder_start_token_id];
+        if self.is_nllb() {
+            input_ids.insert(0, self.language_token(source)?);
+            prefix.push(self.language_token(target)?);
+        }
+        let states = self.encoder_states(&input_ids)?;
+        let output = self.generate(input_ids.len(), states, prefix)?;
+        Ok(self.decode(&output))
+    }
+}
+
+/// NLLB-200 codes for the languages Dikto's models transcribe.
+const NLLB_LANGUAGES: &[(&str, &str)] = &[
+    ("ar", "arb_Arab"),
+    ("bg", "bul_Cyrl"),
+    ("ca", "cat_Latn"),
+    ("cs", "ces_Latn"),
+    ("da", "dan_Latn"),
+    ("de", "deu_Latn"),
+    ("el", "ell_Grek"),
+    ("en", "eng_Latn"),
+    ("es", "spa_Latn"),
+    ("et", "est_Latn"),
+    ("fi", "fin_Latn"),
+    ("fr", "fra_Latn"),
+    ("he", "heb_Hebr"),
+    ("hi", "hin_Deva"),
+    ("hr", "hrv_Latn"),
+    ("hu", "hun_Latn"),
+    ("id", "ind_Latn"),
+    ("it", "ita_Latn"),
+    ("ja", "jpn_Jpan"),
+    ("ko", "kor_Hang"),
+    ("lt", "lit_Latn"),
+    ("lv", "lvs_Latn"),
+    ("ms", "zsm_Latn"),
+    ("nl", "nld_Latn"),
+    ("no", "nob_Latn"),
+    ("pl", "pol_Latn"),
+    ("pt", "por_Latn"),
+    ("ro", "ron_Latn"),
+    ("ru", "rus_Cyrl"),
+    ("sk", "slk_Latn"),
+    ("sl", "slv_Latn"),
+    ("sv", "swe_Latn"),
+    ("ta", "tam_Taml"),
+    ("th", "tha_Thai"),
+    ("tr", "tur_Latn"),
+    ("uk", "ukr_Cyrl"),
+    ("vi", "vie_Latn"),
+    ("zh", "zho_Hans"),
+];
+
+/// NLLB code for an ISO-639-1 code.
+pub fn nllb_language(language: &str) -> Option<&'static str> {
+    let base = language.split(['-', '_']).next().unwrap_or("");
+    NLLB_LANGUAGES
+        .iter()
+        .find(|(iso, _)| iso.eq_ignore_ascii_case(base))
+        .map(|(_, nllb)| *nllb)
+}
+
+/// ISO-639-1 code for an NLLB language token.
+fn nllb_token(piece: &str) -> Option<&'static str> {
+    NLLB_LANGUAGES
+        .iter()
+        .find(|(_, nllb)| *nllb == piece)
+        .map(|(iso, _)| *iso)
+}
+
+// ---------------------------------------------------------------------------
+// HTTP API
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+struct ApiResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+}
+
+/// A LibreTranslate-compatible service: POST {q, source, target, format,
+/// api_key} to the URL, answered with {translatedText}.
+pub struct ApiTranslator {
+    url: String,
+    api_key: String,
+    client: reqwest::Client,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl ApiTranslator {
+    pub fn new(url: &str, api_key: &str) -> Result<Self, TranslateError> {
+        if url.trim().is_empty() {
+            return Err(TranslateError::Api(
+                "No translation API URL set".to_string(),
+            ));
+        }
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self {
+            url: url.trim().to_string(),
+            api_key: api_key.to_string(),
+            client: reqwest::Client::new(),
+            runtime,
+        })
+    }
+}
+
+impl Translator for ApiTranslator {
+    fn translate(&self, text: &str, source: &str, target: &str) -> Result<String, TranslateError> {
+        let mut body = serde_json::json!({
+            "q": text,
+            "source": source,
+            "target": target,
+            "format": "text",
+        });
+        if !self.api_key.is_empty() {
+            body["api_key"] = self.api_key.clone().into();
+        }
+        let request = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+            .timeout(std::time::Duration::from_secs(10))
+            .send();
+        let response = self.runtime.block_on(async {
+            let response = request.await?.error_for_status()?;
+            response.text().await
+        });
+        let response = response.map_err(|e| TranslateError::Api(e.to_string()))?;
+        let parsed: ApiResponse =
+            serde_json::from_str(&response).map_err(|e| TranslateError::Api(e.to_string()))?;
+        Ok(parsed.translated_text)
+    }
+}
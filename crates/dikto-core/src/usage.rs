@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+use crate::config::{data_dir, ConfigError};
+use crate::storage;
+
+/// Local-only usage counters for the personal "stats" screen.
+///
+/// Counters live in `~/.local/share/dikto/usage.json` and are never
+/// transmitted anywhere — there is no network code path that reads them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct UsageCounters {
+    /// Per-day totals keyed by UTC date ("YYYY-MM-DD").
+    #[serde(default)]
+    pub days: BTreeMap<String, DayUsage>,
+    /// Number of sessions recorded with each model.
+    #[serde(default)]
+    pub model_sessions: BTreeMap<String, u64>,
+}
+
+/// Totals for a single day.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DayUsage {
+    pub sessions: u64,
+    pub total_duration_secs: f64,
+}
+
+/// Usage summary for FFI.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct UsageStats {
+    pub total_sessions: u64,
+    pub sessions_today: u64,
+    pub average_duration_secs: f64,
+    pub model_usage: Vec<ModelUsage>,
+    pub daily: Vec<DailyUsage>,
+}
+
+/// Session count for one model.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct ModelUsage {
+    pub model_name: String,
+    pub sessions: u64,
+}
+
+/// Totals for one day, oldest first in `UsageStats::daily`.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct DailyUsage {
+    pub date: String,
+    pub sessions: u64,
+    pub total_duration_secs: f64,
+}
+
+impl UsageCounters {
+    /// Load counters from a file. Missing or unreadable files yield empty counters.
+    pub fn load_from(path: &Path) -> Self {
+        let Ok(contents) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Failed to parse usage counters at {}: {e}", path.display());
+            Self::default()
+        })
+    }
+
+    /// Save counters to a file atomically, creating the parent directory if needed.
+    pub fn save_to(&self, path: &Path) -> Result<(), std::io::Error> {
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        storage::write_atomic(path, json.as_bytes())
+    }
+
+    /// Count one session of `duration_secs` with `model_name` on `date`.
+    pub fn record(&mut self, date: &str, model_name: &str, duration_secs: f64) {
+        let day = self.days.entry(date.to_string()).or_default();
+        day.sessions += 1;
+        day.total_duration_secs += duration_secs.max(0.0);
+        *self
+            .model_sessions
+            .entry(model_name.to_string())
+            .or_default() += 1;
+    }
+
+    /// Summarize counters, treating `today` as the current date.
+    pub fn stats(&self, today: &str) -> UsageStats {
+        let total_sessions: u64 = self.days.values().map(|d| d.sessions).sum();
+        let total_duration: f64 = self.days.values().map(|d| d.total_duration_secs).sum();
+        let mut model_usage: Vec<ModelUsage> = self
+            .model_sessions
+            .iter()
+            .map(|(name, sessions)| ModelUsage {
+                model_name: name.clone(),
+                sessions: *sessions,
+            })
+            .collect();
+        model_usage.sort_by(|a, b| b.sessions.cmp(&a.sessions));
+
+        UsageStats {
+            total_sessions,
+            sessions_today: self.days.get(today).map(|d| d.sessions).unwrap_or(0),
+            average_duration_secs: if total_sessions > 0 {
+                total_duration / total_sessions as f64
+            } else {
+                0.0
+            },
+            model_usage,
+            daily: self
+                .days
+                .iter()
+                .map(|(date, d)| DailyUsage {
+                    date: date.clone(),
+                    sessions: d.sessions,
+                    total_duration_secs: d.total_duration_secs,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Returns the usage counters path: ~/.local/share/dikto/usage.json
+pub fn usage_path() -> Result<PathBuf, ConfigError> {
+    Ok(data_dir()?.join("usage.json"))
+}
+
+/// Record a finished session in the default counters file.
+pub fn record_session(model_name: &str, duration_secs: f64) {
+    let path = match usage_path() {
+        Ok(p) => p,
+        Err(e) => {
+            warn!("Cannot record usage: {e}");
+            return;
+        }
+    };
+    let saved = storage::FileLock::acquire(&path).and_then(|_lock| {
+        let mut counters = UsageCounters::load_from(&path);
+        counters.record(&utc_date(unix_now()), model_name, duration_secs);
+        counters.save_to(&path)
+    });
+    if let Err(e) = saved {
+        warn!("Failed to save usage counters: {e}");
+    }
+}
+
+/// Current time as seconds since the Unix epoch.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Format a Unix timestamp as a UTC calendar date ("YYYY-MM-DD").
+pub fn utc_date(unix_secs: u64) -> String {
+    // Civil-from-days (Howard Hinnant), valid for all dates after 1970
+    let days = (unix_secs / 86_400) as i64;
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
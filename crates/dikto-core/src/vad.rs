@@ -1,3 +1,4 @@
+use std::ops::Range;
 use thiserror::Error;
 use tracing::debug;
 use voice_activity_detector::VoiceActivityDetector;
@@ -63,6 +64,14 @@ pub struct VadProcessor {
     pending_speech_frames: u32,
     /// Samples per chunk (512 for 16kHz = 32ms).
     chunk_size: usize,
+    /// Speech probability of the most recent chunk.
+    last_probability: f32,
+    /// Chunks seen since creation or reset.
+    chunks_processed: u64,
+    /// Value of `chunks_processed` just after the last speech chunk.
+    last_speech_chunk: u64,
+    /// Index of the chunk that started the current or last speech run.
+    speech_start_chunk: u64,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -91,15 +100,64 @@ impl VadProcessor {
             speech_frames: 0,
             pending_speech_frames: 0,
             chunk_size,
+            last_probability: 0.0,
+            chunks_processed: 0,
+            last_speech_chunk: 0,
+            speech_start_chunk: 0,
         })
     }
 
     /// Process a chunk of audio samples and return a VAD event.
     /// Input should be 512 samples at 16kHz (32ms).
     pub fn process_chunk(&mut self, samples: &[f32]) -> Result<VadEvent, VadError> {
-        let probability = self.detector.predict(samples.iter().copied());
+        let probability = self.predict(samples);
+        self.last_probability = probability;
+        Ok(self.process_probability(probability))
+    }
+
+    /// Run VAD over a stream of 16kHz mono samples, yielding each chunk's
+    /// event with its sample range in the stream. A trailing partial chunk
+    /// is dropped.
+    pub fn process_stream<'a, I>(
+        &'a mut self,
+        samples: I,
+    ) -> impl Iterator<Item = (VadEvent, Range<usize>)> + 'a
+    where
+        I: IntoIterator<Item = f32>,
+        I::IntoIter: 'a,
+    {
+        let chunk_size = self.chunk_size;
+        let mut samples = samples.into_iter();
+        let mut chunk = Vec::with_capacity(chunk_size);
+        let mut start = 0;
+        std::iter::from_fn(move || {
+            chunk.clear();
+            chunk.extend(samples.by_ref().take(chunk_size));
+            if chunk.len() < chunk_size {
+                return None;
+            }
+            // process_chunk only fails if the detector does
+            let event = self.process_chunk(&chunk).ok()?;
+            let range = start..start + chunk_size;
+            start += chunk_size;
+            Some((event, range))
+        })
+    }
+
+    /// Speech probability of a chunk, without changing the speech state.
+    pub fn predict(&mut self, samples: &[f32]) -> f32 {
+        self.detector.predict(samples.iter().copied())
+    }
 
+    /// Advance the speech state with a chunk's probability. `process_chunk`
+    /// is `predict` followed by this; replay tools call it directly to try
+    /// other thresholds on recorded probabilities.
+    pub fn process_probability(&mut self, probability: f32) -> VadEvent {
         let is_speech = probability > self.config.speech_threshold;
+        self.chunks_processed += 1;
+        if is_speech {
+            self.last_speech_chunk = self.chunks_processed;
+        }
         let frame_duration_ms =
             (self.chunk_size as f32 / self.config.sample_rate as f32 * 1000.0) as u32;
 
@@ -108,6 +166,7 @@ impl VadProcessor {
             (VadState::Idle, true) => {
                 self.state = VadState::Pending;
                 self.pending_speech_frames = 1;
+                self.speech_start_chunk = self.chunks_processed - 1;
                 debug!("VAD: possible speech (prob={probability:.3}), pending confirmation");
                 VadEvent::Silence
             }
@@ -172,7 +231,7 @@ impl VadProcessor {
             }
         };
 
-        Ok(event)
+        event
     }
 
     /// Reset the VAD state.
@@ -181,6 +240,27 @@ impl VadProcessor {
         self.silence_frames = 0;
         self.speech_frames = 0;
         self.pending_speech_frames = 0;
+        self.chunks_processed = 0;
+        self.last_speech_chunk = 0;
+        self.speech_start_chunk = 0;
+    }
+
+    /// Samples seen since creation or reset.
+    pub fn samples_processed(&self) -> u64 {
+        self.chunks_processed * self.chunk_size as u64
+    }
+
+    /// Sample index just past the last chunk above the speech threshold.
+    /// When SpeechEnd fires, everything after it is the silence that
+    /// triggered the end and can be trimmed before transcription.
+    pub fn last_speech_end_sample(&self) -> u64 {
+        self.last_speech_chunk * self.chunk_size as u64
+    }
+
+    /// Sample index where the current or last speech run began, before the
+    /// activation frames that confirmed it.
+    pub fn speech_start_sample(&self) -> u64 {
+        self.speech_start_chunk * self.chunk_size as u64
     }
 
     /// Get the chunk size expected by this processor.
@@ -188,6 +268,11 @@ impl VadProcessor {
         self.chunk_size
     }
 
+    /// Speech probability of the last chunk passed to `process_chunk`.
+    pub fn last_probability(&self) -> f32 {
+        self.last_probability
+    }
+
     /// Get the current VAD state.
     pub fn state(&self) -> VadState {
         self.state
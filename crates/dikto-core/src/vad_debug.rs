@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+use crate::config::{data_dir, ConfigError};
+use crate::vad::{VadConfig, VadError, VadEvent, VadProcessor, VadState};
+use crate::wav::{self, TARGET_SAMPLE_RATE};
+
+/// One VAD decision, stored as a line of `<session>.jsonl` next to `<session>.wav`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VadTraceEntry {
+    /// Start of the chunk, in ms from the start of the recording.
+    pub offset_ms: u64,
+    pub probability: f32,
+    /// VadEvent returned for the chunk, e.g. "SpeechStart".
+    pub event: String,
+    /// VadState after the chunk, e.g. "Speaking".
+    pub state: String,
+}
+
+/// Returns the VAD debug directory: ~/.local/share/dikto/vad-debug/
+pub fn debug_dir() -> Result<PathBuf, ConfigError> {
+    Ok(data_dir()?.join("vad-debug"))
+}
+
+/// Records a session's audio and VAD decisions while `vad_debug` is on. The
+/// JSONL is written as chunks arrive, the WAV when the trace is dropped.
+pub struct VadTrace {
+    wav_path: PathBuf,
+    log: BufWriter<std::fs::File>,
+    samples: Vec<f32>,
+}
+
+impl VadTrace {
+    /// Start a trace in `dir`, named after the current time.
+    pub fn create(dir: &Path) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        let stem = format!("session-{millis}");
+        let log = std::fs::File::create(dir.join(format!("{stem}.jsonl")))?;
+        Ok(Self {
+            wav_path: dir.join(format!("{stem}.wav")),
+            log: BufWriter::new(log),
+            samples: Vec::new(),
+        })
+    }
+
+    /// Where the audio is written when the trace is dropped.
+    pub fn wav_path(&self) -> &Path {
+        &self.wav_path
+    }
+
+    /// Record one chunk and the VAD's decision on it.
+    pub fn record(&mut self, chunk: &[f32], probability: f32, event: &VadEvent, state: VadState) {
+        let entry = VadTraceEntry {
+            offset_ms: self.samples.len() as u64 * 1000 / TARGET_SAMPLE_RATE as u64,
+            probability,
+            event: format!("{event:?}"),
+            state: format!("{state:?}"),
+        };
+        self.samples.extend_from_slice(chunk);
+        let written = serde_json::to_string(&entry)
+            .map_err(std::io::Error::other)
+            .and_then(|line| writeln!(self.log, "{line}"));
+        if let Err(e) = written {
+            warn!("Failed to write VAD trace: {e}");
+        }
+    }
+}
+
+impl Drop for VadTrace {
+    fn drop(&mut self) {
+        if let Err(e) = self.log.flush() {
+            warn!("Failed to flush VAD trace: {e}");
+        }
+        if let Err(e) = wav::write_wav(&self.wav_path, &self.samples, TARGET_SAMPLE_RATE) {
+            warn!("Failed to save VAD debug audio: {e}");
+        }
+    }
+}
+
+/// An utterance the pipeline would have transcribed, in ms from the start of
+/// the audio. `start_ms` is when speech was confirmed, `end_ms` when the
+/// silence timeout fired (or the end of the audio).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SpeechSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// Utterances found by replaying VAD with one threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayResult {
+    pub speech_threshold: f32,
+    pub segments: Vec<SpeechSegment>,
+}
+
+impl ReplayResult {
+    /// Total length of the detected utterances, in ms.
+    pub fn speech_ms(&self) -> u64 {
+        self.segments.iter().map(|s| s.end_ms - s.start_ms).sum()
+    }
+}
+
+/// Speech probability of each full chunk of 16kHz mono `samples`.
+pub fn chunk_probabilities(samples: &[f32]) -> Result<Vec<f32>, VadError> {
+    let mut vad = VadProcessor::new(VadConfig::default())?;
+    let chunk_size = vad.chunk_size();
+    Ok(samples
+        .chunks_exact(chunk_size)
+        .map(|chunk| vad.predict(chunk))
+        .collect())
+}
+
+/// Run the speech state machine over per-chunk probabilities with `config`.
+pub fn replay_probabilities(
+    probabilities: &[f32],
+    config: VadConfig,
+) -> Result<Vec<SpeechSegment>, VadError> {
+    let sample_rate = config.sample_rate as u64;
+    let mut vad = VadProcessor::new(config)?;
+    let chunk_ms = vad.chunk_size() as u64 * 1000 / sample_rate;
+
+    let mut segments = Vec::new();
+    let mut start = None;
+    for (i, &probability) in probabilities.iter().enumerate() {
+        let at_ms = i as u64 * chunk_ms;
+        match vad.process_probability(probability) {
+            VadEvent::SpeechStart => start = Some(at_ms),
+            VadEvent::SpeechEnd => {
+                if let Some(start_ms) = start.take() {
+                    segments.push(SpeechSegment {
+                        start_ms,
+                        end_ms: at_ms + chunk_ms,
+                    });
+                }
+            }
+            // Speech shorter than min_speech_duration_ms drops back to Idle
+            VadEvent::Silence if vad.state() == VadState::Idle => start = None,
+            VadEvent::Silence | VadEvent::SpeechContinue => {}
+        }
+    }
+    if let Some(start_ms) = start {
+        segments.push(SpeechSegment {
+            start_ms,
+            end_ms: probabilities.len() as u64 * chunk_ms,
+        });
+    }
+    Ok(segments)
+}
+
+/// Where the speech is, for cutting recordings up: like
+/// `replay_probabilities`, but each region starts at the first speech chunk
+/// (before the activation frames that confirmed it) and ends just past the
+/// last one, without the silence that closed it.
+pub fn speech_regions(
+    probabilities: &[f32],
+    config: VadConfig,
+) -> Result<Vec<SpeechSegment>, VadError> {
+    let sample_rate = config.sample_rate as u64;
+    let mut vad = VadProcessor::new(config)?;
+    let region = |vad: &VadProcessor| SpeechSegment {
+        start_ms: vad.speech_start_sample() * 1000 / sample_rate,
+        end_ms: vad.last_speech_end_sample() * 1000 / sample_rate,
+    };
+
+    let mut regions = Vec::new();
+    let mut speaking = false;
+    for &probability in probabilities {
+        match vad.process_probability(probability) {
+            VadEvent::SpeechStart => speaking = true,
+            VadEvent::SpeechEnd => {
+                regions.push(region(&vad));
+                speaking = false;
+            }
+            VadEvent::Silence if vad.state() == VadState::Idle => speaking = false,
+            VadEvent::Silence | VadEvent::SpeechContinue => {}
+        }
+    }
+    if speaking {
+        regions.push(region(&vad));
+    }
+    Ok(regions)
+}
+
+/// Replay VAD over a recording once per threshold. Probabilities don't
+/// depend on the threshold, so the model runs only once.
+pub fn replay(
+    samples: &[f32],
+    thresholds: &[f32],
+    silence_duration_ms: u32,
+) -> Result<Vec<ReplayResult>, VadError> {
+    let probabilities = chunk_probabilities(samples)?;
+    thresholds
+        .iter()
+        .map(|&speech_threshold| {
+            let config = VadConfig {
+                speech_threshold,
+                silence_duration_ms,
+                ..VadConfig::default()
+            };
+            Ok(ReplayResult {
+                speech_threshold,
+                segments: replay_probabilities(&probabilities, config)?,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,164 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use tracing::{error, warn};
+
+/// How often the watchdog checks on a session.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Which session, if any, is recording. Only the session that set it can
+/// clear it, so a finished session (or its watchdog) can't clear the flag
+/// of the next one.
+#[derive(Debug, Default)]
+pub struct RecordingFlag {
+    /// Id of the owning session, or 0 when idle.
+    owner: AtomicU64,
+}
+
+impl RecordingFlag {
+    /// Whether any session is recording.
+    pub fn is_set(&self) -> bool {
+        self.owner.load(Ordering::Acquire) != 0
+    }
+
+    /// Whether `session` is the one recording.
+    pub fn is_owned_by(&self, session: u64) -> bool {
+        session != 0 && self.owner.load(Ordering::Acquire) == session
+    }
+
+    /// Set the flag for `session` (nonzero). False if a session already
+    /// holds it.
+    pub fn claim(&self, session: u64) -> bool {
+        session != 0
+            && self
+                .owner
+                .compare_exchange(0, session, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+    }
+
+    /// Clear the flag if `session` holds it. False if it didn't.
+    pub fn release(&self, session: u64) -> bool {
+        session != 0
+            && self
+                .owner
+                .compare_exchange(session, 0, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+    }
+}
+
+/// Heartbeat of one session's pipeline thread. The pipeline beats on every
+/// loop iteration and around long steps such as model loads; the watchdog
+/// gives up on the session when the beats stop.
+pub struct Liveness {
+    started: Instant,
+    /// Milliseconds after `started` of the last beat.
+    last_beat_ms: AtomicU64,
+    abandoned: AtomicBool,
+}
+
+impl Liveness {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            started: Instant::now(),
+            last_beat_ms: AtomicU64::new(0),
+            abandoned: AtomicBool::new(false),
+        })
+    }
+
+    /// Record that the pipeline is still making progress.
+    pub fn beat(&self) {
+        let now = self.started.elapsed().as_millis() as u64;
+        self.last_beat_ms.store(now, Ordering::Relaxed);
+    }
+
+    /// Time since the last beat (or since the session started).
+    pub fn since_last_beat(&self) -> Duration {
+        let last = Duration::from_millis(self.last_beat_ms.load(Ordering::Relaxed));
+        self.started.elapsed().saturating_sub(last)
+    }
+
+    /// Mark the session as given up on. Returns false if it already was.
+    pub fn abandon(&self) -> bool {
+        !self.abandoned.swap(true, Ordering::AcqRel)
+    }
+
+    /// True once the watchdog gave up on the session. Its pipeline must
+    /// then leave the recording flag and the host's callback alone, since a
+    /// new session may own them.
+    pub fn is_abandoned(&self) -> bool {
+        self.abandoned.load(Ordering::Acquire)
+    }
+}
+
+/// Why the watchdog gave up on a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stall {
+    /// The pipeline thread ended without clearing the recording flag.
+    ThreadExited,
+    /// No heartbeat within the timeout.
+    Unresponsive,
+}
+
+impl std::fmt::Display for Stall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stall::ThreadExited => write!(f, "Recording thread exited unexpectedly"),
+            Stall::Unresponsive => write!(f, "Recording stopped responding"),
+        }
+    }
+}
+
+/// Decide whether a session that still holds the recording flag is stuck.
+pub fn check(liveness: &Liveness, thread_finished: bool, timeout: Duration) -> Option<Stall> {
+    if thread_finished {
+        Some(Stall::ThreadExited)
+    } else if !timeout.is_zero() && liveness.since_last_beat() > timeout {
+        Some(Stall::Unresponsive)
+    } else {
+        None
+    }
+}
+
+/// Watch session `session`'s pipeline thread until it releases
+/// `recording`. If the thread exits without releasing it, or stops beating
+/// for `timeout` (zero disables that check), the session is abandoned:
+/// `stop_flag` is set so the thread winds down if it ever wakes, the flag
+/// is released so a new session can start, and `on_stall` is told why.
+/// Once another session holds the flag the watchdog leaves it alone.
+pub fn spawn(
+    liveness: Arc<Liveness>,
+    pipeline: JoinHandle<()>,
+    recording: Arc<RecordingFlag>,
+    session: u64,
+    stop_flag: Arc<AtomicBool>,
+    timeout: Duration,
+    on_stall: impl FnOnce(Stall) + Send + 'static,
+) {
+    let spawned = std::thread::Builder::new()
+        .name("dikto-watchdog".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(CHECK_INTERVAL);
+            if !recording.is_owned_by(session) || liveness.is_abandoned() {
+                return;
+            }
+            let Some(stall) = check(&liveness, pipeline.is_finished(), timeout) else {
+                continue;
+            };
+            // The thread may have finished normally between the two loads
+            if !recording.is_owned_by(session) || !liveness.abandon() {
+                return;
+            }
+            error!("{stall}; clearing the recording state");
+            stop_flag.store(true, Ordering::Release);
+            if !recording.release(session) {
+                return;
+            }
+            on_stall(stall);
+            return;
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to start session watchdog: {e}");
+    }
+}
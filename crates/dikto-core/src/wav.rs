@@ -0,0 +1,240 @@
+use std::path::Path;
+use thiserror::Error;
+
+use crate::storage;
+
+/// Sample rate the recognizers expect.
+pub const TARGET_SAMPLE_RATE: u32 = 16000;
+
+#[derive(Debug, Error)]
+pub enum WavError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not a WAV file: {0}")]
+    Invalid(String),
+    #[error("Unsupported WAV format: {0}")]
+    Unsupported(String),
+}
+
+/// Sample encoding declared in the fmt chunk.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    Int,
+    Float,
+}
+
+/// Layout of the samples in the data chunk.
+#[derive(Debug, Clone, Copy)]
+struct Format {
+    encoding: Encoding,
+    channels: u16,
+    sample_rate: u32,
+    bits_per_sample: u16,
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+fn parse_format(chunk: &[u8]) -> Result<Format, WavError> {
+    if chunk.len() < 16 {
+        return Err(WavError::Invalid("fmt chunk too short".into()));
+    }
+    let mut tag = u16_at(chunk, 0);
+    // WAVE_FORMAT_EXTENSIBLE keeps the real tag at the start of the sub-format GUID
+    if tag == 0xFFFE && chunk.len() >= 26 {
+        tag = u16_at(chunk, 24);
+    }
+    let encoding = match tag {
+        1 => Encoding::Int,
+        3 => Encoding::Float,
+        other => return Err(WavError::Unsupported(format!("format tag {other}"))),
+    };
+    let format = Format {
+        encoding,
+        channels: u16_at(chunk, 2),
+        sample_rate: u32_at(chunk, 4),
+        bits_per_sample: u16_at(chunk, 14),
+    };
+    match (format.encoding, format.bits_per_sample) {
+        (Encoding::Int, 16 | 24 | 32) | (Encoding::Float, 32) => {}
+        (_, bits) => {
+            return Err(WavError::Unsupported(format!(
+                "{bits}-bit {:?} samples",
+                format.encoding
+            )))
+        }
+    }
+    if format.channels == 0 || format.sample_rate == 0 {
+        return Err(WavError::Invalid("zero channels or sample rate".into()));
+    }
+    Ok(format)
+}
+
+/// Decode one sample to [-1.0, 1.0].
+fn decode_sample(bytes: &[u8], format: Format) -> f32 {
+    match (format.encoding, format.bits_per_sample) {
+        (Encoding::Float, _) => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        (Encoding::Int, 16) => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+        (Encoding::Int, 24) => {
+            // Sign-extend by placing the 3 bytes in the top of an i32
+            i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) as f32 / 2_147_483_648.0
+        }
+        _ => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32 / 2_147_483_648.0,
+    }
+}
+
+/// Linear-interpolation resampling, as used for live capture.
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let ratio = from_rate as f64 / to_rate as f64;
+    let out_len = (samples.len() as f64 / ratio).floor() as usize;
+    (0..out_len)
+        .map(|i| {
+            let pos = i as f64 * ratio;
+            let idx = pos as usize;
+            let frac = (pos - idx as f64) as f32;
+            let a = samples[idx];
+            let b = samples.get(idx + 1).copied().unwrap_or(a);
+            a + (b - a) * frac
+        })
+        .collect()
+}
+
+/// Decode WAV bytes into 16kHz mono samples.
+pub fn decode_wav(bytes: &[u8]) -> Result<Vec<f32>, WavError> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(WavError::Invalid("missing RIFF/WAVE header".into()));
+    }
+
+    let mut format = None;
+    let mut data = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let len = u32_at(bytes, pos + 4) as usize;
+        let body_start = pos + 8;
+        // Recorders that crash mid-write leave a data length past the end
+        let body_end = body_start.saturating_add(len).min(bytes.len());
+        let body = &bytes[body_start..body_end];
+        match id {
+            b"fmt " => format = Some(parse_format(body)?),
+            b"data" => data = Some(body),
+            _ => {}
+        }
+        // Chunks are padded to an even length
+        pos = body_end + (len & 1);
+    }
+
+    let format = format.ok_or_else(|| WavError::Invalid("missing fmt chunk".into()))?;
+    let data = data.ok_or_else(|| WavError::Invalid("missing data chunk".into()))?;
+
+    let sample_bytes = format.bits_per_sample as usize / 8;
+    let frame_bytes = sample_bytes * format.channels as usize;
+    let mono: Vec<f32> = data
+        .chunks_exact(frame_bytes)
+        .map(|frame| {
+            let sum: f32 = frame
+                .chunks_exact(sample_bytes)
+                .map(|s| decode_sample(s, format))
+                .sum();
+            sum / format.channels as f32
+        })
+        .collect();
+
+    Ok(resample(&mono, format.sample_rate, TARGET_SAMPLE_RATE))
+}
+
+/// Samples per fingerprint frame: 100 ms at 16kHz.
+const FINGERPRINT_FRAME: usize = TARGET_SAMPLE_RATE as usize / 10;
+
+/// Frame energy below which two frames count as equally silent.
+const FINGERPRINT_SILENCE: f32 = 1e-6;
+
+/// Cheap fingerprint of a recording, for spotting a file that was already
+/// transcribed. It hashes whether each 100 ms frame is louder than the one
+/// before, so the same audio re-exported at another bit depth, sample rate,
+/// or volume gets the same fingerprint, while a different take doesn't.
+pub fn fingerprint(samples: &[f32]) -> String {
+    // FNV-1a, seeded with the frame count so clips of different lengths differ
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut mix = |byte: u8| {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    };
+    let frames = samples.len() / FINGERPRINT_FRAME;
+    for byte in (frames as u64).to_le_bytes() {
+        mix(byte);
+    }
+
+    let mut previous = 0.0f32;
+    let mut bits = 0u8;
+    for (i, frame) in samples.chunks_exact(FINGERPRINT_FRAME).enumerate() {
+        let energy = frame.iter().map(|s| s * s).sum::<f32>() / FINGERPRINT_FRAME as f32;
+        let louder = energy > FINGERPRINT_SILENCE && energy > previous;
+        bits = (bits << 1) | louder as u8;
+        if i % 8 == 7 {
+            mix(bits);
+            bits = 0;
+        }
+        previous = energy;
+    }
+    mix(bits);
+    format!("{hash:016x}")
+}
+
+/// Read a WAV file into 16kHz mono samples.
+pub fn read_wav(path: &Path) -> Result<Vec<f32>, WavError> {
+    decode_wav(&std::fs::read(path)?)
+}
+
+/// Encode mono samples as a 16-bit PCM WAV file.
+pub fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let mut out = Vec::with_capacity(44 + samples.len() * 2);
+    out.extend_from_slice(&wav_header(samples.len(), sample_rate));
+    out.extend_from_slice(&encode_pcm16(samples));
+    out
+}
+
+/// Header of a 16-bit PCM mono WAV file holding `samples` samples, for
+/// writers that stream the data after it.
+pub fn wav_header(samples: usize, sample_rate: u32) -> Vec<u8> {
+    let data_len = (samples * 2) as u32;
+    let mut out = Vec::with_capacity(44);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    out.extend_from_slice(&1u16.to_le_bytes()); // mono
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    out.extend_from_slice(&2u16.to_le_bytes());
+    out.extend_from_slice(&16u16.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    out
+}
+
+/// Samples as the little-endian 16-bit PCM of a WAV data chunk.
+pub fn encode_pcm16(samples: &[f32]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(samples.len() * 2);
+    for s in samples {
+        let v = (s.clamp(-1.0, 1.0) * 32767.0) as i16;
+        out.extend_from_slice(&v.to_le_bytes());
+    }
+    out
+}
+
+/// Write mono samples to a 16-bit PCM WAV file.
+pub fn write_wav(path: &Path, samples: &[f32], sample_rate: u32) -> Result<(), WavError> {
+    storage::write_atomic(path, &encode_wav(samples, sample_rate))?;
+    Ok(())
+}
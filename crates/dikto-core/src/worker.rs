@@ -0,0 +1,238 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+
+use tracing::{debug, info, warn};
+
+use crate::engine::{AsrEngine, LoadedEngine};
+use crate::models::ModelBackend;
+use crate::transcribe::{TranscribeError, Transcription};
+
+/// A command sent to the inference actor.
+enum InferenceJob {
+    /// Load a model into RAM, replacing any currently loaded one.
+    Load {
+        model_name: String,
+        backend: ModelBackend,
+        model_dir: PathBuf,
+        reply: Sender<Result<(), TranscribeError>>,
+    },
+    /// Drop the loaded model. Replies whether a model was loaded.
+    Unload { reply: Sender<bool> },
+    /// Report the name of the loaded model, if any.
+    LoadedModel { reply: Sender<Option<String>> },
+    /// Run batch inference on a buffer of 16kHz mono samples.
+    Transcribe {
+        samples: Vec<f32>,
+        language: String,
+        /// Whisper initial prompt (ignored by Parakeet).
+        prompt: Option<String>,
+        /// Whisper beam width; 0 or 1 decodes greedily (ignored by Parakeet).
+        beam_size: u32,
+        reply: Sender<Result<Transcription, TranscribeError>>,
+    },
+    /// Stop the worker loop.
+    Shutdown,
+}
+
+/// Persistent inference thread that exclusively owns the loaded ASR engine.
+///
+/// The engine is created, used, and dropped on this one thread; every other
+/// thread talks to it by sending commands over a channel and blocking on the
+/// reply. Because the backend never crosses a thread boundary, ParakeetEngine,
+/// WhisperEngine, and AsrEngine need no `Send`/`Sync` impls of their own.
+pub struct InferenceWorker {
+    jobs: Sender<InferenceJob>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl InferenceWorker {
+    /// Spawn the worker thread with no model loaded.
+    pub fn spawn() -> Self {
+        let (jobs, rx) = mpsc::channel();
+        let thread = std::thread::Builder::new()
+            .name("dikto-inference".to_string())
+            .spawn(move || worker_loop(rx))
+            .map_err(|e| warn!("Failed to spawn inference worker: {e}"))
+            .ok();
+
+        Self { jobs, thread }
+    }
+
+    /// Load a model on the worker thread, replacing the current one.
+    pub fn load(
+        &self,
+        model_name: &str,
+        backend: ModelBackend,
+        model_dir: &Path,
+    ) -> Result<(), TranscribeError> {
+        let (reply, result) = mpsc::channel();
+        self.submit(InferenceJob::Load {
+            model_name: model_name.to_string(),
+            backend,
+            model_dir: model_dir.to_path_buf(),
+            reply,
+        })?;
+        result.recv().map_err(|_| dropped())?
+    }
+
+    /// Unload the current model. Returns true if a model was loaded.
+    pub fn unload(&self) -> bool {
+        let (reply, result) = mpsc::channel();
+        if self.submit(InferenceJob::Unload { reply }).is_err() {
+            return false;
+        }
+        result.recv().unwrap_or(false)
+    }
+
+    /// Name of the model currently loaded in RAM, if any.
+    pub fn loaded_model(&self) -> Option<String> {
+        let (reply, result) = mpsc::channel();
+        self.submit(InferenceJob::LoadedModel { reply }).ok()?;
+        result.recv().ok().flatten()
+    }
+
+    /// Submit a transcription job and wait for its result.
+    pub fn transcribe(
+        &self,
+        samples: Vec<f32>,
+        language: &str,
+    ) -> Result<Transcription, TranscribeError> {
+        self.transcribe_with_prompt(samples, language, None)
+    }
+
+    /// Like `transcribe`, with an initial prompt for vocabulary biasing.
+    pub fn transcribe_with_prompt(
+        &self,
+        samples: Vec<f32>,
+        language: &str,
+        prompt: Option<String>,
+    ) -> Result<Transcription, TranscribeError> {
+        self.transcribe_with_beam(samples, language, prompt, 0)
+    }
+
+    /// Like `transcribe_with_prompt`, decoding with beam search of the given
+    /// width so Whisper also reports alternative readings.
+    pub fn transcribe_with_beam(
+        &self,
+        samples: Vec<f32>,
+        language: &str,
+        prompt: Option<String>,
+        beam_size: u32,
+    ) -> Result<Transcription, TranscribeError> {
+        let (reply, result) = mpsc::channel();
+        self.submit(InferenceJob::Transcribe {
+            samples,
+            language: language.to_string(),
+            prompt,
+            beam_size,
+            reply,
+        })?;
+        result.recv().map_err(|_| dropped())?
+    }
+
+    fn submit(&self, job: InferenceJob) -> Result<(), TranscribeError> {
+        self.jobs
+            .send(job)
+            .map_err(|_| TranscribeError::Inference("Inference worker is not running".into()))
+    }
+}
+
+impl Drop for InferenceWorker {
+    fn drop(&mut self) {
+        let _ = self.jobs.send(InferenceJob::Shutdown);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn dropped() -> TranscribeError {
+    TranscribeError::Inference("Inference worker dropped the job".into())
+}
+
+/// Main loop of the inference thread: process jobs until shutdown.
+fn worker_loop(rx: Receiver<InferenceJob>) {
+    debug!("Inference worker started");
+    let mut loaded: Option<LoadedEngine> = None;
+
+    while let Ok(job) = rx.recv() {
+        if matches!(job, InferenceJob::Shutdown) {
+            break;
+        }
+        // A panicking backend drops the job's reply sender, which the caller
+        // sees as an error; the worker itself keeps serving later jobs.
+        let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            handle_job(&mut loaded, job)
+        }));
+        if outcome.is_err() {
+            warn!("Inference worker recovered from a panic; unloading model");
+            loaded = None;
+        }
+    }
+    debug!("Inference worker stopped");
+}
+
+fn handle_job(loaded: &mut Option<LoadedEngine>, job: InferenceJob) {
+    match job {
+        InferenceJob::Load {
+            model_name,
+            backend,
+            model_dir,
+            reply,
+        } => {
+            // Free the old model before loading the new one to cap peak RAM
+            *loaded = None;
+            let result = AsrEngine::load(backend, &model_dir).map(|engine| {
+                info!("Model '{}' loaded on inference worker", model_name);
+                *loaded = Some(LoadedEngine { model_name, engine });
+            });
+            let _ = reply.send(result);
+        }
+        InferenceJob::Unload { reply } => {
+            let _ = reply.send(loaded.take().is_some());
+        }
+        InferenceJob::LoadedModel { reply } => {
+            let _ = reply.send(loaded.as_ref().map(|l| l.model_name.clone()));
+        }
+        InferenceJob::Transcribe {
+            samples,
+            language,
+            prompt,
+            beam_size,
+            reply,
+        } => {
+            let _ = reply.send(run_inference(
+                loaded,
+                &samples,
+                &language,
+                prompt.as_deref(),
+                beam_size,
+            ));
+        }
+        InferenceJob::Shutdown => {}
+    }
+}
+
+fn run_inference(
+    loaded: &mut Option<LoadedEngine>,
+    samples: &[f32],
+    language: &str,
+    prompt: Option<&str>,
+    beam_size: u32,
+) -> Result<Transcription, TranscribeError> {
+    let loaded = loaded.as_mut().ok_or(TranscribeError::NotLoaded)?;
+
+    let start = std::time::Instant::now();
+    let transcription = match &mut loaded.engine {
+        #[cfg(feature = "backend-parakeet")]
+        AsrEngine::Parakeet(e) => e.transcribe(samples)?,
+        #[cfg(feature = "backend-whisper")]
+        AsrEngine::Whisper(e) => e.transcribe_detect(samples, language, prompt, beam_size)?,
+    };
+    debug!(
+        "worker: inference done in {:.1}s",
+        start.elapsed().as_secs_f32()
+    );
+    Ok(transcription)
+}
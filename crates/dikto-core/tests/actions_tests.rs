@@ -0,0 +1,64 @@
+// Tests for dikto_core::actions — action item extraction and the tasks file.
+
+use dikto_core::actions::{append_to_tasks_file, extract_action_items, tasks_path, ActionItem};
+
+fn texts(items: &[ActionItem]) -> Vec<&str> {
+    items.iter().map(|i| i.text.as_str()).collect()
+}
+
+/// Each sentence with a trigger phrase gives one task.
+#[test]
+fn extracts_tasks_after_triggers() {
+    let items = extract_action_items(
+        "Good meeting today. Remind me to send the slides to Ana. \
+         Action item: book the room for Friday! The budget looks fine.",
+    );
+    assert_eq!(
+        texts(&items),
+        vec!["Send the slides to Ana", "Book the room for Friday"]
+    );
+    assert_eq!(items[0].trigger, "remind me to");
+    assert_eq!(items[1].trigger, "action item");
+}
+
+/// Follow-ups keep their verb; triggers only match whole words.
+#[test]
+fn follow_up_and_word_boundaries() {
+    let items = extract_action_items("We should follow up with the vendor. Mastodon is fun.");
+    assert_eq!(texts(&items), vec!["Follow up with the vendor"]);
+    assert!(extract_action_items("Tokyo todos are listed").is_empty());
+}
+
+/// A trigger with nothing after it is not a task, and text without
+/// triggers gives none.
+#[test]
+fn no_tasks_without_content() {
+    assert!(extract_action_items("Todo.").is_empty());
+    assert!(extract_action_items("Nothing to do here, café is open.").is_empty());
+}
+
+/// Tasks are appended as Markdown checkboxes, keeping what was there.
+#[test]
+fn append_tasks_file() {
+    let dir = std::env::temp_dir().join("dikto_test_actions");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("tasks.md");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&path, "# Tasks\n").unwrap();
+
+    let items = extract_action_items("Don't forget to water the plants.");
+    append_to_tasks_file(&path, &items).unwrap();
+    append_to_tasks_file(&path, &[]).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "# Tasks\n- [ ] Water the plants\n"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Absolute task file paths are kept as given.
+#[test]
+fn tasks_path_absolute() {
+    let path = std::env::temp_dir().join("tasks.md");
+    assert_eq!(tasks_path(&path.to_string_lossy()).unwrap(), path);
+}
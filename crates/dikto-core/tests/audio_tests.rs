@@ -1,7 +1,13 @@
 // Tests for dikto_core::audio — AudioCaptureConfig defaults and AudioError
 // display messages. Actual audio capture requires hardware and is not tested.
 
-use dikto_core::audio::{AudioCaptureConfig, AudioError};
+use dikto_core::audio::{
+    is_microphone_open, microphone_permission, set_microphone_permission, AudioCaptureConfig,
+    AudioError, AudioInput, CaptureControl, FeedBuffer, MicrophonePermission, MuteDetector,
+    PermissionProbe, Resampler,
+};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 // ---------------------------------------------------------------------------
 // AudioCaptureConfig defaults
@@ -43,6 +49,14 @@ fn audio_error_no_input_device_display() {
     assert!(err.to_string().contains("No input device"));
 }
 
+/// AudioError::PermissionDenied says access was denied, not that there
+/// is no device.
+#[test]
+fn audio_error_permission_denied_display() {
+    let err = AudioError::PermissionDenied;
+    assert!(err.to_string().contains("access denied"));
+}
+
 /// AudioError::NoSupportedConfig should produce a human-readable message.
 #[test]
 fn audio_error_no_supported_config_display() {
@@ -75,3 +89,287 @@ fn audio_error_device_display() {
     let msg = err.to_string();
     assert!(msg.contains("unplugged"));
 }
+
+// ---------------------------------------------------------------------------
+// FeedBuffer (host-supplied audio)
+// ---------------------------------------------------------------------------
+
+/// Pushed samples should be read back in order.
+#[test]
+fn feed_buffer_push_then_read() {
+    let feed = FeedBuffer::new(16);
+    feed.push(&[0.1, 0.2, 0.3]);
+    assert_eq!(feed.available(), 3);
+
+    let mut buf = [0.0f32; 2];
+    assert_eq!(feed.read_into(&mut buf), 2);
+    assert_eq!(buf, [0.1, 0.2]);
+    assert_eq!(feed.read_into(&mut buf), 1);
+    assert_eq!(buf[0], 0.3);
+    assert_eq!(feed.read_into(&mut buf), 0);
+}
+
+/// A full buffer should drop its oldest samples.
+#[test]
+fn feed_buffer_drops_oldest_when_full() {
+    let feed = FeedBuffer::new(3);
+    feed.push(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+    let mut buf = [0.0f32; 8];
+    assert_eq!(feed.read_into(&mut buf), 3);
+    assert_eq!(&buf[..3], &[3.0, 4.0, 5.0]);
+}
+
+/// wait_for_samples should return as soon as another thread pushes.
+#[test]
+fn feed_buffer_wait_wakes_on_push() {
+    let feed = Arc::new(FeedBuffer::new(16));
+    let pusher = feed.clone();
+    let handle = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+        pusher.push(&[0.5]);
+    });
+
+    let start = Instant::now();
+    feed.wait_for_samples(Duration::from_secs(5));
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(feed.available(), 1);
+    handle.join().unwrap();
+}
+
+/// wait_for_samples should time out when nothing is pushed.
+#[test]
+fn feed_buffer_wait_times_out() {
+    let feed = FeedBuffer::new(16);
+    feed.wait_for_samples(Duration::from_millis(10));
+    assert_eq!(feed.available(), 0);
+}
+
+/// Host input should read from its feed buffer.
+#[test]
+fn audio_input_host_reads_feed() {
+    let feed = Arc::new(FeedBuffer::new(16));
+    feed.push(&[0.25, 0.75]);
+    let mut input = AudioInput::Host(feed);
+    let mut buf = [0.0f32; 4];
+    assert_eq!(input.read_into(&mut buf), 2);
+    input.stop();
+}
+
+/// Host input never reports a stream failure, and reopening it keeps the
+/// samples already fed.
+#[test]
+fn audio_input_host_survives_reopen() {
+    let feed = Arc::new(FeedBuffer::new(16));
+    feed.push(&[0.5]);
+    let mut input = AudioInput::Host(feed);
+    assert!(input.take_failure().is_none());
+    assert!(input.reopen().is_ok());
+    let mut buf = [0.0f32; 4];
+    assert_eq!(input.read_into(&mut buf), 1);
+}
+
+// ---------------------------------------------------------------------------
+// Microphone permission
+// ---------------------------------------------------------------------------
+
+/// A second of exact zeros at the start of a stream means denied access.
+#[test]
+fn probe_flags_digital_silence() {
+    let mut probe = PermissionProbe::new(16000);
+    assert!(!probe.observe(&[0.0; 8000]));
+    assert!(probe.observe(&[0.0; 8000]));
+}
+
+/// Any real sound ends the probe for good.
+#[test]
+fn probe_passes_real_audio() {
+    let mut probe = PermissionProbe::new(16000);
+    assert!(!probe.observe(&[0.0, 0.0, 0.0001]));
+    assert!(probe.is_finished());
+    assert!(!probe.observe(&[0.0; 32000]));
+    assert!(!probe.stalled(Duration::from_secs(5)));
+}
+
+/// A stream that delivers nothing for a second is also flagged.
+#[test]
+fn probe_flags_stalled_stream() {
+    let probe = PermissionProbe::new(16000);
+    assert!(!probe.stalled(Duration::from_millis(500)));
+    assert!(probe.stalled(Duration::from_secs(1)));
+}
+
+/// The host's permission is remembered, and only device input is probed.
+#[test]
+fn microphone_permission_hint() {
+    set_microphone_permission(MicrophonePermission::Denied);
+    assert_eq!(microphone_permission(), MicrophonePermission::Denied);
+    set_microphone_permission(MicrophonePermission::Unknown);
+    assert_eq!(microphone_permission(), MicrophonePermission::Unknown);
+    let input = AudioInput::Host(Arc::new(FeedBuffer::new(16)));
+    assert!(input.permission_probe().is_none());
+}
+
+/// Two seconds of exact zeros are reported once.
+#[test]
+fn mute_detector_reports_zero_input_once() {
+    let mut detector = MuteDetector::new(16000);
+    assert!(!detector.observe(&[0.0; 16000]));
+    assert!(detector.observe(&[0.0; 16000]));
+    assert!(!detector.observe(&[0.0; 16000]));
+}
+
+/// Any sound restarts the count, and a later muted stretch is reported again.
+#[test]
+fn mute_detector_resets_on_sound() {
+    let mut detector = MuteDetector::new(16000);
+    assert!(!detector.observe(&[0.0; 31000]));
+    let mut chunk = vec![0.0; 2000];
+    chunk[0] = 0.001;
+    assert!(!detector.observe(&chunk));
+    assert!(!detector.observe(&[0.0; 30000]));
+    assert!(detector.observe(&[0.0; 1000]));
+}
+
+/// Quiet but real input is never taken for a muted microphone.
+#[test]
+fn mute_detector_ignores_quiet_input() {
+    let mut detector = MuteDetector::new(16000);
+    for _ in 0..10 {
+        assert!(!detector.observe(&[1e-6; 16000]));
+    }
+}
+
+/// Halting a control with no stream open returns immediately.
+#[test]
+fn capture_control_halt_without_stream() {
+    let control = CaptureControl::default();
+    assert!(!control.is_open());
+    assert!(!control.is_halted());
+    let start = Instant::now();
+    assert!(control.halt(Duration::from_secs(5)));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(control.is_halted());
+}
+
+/// The open-microphone count drops when a stream is released normally, by
+/// halt() from another thread, and by a panic unwinding the reading thread;
+/// halt() reports a reader that won't let go. One test, since the count is
+/// process-wide.
+#[test]
+fn microphone_released_on_every_path() {
+    assert!(!is_microphone_open());
+
+    let control = Arc::new(CaptureControl::default());
+    let open = control.stream_opened();
+    assert!(is_microphone_open());
+    assert!(control.is_open());
+    drop(open);
+    assert!(!is_microphone_open());
+    assert!(!control.is_open());
+
+    // A reader thread that lets go of the stream once woken by halt()
+    let control = Arc::new(CaptureControl::default());
+    let (opened_tx, opened_rx) = std::sync::mpsc::channel();
+    let reader = {
+        let control = control.clone();
+        std::thread::spawn(move || {
+            let _open = control.stream_opened();
+            opened_tx.send(()).unwrap();
+            while !control.is_halted() {
+                std::thread::park_timeout(Duration::from_secs(10));
+            }
+        })
+    };
+    opened_rx.recv().unwrap();
+    assert!(is_microphone_open());
+    let start = Instant::now();
+    assert!(control.halt(Duration::from_secs(5)));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(!is_microphone_open());
+    reader.join().unwrap();
+
+    let control = Arc::new(CaptureControl::default());
+    let panicked = std::thread::spawn(move || {
+        let _open = control.stream_opened();
+        panic!("session thread panicked");
+    })
+    .join();
+    assert!(panicked.is_err());
+    assert!(!is_microphone_open());
+
+    // halt() gives up after its timeout when the reader never lets go
+    let control = Arc::new(CaptureControl::default());
+    let (opened_tx, opened_rx) = std::sync::mpsc::channel();
+    let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+    let reader = {
+        let control = control.clone();
+        std::thread::spawn(move || {
+            let _open = control.stream_opened();
+            opened_tx.send(()).unwrap();
+            let _ = release_rx.recv();
+        })
+    };
+    opened_rx.recv().unwrap();
+    assert!(!control.halt(Duration::from_millis(20)));
+    assert!(control.is_open());
+    release_tx.send(()).unwrap();
+    reader.join().unwrap();
+    assert!(!control.is_open());
+    assert!(!is_microphone_open());
+}
+
+// ---------------------------------------------------------------------------
+// Resampler
+// ---------------------------------------------------------------------------
+
+/// Equal rates pass samples through untouched.
+#[test]
+fn resampler_passthrough_at_equal_rates() {
+    let mut resampler = Resampler::new(16000, 16000);
+    assert!(resampler.is_passthrough());
+    let mut out = Vec::new();
+    resampler.process(&[0.1, 0.2, 0.3], &mut out);
+    assert_eq!(out, vec![0.1, 0.2, 0.3]);
+}
+
+/// 48kHz (WASAPI's usual shared-mode rate) comes out at a third the length.
+#[test]
+fn resampler_downsamples_48k_to_16k() {
+    let input: Vec<f32> = (0..4800).map(|i| i as f32).collect();
+    let mut resampler = Resampler::new(48000, 16000);
+    let mut out = Vec::new();
+    resampler.process(&input, &mut out);
+    assert_eq!(out.len(), 1600);
+    assert_eq!(out[0], 0.0);
+    assert_eq!(out[1], 3.0);
+    assert_eq!(out[1599], 4797.0);
+}
+
+/// Device callbacks deliver odd chunk sizes; splitting the input must not
+/// change the output or drop samples at chunk boundaries.
+#[test]
+fn resampler_chunked_matches_whole() {
+    let input: Vec<f32> = (0..4410).map(|i| (i as f32 * 0.01).sin()).collect();
+    let mut whole = Vec::new();
+    Resampler::new(44100, 16000).process(&input, &mut whole);
+
+    let mut resampler = Resampler::new(44100, 16000);
+    let mut chunked = Vec::new();
+    for chunk in input.chunks(441).chain(std::iter::once(&[][..])) {
+        resampler.process(chunk, &mut chunked);
+    }
+    assert_eq!(whole.len(), chunked.len());
+    for (a, b) in whole.iter().zip(&chunked) {
+        assert!((a - b).abs() < 1e-5, "{a} != {b}");
+    }
+}
+
+/// Upsampling from an 8kHz device interpolates between samples.
+#[test]
+fn resampler_upsamples_8k_to_16k() {
+    let mut resampler = Resampler::new(8000, 16000);
+    let mut out = Vec::new();
+    resampler.process(&[0.0, 1.0], &mut out);
+    resampler.process(&[2.0], &mut out);
+    assert_eq!(out, vec![0.0, 0.5, 1.0, 1.5]);
+}
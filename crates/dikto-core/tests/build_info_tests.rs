@@ -0,0 +1,47 @@
+// Tests for dikto_core::build_info — version metadata recorded by build.rs.
+
+use dikto_core::build_info::{enabled_features, version_info};
+
+/// The reported version should match the crate version.
+#[test]
+fn version_matches_crate_version() {
+    assert_eq!(version_info().version, env!("CARGO_PKG_VERSION"));
+}
+
+/// The git hash should be non-empty ("unknown" outside a git checkout).
+#[test]
+fn git_hash_is_non_empty() {
+    assert!(!version_info().git_hash.is_empty());
+}
+
+/// Linked backend versions should be resolved from Cargo.lock, and
+/// backends left out of the build reported as "none".
+#[test]
+fn backend_versions_are_resolved() {
+    let info = version_info();
+    let expected = |enabled: bool, version: &str| {
+        if enabled {
+            assert!(version != "unknown" && version != "none", "{version}");
+        } else {
+            assert_eq!(version, "none");
+        }
+    };
+    expected(cfg!(feature = "backend-whisper"), &info.whisper_rs_version);
+    expected(cfg!(feature = "native"), &info.ort_version);
+    expected(
+        cfg!(feature = "backend-parakeet"),
+        &info.parakeet_rs_version,
+    );
+}
+
+/// The features list should agree with enabled_features().
+#[test]
+fn features_match_enabled_features() {
+    assert_eq!(version_info().features, enabled_features());
+}
+
+/// The target triple should be recorded.
+#[test]
+fn target_is_recorded() {
+    assert!(!version_info().target.is_empty());
+}
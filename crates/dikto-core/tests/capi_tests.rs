@@ -0,0 +1,90 @@
+// Tests for dikto_core::capi — NULL handling, dikto_last_error, and string and
+// session ownership across the C ABI.
+
+use std::ffi::{CStr, CString};
+use std::sync::Arc;
+
+use dikto_core::capi::{
+    dikto_engine_free, dikto_last_error, dikto_listen, dikto_session_free, dikto_session_stop,
+    dikto_string_free, dikto_transcribe_file, DiktoCallbacks,
+};
+use dikto_core::SessionHandle;
+
+fn last_error() -> String {
+    let ptr = dikto_last_error();
+    assert!(!ptr.is_null());
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+// ---------------------------------------------------------------------------
+// NULL arguments
+// ---------------------------------------------------------------------------
+
+/// A NULL engine fails with a message instead of crashing.
+#[test]
+fn transcribe_file_null_engine() {
+    let path = CString::new("clip.wav").unwrap();
+    let lang = CString::new("en").unwrap();
+    let text = unsafe { dikto_transcribe_file(std::ptr::null(), path.as_ptr(), lang.as_ptr()) };
+    assert!(text.is_null());
+    assert_eq!(last_error(), "engine is NULL");
+}
+
+/// dikto_listen rejects a NULL engine.
+#[test]
+fn listen_null_engine() {
+    let callbacks = DiktoCallbacks {
+        user_data: std::ptr::null_mut(),
+        on_partial: None,
+        on_final_segment: None,
+        on_done: None,
+        on_error: None,
+    };
+    let session = unsafe { dikto_listen(std::ptr::null(), &callbacks) };
+    assert!(session.is_null());
+    assert_eq!(last_error(), "engine is NULL");
+}
+
+/// Free and stop functions ignore NULL.
+#[test]
+fn free_functions_accept_null() {
+    unsafe {
+        dikto_string_free(std::ptr::null_mut());
+        dikto_engine_free(std::ptr::null_mut());
+        dikto_session_stop(std::ptr::null());
+        dikto_session_free(std::ptr::null());
+    }
+}
+
+// ---------------------------------------------------------------------------
+// dikto_last_error
+// ---------------------------------------------------------------------------
+
+/// Errors are per thread: a fresh thread has none.
+#[test]
+fn last_error_is_thread_local() {
+    let has_error = std::thread::spawn(|| !dikto_last_error().is_null())
+        .join()
+        .unwrap();
+    assert!(!has_error);
+}
+
+// ---------------------------------------------------------------------------
+// Sessions
+// ---------------------------------------------------------------------------
+
+/// Stopping through the C ABI stops the shared session, and freeing drops
+/// only the C side's reference.
+#[test]
+fn session_stop_and_free_via_raw_pointer() {
+    let handle = Arc::new(SessionHandle::new_for_test());
+    let raw = Arc::into_raw(handle.clone());
+    unsafe {
+        dikto_session_stop(raw);
+        dikto_session_free(raw);
+    }
+    assert!(!handle.is_active());
+    assert_eq!(Arc::strong_count(&handle), 1);
+}
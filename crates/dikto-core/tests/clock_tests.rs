@@ -0,0 +1,141 @@
+// Tests for dikto_core::clock — the mock clock and session timing driven by
+// it: max_duration timeouts, overlay throttling, and silence detection.
+
+use dikto_core::clock::{Clock, MockClock, SessionTimer, SystemClock};
+use dikto_core::countdown::{Countdown, SessionDeadline};
+use dikto_core::vad::{VadConfig, VadEvent, VadProcessor};
+use std::sync::Arc;
+use std::time::Duration;
+
+// ---------------------------------------------------------------------------
+// Clocks
+// ---------------------------------------------------------------------------
+
+/// A mock clock stands still until advanced.
+#[test]
+fn mock_clock_moves_only_when_advanced() {
+    let clock = MockClock::new();
+    let start = clock.now();
+    assert_eq!(clock.now(), start);
+    clock.advance(Duration::from_millis(1500));
+    assert_eq!(clock.now() - start, Duration::from_millis(1500));
+}
+
+/// The system clock never goes backwards.
+#[test]
+fn system_clock_is_monotonic() {
+    let first = SystemClock.now();
+    assert!(SystemClock.now() >= first);
+}
+
+// ---------------------------------------------------------------------------
+// Timeout
+// ---------------------------------------------------------------------------
+
+/// A session times out exactly at max_duration, counting down on the way.
+#[test]
+fn session_times_out_at_max_duration() {
+    let clock = Arc::new(MockClock::new());
+    let timer = SessionTimer::start(clock.clone(), Duration::from_millis(500));
+    let deadline = SessionDeadline::new(Duration::from_secs(30));
+    let mut countdown = Countdown::new(5);
+
+    clock.advance(Duration::from_secs(24));
+    let remaining = deadline.remaining(timer.elapsed());
+    assert_eq!(remaining, Duration::from_secs(6));
+    assert_eq!(countdown.tick(remaining), None);
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(countdown.tick(deadline.remaining(timer.elapsed())), Some(5));
+
+    clock.advance(Duration::from_millis(4999));
+    assert!(!deadline.remaining(timer.elapsed()).is_zero());
+    clock.advance(Duration::from_millis(1));
+    assert!(deadline.remaining(timer.elapsed()).is_zero());
+}
+
+/// Speech pushes an extending deadline out until its cap.
+#[test]
+fn extending_session_times_out_at_cap() {
+    let clock = Arc::new(MockClock::new());
+    let timer = SessionTimer::start(clock.clone(), Duration::from_millis(500));
+    let mut deadline = SessionDeadline::extending(Duration::from_secs(10), Duration::from_secs(20));
+
+    clock.advance(Duration::from_secs(10));
+    deadline.add_speech(Duration::from_secs(8));
+    assert_eq!(deadline.remaining(timer.elapsed()), Duration::from_secs(8));
+
+    clock.advance(Duration::from_secs(8));
+    deadline.add_speech(Duration::from_secs(8));
+    assert_eq!(deadline.remaining(timer.elapsed()), Duration::from_secs(2));
+    clock.advance(Duration::from_secs(2));
+    assert!(deadline.remaining(timer.elapsed()).is_zero());
+}
+
+// ---------------------------------------------------------------------------
+// Throttling
+// ---------------------------------------------------------------------------
+
+/// Partials are due once per interval, the first one an interval after start.
+#[test]
+fn partials_are_throttled_to_the_interval() {
+    let clock = Arc::new(MockClock::new());
+    let mut timer = SessionTimer::start(clock.clone(), Duration::from_millis(500));
+    assert!(!timer.partial_due());
+
+    clock.advance(Duration::from_millis(499));
+    assert!(!timer.partial_due());
+    clock.advance(Duration::from_millis(1));
+    assert!(timer.partial_due());
+    assert!(!timer.partial_due());
+
+    // A long stall yields one partial, not a burst of catch-up ones
+    clock.advance(Duration::from_secs(3));
+    assert!(timer.partial_due());
+    assert!(!timer.partial_due());
+}
+
+/// A zero interval sends a partial on every read.
+#[test]
+fn zero_partial_interval_never_throttles() {
+    let clock = Arc::new(MockClock::new());
+    let mut timer = SessionTimer::start(clock, Duration::ZERO);
+    assert!(timer.partial_due());
+    assert!(timer.partial_due());
+}
+
+// ---------------------------------------------------------------------------
+// Silence duration
+// ---------------------------------------------------------------------------
+
+/// Silence is measured in audio, not wall time: a slow machine that takes
+/// seconds per chunk still ends the utterance after silence_duration_ms of
+/// silent audio, and the session clock keeps its own count.
+#[test]
+fn silence_duration_counts_audio_not_wall_time() {
+    let clock = Arc::new(MockClock::new());
+    let timer = SessionTimer::start(clock.clone(), Duration::from_millis(500));
+    let mut vad = VadProcessor::new(VadConfig {
+        speech_activation_frames: 1,
+        silence_duration_ms: 96,
+        min_speech_duration_ms: 0,
+        ..VadConfig::default()
+    })
+    .unwrap();
+
+    assert_eq!(vad.process_probability(0.9), VadEvent::SpeechStart);
+    let mut events = Vec::new();
+    for _ in 0..3 {
+        clock.advance(Duration::from_secs(2));
+        events.push(vad.process_probability(0.0));
+    }
+    assert_eq!(
+        events,
+        vec![
+            VadEvent::SpeechContinue,
+            VadEvent::SpeechContinue,
+            VadEvent::SpeechEnd
+        ]
+    );
+    assert_eq!(timer.elapsed(), Duration::from_secs(6));
+}
@@ -3,8 +3,11 @@
 
 use dikto_core::config::{
     config_dir, config_path, data_dir, default_model_name, is_valid_shortcut, models_dir,
-    ActivationMode, DiktoConfig,
+    set_path_provider, ActivationMode, AdvancedConfig, DiktoConfig, InputNormalization,
+    LowPowerMode, OutputConfig, OutputSource, PathProvider, TextProcConfig, TranslationEngine,
+    LONG_FORM_MAX_DURATION_SECS, MAX_DURATION_SECS,
 };
+use std::sync::Arc;
 
 // ---------------------------------------------------------------------------
 // Default config
@@ -21,8 +24,47 @@ fn default_config_has_expected_values() {
     assert!((config.speech_threshold - 0.35).abs() < f32::EPSILON);
     assert_eq!(config.global_shortcut, Some("option+space".to_string()));
     assert_eq!(config.activation_mode, ActivationMode::Hold);
+    assert!(!config.continuous_mode);
     assert!(config.auto_paste);
     assert!(config.auto_copy);
+    assert!(!config.save_history);
+    assert!(!config.personal_lexicon);
+    assert!(config.model_path_override.is_none());
+    assert!(config.huggingface_cache);
+    assert!(config.previous_model.is_none());
+}
+
+// ---------------------------------------------------------------------------
+// roll_back_model
+// ---------------------------------------------------------------------------
+
+/// roll_back_model restores the previous model and clears the override.
+#[test]
+fn roll_back_model_restores_previous() {
+    let mut config = DiktoConfig {
+        model_name: "whisper-small".to_string(),
+        model_path_override: Some("ggml-small-custom.bin".to_string()),
+        previous_model: Some("parakeet-tdt-0.6b-v2".to_string()),
+        ..DiktoConfig::default()
+    };
+    assert_eq!(
+        config.roll_back_model(),
+        Some("parakeet-tdt-0.6b-v2".to_string())
+    );
+    assert_eq!(config.model_name, "parakeet-tdt-0.6b-v2");
+    assert!(config.model_path_override.is_none());
+    assert!(config.previous_model.is_none());
+}
+
+/// With no previous model, or the same one, nothing changes.
+#[test]
+fn roll_back_model_without_previous() {
+    let mut config = DiktoConfig::default();
+    assert_eq!(config.roll_back_model(), None);
+
+    config.previous_model = Some(config.model_name.clone());
+    assert_eq!(config.roll_back_model(), None);
+    assert_eq!(config.model_name, "parakeet-tdt-0.6b-v2");
 }
 
 /// default_model_name() should match the default config.
@@ -273,6 +315,76 @@ fn validate_clamps_max_duration_low() {
     assert_eq!(config.max_duration, 1);
 }
 
+/// The max_duration countdown covers the last 5 seconds by default and
+/// at most a minute.
+#[test]
+fn time_warning_defaults_and_clamps() {
+    let config: DiktoConfig = serde_json::from_str("{}").unwrap();
+    assert_eq!(config.time_warning_secs, 5);
+    let mut config = DiktoConfig {
+        time_warning_secs: 600,
+        ..DiktoConfig::default()
+    };
+    config.validate();
+    assert_eq!(config.time_warning_secs, 60);
+}
+
+/// Speech doesn't extend max_duration unless asked to.
+#[test]
+fn extend_while_speaking_defaults_off() {
+    let config: DiktoConfig = serde_json::from_str("{}").unwrap();
+    assert!(!config.extend_while_speaking);
+    let config: DiktoConfig = serde_json::from_str(r#"{"extend_while_speaking":true}"#).unwrap();
+    assert!(config.extend_while_speaking);
+}
+
+/// flush_threshold_secs should default to 30s and stay within 5–240s,
+/// with 0 left alone to disable it.
+#[test]
+fn validate_clamps_flush_threshold() {
+    let mut config = DiktoConfig::default();
+    assert_eq!(config.flush_threshold_secs, 30);
+
+    config.flush_threshold_secs = 1;
+    config.validate();
+    assert_eq!(config.flush_threshold_secs, 5);
+
+    config.flush_threshold_secs = 600;
+    config.validate();
+    assert_eq!(config.flush_threshold_secs, 240);
+
+    config.flush_threshold_secs = 0;
+    config.validate();
+    assert_eq!(config.flush_threshold_secs, 0);
+}
+
+/// long_form should raise the max_duration ceiling to an hour.
+#[test]
+fn validate_long_form_allows_an_hour() {
+    let mut config = DiktoConfig {
+        max_duration: 45 * 60,
+        long_form: true,
+        ..DiktoConfig::default()
+    };
+    config.validate();
+    assert_eq!(config.max_duration, 45 * 60);
+
+    config.max_duration = 99_999;
+    config.validate();
+    assert_eq!(config.max_duration, LONG_FORM_MAX_DURATION_SECS);
+}
+
+/// Turning long_form off should bring max_duration back under 120s.
+#[test]
+fn validate_without_long_form_clamps_long_duration() {
+    let mut config = DiktoConfig {
+        max_duration: 45 * 60,
+        ..DiktoConfig::default()
+    };
+    config.validate();
+    assert_eq!(config.max_duration, MAX_DURATION_SECS);
+}
+
 /// silence_duration_ms above 10000 should be clamped to 10000.
 #[test]
 fn validate_clamps_silence_duration_high() {
@@ -317,6 +429,371 @@ fn validate_clamps_speech_threshold_low() {
     assert!((config.speech_threshold - 0.01).abs() < f32::EPSILON);
 }
 
+// ---------------------------------------------------------------------------
+// Lint
+// ---------------------------------------------------------------------------
+
+/// The default config has no conflicting settings.
+#[test]
+fn lint_default_config_is_clean() {
+    assert!(DiktoConfig::default().lint().is_empty());
+}
+
+/// Continuous mode with hold activation is flagged, toggle is not.
+#[test]
+fn lint_flags_continuous_mode_with_hold() {
+    let mut config = DiktoConfig {
+        continuous_mode: true,
+        activation_mode: ActivationMode::Hold,
+        ..DiktoConfig::default()
+    };
+    let warnings = config.lint();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].field, "continuous_mode");
+
+    config.activation_mode = ActivationMode::Toggle;
+    assert!(config.lint().is_empty());
+}
+
+/// Translating into the spoken language is flagged unless the language is
+/// detected.
+#[test]
+fn lint_flags_translation_into_source_language() {
+    let mut config = DiktoConfig::default();
+    config.translation.target_language = "en".to_string();
+    let fields: Vec<String> = config.lint().into_iter().map(|w| w.field).collect();
+    assert_eq!(fields, vec!["translation.target_language"]);
+
+    config.language = "auto".to_string();
+    assert!(config.lint().is_empty());
+}
+
+/// Output settings that can't take effect are each reported.
+#[test]
+fn lint_flags_output_conflicts() {
+    let mut config = DiktoConfig {
+        auto_paste: false,
+        save_history: false,
+        personal_lexicon: false,
+        ..DiktoConfig::default()
+    };
+    config.output.incremental_paste = true;
+    config.output.append_space = true;
+    config.output.append_newline = true;
+    config.output.routes.paste = OutputSource::Summary;
+    config.output.routes.history_raw = true;
+    let fields: Vec<String> = config.lint().into_iter().map(|w| w.field).collect();
+    assert_eq!(
+        fields,
+        vec![
+            "output.incremental_paste",
+            "output.incremental_paste",
+            "output.append_space",
+            "output.routes.paste",
+            "output.routes.history_raw",
+        ]
+    );
+
+    // With a summarizer, pasting the summary is fine
+    config.summary.command = "summarize".to_string();
+    assert!(!config
+        .lint()
+        .iter()
+        .any(|w| w.field == "output.routes.paste"));
+}
+
+/// Lifecycle settings: a countdown as long as the session, extension in
+/// continuous mode, and the lexicon without history.
+#[test]
+fn lint_flags_session_conflicts() {
+    let config = DiktoConfig {
+        max_duration: 5,
+        time_warning_secs: 5,
+        continuous_mode: true,
+        activation_mode: ActivationMode::Toggle,
+        extend_while_speaking: true,
+        save_history: false,
+        personal_lexicon: true,
+        ..DiktoConfig::default()
+    };
+    let fields: Vec<String> = config.lint().into_iter().map(|w| w.field).collect();
+    assert_eq!(
+        fields,
+        vec![
+            "extend_while_speaking",
+            "time_warning_secs",
+            "personal_lexicon"
+        ]
+    );
+}
+
+/// Warnings print as "field: message".
+#[test]
+fn lint_warning_display() {
+    let config = DiktoConfig {
+        continuous_mode: true,
+        ..DiktoConfig::default()
+    };
+    let warning = config.lint().remove(0);
+    assert_eq!(
+        warning.to_string(),
+        format!("continuous_mode: {}", warning.message)
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Advanced config
+// ---------------------------------------------------------------------------
+
+/// AdvancedConfig::default() should use a 50ms poll interval and 500ms partials.
+#[test]
+fn advanced_config_defaults() {
+    let advanced = AdvancedConfig::default();
+    assert_eq!(advanced.poll_interval_ms, 50);
+    assert_eq!(advanced.partial_interval_ms, 500);
+    assert!(!advanced.vad_debug);
+    assert_eq!(advanced.beam_size, 0);
+    assert_eq!(advanced.no_speech_threshold, 0.6);
+    assert_eq!(advanced.input_normalization, InputNormalization::Off);
+    assert_eq!(advanced.normalization_min_range_db, 12.0);
+    assert_eq!(advanced.watchdog_timeout_secs, 300);
+    assert_eq!(advanced.min_session_interval_ms, 250);
+    assert_eq!(advanced.max_sessions_per_minute, 30);
+}
+
+/// Config JSON without an "advanced" block should get the advanced defaults.
+#[test]
+fn deserialize_missing_advanced_gives_defaults() {
+    let config: DiktoConfig = serde_json::from_str(r#"{"language":"en"}"#).unwrap();
+    assert_eq!(config.advanced, AdvancedConfig::default());
+}
+
+/// A partial "advanced" block should fill in the missing field.
+#[test]
+fn deserialize_partial_advanced_block() {
+    let json = r#"{"advanced":{"poll_interval_ms":20}}"#;
+    let config: DiktoConfig = serde_json::from_str(json).unwrap();
+    assert_eq!(config.advanced.poll_interval_ms, 20);
+    assert_eq!(config.advanced.partial_interval_ms, 500);
+}
+
+/// validate() should clamp out-of-range advanced timings.
+#[test]
+fn validate_clamps_advanced_timings() {
+    let mut config = DiktoConfig {
+        advanced: AdvancedConfig {
+            poll_interval_ms: 0,
+            partial_interval_ms: 60_000,
+            vad_debug: false,
+            beam_size: 64,
+            no_speech_threshold: 1.5,
+            input_normalization: InputNormalization::Rms,
+            normalization_min_range_db: 90.0,
+            watchdog_timeout_secs: 5,
+            min_session_interval_ms: 60_000,
+            max_sessions_per_minute: 0,
+        },
+        ..DiktoConfig::default()
+    };
+    config.validate();
+    assert_eq!(config.advanced.poll_interval_ms, 1);
+    assert_eq!(config.advanced.partial_interval_ms, 5000);
+    assert_eq!(config.advanced.beam_size, 8);
+    assert_eq!(config.advanced.no_speech_threshold, 1.0);
+    assert_eq!(config.advanced.normalization_min_range_db, 60.0);
+    assert_eq!(config.advanced.watchdog_timeout_secs, 30);
+    assert_eq!(config.advanced.min_session_interval_ms, 10_000);
+    assert_eq!(config.advanced.max_sessions_per_minute, 0);
+}
+
+/// Config JSON without a "text_processing" block should enable the pipeline
+/// with spoken punctuation off and no overrides.
+#[test]
+fn deserialize_missing_text_processing_gives_defaults() {
+    let config: DiktoConfig = serde_json::from_str("{}").unwrap();
+    assert!(config.text_processing.enabled);
+    assert!(!config.text_processing.spoken_punctuation);
+    assert!(!config.text_processing.math_mode);
+    assert!(!config.text_processing.spelling_mode);
+    assert!(config.text_processing.spelling_command);
+    assert!(config.text_processing.language_overrides.is_empty());
+    assert_eq!(config.text_processing.sentence_break_ms, 2000);
+    assert_eq!(config.text_processing.paragraph_break_ms, 4000);
+    assert!(!config.text_processing.prose_rules);
+    assert!(!config.text_processing.group_numbers);
+}
+
+/// Per-language overrides should deserialize with unset fields left as None.
+#[test]
+fn deserialize_language_override() {
+    let json = r#"{"text_processing":{"language_overrides":{"de":{"thousands_separator":"'"}}}}"#;
+    let config: DiktoConfig = serde_json::from_str(json).unwrap();
+    let de = &config.text_processing.language_overrides["de"];
+    assert_eq!(de.thousands_separator.as_deref(), Some("'"));
+    assert_eq!(de.capitalize_sentences, None);
+    assert!(de.spoken_punctuation.is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// Output config
+// ---------------------------------------------------------------------------
+
+/// Config JSON without an "output" block pastes after 200ms and doesn't type.
+#[test]
+fn deserialize_missing_output_gives_defaults() {
+    let config: DiktoConfig = serde_json::from_str("{}").unwrap();
+    assert_eq!(config.output, OutputConfig::default());
+    assert_eq!(config.output.paste_delay_ms, 200);
+    assert!(!config.output.typing_mode);
+    assert_eq!(config.output.keystroke_delay_ms, 5);
+    assert!(!config.output.append_space);
+    assert!(!config.output.append_newline);
+    assert!(config.output.smart_spacing);
+    assert!(config.output.smart_capitalization);
+}
+
+/// An app profile needs only the app; everything else is off.
+#[test]
+fn deserialize_minimal_app_profile() {
+    let json = r#"{"output":{"app_profiles":[{"app":"Slack","plain_text":true}]}}"#;
+    let config: DiktoConfig = serde_json::from_str(json).unwrap();
+    let profile = &config.output.app_profiles[0];
+    assert_eq!(profile.app, "Slack");
+    assert!(profile.plain_text);
+    assert_eq!(profile.auto_paste, None);
+    assert!(!profile.trailing_newline);
+    assert!(!profile.timestamp_prefix);
+    assert_eq!(config.output.paste_delay_ms, 200);
+}
+
+/// Routes send the transcript everywhere unless configured, and keep no
+/// raw text in history.
+#[test]
+fn deserialize_output_routes() {
+    let config: DiktoConfig = serde_json::from_str("{}").unwrap();
+    assert_eq!(config.output.routes.clipboard, OutputSource::Transcript);
+    assert_eq!(config.output.routes.paste, OutputSource::Transcript);
+    assert!(!config.output.routes.history_raw);
+
+    let json = r#"{"output":{"routes":{"paste":"summary","history_raw":true}}}"#;
+    let config: DiktoConfig = serde_json::from_str(json).unwrap();
+    assert_eq!(config.output.routes.clipboard, OutputSource::Transcript);
+    assert_eq!(config.output.routes.paste, OutputSource::Summary);
+    assert!(config.output.routes.history_raw);
+}
+
+/// Translation is off unless a target language is set, and runs locally.
+#[test]
+fn deserialize_translation() {
+    let config: DiktoConfig = serde_json::from_str("{}").unwrap();
+    assert!(config.translation.target_language.is_empty());
+    assert_eq!(config.translation.engine, TranslationEngine::Local);
+
+    let json = r#"{"translation":{"target_language":"en","engine":"api","api_url":"http://localhost:5000/translate"},
+        "output":{"app_profiles":[{"app":"Slack","translate_to":"de"}]}}"#;
+    let config: DiktoConfig = serde_json::from_str(json).unwrap();
+    assert_eq!(config.translation.target_language, "en");
+    assert_eq!(config.translation.engine, TranslationEngine::Api);
+    assert_eq!(
+        config.output.app_profiles[0].translate_to.as_deref(),
+        Some("de")
+    );
+}
+
+/// Summaries are off by default and the timeout is clamped.
+#[test]
+fn summary_config_defaults_and_validation() {
+    let config: DiktoConfig = serde_json::from_str("{}").unwrap();
+    assert!(!config.summary.is_enabled());
+    assert_eq!(config.summary.min_words, 300);
+
+    let json = r#"{"summary":{"command":"llm -s 'TL;DR'","timeout_secs":0}}"#;
+    let mut config: DiktoConfig = serde_json::from_str(json).unwrap();
+    config.validate();
+    assert!(config.summary.is_enabled());
+    assert_eq!(config.summary.timeout_secs, 1);
+}
+
+/// Saved sessions are off by default and filed by month.
+#[test]
+fn saved_sessions_config_defaults() {
+    let config: DiktoConfig = serde_json::from_str("{}").unwrap();
+    assert!(!config.saved_sessions.is_enabled());
+    assert_eq!(config.saved_sessions.dir, "sessions");
+    assert_eq!(
+        config.saved_sessions.file_name_template,
+        "{date}-{time}-{first_words}"
+    );
+    assert!(config.saved_sessions.folder_by_month);
+
+    let json = r#"{"saved_sessions":{"save_audio":true,"folder_by_month":false}}"#;
+    let config: DiktoConfig = serde_json::from_str(json).unwrap();
+    assert!(config.saved_sessions.is_enabled());
+    assert!(!config.saved_sessions.folder_by_month);
+}
+
+/// Redaction has no keywords by default and masks with "[redacted]".
+#[test]
+fn redaction_config_defaults() {
+    let config: DiktoConfig = serde_json::from_str("{}").unwrap();
+    assert!(config.redaction.keywords.is_empty());
+    assert_eq!(config.redaction.mask, "[redacted]");
+
+    let json = r#"{"redaction":{"keywords":["falcon"]}}"#;
+    let config: DiktoConfig = serde_json::from_str(json).unwrap();
+    assert_eq!(config.redaction.keywords, vec!["falcon"]);
+    assert_eq!(config.redaction.mask, "[redacted]");
+}
+
+/// A second session started while one is finishing fails unless queueing is on.
+#[test]
+fn queue_sessions_off_by_default() {
+    let config: DiktoConfig = serde_json::from_str("{}").unwrap();
+    assert!(!config.queue_sessions);
+    assert!(!DiktoConfig::default().queue_sessions);
+
+    let config: DiktoConfig = serde_json::from_str(r#"{"queue_sessions":true}"#).unwrap();
+    assert!(config.queue_sessions);
+}
+
+/// Incremental paste is off by default and pastes 1–64 words at a time.
+#[test]
+fn incremental_paste_defaults_and_clamps() {
+    let config: DiktoConfig = serde_json::from_str("{}").unwrap();
+    assert!(!config.output.incremental_paste);
+    assert_eq!(config.output.paste_chunk_words, 8);
+
+    let mut config = DiktoConfig::default();
+    config.output.paste_chunk_words = 0;
+    config.validate();
+    assert_eq!(config.output.paste_chunk_words, 1);
+    config.output.paste_chunk_words = 1000;
+    config.validate();
+    assert_eq!(config.output.paste_chunk_words, 64);
+}
+
+/// validate() caps the paste delay and keystroke pacing.
+#[test]
+fn validate_clamps_output_delays() {
+    let mut config = DiktoConfig {
+        output: OutputConfig {
+            paste_delay_ms: 60_000,
+            typing_mode: true,
+            keystroke_delay_ms: 1000,
+            append_space: false,
+            append_newline: false,
+            smart_spacing: true,
+            smart_capitalization: true,
+            app_profiles: Vec::new(),
+            ..OutputConfig::default()
+        },
+        ..DiktoConfig::default()
+    };
+    config.validate();
+    assert_eq!(config.output.paste_delay_ms, 2000);
+    assert_eq!(config.output.keystroke_delay_ms, 200);
+}
+
 // ---------------------------------------------------------------------------
 // Serialize / roundtrip
 // ---------------------------------------------------------------------------
@@ -334,6 +811,18 @@ fn serialize_deserialize_roundtrip() {
         auto_paste: false,
         auto_copy: true,
         activation_mode: ActivationMode::Toggle,
+        continuous_mode: true,
+        save_history: true,
+        personal_lexicon: true,
+        low_power_mode: LowPowerMode::Off,
+        low_power_model: Some("whisper-tiny".to_string()),
+        model_path_override: Some("/opt/models/ggml-large-v3.bin".to_string()),
+        huggingface_cache: false,
+        previous_model: None,
+        advanced: AdvancedConfig::default(),
+        text_processing: TextProcConfig::default(),
+        output: OutputConfig::default(),
+        ..DiktoConfig::default()
     };
     let json = serde_json::to_string_pretty(&original).unwrap();
     let loaded: DiktoConfig = serde_json::from_str(&json).unwrap();
@@ -346,6 +835,16 @@ fn serialize_deserialize_roundtrip() {
     assert!(!loaded.auto_paste);
     assert!(loaded.auto_copy);
     assert_eq!(loaded.activation_mode, ActivationMode::Toggle);
+    assert!(loaded.continuous_mode);
+    assert!(loaded.save_history);
+    assert!(loaded.personal_lexicon);
+    assert_eq!(loaded.low_power_mode, LowPowerMode::Off);
+    assert_eq!(loaded.low_power_model, Some("whisper-tiny".to_string()));
+    assert_eq!(
+        loaded.model_path_override,
+        Some("/opt/models/ggml-large-v3.bin".to_string())
+    );
+    assert!(!loaded.huggingface_cache);
 }
 
 /// Write config to a temp file and read it back — file-level roundtrip.
@@ -366,6 +865,18 @@ fn save_load_file_roundtrip() {
         auto_paste: false,
         auto_copy: true,
         activation_mode: ActivationMode::Toggle,
+        continuous_mode: true,
+        save_history: false,
+        personal_lexicon: false,
+        low_power_mode: LowPowerMode::Off,
+        low_power_model: Some("whisper-tiny".to_string()),
+        model_path_override: Some("/opt/models/ggml-large-v3.bin".to_string()),
+        huggingface_cache: false,
+        previous_model: None,
+        advanced: AdvancedConfig::default(),
+        text_processing: TextProcConfig::default(),
+        output: OutputConfig::default(),
+        ..DiktoConfig::default()
     };
 
     let json = serde_json::to_string_pretty(&original).unwrap();
@@ -429,3 +940,66 @@ fn models_dir_contains_models() {
     let dir = models_dir();
     assert!(dir.to_string_lossy().contains("models"));
 }
+
+/// Container paths a sandboxed host might inject. They keep "dikto" and
+/// "models" in them so the path tests above hold while this one runs.
+struct GroupPaths {
+    models: Option<String>,
+}
+
+impl PathProvider for GroupPaths {
+    fn config_dir(&self) -> String {
+        "/tmp/group.dikto/config".to_string()
+    }
+
+    fn data_dir(&self) -> String {
+        "/tmp/group.dikto/data".to_string()
+    }
+
+    fn models_dir(&self) -> Option<String> {
+        self.models.clone()
+    }
+}
+
+/// An installed PathProvider should replace every home-relative path, and
+/// clearing it should restore the defaults.
+#[test]
+fn path_provider_overrides_paths() {
+    set_path_provider(Some(Arc::new(GroupPaths { models: None })));
+    assert_eq!(
+        config_path().unwrap(),
+        std::path::Path::new("/tmp/group.dikto/config/config.json")
+    );
+    assert_eq!(
+        data_dir().unwrap(),
+        std::path::Path::new("/tmp/group.dikto/data")
+    );
+    assert_eq!(
+        models_dir(),
+        std::path::Path::new("/tmp/group.dikto/data/models")
+    );
+
+    set_path_provider(Some(Arc::new(GroupPaths {
+        models: Some("/Volumes/Shared/dikto-models".to_string()),
+    })));
+    assert_eq!(
+        models_dir(),
+        std::path::Path::new("/Volumes/Shared/dikto-models")
+    );
+
+    set_path_provider(None);
+    assert!(!config_dir().unwrap().starts_with("/tmp/group.dikto"));
+}
+
+/// input_normalization should round-trip through its lowercase JSON name.
+#[test]
+fn input_normalization_serializes_lowercase() {
+    let config: DiktoConfig =
+        serde_json::from_str(r#"{"advanced": {"input_normalization": "peak"}}"#).unwrap();
+    assert_eq!(
+        config.advanced.input_normalization,
+        InputNormalization::Peak
+    );
+    let json = serde_json::to_string(&config).unwrap();
+    assert!(json.contains(r#""input_normalization":"peak""#));
+}
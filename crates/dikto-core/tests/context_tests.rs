@@ -0,0 +1,83 @@
+// Tests for dikto_core::context — normalizing host context hints into a
+// Whisper initial prompt.
+
+use dikto_core::context::{initial_prompt, normalize_hint, MAX_HINT_CHARS};
+
+/// Empty and whitespace-only hints clear the prompt.
+#[test]
+fn empty_hint_is_none() {
+    assert_eq!(normalize_hint(""), None);
+    assert_eq!(normalize_hint("  \n\t "), None);
+}
+
+/// Whitespace runs (newlines, indentation) collapse to single spaces.
+#[test]
+fn whitespace_is_collapsed() {
+    assert_eq!(
+        normalize_hint("  Dear Dr.  Okafor,\n\n  thanks ").as_deref(),
+        Some("Dear Dr. Okafor, thanks")
+    );
+}
+
+/// Long hints keep the tail, trimmed to a word boundary.
+#[test]
+fn long_hint_keeps_tail() {
+    let text = format!("{} kubernetes ingress", "lorem ipsum ".repeat(200));
+    let hint = normalize_hint(&text).unwrap();
+    assert!(hint.chars().count() <= MAX_HINT_CHARS);
+    assert!(hint.ends_with("kubernetes ingress"));
+    assert!(hint.starts_with("lorem") || hint.starts_with("ipsum"));
+}
+
+/// Truncation counts characters, so multi-byte text never splits a code point.
+#[test]
+fn long_hint_multibyte_safe() {
+    let text = "日本語 ".repeat(400);
+    let hint = normalize_hint(&text).unwrap();
+    assert!(hint.chars().count() <= MAX_HINT_CHARS);
+    assert!(hint.starts_with('日'));
+}
+
+/// Truncation never splits an emoji sequence.
+#[test]
+fn long_hint_keeps_emoji_whole() {
+    let text = "\u{1F469}\u{200D}\u{1F4BB} ".repeat(600);
+    let hint = normalize_hint(&text).unwrap();
+    assert!(hint.starts_with("\u{1F469}\u{200D}\u{1F4BB}"));
+}
+
+// ---------------------------------------------------------------------------
+// initial_prompt
+// ---------------------------------------------------------------------------
+
+/// No terms and no hint means no prompt.
+#[test]
+fn initial_prompt_empty() {
+    assert_eq!(initial_prompt(None, &[]), None);
+}
+
+/// Lexicon terms precede the hint.
+#[test]
+fn initial_prompt_terms_then_hint() {
+    let terms = vec!["Kubernetes".to_string(), "Okafor".to_string()];
+    assert_eq!(
+        initial_prompt(Some("Meeting notes"), &terms).as_deref(),
+        Some("Kubernetes, Okafor. Meeting notes")
+    );
+    assert_eq!(
+        initial_prompt(None, &terms).as_deref(),
+        Some("Kubernetes, Okafor.")
+    );
+    assert_eq!(initial_prompt(Some("Xcode"), &[]).as_deref(), Some("Xcode"));
+}
+
+/// The combined prompt stays within the budget, trimming the hint's head.
+#[test]
+fn initial_prompt_respects_budget() {
+    let terms: Vec<String> = (0..40).map(|i| format!("Term{i}")).collect();
+    let hint = normalize_hint(&"word ".repeat(400)).unwrap();
+    let prompt = initial_prompt(Some(&hint), &terms).unwrap();
+    assert!(prompt.chars().count() <= MAX_HINT_CHARS);
+    assert!(prompt.starts_with("Term0, Term1"));
+    assert!(prompt.ends_with("word"));
+}
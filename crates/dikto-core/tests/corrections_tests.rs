@@ -0,0 +1,98 @@
+// Tests for dikto_core::corrections — correction pair persistence, changed
+// span extraction, and replacement suggestions.
+
+use dikto_core::corrections::{
+    append_pair, changed_span, load_pairs, suggest_replacements, CorrectionPair,
+};
+
+fn pair(original: &str, corrected: &str) -> CorrectionPair {
+    CorrectionPair {
+        timestamp: 0,
+        original: original.to_string(),
+        corrected: corrected.to_string(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// changed_span
+// ---------------------------------------------------------------------------
+
+/// The span between common prefix and suffix is the change.
+#[test]
+fn changed_span_finds_middle_edit() {
+    assert_eq!(
+        changed_span("call oak offer tomorrow", "call Okafor tomorrow"),
+        Some(("oak offer".to_string(), "Okafor".to_string()))
+    );
+}
+
+/// Trailing punctuation differences don't widen the span.
+#[test]
+fn changed_span_ignores_punctuation() {
+    assert_eq!(
+        changed_span("use cube control.", "use kubectl"),
+        Some(("cube control".to_string(), "kubectl".to_string()))
+    );
+}
+
+/// Identical text, pure insertions/deletions, and rewrites aren't rules.
+#[test]
+fn changed_span_rejects_non_rules() {
+    assert_eq!(changed_span("same text", "same text"), None);
+    assert_eq!(changed_span("hello world", "hello big world"), None);
+    assert_eq!(
+        changed_span(
+            "one two three four five six",
+            "seven eight nine ten eleven twelve"
+        ),
+        None
+    );
+}
+
+/// Repeated words at the boundary don't make prefix and suffix overlap.
+#[test]
+fn changed_span_repeated_words() {
+    assert_eq!(changed_span("the the cat", "the cat"), None);
+    assert_eq!(
+        changed_span("a b a", "a c a"),
+        Some(("b".to_string(), "c".to_string()))
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Suggestions
+// ---------------------------------------------------------------------------
+
+/// Only changes repeated at least min_count times are suggested.
+#[test]
+fn suggestions_require_repetition() {
+    let pairs = vec![
+        pair("ask oak offer", "ask Okafor"),
+        pair("tell oak offer now", "tell Okafor now"),
+        pair("use cube control", "use kubectl"),
+    ];
+    let suggestions = suggest_replacements(&pairs, 2);
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0].from, "oak offer");
+    assert_eq!(suggestions[0].to, "Okafor");
+    assert_eq!(suggestions[0].count, 2);
+}
+
+// ---------------------------------------------------------------------------
+// Persistence
+// ---------------------------------------------------------------------------
+
+/// Appended pairs load back in order.
+#[test]
+fn append_and_load_roundtrip() {
+    let tmp = std::env::temp_dir().join("dikto_test_corrections_roundtrip");
+    let _ = std::fs::remove_dir_all(&tmp);
+    let path = tmp.join("corrections.jsonl");
+    assert!(load_pairs(&path).is_empty());
+
+    append_pair(&path, &pair("a", "b")).unwrap();
+    append_pair(&path, &pair("c", "d")).unwrap();
+    let loaded = load_pairs(&path);
+    assert_eq!(loaded, vec![pair("a", "b"), pair("c", "d")]);
+    let _ = std::fs::remove_dir_all(&tmp);
+}
@@ -0,0 +1,81 @@
+// Tests for dikto_core::countdown — when a session ends and the warning
+// before it does.
+
+use dikto_core::countdown::{Countdown, SessionDeadline};
+use std::time::Duration;
+
+fn secs(s: f32) -> Duration {
+    Duration::from_secs_f32(s)
+}
+
+// ---------------------------------------------------------------------------
+// SessionDeadline
+// ---------------------------------------------------------------------------
+
+/// A fixed deadline ignores speech.
+#[test]
+fn fixed_deadline_ignores_speech() {
+    let mut deadline = SessionDeadline::new(secs(30.0));
+    deadline.add_speech(secs(20.0));
+    assert_eq!(deadline.remaining(secs(10.0)), secs(20.0));
+    assert_eq!(deadline.remaining(secs(30.0)), Duration::ZERO);
+    assert_eq!(deadline.remaining(secs(45.0)), Duration::ZERO);
+}
+
+/// An extending deadline only counts silence.
+#[test]
+fn extending_deadline_counts_only_silence() {
+    let mut deadline = SessionDeadline::extending(secs(30.0), secs(120.0));
+    deadline.add_speech(secs(25.0));
+    // 40s in, 25s of it speech: 15s counted
+    assert_eq!(deadline.remaining(secs(40.0)), secs(15.0));
+    assert_eq!(deadline.remaining(secs(55.0)), Duration::ZERO);
+}
+
+/// Speech can't push an extending deadline past its cap.
+#[test]
+fn extending_deadline_stops_at_cap() {
+    let mut deadline = SessionDeadline::extending(secs(30.0), secs(120.0));
+    deadline.add_speech(secs(115.0));
+    assert_eq!(deadline.remaining(secs(118.0)), secs(2.0));
+    assert_eq!(deadline.remaining(secs(120.0)), Duration::ZERO);
+}
+
+// ---------------------------------------------------------------------------
+// Countdown
+// ---------------------------------------------------------------------------
+
+/// Nothing is reported before the warning window opens.
+#[test]
+fn silent_before_window() {
+    let mut countdown = Countdown::new(5);
+    assert_eq!(countdown.tick(secs(30.0)), None);
+    assert_eq!(countdown.tick(secs(5.1)), None);
+}
+
+/// Each whole second is reported once, rounded up, down to 1.
+#[test]
+fn counts_down_once_per_second() {
+    let mut countdown = Countdown::new(5);
+    assert_eq!(countdown.tick(secs(5.0)), Some(5));
+    assert_eq!(countdown.tick(secs(4.5)), None);
+    assert_eq!(countdown.tick(secs(3.8)), Some(4));
+    // A slow loop can skip numbers
+    assert_eq!(countdown.tick(secs(1.5)), Some(2));
+    assert_eq!(countdown.tick(secs(0.1)), Some(1));
+    assert_eq!(countdown.tick(Duration::ZERO), None);
+}
+
+/// A session shorter than the window starts counting right away.
+#[test]
+fn short_session_counts_from_start() {
+    let mut countdown = Countdown::new(5);
+    assert_eq!(countdown.tick(secs(3.0)), Some(3));
+}
+
+/// A zero window disables the countdown.
+#[test]
+fn zero_window_disables() {
+    let mut countdown = Countdown::new(0);
+    assert_eq!(countdown.tick(secs(0.5)), None);
+}
@@ -0,0 +1,151 @@
+// Tests for dikto_core::diagnostics — redaction and the bundle contents.
+
+use dikto_core::config::DiktoConfig;
+use dikto_core::diagnostics::{config_json, model_json, recent_logs, redact, write_bundle};
+use dikto_core::{ModelFileRecord, ModelFileState, ModelInfoRecord, ModelState};
+use serde_json::json;
+
+// ---------------------------------------------------------------------------
+// redact
+// ---------------------------------------------------------------------------
+
+/// Paths under the home directory are shortened to "~".
+#[test]
+fn redact_home_paths() {
+    let mut value = json!({
+        "path": "/home/ana/models/x.bin",
+        "home": "/home/ana",
+        "other": "/home/anabel/x",
+        "list": ["/home/ana/a", "relative"],
+    });
+    redact(&mut value, Some("/home/ana"));
+    assert_eq!(value["path"], "~/models/x.bin");
+    assert_eq!(value["home"], "~");
+    assert_eq!(value["other"], "/home/anabel/x");
+    assert_eq!(value["list"], json!(["~/a", "relative"]));
+}
+
+/// Values of secret-looking keys are replaced, at any depth; unset ones stay null.
+#[test]
+fn redact_secret_keys() {
+    let mut value = json!({
+        "api_key": "sk-123",
+        "nested": { "HfToken": "hf_abc", "password": null },
+        "model_name": "parakeet-tdt-0.6b-v2",
+    });
+    redact(&mut value, None);
+    assert_eq!(value["api_key"], "[redacted]");
+    assert_eq!(value["nested"]["HfToken"], "[redacted]");
+    assert!(value["nested"]["password"].is_null());
+    assert_eq!(value["model_name"], "parakeet-tdt-0.6b-v2");
+}
+
+/// The exported config hides a model override under the home directory.
+#[test]
+fn config_json_redacts_override() {
+    let config = DiktoConfig {
+        model_path_override: Some("/home/ana/whisper/ggml.bin".to_string()),
+        ..DiktoConfig::default()
+    };
+    let value = config_json(&config, Some("/home/ana"));
+    assert_eq!(value["model_path_override"], "~/whisper/ggml.bin");
+    assert_eq!(value["model_name"], config.model_name);
+}
+
+/// Redaction keywords never reach a bundle.
+#[test]
+fn config_json_drops_redaction_keywords() {
+    let mut config = DiktoConfig::default();
+    config.redaction.keywords = vec!["Project Falcon".to_string()];
+    let value = config_json(&config, None);
+    assert!(value["redaction"].get("keywords").is_none());
+    assert_eq!(value["redaction"]["mask"], "[redacted]");
+}
+
+// ---------------------------------------------------------------------------
+// model_json
+// ---------------------------------------------------------------------------
+
+/// Each file lists its expected hash and what is on disk.
+#[test]
+fn model_json_lists_files() {
+    let model = ModelInfoRecord {
+        name: "m".to_string(),
+        size_mb: 10,
+        description: String::new(),
+        is_downloaded: false,
+        state: ModelState::PartiallyDownloaded,
+        backend: "Whisper".to_string(),
+        aliases: Vec::new(),
+        ram_mb: 0,
+        relative_speed: 0.0,
+        wer: 0.0,
+        languages: vec!["en".to_string()],
+        files: vec![
+            ModelFileRecord {
+                filename: "a.bin".to_string(),
+                size_mb: 10,
+                sha256: "abc".to_string(),
+                state: ModelFileState::Partial { bytes: 42 },
+            },
+            ModelFileRecord {
+                filename: "b.bin".to_string(),
+                size_mb: 1,
+                sha256: String::new(),
+                state: ModelFileState::Missing,
+            },
+        ],
+    };
+    let value = model_json(&model);
+    assert_eq!(value["state"], "PartiallyDownloaded");
+    assert_eq!(value["files"][0]["sha256"], "abc");
+    assert_eq!(value["files"][0]["state"], "partial");
+    assert_eq!(value["files"][0]["bytes"], 42);
+    assert_eq!(value["files"][1]["state"], "missing");
+}
+
+// ---------------------------------------------------------------------------
+// recent_logs / write_bundle
+// ---------------------------------------------------------------------------
+
+/// Only JSONL traces are picked, newest first, up to the limit.
+#[test]
+fn recent_logs_newest_first() {
+    let tmp = std::env::temp_dir().join("dikto_test_diagnostics_logs");
+    let _ = std::fs::remove_dir_all(&tmp);
+    std::fs::create_dir_all(&tmp).unwrap();
+    for name in ["session-1.jsonl", "session-2.jsonl", "session-3.jsonl"] {
+        std::fs::write(tmp.join(name), "{}\n").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    std::fs::write(tmp.join("session-3.wav"), "RIFF").unwrap();
+
+    let logs = recent_logs(&tmp, 2);
+    let names: Vec<_> = logs
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, ["session-3.jsonl", "session-2.jsonl"]);
+    assert!(recent_logs(&tmp.join("missing"), 2).is_empty());
+    let _ = std::fs::remove_dir_all(&tmp);
+}
+
+/// The bundle is a zip holding each file, in a freshly created directory.
+#[test]
+fn write_bundle_creates_zip() {
+    let tmp = std::env::temp_dir().join("dikto_test_diagnostics_bundle");
+    let _ = std::fs::remove_dir_all(&tmp);
+    let path = tmp.join("out/bundle.zip");
+    let files = vec![
+        ("config.json".to_string(), b"{}".to_vec()),
+        ("logs/session-1.jsonl".to_string(), b"{}\n".to_vec()),
+    ];
+    write_bundle(&path, &files).unwrap();
+
+    let data = std::fs::read(&path).unwrap();
+    assert_eq!(&data[..4], &[0x50, 0x4b, 0x03, 0x04]);
+    let eocd = data.len() - 22;
+    assert_eq!(&data[eocd..eocd + 4], &[0x50, 0x4b, 0x05, 0x06]);
+    assert_eq!(u16::from_le_bytes([data[eocd + 10], data[eocd + 11]]), 2);
+    let _ = std::fs::remove_dir_all(&tmp);
+}
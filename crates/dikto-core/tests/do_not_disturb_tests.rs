@@ -0,0 +1,111 @@
+// Tests for dikto_core::do_not_disturb — time windows, app rules, and the
+// screen-sharing hint.
+
+use dikto_core::config::{DiktoConfig, DoNotDisturbConfig};
+use dikto_core::do_not_disturb::{check, in_window, parse_window, Context, Reason};
+
+fn at(hours: u32, minutes: u32) -> Context<'static> {
+    Context {
+        minute_of_day: hours * 60 + minutes,
+        ..Context::default()
+    }
+}
+
+/// Windows parse to minutes after midnight; malformed ones don't parse.
+#[test]
+fn parse_windows() {
+    assert_eq!(parse_window("09:00-10:30"), Some((540, 630)));
+    assert_eq!(parse_window(" 22:00 - 07:00 "), Some((1320, 420)));
+    assert_eq!(parse_window("9-10"), None);
+    assert_eq!(parse_window("24:00-01:00"), None);
+    assert_eq!(parse_window("09:60-10:00"), None);
+    assert_eq!(parse_window("09:00"), None);
+}
+
+/// The end is exclusive, and windows may wrap past midnight.
+#[test]
+fn window_membership() {
+    let morning = (540, 630);
+    assert!(in_window(morning, 540));
+    assert!(in_window(morning, 629));
+    assert!(!in_window(morning, 630));
+    let night = (1320, 420);
+    assert!(in_window(night, 1400));
+    assert!(in_window(night, 0));
+    assert!(!in_window(night, 420));
+    assert!(!in_window(night, 720));
+}
+
+/// Nothing is refused by default.
+#[test]
+fn default_config_allows_everything() {
+    let config = DoNotDisturbConfig::default();
+    let context = Context {
+        screen_sharing: true,
+        app_name: "Keynote",
+        ..at(12, 0)
+    };
+    assert_eq!(check(&config, &context), None);
+}
+
+/// Each rule reports its own reason.
+#[test]
+fn rules_report_reasons() {
+    let config = DoNotDisturbConfig {
+        windows: vec!["12:00-13:00".to_string()],
+        apps: vec!["com.apple.Keynote".to_string(), "Zoom".to_string()],
+        while_screen_sharing: true,
+    };
+    assert_eq!(
+        check(&config, &at(12, 30)),
+        Some(Reason::Window("12:00-13:00".to_string()))
+    );
+    assert_eq!(check(&config, &at(13, 0)), None);
+
+    let keynote = Context {
+        bundle_id: "com.apple.keynote",
+        ..at(8, 0)
+    };
+    assert_eq!(
+        check(&config, &keynote),
+        Some(Reason::App("com.apple.Keynote".to_string()))
+    );
+    let zoom = Context {
+        app_name: "zoom",
+        ..at(8, 0)
+    };
+    assert!(matches!(check(&config, &zoom), Some(Reason::App(_))));
+
+    let sharing = Context {
+        screen_sharing: true,
+        ..at(8, 0)
+    };
+    assert_eq!(check(&config, &sharing), Some(Reason::ScreenSharing));
+    assert_eq!(
+        Reason::ScreenSharing.to_string(),
+        "the screen is being shared"
+    );
+}
+
+/// The screen-sharing hint only counts when the rule is on.
+#[test]
+fn screen_sharing_needs_opt_in() {
+    let config = DoNotDisturbConfig {
+        apps: vec!["Keynote".to_string()],
+        ..DoNotDisturbConfig::default()
+    };
+    let sharing = Context {
+        screen_sharing: true,
+        ..at(8, 0)
+    };
+    assert_eq!(check(&config, &sharing), None);
+}
+
+/// validate() drops windows that don't parse.
+#[test]
+fn validate_drops_bad_windows() {
+    let mut config = DiktoConfig::default();
+    config.do_not_disturb.windows = vec!["22:00-07:00".to_string(), "lunch".to_string()];
+    config.validate();
+    assert_eq!(config.do_not_disturb.windows, vec!["22:00-07:00"]);
+}
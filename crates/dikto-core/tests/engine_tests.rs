@@ -1,7 +1,12 @@
 // Tests for dikto_core::engine — hallucination detection, AsrSession buffer
-// accumulation, feed_samples, and buffer_duration_secs.
+// accumulation, feed_samples, trim_end, buffer_duration_secs, spooling,
+// no-speech filtering, overlap detection, and merging overlapping segments.
 
-use dikto_core::engine::{is_hallucination, AsrSession};
+use dikto_core::engine::{
+    is_hallucination, is_no_speech, is_possible_overlap, merge_overlapping_segments,
+    overlap_word_count, AsrSession, SPOOL_KEEP_SAMPLES, SPOOL_RAM_SAMPLES,
+};
+use dikto_core::transcribe::{TranscriptSegment, WordConfidence};
 
 // ---------------------------------------------------------------------------
 // is_hallucination — bracket-style tokens
@@ -184,3 +189,210 @@ fn buffer_duration_half_second() {
     session.feed_samples(&vec![0.0f32; 8000]);
     assert!((session.buffer_duration_secs() - 0.5).abs() < 0.01);
 }
+
+// ---------------------------------------------------------------------------
+// AsrSession — trim_end
+// ---------------------------------------------------------------------------
+
+/// trim_end drops samples from the end of the buffer.
+#[test]
+fn trim_end_drops_trailing_samples() {
+    let mut session = AsrSession::new("en".to_string());
+    session.feed_samples(&[0.0; 16000]);
+    session.trim_end(8000);
+    assert!((session.buffer_duration_secs() - 0.5).abs() < 1e-6);
+}
+
+/// Trimming more than is buffered empties the buffer without panicking.
+#[test]
+fn trim_end_saturates() {
+    let mut session = AsrSession::new("en".to_string());
+    session.feed_samples(&[0.0; 100]);
+    session.trim_end(1000);
+    assert_eq!(session.buffer_duration_secs(), 0.0);
+}
+
+// ---------------------------------------------------------------------------
+// AsrSession — spooling
+// ---------------------------------------------------------------------------
+
+/// Without spooling, all audio stays in RAM.
+#[test]
+fn spooling_off_by_default() {
+    let mut session = AsrSession::new("en".to_string());
+    session.feed_samples(&vec![0.0; SPOOL_RAM_SAMPLES + 16000]);
+    assert_eq!(session.spooled_samples(), 0);
+}
+
+/// Past the RAM threshold, older audio moves to disk but still counts
+/// toward the buffered duration.
+#[test]
+fn spooling_moves_older_audio_to_disk() {
+    let dir = std::env::temp_dir().join("dikto_test_engine_spool");
+    let mut session = AsrSession::new("en".to_string());
+    session.enable_spooling(dir.clone());
+    let total = SPOOL_RAM_SAMPLES + 16000;
+    session.feed_samples(&vec![0.0; total]);
+    assert_eq!(session.spooled_samples(), total - SPOOL_KEEP_SAMPLES);
+    assert!((session.buffer_duration_secs() - total as f32 / 16000.0).abs() < 1e-3);
+    drop(session);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// ---------------------------------------------------------------------------
+// is_no_speech
+// ---------------------------------------------------------------------------
+
+/// Segments more likely silence than the threshold are dropped.
+#[test]
+fn no_speech_above_threshold() {
+    assert!(is_no_speech(Some(0.9), 0.6));
+    assert!(!is_no_speech(Some(0.6), 0.6));
+    assert!(!is_no_speech(Some(0.1), 0.6));
+}
+
+/// Backends without a probability, and a threshold of 1.0, keep everything.
+#[test]
+fn no_speech_keeps_unknown_and_disabled() {
+    assert!(!is_no_speech(None, 0.0));
+    assert!(!is_no_speech(Some(1.0), 1.0));
+}
+
+// ---------------------------------------------------------------------------
+// is_possible_overlap
+// ---------------------------------------------------------------------------
+
+fn words(confidences: &[f32]) -> Vec<WordConfidence> {
+    confidences
+        .iter()
+        .map(|&confidence| WordConfidence {
+            word: "w".to_string(),
+            confidence,
+        })
+        .collect()
+}
+
+/// Saturated VAD with collapsed confidence is flagged.
+#[test]
+fn overlap_flagged_when_confidence_collapses() {
+    assert!(is_possible_overlap(
+        &[0.95, 0.97, 0.9],
+        &words(&[0.2, 0.3, 0.1])
+    ));
+}
+
+/// Confident words are not flagged, however loud the speech.
+#[test]
+fn overlap_not_flagged_when_confident() {
+    assert!(!is_possible_overlap(
+        &[0.95, 0.97],
+        &words(&[0.9, 0.8, 0.95])
+    ));
+}
+
+/// Uncertain words in borderline speech are just quiet audio, not crosstalk.
+#[test]
+fn overlap_not_flagged_when_vad_unsure() {
+    assert!(!is_possible_overlap(&[0.5, 0.6], &words(&[0.2, 0.3, 0.1])));
+}
+
+/// Too few words or no VAD data gives no verdict.
+#[test]
+fn overlap_needs_evidence() {
+    assert!(!is_possible_overlap(&[0.95], &words(&[0.1, 0.1])));
+    assert!(!is_possible_overlap(&[], &words(&[0.1, 0.1, 0.1])));
+}
+
+// ---------------------------------------------------------------------------
+// merge_overlapping_segments
+// ---------------------------------------------------------------------------
+
+/// A final segment from a window spanning `start`..`end` seconds, with a
+/// confidence for each word.
+fn segment(text: &str, start: f32, end: f32) -> TranscriptSegment {
+    TranscriptSegment {
+        text: text.to_string(),
+        is_final: true,
+        language: None,
+        words: text
+            .split_whitespace()
+            .map(|w| WordConfidence {
+                word: w.to_string(),
+                confidence: 0.9,
+            })
+            .collect(),
+        start_secs: Some(start),
+        end_secs: Some(end),
+        possible_overlap: false,
+        no_speech_prob: None,
+    }
+}
+
+fn texts(segments: &[TranscriptSegment]) -> Vec<&str> {
+    segments.iter().map(|s| s.text.as_str()).collect()
+}
+
+/// The longest suffix/prefix match wins, ignoring case and punctuation.
+#[test]
+fn overlap_word_count_finds_longest_match() {
+    assert_eq!(
+        overlap_word_count(
+            &["so", "we", "went", "home."],
+            &["Went", "home", "and", "slept"]
+        ),
+        2
+    );
+    assert_eq!(overlap_word_count(&["a", "b"], &["c", "d"]), 0);
+    assert_eq!(overlap_word_count(&[], &["a"]), 0);
+}
+
+/// Words repeated at the start of an overlapping window are dropped, along
+/// with their confidences, and the segment starts where the previous ended.
+#[test]
+fn merge_drops_repeated_boundary_words() {
+    let merged = merge_overlapping_segments(vec![
+        segment("the quick brown fox", 0.0, 30.0),
+        segment("brown fox jumps over the lazy dog", 28.0, 58.0),
+    ]);
+    assert_eq!(
+        texts(&merged),
+        vec!["the quick brown fox", "jumps over the lazy dog"]
+    );
+    assert_eq!(merged[1].words.len(), 5);
+    assert_eq!(merged[1].words[0].word, "jumps");
+    assert_eq!(merged[1].start_secs, Some(30.0));
+}
+
+/// Segments that don't overlap in time keep a genuine repetition.
+#[test]
+fn merge_keeps_repeats_without_time_overlap() {
+    let merged = merge_overlapping_segments(vec![
+        segment("say it again", 0.0, 2.0),
+        segment("again and again", 2.5, 4.0),
+    ]);
+    assert_eq!(texts(&merged), vec!["say it again", "again and again"]);
+}
+
+/// A window that only repeats the previous one is folded into it.
+#[test]
+fn merge_folds_fully_repeated_segment() {
+    let merged = merge_overlapping_segments(vec![
+        segment("hello there world", 0.0, 30.0),
+        segment("there world", 28.0, 31.0),
+    ]);
+    assert_eq!(texts(&merged), vec!["hello there world"]);
+    assert_eq!(merged[0].end_secs, Some(31.0));
+}
+
+/// Segments without timing are merged on their text alone.
+#[test]
+fn merge_untimed_segments_by_text() {
+    let mut first = segment("one two three", 0.0, 0.0);
+    let mut second = segment("three four", 0.0, 0.0);
+    first.start_secs = None;
+    first.end_secs = None;
+    second.start_secs = None;
+    second.end_secs = None;
+    let merged = merge_overlapping_segments(vec![first, second]);
+    assert_eq!(texts(&merged), vec!["one two three", "four"]);
+}
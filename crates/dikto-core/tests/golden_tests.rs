@@ -0,0 +1,197 @@
+// Golden transcript tests — run the clips in tests/golden through the whole
+// engine with real models and compare against their expected transcripts.
+// Needs `--features golden-tests` and network access on the first run; see
+// tests/golden/README.md.
+#![cfg(feature = "golden-tests")]
+
+use dikto_core::config::{DiktoConfig, PathProvider};
+use dikto_core::{models, DiktoEngine};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+struct Manifest {
+    vectors: Vec<Vector>,
+}
+
+/// One clip and the transcript expected for it. Clips with a `url` are
+/// downloaded instead of checked in.
+#[derive(Deserialize)]
+struct Vector {
+    clip: String,
+    language: String,
+    expected: String,
+    max_wer: f32,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    sha256: Option<String>,
+}
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+/// Where downloaded models are kept between runs.
+fn models_cache() -> PathBuf {
+    std::env::var_os("DIKTO_GOLDEN_MODELS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/golden-models"))
+}
+
+/// Where downloaded clips are kept between runs.
+fn clips_cache() -> PathBuf {
+    models_cache().join("clips")
+}
+
+/// Path of `vector`'s clip, downloading it on first use. A downloaded clip
+/// must match its hash once one is pinned.
+fn clip_path(vector: &Vector, rt: &tokio::runtime::Runtime) -> PathBuf {
+    let Some(url) = &vector.url else {
+        return golden_dir().join(&vector.clip);
+    };
+    let path = clips_cache().join(&vector.clip);
+    if !path.exists() {
+        let bytes = rt
+            .block_on(async { reqwest::get(url).await?.error_for_status()?.bytes().await })
+            .unwrap_or_else(|e| panic!("Failed to download {}: {e}", vector.clip));
+        std::fs::create_dir_all(clips_cache()).unwrap();
+        std::fs::write(&path, &bytes).unwrap();
+    }
+    match &vector.sha256 {
+        Some(expected) => assert!(
+            models::verify_file_sha256(&path, expected),
+            "{} does not match its sha256 in manifest.json",
+            vector.clip
+        ),
+        None => eprintln!(
+            "{} has no sha256 in manifest.json; pin the hash of {}",
+            vector.clip,
+            path.display()
+        ),
+    }
+    path
+}
+
+/// Keeps the test's config and data out of the user's real directories.
+struct GoldenPaths {
+    root: PathBuf,
+    models: PathBuf,
+}
+
+impl PathProvider for GoldenPaths {
+    fn config_dir(&self) -> String {
+        self.root.join("config").to_string_lossy().into_owned()
+    }
+
+    fn data_dir(&self) -> String {
+        self.root.join("data").to_string_lossy().into_owned()
+    }
+
+    fn models_dir(&self) -> Option<String> {
+        Some(self.models.to_string_lossy().into_owned())
+    }
+}
+
+/// Lowercase words without punctuation, so formatting differences don't count.
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| {
+            w.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '\'')
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Word-level edit distance over the expected word count. Any text at all
+/// counts as a full error when nothing was expected.
+fn word_error_rate(expected: &str, actual: &str) -> f32 {
+    let expected = words(expected);
+    let actual = words(actual);
+    if expected.is_empty() {
+        return if actual.is_empty() { 0.0 } else { 1.0 };
+    }
+    let mut row: Vec<usize> = (0..=actual.len()).collect();
+    for (i, e) in expected.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, a) in actual.iter().enumerate() {
+            let substitution = diagonal + usize::from(e != a);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[actual.len()] as f32 / expected.len() as f32
+}
+
+/// Every clip transcribes within its allowed word error rate on every model.
+#[test]
+fn golden_transcripts() {
+    let manifest = std::fs::read_to_string(golden_dir().join("manifest.json")).unwrap();
+    let manifest: Manifest = serde_json::from_str(&manifest).unwrap();
+    let model_names =
+        std::env::var("DIKTO_GOLDEN_MODELS").unwrap_or_else(|_| "whisper-tiny".to_string());
+
+    let root = std::env::temp_dir().join("dikto_test_golden");
+    let engine = DiktoEngine::with_path_provider(Arc::new(GoldenPaths {
+        root: root.clone(),
+        models: models_cache(),
+    }));
+    let rt = tokio::runtime::Runtime::new().unwrap();
+
+    let mut failures = Vec::new();
+    for model in model_names
+        .split(',')
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+    {
+        if !models::is_model_downloaded(model) {
+            rt.block_on(models::download_model(model, None, |_, _| {}))
+                .unwrap_or_else(|e| panic!("Failed to download {model}: {e}"));
+        }
+        engine
+            .update_config(DiktoConfig {
+                model_name: model.to_string(),
+                ..DiktoConfig::default()
+            })
+            .unwrap();
+
+        for vector in &manifest.vectors {
+            let path = clip_path(vector, &rt);
+            let result = engine
+                .transcribe_file(path.to_string_lossy().into_owned(), vector.language.clone())
+                .unwrap_or_else(|e| panic!("{model} failed on {}: {e}", vector.clip));
+            let wer = word_error_rate(&vector.expected, &result.text);
+            if wer > vector.max_wer {
+                failures.push(format!(
+                    "{model} / {}: expected {:?}, got {:?} (WER {wer:.2} > {:.2})",
+                    vector.clip, vector.expected, result.text, vector.max_wer
+                ));
+            }
+        }
+    }
+
+    let _ = std::fs::remove_dir_all(&root);
+    assert!(
+        failures.is_empty(),
+        "Golden transcripts changed:\n{}",
+        failures.join("\n")
+    );
+}
+
+/// The scorer itself: formatting is ignored, and each edit counts once.
+#[test]
+fn word_error_rate_scoring() {
+    assert_eq!(word_error_rate("Hello, world.", "hello world"), 0.0);
+    assert_eq!(
+        word_error_rate("the quick fox", "the quick brown fox"),
+        1.0 / 3.0
+    );
+    assert_eq!(word_error_rate("the quick fox", "the slow fox"), 1.0 / 3.0);
+    assert_eq!(word_error_rate("", ""), 0.0);
+    assert_eq!(word_error_rate("", "thank you"), 1.0);
+}
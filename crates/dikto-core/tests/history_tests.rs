@@ -0,0 +1,173 @@
+// Tests for dikto_core::history — JSONL persistence, word counting, period
+// filtering, WPM, and streak calculation.
+
+use dikto_core::history::{
+    append_entry, dictation_stats, load_entries, streak_days, transcribed_fingerprints, word_count,
+    HistoryEntry, StatsPeriod,
+};
+
+const DAY: u64 = 86_400;
+/// 2026-01-10 12:00:00 UTC
+const NOW: u64 = 1_768_046_400;
+
+fn entry(timestamp: u64, text: &str, speech_secs: f32) -> HistoryEntry {
+    HistoryEntry {
+        timestamp,
+        text: text.to_string(),
+        word_count: word_count(text),
+        speech_duration_secs: speech_secs,
+        model_name: "whisper-tiny".to_string(),
+        language: "en".to_string(),
+        raw_text: None,
+        audio_fingerprint: None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// word_count
+// ---------------------------------------------------------------------------
+
+/// Words should be split on any whitespace.
+#[test]
+fn word_count_splits_whitespace() {
+    assert_eq!(word_count("hello  world\nagain"), 3);
+    assert_eq!(word_count("   "), 0);
+}
+
+// ---------------------------------------------------------------------------
+// Persistence
+// ---------------------------------------------------------------------------
+
+/// Appended entries should load back in order.
+#[test]
+fn append_and_load_roundtrip() {
+    let tmp = std::env::temp_dir().join("dikto_test_history_roundtrip");
+    let _ = std::fs::remove_dir_all(&tmp);
+    let path = tmp.join("history.jsonl");
+
+    let first = entry(NOW - 10, "first entry", 1.0);
+    let second = entry(NOW, "second", 0.5);
+    append_entry(&path, &first).unwrap();
+    append_entry(&path, &second).unwrap();
+
+    assert_eq!(load_entries(&path), vec![first, second]);
+    let _ = std::fs::remove_dir_all(&tmp);
+}
+
+/// Corrupt lines should be skipped rather than failing the whole load.
+#[test]
+fn load_skips_corrupt_lines() {
+    let tmp = std::env::temp_dir().join("dikto_test_history_corrupt");
+    let _ = std::fs::remove_dir_all(&tmp);
+    let path = tmp.join("history.jsonl");
+
+    append_entry(&path, &entry(NOW, "good", 1.0)).unwrap();
+    let mut contents = std::fs::read_to_string(&path).unwrap();
+    contents.push_str("{not json\n");
+    std::fs::write(&path, contents).unwrap();
+
+    assert_eq!(load_entries(&path).len(), 1);
+    let _ = std::fs::remove_dir_all(&tmp);
+}
+
+/// The raw transcript is stored only when present, and entries written
+/// before it existed still load.
+#[test]
+fn raw_text_is_optional() {
+    let tmp = std::env::temp_dir().join("dikto_test_history_raw");
+    let _ = std::fs::remove_dir_all(&tmp);
+    let path = tmp.join("history.jsonl");
+
+    let plain = entry(NOW - 10, "plain", 1.0);
+    let with_raw = HistoryEntry {
+        raw_text: Some("um plain".to_string()),
+        ..entry(NOW, "plain", 1.0)
+    };
+    append_entry(&path, &plain).unwrap();
+    append_entry(&path, &with_raw).unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(contents.matches("raw_text").count(), 1);
+    assert_eq!(load_entries(&path), vec![plain, with_raw]);
+    let _ = std::fs::remove_dir_all(&tmp);
+}
+
+/// Only entries from transcribed files contribute fingerprints.
+#[test]
+fn transcribed_fingerprints_skip_dictation() {
+    let from_file = HistoryEntry {
+        audio_fingerprint: Some("00ff00ff00ff00ff".to_string()),
+        ..entry(NOW, "memo", 1.0)
+    };
+    let fingerprints = transcribed_fingerprints(&[entry(NOW - 10, "dictated", 1.0), from_file]);
+    assert_eq!(fingerprints.len(), 1);
+    assert!(fingerprints.contains("00ff00ff00ff00ff"));
+}
+
+// ---------------------------------------------------------------------------
+// dictation_stats
+// ---------------------------------------------------------------------------
+
+/// Stats should only include entries inside the requested period.
+#[test]
+fn stats_filter_by_period() {
+    let entries = vec![
+        entry(NOW - 40 * DAY, "old old old", 1.0),
+        entry(NOW - 10 * DAY, "this month", 1.0),
+        entry(NOW - 3 * DAY, "this week", 1.0),
+        entry(NOW - 60, "today", 1.0),
+    ];
+    assert_eq!(dictation_stats(&entries, StatsPeriod::Today, NOW).words, 1);
+    assert_eq!(dictation_stats(&entries, StatsPeriod::Week, NOW).words, 3);
+    assert_eq!(dictation_stats(&entries, StatsPeriod::Month, NOW).words, 5);
+    assert_eq!(
+        dictation_stats(&entries, StatsPeriod::AllTime, NOW).words,
+        8
+    );
+}
+
+/// Average WPM should be words per minute of speech.
+#[test]
+fn stats_average_wpm() {
+    let entries = vec![entry(NOW, "one two three four five six", 3.0)];
+    let stats = dictation_stats(&entries, StatsPeriod::Today, NOW);
+    assert_eq!(stats.sessions, 1);
+    assert!((stats.average_wpm - 120.0).abs() < 1e-6);
+}
+
+/// No speech should give 0 WPM rather than dividing by zero.
+#[test]
+fn stats_empty_has_zero_wpm() {
+    let stats = dictation_stats(&[], StatsPeriod::AllTime, NOW);
+    assert_eq!(stats.words, 0);
+    assert!((stats.average_wpm - 0.0).abs() < f64::EPSILON);
+}
+
+// ---------------------------------------------------------------------------
+// streak_days
+// ---------------------------------------------------------------------------
+
+/// Consecutive days ending today should count as a streak.
+#[test]
+fn streak_counts_consecutive_days() {
+    let entries = vec![
+        entry(NOW - 2 * DAY, "a", 1.0),
+        entry(NOW - DAY, "b", 1.0),
+        entry(NOW, "c", 1.0),
+    ];
+    assert_eq!(streak_days(&entries, NOW), 3);
+}
+
+/// A streak ending yesterday should still count if nothing was dictated today.
+#[test]
+fn streak_continues_from_yesterday() {
+    let entries = vec![entry(NOW - 2 * DAY, "a", 1.0), entry(NOW - DAY, "b", 1.0)];
+    assert_eq!(streak_days(&entries, NOW), 2);
+}
+
+/// A gap should break the streak.
+#[test]
+fn streak_broken_by_gap() {
+    let entries = vec![entry(NOW - 3 * DAY, "a", 1.0), entry(NOW, "b", 1.0)];
+    assert_eq!(streak_days(&entries, NOW), 1);
+}
@@ -0,0 +1,148 @@
+// Tests for dikto_core::level — dynamic range measurement and peak/RMS
+// normalization of utterance buffers before inference.
+
+use dikto_core::config::InputNormalization;
+use dikto_core::level::{
+    dynamic_range_db, normalization_gain, normalize, MAX_GAIN, PEAK_TARGET, RMS_TARGET,
+};
+
+/// One second of quiet "speech": a 220 Hz tone at `amplitude` for the middle
+/// half, with near-silence before and after.
+fn quiet_utterance(amplitude: f32) -> Vec<f32> {
+    (0..16_000)
+        .map(|i| {
+            if (4_000..12_000).contains(&i) {
+                amplitude * (i as f32 * 220.0 * std::f32::consts::TAU / 16_000.0).sin()
+            } else {
+                0.0001 * if i % 2 == 0 { 1.0 } else { -1.0 }
+            }
+        })
+        .collect()
+}
+
+/// Steady low-level noise with no louder passages.
+fn steady_noise(amplitude: f32) -> Vec<f32> {
+    (0..16_000)
+        .map(|i| amplitude * if i % 3 == 0 { 1.0 } else { -0.5 })
+        .collect()
+}
+
+fn peak(samples: &[f32]) -> f32 {
+    samples.iter().fold(0.0f32, |m, s| m.max(s.abs()))
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+// ---------------------------------------------------------------------------
+// Dynamic range
+// ---------------------------------------------------------------------------
+
+/// Speech over a quiet background should have a wide dynamic range.
+#[test]
+fn dynamic_range_of_speech_is_wide() {
+    assert!(dynamic_range_db(&quiet_utterance(0.05)) > 40.0);
+}
+
+/// Steady noise should have almost no dynamic range.
+#[test]
+fn dynamic_range_of_steady_noise_is_narrow() {
+    assert!(dynamic_range_db(&steady_noise(0.01)) < 1.0);
+}
+
+/// Buffers shorter than two frames and pure silence should score 0.
+#[test]
+fn dynamic_range_of_short_or_silent_buffer_is_zero() {
+    assert_eq!(dynamic_range_db(&[0.5; 100]), 0.0);
+    assert_eq!(dynamic_range_db(&[0.0; 16_000]), 0.0);
+}
+
+// ---------------------------------------------------------------------------
+// Normalization
+// ---------------------------------------------------------------------------
+
+/// Off should never change the buffer.
+#[test]
+fn off_leaves_samples_untouched() {
+    let original = quiet_utterance(0.05);
+    let mut samples = original.clone();
+    assert_eq!(normalize(&mut samples, InputNormalization::Off, 12.0), 1.0);
+    assert_eq!(samples, original);
+}
+
+/// Peak mode should bring a quiet utterance's loudest sample to PEAK_TARGET.
+#[test]
+fn peak_mode_raises_peak_to_target() {
+    let mut samples = quiet_utterance(0.05);
+    let gain = normalize(&mut samples, InputNormalization::Peak, 12.0);
+    assert!(gain > 1.0);
+    assert!((peak(&samples) - PEAK_TARGET).abs() < 1e-3);
+}
+
+/// RMS mode should reach RMS_TARGET when that keeps peaks in range.
+#[test]
+fn rms_mode_raises_level_to_target() {
+    let mut samples = quiet_utterance(0.02);
+    normalize(&mut samples, InputNormalization::Rms, 12.0);
+    assert!((rms(&samples) - RMS_TARGET).abs() < 1e-3);
+    assert!(peak(&samples) <= PEAK_TARGET + 1e-4);
+}
+
+/// RMS mode should stop at PEAK_TARGET rather than clip a peaky buffer.
+#[test]
+fn rms_mode_never_pushes_peak_past_target() {
+    let mut samples = quiet_utterance(0.02);
+    samples[8_000] = 0.5;
+    normalize(&mut samples, InputNormalization::Rms, 12.0);
+    assert!((peak(&samples) - PEAK_TARGET).abs() < 1e-3);
+    assert!(rms(&samples) < RMS_TARGET);
+}
+
+/// Gain should be capped at MAX_GAIN for a barely audible recording.
+#[test]
+fn gain_is_capped() {
+    let samples = quiet_utterance(0.001);
+    assert_eq!(
+        normalization_gain(&samples, InputNormalization::Peak, 12.0),
+        MAX_GAIN
+    );
+}
+
+/// Recordings already at or above the target should not be attenuated.
+#[test]
+fn loud_recording_is_not_attenuated() {
+    let samples = quiet_utterance(0.99);
+    assert_eq!(
+        normalization_gain(&samples, InputNormalization::Peak, 12.0),
+        1.0
+    );
+    assert_eq!(
+        normalization_gain(&samples, InputNormalization::Rms, 12.0),
+        1.0
+    );
+}
+
+/// Noise with a dynamic range under the floor should not be amplified.
+#[test]
+fn noise_below_range_floor_is_not_amplified() {
+    let original = steady_noise(0.01);
+    let mut samples = original.clone();
+    assert_eq!(normalize(&mut samples, InputNormalization::Rms, 12.0), 1.0);
+    assert_eq!(samples, original);
+}
+
+/// A floor of 0 should normalize even steady noise.
+#[test]
+fn zero_range_floor_normalizes_noise() {
+    let mut samples = steady_noise(0.01);
+    assert!(normalize(&mut samples, InputNormalization::Peak, 0.0) > 1.0);
+}
+
+/// Digital silence should be left alone in every mode.
+#[test]
+fn silence_is_not_amplified() {
+    let mut samples = vec![0.0; 16_000];
+    assert_eq!(normalize(&mut samples, InputNormalization::Peak, 0.0), 1.0);
+    assert_eq!(normalize(&mut samples, InputNormalization::Rms, 0.0), 1.0);
+}
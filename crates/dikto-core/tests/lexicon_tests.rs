@@ -0,0 +1,102 @@
+// Tests for dikto_core::lexicon — term extraction, correction learning,
+// ranking, and persistence.
+
+use dikto_core::history::{word_count, HistoryEntry};
+use dikto_core::lexicon::{build_terms, candidate_terms, correction_terms, Lexicon};
+
+fn entry(text: &str) -> HistoryEntry {
+    HistoryEntry {
+        timestamp: 0,
+        text: text.to_string(),
+        word_count: word_count(text),
+        speech_duration_secs: 1.0,
+        model_name: "whisper-tiny".to_string(),
+        language: "en".to_string(),
+        raw_text: None,
+        audio_fingerprint: None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Term extraction
+// ---------------------------------------------------------------------------
+
+/// Proper nouns mid-sentence and jargon count; sentence-initial capitals don't.
+#[test]
+fn candidate_terms_picks_distinctive_words() {
+    let terms = candidate_terms("Then ask Okafor about the iOS build. Deploy GPT4 to Kubernetes.");
+    assert_eq!(terms, vec!["Okafor", "iOS", "GPT4", "Kubernetes"]);
+}
+
+/// Repeated terms within one transcript are listed once.
+#[test]
+fn candidate_terms_deduplicates() {
+    assert_eq!(candidate_terms("ping Okafor and Okafor"), vec!["Okafor"]);
+}
+
+/// Corrections yield the words the user introduced.
+#[test]
+fn correction_terms_finds_new_words() {
+    assert_eq!(
+        correction_terms("call oak offer tomorrow", "call Okafor tomorrow"),
+        vec!["Okafor"]
+    );
+    assert!(correction_terms("same text", "same text").is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// Ranking
+// ---------------------------------------------------------------------------
+
+/// One-off terms are ignored; recurring ones rank by frequency.
+#[test]
+fn build_terms_requires_recurrence() {
+    let entries = vec![
+        entry("ask Okafor about Kubernetes"),
+        entry("ask Okafor again"),
+        entry("tell Okafor about Kubernetes and Zanzibar"),
+    ];
+    let terms = build_terms(&entries, &Lexicon::default(), 10);
+    assert_eq!(terms, vec!["Okafor", "Kubernetes"]);
+}
+
+/// Corrections count even without history, and outweigh history.
+#[test]
+fn build_terms_weights_corrections() {
+    let entries = vec![entry("ask Okafor"), entry("ask Okafor")];
+    let mut lexicon = Lexicon::default();
+    lexicon.record_correction("use cube control", "use kubectl");
+    let terms = build_terms(&entries, &lexicon, 10);
+    assert_eq!(terms, vec!["kubectl", "Okafor"]);
+}
+
+/// The term list is capped.
+#[test]
+fn build_terms_caps_length() {
+    let entries: Vec<HistoryEntry> = (0..2)
+        .map(|_| entry("ping Alpha1 Beta2 Gamma3 Delta4"))
+        .collect();
+    assert_eq!(build_terms(&entries, &Lexicon::default(), 2).len(), 2);
+}
+
+// ---------------------------------------------------------------------------
+// Persistence
+// ---------------------------------------------------------------------------
+
+/// Lexicon save/load roundtrips; a missing file loads empty.
+#[test]
+fn lexicon_roundtrip() {
+    let tmp = std::env::temp_dir().join("dikto_test_lexicon_roundtrip");
+    let _ = std::fs::remove_dir_all(&tmp);
+    let path = tmp.join("lexicon.json");
+    assert_eq!(Lexicon::load_from(&path), Lexicon::default());
+
+    let mut lexicon = Lexicon::default();
+    lexicon.record_correction("hi oak offer", "hi Okafor");
+    lexicon.record_correction("oak offer", "Okafor");
+    lexicon.save_to(&path).unwrap();
+
+    let loaded = Lexicon::load_from(&path);
+    assert_eq!(loaded.corrections.get("Okafor"), Some(&2));
+    let _ = std::fs::remove_dir_all(&tmp);
+}
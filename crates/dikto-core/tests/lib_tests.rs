@@ -1,15 +1,23 @@
 // Tests for dikto_core top-level types — SessionHandle, ListenConfig, DiktoError,
-// RecordingState, ModelInfoRecord, LanguageInfo, and language helper functions.
+// RecordingState, ModelInfoRecord, LanguageInfo, language helper functions,
+// and the callbacks for a final segment.
 
 use dikto_core::audio::AudioError;
-use dikto_core::config::DiktoConfig;
+use dikto_core::config::{
+    data_dir, set_data_dir_override, AppProfile, DiktoConfig, TextProcPreset,
+};
+use dikto_core::messages::ErrorCode;
 use dikto_core::models::ModelError;
-use dikto_core::transcribe::TranscribeError;
+use dikto_core::output::{OutputAction, OutputTarget};
+use dikto_core::transcribe::{TranscribeError, TranscriptSegment, WordConfidence};
 use dikto_core::vad::VadError;
 use dikto_core::{
-    parakeet_v3_languages, whisper_languages, DiktoError, LanguageInfo, ListenConfig,
-    ModelInfoRecord, RecordingState, SessionHandle,
+    dry_run_actions_for_test, emit_segment_for_test, filter_models, parakeet_v3_languages,
+    session_snapshot_for_test, whisper_languages, DiktoEngineBuilder, DiktoError, LanguageInfo,
+    ListenConfig, ModelInfoRecord, ModelSortKey, ModelState, ModelSwitchHandle, OverlapWarning,
+    RecordingState, SessionHandle, SessionSource, SessionState, TranscriptionCallback,
 };
+use std::sync::{Arc, Mutex};
 
 // ---------------------------------------------------------------------------
 // SessionHandle
@@ -22,6 +30,14 @@ fn session_handle_is_active_initially() {
     assert!(handle.is_active());
 }
 
+/// feed_audio on a session without host audio is ignored, not a panic.
+#[test]
+fn session_handle_feed_audio_without_host_audio() {
+    let handle = SessionHandle::new_for_test();
+    handle.feed_audio(vec![0.0; 160]);
+    assert!(handle.is_active());
+}
+
 /// Calling stop() should make is_active() return false.
 #[test]
 fn session_handle_stop_makes_inactive() {
@@ -30,6 +46,15 @@ fn session_handle_stop_makes_inactive() {
     assert!(!handle.is_active());
 }
 
+/// stop_capture_now() stops the session and, with no microphone open,
+/// returns at once.
+#[test]
+fn session_handle_stop_capture_now() {
+    let handle = SessionHandle::new_for_test();
+    assert!(handle.stop_capture_now());
+    assert!(!handle.is_active());
+}
+
 /// Calling stop() twice should be harmless (idempotent).
 #[test]
 fn session_handle_double_stop() {
@@ -39,6 +64,26 @@ fn session_handle_double_stop() {
     assert!(!handle.is_active());
 }
 
+/// A session that hasn't started listening reports no time, no buffered
+/// audio, and no speech.
+#[test]
+fn session_handle_stats_before_start() {
+    let handle = SessionHandle::new_for_test();
+    assert_eq!(handle.elapsed_secs(), 0.0);
+    assert_eq!(handle.buffered_audio_secs(), 0.0);
+    assert!(!handle.speech_detected());
+}
+
+/// A session that hasn't started listening is listed as queued, from the
+/// microphone.
+#[test]
+fn session_handle_summary_before_start() {
+    let summary = SessionHandle::new_for_test().summary();
+    assert_eq!(summary.state, SessionState::Queued);
+    assert_eq!(summary.source, SessionSource::Microphone);
+    assert_eq!(summary.elapsed_secs, 0.0);
+}
+
 // ---------------------------------------------------------------------------
 // ListenConfig
 // ---------------------------------------------------------------------------
@@ -51,6 +96,11 @@ fn listen_config_default() {
     assert_eq!(config.max_duration, 30);
     assert_eq!(config.silence_duration_ms, 1500);
     assert!((config.speech_threshold - 0.35).abs() < f32::EPSILON);
+    assert!(!config.continuous);
+    assert!(!config.long_form);
+    assert_eq!(config.flush_threshold_secs, 30);
+    assert!(!config.host_audio);
+    assert_eq!(config.text_preset, TextProcPreset::Config);
 }
 
 /// ListenConfig::from(&DiktoConfig) should copy the relevant fields.
@@ -61,13 +111,46 @@ fn listen_config_from_dikto_config() {
         max_duration: 60,
         silence_duration_ms: 2000,
         speech_threshold: 0.5,
+        flush_threshold_secs: 45,
         ..DiktoConfig::default()
     };
     let listen_config = ListenConfig::from(&dikto_config);
+    assert_eq!(listen_config.flush_threshold_secs, 45);
     assert_eq!(listen_config.language, "fr");
     assert_eq!(listen_config.max_duration, 60);
     assert_eq!(listen_config.silence_duration_ms, 2000);
     assert!((listen_config.speech_threshold - 0.5).abs() < f32::EPSILON);
+    assert!(!listen_config.continuous);
+    assert!(!listen_config.host_audio);
+}
+
+/// continuous_mode in DiktoConfig should map to ListenConfig::continuous.
+#[test]
+fn listen_config_continuous_from_dikto_config() {
+    let dikto_config = DiktoConfig {
+        continuous_mode: true,
+        ..DiktoConfig::default()
+    };
+    assert!(ListenConfig::from(&dikto_config).continuous);
+}
+
+/// long_form in DiktoConfig should map to ListenConfig::long_form.
+#[test]
+fn listen_config_long_form_from_dikto_config() {
+    let dikto_config = DiktoConfig {
+        long_form: true,
+        ..DiktoConfig::default()
+    };
+    assert!(ListenConfig::from(&dikto_config).long_form);
+}
+
+/// output.dry_run in DiktoConfig should map to ListenConfig::dry_run.
+#[test]
+fn listen_config_dry_run_from_dikto_config() {
+    let mut dikto_config = DiktoConfig::default();
+    assert!(!ListenConfig::from(&dikto_config).dry_run);
+    dikto_config.output.dry_run = true;
+    assert!(ListenConfig::from(&dikto_config).dry_run);
 }
 
 // ---------------------------------------------------------------------------
@@ -133,6 +216,29 @@ fn dikto_error_config_display() {
     assert!(msg.contains("bad json"));
 }
 
+/// Each DiktoError maps to its own code.
+#[test]
+fn dikto_error_codes() {
+    assert_eq!(DiktoError::NoModel.code(), ErrorCode::NoModel);
+    assert_eq!(
+        DiktoError::DoNotDisturb("screen sharing".to_string()).code(),
+        ErrorCode::DoNotDisturb
+    );
+    assert_eq!(
+        DiktoError::RateLimited { retry_after_ms: 10 }.code(),
+        ErrorCode::RateLimited
+    );
+    assert_eq!(
+        DiktoError::AlreadyRecording.code(),
+        ErrorCode::AlreadyRecording
+    );
+    assert_eq!(DiktoError::Audio("x".to_string()).code(), ErrorCode::Audio);
+    assert_eq!(
+        DiktoError::Config("x".to_string()).code(),
+        ErrorCode::Config
+    );
+}
+
 // ---------------------------------------------------------------------------
 // DiktoError — From impls
 // ---------------------------------------------------------------------------
@@ -146,6 +252,15 @@ fn dikto_error_from_audio_error() {
     assert!(dikto_err.to_string().contains("No input device"));
 }
 
+/// Denied microphone access gets its own DiktoError and code.
+#[test]
+fn dikto_error_from_permission_denied() {
+    let dikto_err: DiktoError = AudioError::PermissionDenied.into();
+    assert!(matches!(dikto_err, DiktoError::PermissionDenied));
+    assert_eq!(dikto_err.code(), ErrorCode::PermissionDenied);
+    assert!(dikto_err.to_string().contains("Microphone access denied"));
+}
+
 /// VadError should convert into DiktoError::Vad.
 #[test]
 fn dikto_error_from_vad_error() {
@@ -207,9 +322,11 @@ fn recording_state_done_carries_text() {
 fn recording_state_error_carries_message() {
     let state = RecordingState::Error {
         message: "boom".to_string(),
+        code: ErrorCode::Internal,
     };
-    if let RecordingState::Error { message } = &state {
+    if let RecordingState::Error { message, code } = &state {
         assert_eq!(message, "boom");
+        assert_eq!(*code, ErrorCode::Internal);
     } else {
         panic!("Expected Error variant");
     }
@@ -225,6 +342,19 @@ fn recording_state_clone() {
     assert_eq!(state, cloned);
 }
 
+// ---------------------------------------------------------------------------
+// ModelSwitchHandle
+// ---------------------------------------------------------------------------
+
+/// A new ModelSwitchHandle is not cancelled until cancel() is called.
+#[test]
+fn model_switch_handle_cancel() {
+    let handle = ModelSwitchHandle::default();
+    assert!(!handle.is_cancelled());
+    handle.cancel();
+    assert!(handle.is_cancelled());
+}
+
 // ---------------------------------------------------------------------------
 // ModelInfoRecord
 // ---------------------------------------------------------------------------
@@ -237,13 +367,23 @@ fn model_info_record_construction() {
         size_mb: 100,
         description: "A test model".to_string(),
         is_downloaded: false,
+        state: ModelState::NotDownloaded,
         backend: "Parakeet".to_string(),
+        aliases: vec!["default".to_string()],
+        ram_mb: 3000,
+        relative_speed: 0.05,
+        wer: 6.05,
+        languages: vec!["en".to_string()],
+        files: Vec::new(),
     };
     assert_eq!(record.name, "test-model");
     assert_eq!(record.size_mb, 100);
     assert_eq!(record.description, "A test model");
     assert!(!record.is_downloaded);
     assert_eq!(record.backend, "Parakeet");
+    assert_eq!(record.aliases, vec!["default".to_string()]);
+    assert_eq!(record.ram_mb, 3000);
+    assert_eq!(record.languages, vec!["en".to_string()]);
 }
 
 /// ModelInfoRecord should be clonable.
@@ -254,13 +394,118 @@ fn model_info_record_clone() {
         size_mb: 50,
         description: "d".to_string(),
         is_downloaded: true,
+        state: ModelState::Downloaded,
         backend: "Whisper".to_string(),
+        aliases: Vec::new(),
+        ram_mb: 250,
+        relative_speed: 0.02,
+        wer: 12.8,
+        languages: Vec::new(),
+        files: Vec::new(),
     };
     let cloned = record.clone();
     assert_eq!(cloned.name, "m");
     assert!(cloned.is_downloaded);
 }
 
+// ---------------------------------------------------------------------------
+// filter_models
+// ---------------------------------------------------------------------------
+
+fn record(
+    name: &str,
+    backend: &str,
+    size_mb: u32,
+    wer: f32,
+    languages: &[&str],
+) -> ModelInfoRecord {
+    ModelInfoRecord {
+        name: name.to_string(),
+        size_mb,
+        description: String::new(),
+        is_downloaded: name != "large",
+        state: if name == "large" {
+            ModelState::PartiallyDownloaded
+        } else {
+            ModelState::Downloaded
+        },
+        backend: backend.to_string(),
+        aliases: Vec::new(),
+        ram_mb: size_mb,
+        relative_speed: size_mb as f32 / 10_000.0,
+        wer,
+        languages: languages.iter().map(|l| l.to_string()).collect(),
+        files: Vec::new(),
+    }
+}
+
+fn sample_models() -> Vec<ModelInfoRecord> {
+    vec![
+        record("parakeet", "Parakeet", 2500, 6.0, &["en"]),
+        record("large", "Whisper", 1600, 7.8, &["en", "de"]),
+        record("tiny", "Whisper", 75, 12.8, &["en", "de"]),
+        record("custom", "OnnxNemo", 600, 0.0, &["fr"]),
+    ]
+}
+
+fn names(models: &[ModelInfoRecord]) -> Vec<&str> {
+    models.iter().map(|m| m.name.as_str()).collect()
+}
+
+/// With no filters and the default order, every model is returned as given.
+#[test]
+fn filter_models_no_filters() {
+    let models = filter_models(sample_models(), None, None, false, ModelSortKey::Default);
+    assert_eq!(names(&models), ["parakeet", "large", "tiny", "custom"]);
+}
+
+/// Backend matches the label ignoring case.
+#[test]
+fn filter_models_by_backend() {
+    let models = filter_models(
+        sample_models(),
+        Some("whisper".to_string()),
+        None,
+        false,
+        ModelSortKey::Default,
+    );
+    assert_eq!(names(&models), ["large", "tiny"]);
+}
+
+/// Language and downloaded_only combine.
+#[test]
+fn filter_models_by_language_and_download() {
+    let models = filter_models(
+        sample_models(),
+        None,
+        Some("de".to_string()),
+        true,
+        ModelSortKey::Default,
+    );
+    assert_eq!(names(&models), ["tiny"]);
+}
+
+/// Size sorts smallest first, Name alphabetically.
+#[test]
+fn filter_models_sort_size_and_name() {
+    let by_size = filter_models(sample_models(), None, None, false, ModelSortKey::Size);
+    assert_eq!(names(&by_size), ["tiny", "custom", "large", "parakeet"]);
+    let by_name = filter_models(sample_models(), None, None, false, ModelSortKey::Name);
+    assert_eq!(names(&by_name), ["custom", "large", "parakeet", "tiny"]);
+}
+
+/// Accuracy and Speed sort models with unknown metadata last.
+#[test]
+fn filter_models_sort_unknown_last() {
+    let by_accuracy = filter_models(sample_models(), None, None, false, ModelSortKey::Accuracy);
+    assert_eq!(names(&by_accuracy), ["parakeet", "large", "tiny", "custom"]);
+
+    let mut models = sample_models();
+    models[3].relative_speed = 0.0;
+    let by_speed = filter_models(models, None, None, false, ModelSortKey::Speed);
+    assert_eq!(names(&by_speed), ["tiny", "large", "parakeet", "custom"]);
+}
+
 // ---------------------------------------------------------------------------
 // LanguageInfo
 // ---------------------------------------------------------------------------
@@ -348,3 +593,233 @@ fn whisper_languages_auto_is_first() {
     assert_eq!(langs[0].code, "auto");
     assert_eq!(langs[0].name, "Auto-detect");
 }
+
+// ---------------------------------------------------------------------------
+// DiktoEngineBuilder
+// ---------------------------------------------------------------------------
+
+/// An injected config is validated and used instead of config.json.
+#[test]
+fn builder_uses_injected_config() {
+    let engine = DiktoEngineBuilder::new()
+        .config(DiktoConfig {
+            language: "de".to_string(),
+            max_duration: 0,
+            ..DiktoConfig::default()
+        })
+        .without_config_file()
+        .build();
+    let config = engine.get_config();
+    assert_eq!(config.language, "de");
+    assert!(config.max_duration > 0);
+}
+
+/// Without a config file, the engine starts from defaults and keeps
+/// updates in memory.
+#[test]
+fn builder_without_config_file_keeps_updates_in_memory() {
+    let engine = DiktoEngineBuilder::new().without_config_file().build();
+    assert_eq!(
+        engine.get_config().language,
+        DiktoConfig::default().language
+    );
+    engine
+        .update_config(DiktoConfig {
+            language: "fr".to_string(),
+            ..DiktoConfig::default()
+        })
+        .unwrap();
+    assert_eq!(engine.get_config().language, "fr");
+}
+
+/// The data directory is process-wide: an engine built without a data_dir
+/// keeps the one an earlier engine set.
+#[test]
+fn builder_keeps_data_dir_of_earlier_engine() {
+    let moved = std::env::temp_dir().join("dikto_builder_data_dir");
+    let _first = DiktoEngineBuilder::new()
+        .config(DiktoConfig {
+            data_dir: Some(moved.to_string_lossy().into_owned()),
+            ..DiktoConfig::default()
+        })
+        .without_config_file()
+        .build();
+    let _second = DiktoEngineBuilder::new().without_config_file().build();
+    assert_eq!(data_dir().unwrap(), moved);
+    set_data_dir_override(None);
+}
+
+/// A new engine has no sessions, and stopping them all is a no-op.
+#[test]
+fn engine_without_sessions() {
+    let engine = DiktoEngineBuilder::new().without_config_file().build();
+    assert!(engine.active_sessions().is_empty());
+    assert!(engine.stop_all());
+}
+
+/// listen_config_for applies the target app's translation language.
+#[test]
+fn listen_config_for_uses_profile_translation() {
+    let mut config = DiktoConfig::default();
+    config.translation.target_language = "en".to_string();
+    config.output.app_profiles = vec![AppProfile {
+        app: "Slack".to_string(),
+        translate_to: Some("de".to_string()),
+        ..AppProfile::default()
+    }];
+    let engine = DiktoEngineBuilder::new()
+        .config(config)
+        .without_config_file()
+        .build();
+    let slack = OutputTarget {
+        app_name: "Slack".to_string(),
+        ..OutputTarget::default()
+    };
+    assert_eq!(engine.listen_config_for(slack).translate_to, "de");
+    assert_eq!(
+        engine
+            .listen_config_for(OutputTarget::default())
+            .translate_to,
+        "en"
+    );
+}
+
+/// A dry run reports the paste and copy the host would have done, with the
+/// target app's profile applied.
+#[test]
+fn dry_run_reports_paste_and_copy() {
+    let mut config = DiktoConfig::default();
+    config.save_history = true;
+    config.output.app_profiles = vec![AppProfile {
+        app: "Terminal".to_string(),
+        auto_paste: Some(false),
+        ..AppProfile::default()
+    }];
+    let listen = |app: &str| ListenConfig {
+        app_name: app.to_string(),
+        dry_run: true,
+        ..ListenConfig::default()
+    };
+    assert_eq!(
+        dry_run_actions_for_test(&config, listen("Notes"), "Hello."),
+        vec![
+            OutputAction::RecordHistory,
+            OutputAction::Paste {
+                app: "Notes".to_string(),
+                chars: 6
+            },
+            OutputAction::Copy { chars: 6 },
+        ]
+    );
+    assert_eq!(
+        dry_run_actions_for_test(&config, listen("Terminal"), "Hello."),
+        vec![OutputAction::RecordHistory, OutputAction::Copy { chars: 6 }]
+    );
+}
+
+/// The settings saved with a recording leave out the redaction keywords,
+/// API keys, endpoints, and the summary command.
+#[test]
+fn session_snapshot_leaves_out_private_settings() {
+    let mut config = DiktoConfig::default();
+    config.redaction.keywords = vec!["project falcon".to_string()];
+    config.translation.api_url = "https://translate.example.com".to_string();
+    config.translation.api_key = "secret".to_string();
+    config.summary.api_url = "https://summary.example.com".to_string();
+    config.summary.api_key = "secret".to_string();
+    config.summary.command = "llm --summarize".to_string();
+    let snapshot = session_snapshot_for_test(&config);
+    assert!(snapshot.redaction.keywords.is_empty());
+    assert!(snapshot.translation.api_url.is_empty());
+    assert!(snapshot.translation.api_key.is_empty());
+    assert!(snapshot.summary.api_url.is_empty());
+    assert!(snapshot.summary.api_key.is_empty());
+    assert!(snapshot.summary.command.is_empty());
+    assert_eq!(snapshot.model_name, config.model_name);
+}
+
+// ---------------------------------------------------------------------------
+// Final segments — redaction across callbacks
+// ---------------------------------------------------------------------------
+
+/// Every callback, as debug text.
+#[derive(Default)]
+struct Recorder(Mutex<Vec<String>>);
+
+impl Recorder {
+    fn log(&self, event: String) {
+        self.0.lock().unwrap().push(event);
+    }
+}
+
+impl TranscriptionCallback for Recorder {
+    fn on_partial(&self, text: String) {
+        self.log(format!("partial {text}"));
+    }
+    fn on_final_segment(&self, text: String) {
+        self.log(format!("final {text}"));
+    }
+    fn on_silence(&self) {}
+    fn on_error(&self, error: String) {
+        self.log(format!("error {error}"));
+    }
+    fn on_state_change(&self, state: RecordingState) {
+        self.log(format!("state {state:?}"));
+    }
+    fn on_language_change(&self, language: String) {
+        self.log(format!("language {language}"));
+    }
+    fn on_word_confidence(&self, words: Vec<WordConfidence>) {
+        self.log(format!("words {words:?}"));
+    }
+    fn on_overlap_warning(&self, warning: OverlapWarning) {
+        self.log(format!("overlap {warning:?}"));
+    }
+    fn on_audio_interrupted(&self, reason: String) {
+        self.log(format!("interrupted {reason}"));
+    }
+    fn on_summary(&self, summary: String) {
+        self.log(format!("summary {summary}"));
+    }
+    fn on_no_audio_detected(&self) {}
+    fn on_time_remaining(&self, _seconds: u32) {}
+    fn on_ready(&self) {}
+    fn on_dry_run(&self, actions: Vec<OutputAction>) {
+        self.log(format!("dry run {actions:?}"));
+    }
+}
+
+/// A redacted keyword reaches no callback: not the final segment, the
+/// crosstalk warning, or the per-word confidence.
+#[test]
+fn redacted_keyword_reaches_no_callback() {
+    let mut config = DiktoConfig::default();
+    config.redaction.keywords = vec!["project falcon".to_string()];
+    let words = ["Project", "Falcon", "ships", "Friday."]
+        .iter()
+        .map(|w| WordConfidence {
+            word: w.to_string(),
+            confidence: 0.9,
+        })
+        .collect();
+    let segment = TranscriptSegment {
+        text: "Project Falcon ships Friday.".to_string(),
+        is_final: true,
+        language: Some("en".to_string()),
+        words,
+        start_secs: Some(0.0),
+        end_secs: Some(2.0),
+        possible_overlap: true,
+        no_speech_prob: None,
+    };
+    let recorder = Arc::new(Recorder::default());
+    emit_segment_for_test(&config, segment, recorder.clone());
+
+    let events = recorder.0.lock().unwrap().clone();
+    assert!(events.iter().any(|e| e.starts_with("final ")));
+    assert!(events.iter().any(|e| e.starts_with("overlap ")));
+    assert!(events.iter().any(|e| e.starts_with("words ")));
+    for event in &events {
+        assert!(!event.to_lowercase().contains("falcon"), "{event}");
+    }
+}
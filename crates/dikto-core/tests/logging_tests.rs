@@ -0,0 +1,55 @@
+// Tests for dikto_core::logging — installing the subscriber. It is
+// process-wide, so everything that installs it is in one test.
+
+use dikto_core::logging::{init, LogError, LogFormat, LogOptions};
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dikto_test_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// Defaults log at info to stderr only.
+#[test]
+fn default_options() {
+    let options = LogOptions::default();
+    assert_eq!(options.level, "info");
+    assert_eq!(options.file_path, None);
+    assert_eq!(options.format, LogFormat::Pretty);
+    assert!(options.stderr);
+}
+
+/// Bad levels are refused before anything is installed; a JSON file sink
+/// gets one object per event; a second init fails.
+#[test]
+fn init_writes_json_to_file_once() {
+    let bad = LogOptions {
+        level: "dikto_core=loud".to_string(),
+        ..LogOptions::default()
+    };
+    assert!(matches!(init(bad), Err(LogError::InvalidLevel(_))));
+
+    let dir = temp_dir("logging");
+    let path = dir.join("logs").join("dikto.log");
+    let options = LogOptions {
+        level: "debug".to_string(),
+        file_path: Some(path.to_string_lossy().into_owned()),
+        format: LogFormat::Json,
+        stderr: false,
+    };
+    init(options.clone()).unwrap();
+    tracing::info!(answer = 42, "hello from the test");
+    tracing::trace!("below the level");
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 1);
+    let event: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(event["level"], "INFO");
+    assert_eq!(event["fields"]["message"], "hello from the test");
+    assert_eq!(event["fields"]["answer"], 42);
+
+    assert!(matches!(init(options), Err(LogError::AlreadyInitialized)));
+    let _ = std::fs::remove_dir_all(&dir);
+}
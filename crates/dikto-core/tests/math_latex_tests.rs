@@ -0,0 +1,80 @@
+// Tests for dikto_core::math_latex — spoken math to LaTeX conversion.
+
+use dikto_core::config::TextProcConfig;
+use dikto_core::math_latex::MathLatex;
+use dikto_core::textproc::{PostProcessor, TextPipeline};
+
+fn latex(text: &str) -> String {
+    MathLatex::new().process(text)
+}
+
+// ---------------------------------------------------------------------------
+// Operators and operands
+// ---------------------------------------------------------------------------
+
+/// The canonical example from the feature request.
+#[test]
+fn squared_plus_fraction() {
+    assert_eq!(latex("x squared plus two over n"), "x^2 + \\frac{2}{n}");
+}
+
+/// Multi-word operators and Greek letters map to LaTeX commands.
+#[test]
+fn relations_and_greek_letters() {
+    assert_eq!(
+        latex("alpha is less than or equal to beta"),
+        "\\alpha \\leq \\beta"
+    );
+    assert_eq!(latex("theta times pi"), "\\theta \\cdot \\pi");
+}
+
+/// Spoken numbers become digits, including compound tens.
+#[test]
+fn number_words_become_digits() {
+    assert_eq!(latex("twenty one minus seven"), "21 - 7");
+    assert_eq!(latex("forty"), "40");
+}
+
+/// Recognizer capitalization and punctuation don't leak into the formula.
+#[test]
+fn strips_recognizer_punctuation_keeps_variable_case() {
+    assert_eq!(latex("X squared plus two over N."), "X^2 + \\frac{2}{N}");
+}
+
+// ---------------------------------------------------------------------------
+// Prefix and postfix operators
+// ---------------------------------------------------------------------------
+
+/// Powers, subscripts, and roots bind to the neighbouring operand.
+#[test]
+fn prefix_operators_bind_operands() {
+    assert_eq!(latex("e to the power of twenty one"), "e^{21}");
+    assert_eq!(latex("x sub one squared"), "x_{1}^2");
+    assert_eq!(
+        latex("square root of x equals alpha sub zero"),
+        "\\sqrt{x} = \\alpha_{0}"
+    );
+}
+
+/// An "over" without operands on both sides falls back to a slash.
+#[test]
+fn dangling_over_is_slash() {
+    assert_eq!(latex("plus over two"), "+ / 2");
+}
+
+// ---------------------------------------------------------------------------
+// Pipeline integration
+// ---------------------------------------------------------------------------
+
+/// Math mode replaces the prose stages.
+#[test]
+fn pipeline_math_mode_replaces_prose_stages() {
+    let config = TextProcConfig {
+        math_mode: true,
+        spoken_punctuation: true,
+        ..TextProcConfig::default()
+    };
+    let pipeline = TextPipeline::for_language("en", &config);
+    assert_eq!(pipeline.stage_names(), vec!["math_latex"]);
+    assert_eq!(pipeline.process("x cubed"), "x^3");
+}
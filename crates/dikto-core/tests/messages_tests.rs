@@ -0,0 +1,64 @@
+// Tests for dikto_core::messages — error codes and the message catalog.
+
+use dikto_core::messages::{
+    catalog_language, error_message, localized_error_message, status_message, ErrorCode,
+    StatusMessage, CATALOG_LANGUAGES,
+};
+
+const ALL_CODES: &[ErrorCode] = &[
+    ErrorCode::Audio,
+    ErrorCode::Vad,
+    ErrorCode::Transcribe,
+    ErrorCode::Model,
+    ErrorCode::NoModel,
+    ErrorCode::PermissionDenied,
+    ErrorCode::AlreadyRecording,
+    ErrorCode::DoNotDisturb,
+    ErrorCode::RateLimited,
+    ErrorCode::Config,
+    ErrorCode::Internal,
+];
+
+/// Region, encoding, and case are ignored; unknown languages get English.
+#[test]
+fn locale_selection() {
+    assert_eq!(catalog_language("de-DE"), "de");
+    assert_eq!(catalog_language("fr_CA.UTF-8"), "fr");
+    assert_eq!(catalog_language("ES"), "es");
+    assert_eq!(catalog_language("ja-JP"), "en");
+    assert_eq!(catalog_language(""), "en");
+}
+
+/// Messages are in the requested language.
+#[test]
+fn localized_messages() {
+    assert_eq!(
+        error_message(ErrorCode::AlreadyRecording, "de-AT"),
+        "Es läuft bereits eine Aufnahme."
+    );
+    assert_eq!(
+        localized_error_message(ErrorCode::NoModel, "en-US".to_string()),
+        "No model loaded. Download one in Settings."
+    );
+    assert_eq!(
+        status_message(StatusMessage::LoadingModel, ""),
+        "Loading model..."
+    );
+    assert_eq!(status_message(StatusMessage::Listening, "fr"), "Écoute…");
+}
+
+/// Every language has its own text for every code, and none of it points
+/// at the CLI.
+#[test]
+fn catalog_is_complete() {
+    for &code in ALL_CODES {
+        let english = error_message(code, "en");
+        for language in CATALOG_LANGUAGES {
+            let text = error_message(code, language);
+            assert!(!text.contains("dikto --"), "{language}: {text}");
+            if *language != "en" {
+                assert_ne!(text, english, "{language} is missing {code:?}");
+            }
+        }
+    }
+}
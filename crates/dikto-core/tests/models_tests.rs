@@ -2,8 +2,13 @@
 // URL validation, SHA-256 verification, and download/delete error paths.
 
 use dikto_core::models::{
-    delete_model, find_model, is_model_downloaded, list_models, model_path, verify_file_sha256,
-    ModelBackend, ModelError, MODELS,
+    aliases_for, cached_file_matches, canonical_model_name, checkpoint_path, delete_model,
+    file_state_in, find_custom_model_in, find_in_hf_cache, find_language_pack, find_model,
+    has_partial_download_in, has_whisper_model, huggingface_repo, is_model_downloaded,
+    list_custom_models_in, list_models, model_backend, model_path, model_state_in, size_matches,
+    stage_nemo_model, verified_partial, verify_file_sha256, whisper_override_path,
+    CustomModelMetadata, DownloadCheckpoint, FileState, ModelBackend, ModelError, ModelFile,
+    ModelInfo, ModelState, LANGUAGE_PACKS, MODELS, MODEL_ALIASES,
 };
 use std::collections::HashSet;
 
@@ -53,14 +58,80 @@ fn find_model_nonexistent_returns_none() {
     assert!(find_model("nonexistent").is_none());
 }
 
+// ---------------------------------------------------------------------------
+// Aliases
+// ---------------------------------------------------------------------------
+
+/// find_model should resolve each alias to its registry model.
+#[test]
+fn find_model_resolves_aliases() {
+    assert_eq!(find_model("default").unwrap().name, "parakeet-tdt-0.6b-v2");
+    assert_eq!(
+        find_model("best-english").unwrap().name,
+        "parakeet-tdt-0.6b-v2"
+    );
+    assert_eq!(
+        find_model("multilingual").unwrap().name,
+        "parakeet-tdt-0.6b-v3"
+    );
+}
+
+/// Every alias should point at a registry model and never shadow one.
+#[test]
+fn aliases_target_registry_models() {
+    for (alias, target) in MODEL_ALIASES {
+        assert!(
+            MODELS.iter().any(|m| m.name == *target),
+            "{alias} -> {target}"
+        );
+        assert!(
+            MODELS.iter().all(|m| m.name != *alias),
+            "{alias} shadows a model"
+        );
+    }
+}
+
+/// The default alias should match the default configured model.
+#[test]
+fn default_alias_matches_default_config() {
+    assert_eq!(
+        canonical_model_name("default"),
+        dikto_core::config::default_model_name()
+    );
+}
+
+/// canonical_model_name should leave registry names and unknown names alone.
+#[test]
+fn canonical_model_name_passthrough() {
+    assert_eq!(canonical_model_name("whisper-tiny"), "whisper-tiny");
+    assert_eq!(canonical_model_name("my-tuned-model"), "my-tuned-model");
+}
+
+/// aliases_for should list the aliases of a model.
+#[test]
+fn aliases_for_lists_model_aliases() {
+    assert_eq!(
+        aliases_for("parakeet-tdt-0.6b-v2"),
+        vec!["default", "best-english"]
+    );
+    assert!(aliases_for("whisper-tiny").is_empty());
+}
+
+/// model_path for an alias should point at the resolved model's folder.
+#[test]
+fn model_path_alias_uses_model_folder() {
+    let path = model_path("multilingual").unwrap();
+    assert!(path.ends_with("parakeet-tdt-0.6b-v3"));
+}
+
 // ---------------------------------------------------------------------------
 // Registry structure
 // ---------------------------------------------------------------------------
 
-/// The model registry should contain exactly 6 models.
+/// The model registry should contain exactly 8 models.
 #[test]
-fn registry_has_six_models() {
-    assert_eq!(MODELS.len(), 6);
+fn registry_has_eight_models() {
+    assert_eq!(MODELS.len(), 8);
 }
 
 /// The first model should be parakeet-tdt-0.6b-v2 with Parakeet backend and 4 files.
@@ -146,6 +217,17 @@ fn whisper_models_have_bin_file() {
     }
 }
 
+/// Quantized Whisper entries should be smaller than the model they quantize.
+#[test]
+fn quantized_whisper_models_are_smaller() {
+    let base = find_model("whisper-large-v3-turbo").unwrap();
+    for name in ["whisper-large-v3-turbo-q5_0", "whisper-large-v3-turbo-q8_0"] {
+        let model = find_model(name).unwrap();
+        assert_eq!(model.backend, ModelBackend::Whisper);
+        assert!(model.size_mb < base.size_mb, "{name} is not smaller");
+    }
+}
+
 // ---------------------------------------------------------------------------
 // model_path
 // ---------------------------------------------------------------------------
@@ -195,6 +277,50 @@ fn model_sizes_are_positive() {
     }
 }
 
+/// Every model should declare its RAM needs, speed, and languages.
+#[test]
+fn model_resource_metadata_present() {
+    for model in MODELS {
+        assert!(model.ram_mb > 0, "Model {} has no RAM estimate", model.name);
+        assert!(
+            model.relative_speed > 0.0,
+            "Model {} has no speed estimate",
+            model.name
+        );
+        assert!(
+            !model.languages.is_empty(),
+            "Model {} lists no languages",
+            model.name
+        );
+    }
+}
+
+/// Parakeet v2 is English only; v3 and Whisper are multilingual.
+#[test]
+fn model_languages_match_backend() {
+    assert_eq!(
+        find_model("parakeet-tdt-0.6b-v2").unwrap().languages,
+        ["en"]
+    );
+    assert_eq!(
+        find_model("parakeet-tdt-0.6b-v3").unwrap().languages.len(),
+        25
+    );
+    assert!(find_model("whisper-tiny")
+        .unwrap()
+        .languages
+        .contains(&"ja"));
+}
+
+/// Quantized Whisper entries should need less RAM than the model they quantize.
+#[test]
+fn quantized_whisper_models_need_less_ram() {
+    let full = find_model("whisper-large-v3-turbo").unwrap().ram_mb;
+    for name in ["whisper-large-v3-turbo-q5_0", "whisper-large-v3-turbo-q8_0"] {
+        assert!(find_model(name).unwrap().ram_mb < full, "{name}");
+    }
+}
+
 // ---------------------------------------------------------------------------
 // SHA-256 hashes
 // ---------------------------------------------------------------------------
@@ -274,6 +400,81 @@ fn verify_sha256_nonexistent_file() {
     assert!(!verify_file_sha256(path, "abc123"));
 }
 
+// ---------------------------------------------------------------------------
+// Resume checkpoints
+// ---------------------------------------------------------------------------
+
+/// Write a partial download and its checkpoint covering the first `len` bytes.
+fn write_partial(name: &str, contents: &[u8], len: u64, sha256: &str) -> std::path::PathBuf {
+    let partial = std::env::temp_dir().join(format!("dikto_partial_{name}.downloading"));
+    std::fs::write(&partial, contents).unwrap();
+    let checkpoint = DownloadCheckpoint {
+        len,
+        sha256: sha256.to_string(),
+    };
+    std::fs::write(
+        checkpoint_path(&partial),
+        serde_json::to_string(&checkpoint).unwrap(),
+    )
+    .unwrap();
+    partial
+}
+
+/// Remove a partial written by write_partial.
+fn remove_partial(partial: &std::path::Path) {
+    let _ = std::fs::remove_file(partial);
+    let _ = std::fs::remove_file(checkpoint_path(partial));
+}
+
+// SHA-256 of "hello"
+const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+/// The checkpoint sidecar sits next to the partial file.
+#[test]
+fn checkpoint_path_appends_suffix() {
+    let path = checkpoint_path(std::path::Path::new("/m/ggml-tiny.bin.downloading"));
+    assert_eq!(
+        path,
+        std::path::Path::new("/m/ggml-tiny.bin.downloading.checkpoint")
+    );
+}
+
+/// A partial whose prefix matches its checkpoint resumes at the checkpoint,
+/// even if unverified bytes were written after it.
+#[test]
+fn verified_partial_resumes_at_checkpoint() {
+    let partial = write_partial("ok", b"hello world", 5, HELLO_SHA256);
+    let (offset, _) = verified_partial(&partial).unwrap();
+    assert_eq!(offset, 5);
+    remove_partial(&partial);
+}
+
+/// A corrupted prefix must not be resumed.
+#[test]
+fn verified_partial_rejects_hash_mismatch() {
+    let partial = write_partial("corrupt", b"jello world", 5, HELLO_SHA256);
+    assert!(verified_partial(&partial).is_none());
+    remove_partial(&partial);
+}
+
+/// A partial shorter than its checkpoint was truncated and must restart.
+#[test]
+fn verified_partial_rejects_shrunk_file() {
+    let partial = write_partial("shrunk", b"hel", 5, HELLO_SHA256);
+    assert!(verified_partial(&partial).is_none());
+    remove_partial(&partial);
+}
+
+/// Without a checkpoint there is nothing to trust, so the download restarts.
+#[test]
+fn verified_partial_requires_checkpoint() {
+    let partial = std::env::temp_dir().join("dikto_partial_nocheckpoint.downloading");
+    std::fs::write(&partial, b"hello").unwrap();
+    let _ = std::fs::remove_file(checkpoint_path(&partial));
+    assert!(verified_partial(&partial).is_none());
+    remove_partial(&partial);
+}
+
 // ---------------------------------------------------------------------------
 // is_model_downloaded
 // ---------------------------------------------------------------------------
@@ -295,11 +496,11 @@ fn is_model_downloaded_false_for_nonexistent() {
 // list_models
 // ---------------------------------------------------------------------------
 
-/// list_models should return all 6 registered models.
+/// list_models should return all 8 registered models.
 #[test]
 fn list_models_returns_all() {
     let models = list_models();
-    assert_eq!(models.len(), 6);
+    assert_eq!(models.len(), 8);
 }
 
 /// list_models entries should have a consistent download status with is_model_downloaded.
@@ -317,6 +518,301 @@ fn list_models_download_status_consistent() {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Language packs
+// ---------------------------------------------------------------------------
+
+/// Every pack's model is in the registry and transcribes the pack's language.
+#[test]
+fn language_packs_use_registry_models_for_their_language() {
+    for pack in LANGUAGE_PACKS {
+        let model = find_model(pack.model)
+            .unwrap_or_else(|| panic!("{} pack names unknown model {}", pack.name, pack.model));
+        assert!(
+            model.languages.contains(&pack.language),
+            "{} does not transcribe {}",
+            pack.model,
+            pack.language
+        );
+    }
+}
+
+/// Each language has at most one pack.
+#[test]
+fn language_packs_are_unique() {
+    let mut seen = HashSet::new();
+    for pack in LANGUAGE_PACKS {
+        assert!(
+            seen.insert(pack.language),
+            "duplicate pack {}",
+            pack.language
+        );
+    }
+}
+
+/// Packs are found by language code only.
+#[test]
+fn find_language_pack_by_code() {
+    assert_eq!(find_language_pack("de").unwrap().name, "German");
+    assert!(find_language_pack("German").is_none());
+    assert!(find_language_pack("auto").is_none());
+}
+
+// ---------------------------------------------------------------------------
+// file_state_in
+// ---------------------------------------------------------------------------
+
+/// file_state_in reports missing, partial, and finished files.
+#[test]
+fn file_state_in_reports_each_state() {
+    let dir = std::env::temp_dir().join("dikto_file_state_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    assert_eq!(file_state_in(&dir, "vocab.txt"), FileState::Missing);
+
+    std::fs::write(dir.join("vocab.txt.downloading"), b"abc").unwrap();
+    assert_eq!(
+        file_state_in(&dir, "vocab.txt"),
+        FileState::Partial { bytes: 3 }
+    );
+
+    std::fs::write(dir.join("vocab.txt"), b"hello").unwrap();
+    assert_eq!(
+        file_state_in(&dir, "vocab.txt"),
+        FileState::Present { bytes: 5 }
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// ---------------------------------------------------------------------------
+// model_state_in
+// ---------------------------------------------------------------------------
+
+/// size_matches accepts rounding differences but not truncation.
+#[test]
+fn size_matches_tolerance() {
+    const MIB: u64 = 1024 * 1024;
+    assert!(size_matches(2440 * MIB, 2440));
+    assert!(size_matches(2323 * MIB, 2440));
+    assert!(!size_matches(1200 * MIB, 2440));
+    assert!(size_matches(4096, 0));
+    assert!(!size_matches(0, 42));
+}
+
+/// A model is NotDownloaded with no files, PartiallyDownloaded with a
+/// truncated or missing file, and Downloaded once every size matches.
+#[test]
+fn model_state_in_detects_partial_download() {
+    const MODEL: ModelInfo = ModelInfo {
+        name: "state-test",
+        size_mb: 1,
+        description: "",
+        backend: ModelBackend::Parakeet,
+        ram_mb: 1,
+        relative_speed: 1.0,
+        wer: 1.0,
+        languages: &["en"],
+        files: &[
+            ModelFile {
+                filename: "model.bin",
+                url: "https://example.com/model.bin",
+                size_mb: 2,
+                sha256: "",
+            },
+            ModelFile {
+                filename: "vocab.txt",
+                url: "https://example.com/vocab.txt",
+                size_mb: 0,
+                sha256: "",
+            },
+        ],
+    };
+    let dir = std::env::temp_dir().join("dikto_model_state_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    assert_eq!(model_state_in(&dir, &MODEL), ModelState::NotDownloaded);
+
+    std::fs::write(dir.join("vocab.txt"), b"a b c").unwrap();
+    std::fs::write(dir.join("model.bin"), vec![0u8; 1024]).unwrap();
+    assert_eq!(
+        model_state_in(&dir, &MODEL),
+        ModelState::PartiallyDownloaded
+    );
+
+    std::fs::write(dir.join("model.bin"), vec![0u8; 2 * 1024 * 1024]).unwrap();
+    assert_eq!(model_state_in(&dir, &MODEL), ModelState::Downloaded);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Only a `.downloading` partial marks a download as interrupted; missing
+/// or finished files don't.
+#[test]
+fn has_partial_download_in_detects_downloading_file() {
+    const MODEL: ModelInfo = ModelInfo {
+        name: "partial-test",
+        size_mb: 1,
+        description: "",
+        backend: ModelBackend::Parakeet,
+        ram_mb: 1,
+        relative_speed: 1.0,
+        wer: 1.0,
+        languages: &["en"],
+        files: &[
+            ModelFile {
+                filename: "model.bin",
+                url: "https://example.com/model.bin",
+                size_mb: 2,
+                sha256: "",
+            },
+            ModelFile {
+                filename: "vocab.txt",
+                url: "https://example.com/vocab.txt",
+                size_mb: 0,
+                sha256: "",
+            },
+        ],
+    };
+    let dir = std::env::temp_dir().join("dikto_test_partial_download");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    assert!(!has_partial_download_in(&dir, &MODEL));
+    std::fs::write(dir.join("vocab.txt"), b"a b c").unwrap();
+    assert!(!has_partial_download_in(&dir, &MODEL));
+
+    std::fs::write(dir.join("model.bin.downloading"), vec![0u8; 1024]).unwrap();
+    assert!(has_partial_download_in(&dir, &MODEL));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// ---------------------------------------------------------------------------
+// model_path_override
+// ---------------------------------------------------------------------------
+
+/// Absolute overrides are used as-is; relative ones live in the model folder.
+#[test]
+fn whisper_override_path_absolute_and_relative() {
+    let dir = std::path::Path::new("/models/whisper-small");
+    let absolute = std::env::temp_dir().join("ggml-custom.bin");
+    assert_eq!(
+        whisper_override_path(dir, absolute.to_str().unwrap()),
+        absolute
+    );
+    assert_eq!(
+        whisper_override_path(dir, "ggml-small-q5.bin"),
+        dir.join("ggml-small-q5.bin")
+    );
+}
+
+/// has_whisper_model accepts any file, a symlink to one, or a directory
+/// holding a ggml/gguf file.
+#[test]
+fn has_whisper_model_file_symlink_and_dir() {
+    let dir = std::env::temp_dir().join("dikto_whisper_override_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    assert!(!has_whisper_model(&dir));
+    assert!(!has_whisper_model(&dir.join("missing.bin")));
+
+    let file = dir.join("lmstudio-whisper.bin");
+    std::fs::write(&file, b"model").unwrap();
+    assert!(has_whisper_model(&file));
+    // The directory only counts with a conventionally named file
+    assert!(!has_whisper_model(&dir));
+
+    #[cfg(unix)]
+    {
+        let link = dir.join("linked.bin");
+        std::os::unix::fs::symlink(&file, &link).unwrap();
+        assert!(has_whisper_model(&link));
+    }
+
+    std::fs::write(dir.join("ggml-base.bin"), b"model").unwrap();
+    assert!(has_whisper_model(&dir));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// ---------------------------------------------------------------------------
+// Hugging Face cache
+// ---------------------------------------------------------------------------
+
+/// huggingface_repo extracts owner/repo from a resolve URL.
+#[test]
+fn huggingface_repo_from_url() {
+    assert_eq!(
+        huggingface_repo(MODELS[0].files[0].url),
+        Some("istupakov/parakeet-tdt-0.6b-v2-onnx")
+    );
+    assert_eq!(
+        huggingface_repo("https://example.com/a/b/resolve/main/x"),
+        None
+    );
+}
+
+/// find_in_hf_cache finds a snapshot file whose size matches the registry.
+#[test]
+fn find_in_hf_cache_matches_size() {
+    let cache = std::env::temp_dir().join("dikto_hf_cache_test");
+    let _ = std::fs::remove_dir_all(&cache);
+    let vocab = &MODELS[0].files[3];
+    assert_eq!(vocab.filename, "vocab.txt");
+    assert!(find_in_hf_cache(&cache, vocab).is_none());
+
+    let snapshot = cache
+        .join("models--istupakov--parakeet-tdt-0.6b-v2-onnx")
+        .join("snapshots")
+        .join("abc123");
+    std::fs::create_dir_all(&snapshot).unwrap();
+    std::fs::write(snapshot.join("vocab.txt"), b"a\nb\n").unwrap();
+    assert_eq!(
+        find_in_hf_cache(&cache, vocab),
+        Some(snapshot.join("vocab.txt"))
+    );
+
+    // A truncated copy of a large file is not used
+    let encoder = &MODELS[0].files[0];
+    std::fs::write(snapshot.join(encoder.filename), b"short").unwrap();
+    assert!(find_in_hf_cache(&cache, encoder).is_none());
+
+    let _ = std::fs::remove_dir_all(&cache);
+}
+
+/// A cached copy is only used when it matches the registry's SHA-256.
+#[test]
+fn cached_file_must_match_hash() {
+    let dir = std::env::temp_dir().join("dikto_hf_cache_hash_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("vocab.txt");
+    std::fs::write(&path, b"a\nb\n").unwrap();
+    let pinned = ModelFile {
+        filename: "vocab.txt",
+        url: "https://huggingface.co/a/b/resolve/main/vocab.txt",
+        size_mb: 0,
+        sha256: "911169ddaaf146aff539f58c26c489af3b892dff0fe283c1c264c65ae5aa59a2",
+    };
+    assert!(cached_file_matches(&path, &pinned));
+
+    // The registry's vocab.txt has a different hash, so this copy is stale
+    assert!(!cached_file_matches(&path, &MODELS[0].files[3]));
+    assert!(cached_file_matches(
+        &path,
+        &ModelFile {
+            sha256: "",
+            ..pinned
+        }
+    ));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
 // ---------------------------------------------------------------------------
 // delete_model error paths
 // ---------------------------------------------------------------------------
@@ -349,3 +845,211 @@ fn model_error_download_failed_display() {
     let err = ModelError::DownloadFailed("timeout".to_string());
     assert!(err.to_string().contains("timeout"));
 }
+
+/// ModelError::InvalidMetadata should include the reason.
+#[test]
+fn model_error_invalid_metadata_display() {
+    let err = ModelError::InvalidMetadata("missing field".to_string());
+    assert!(err.to_string().contains("missing field"));
+}
+
+/// ModelError::Cancelled should say the download was cancelled.
+#[test]
+fn model_error_cancelled_display() {
+    assert_eq!(ModelError::Cancelled.to_string(), "Download cancelled");
+}
+
+// ---------------------------------------------------------------------------
+// User-converted models
+// ---------------------------------------------------------------------------
+
+/// Create a fresh models root for a test.
+fn custom_root(test: &str) -> std::path::PathBuf {
+    let root = std::env::temp_dir().join(format!("dikto_custom_models_{test}"));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
+/// Write a model folder with the given metadata and files.
+fn write_model(root: &std::path::Path, name: &str, metadata: &str, files: &[&str]) {
+    let dir = root.join(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("metadata.json"), metadata).unwrap();
+    for file in files {
+        std::fs::write(dir.join(file), file.as_bytes()).unwrap();
+    }
+}
+
+/// Metadata with only a backend should default to the Parakeet file layout.
+#[test]
+fn custom_metadata_defaults_to_parakeet_layout() {
+    let root = custom_root("defaults");
+    write_model(&root, "my-model", r#"{"backend": "onnx-nemo"}"#, &[]);
+
+    let metadata = CustomModelMetadata::load(&root.join("my-model")).unwrap();
+    assert_eq!(
+        metadata.files(),
+        [
+            "encoder-model.onnx",
+            "decoder_joint-model.onnx",
+            "vocab.txt"
+        ]
+    );
+    assert!(metadata.languages.is_empty());
+    assert!(metadata.description.is_empty());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+/// Metadata for any backend other than onnx-nemo should be rejected.
+#[test]
+fn custom_metadata_rejects_unknown_backend() {
+    let root = custom_root("bad_backend");
+    write_model(&root, "my-model", r#"{"backend": "tensorrt"}"#, &[]);
+
+    let err = CustomModelMetadata::load(&root.join("my-model")).unwrap_err();
+    assert!(matches!(err, ModelError::InvalidMetadata(_)));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+/// File names must stay inside the model folder.
+#[test]
+fn custom_metadata_rejects_paths() {
+    let root = custom_root("paths");
+    write_model(
+        &root,
+        "my-model",
+        r#"{"backend": "onnx-nemo", "vocab": "../vocab.txt"}"#,
+        &[],
+    );
+
+    let err = CustomModelMetadata::load(&root.join("my-model")).unwrap_err();
+    assert!(matches!(err, ModelError::InvalidMetadata(_)));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+/// A user model is complete only once encoder, decoder, and vocab exist.
+#[test]
+fn custom_model_complete_requires_all_files() {
+    let root = custom_root("complete");
+    let metadata = r#"{"backend": "onnx-nemo", "description": "Tuned", "languages": ["de"]}"#;
+    write_model(
+        &root,
+        "tuned",
+        metadata,
+        &["encoder-model.onnx", "vocab.txt"],
+    );
+
+    let model = find_custom_model_in(&root, "tuned").unwrap();
+    assert_eq!(model.metadata.description, "Tuned");
+    assert_eq!(model.metadata.languages, vec!["de".to_string()]);
+    assert!(!model.is_complete());
+
+    std::fs::write(root.join("tuned/decoder_joint-model.onnx"), b"d").unwrap();
+    assert!(model.is_complete());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+/// Folders without metadata.json and registry names are not user models.
+#[test]
+fn custom_model_lookup_ignores_registry_and_plain_folders() {
+    let root = custom_root("lookup");
+    std::fs::create_dir_all(root.join("plain")).unwrap();
+    write_model(
+        &root,
+        "parakeet-tdt-0.6b-v2",
+        r#"{"backend": "onnx-nemo"}"#,
+        &[],
+    );
+
+    assert!(find_custom_model_in(&root, "plain").is_none());
+    assert!(find_custom_model_in(&root, "parakeet-tdt-0.6b-v2").is_none());
+    assert!(find_custom_model_in(&root, "missing").is_none());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+/// list_custom_models_in should return valid user models sorted by name.
+#[test]
+fn list_custom_models_sorted_and_filtered() {
+    let root = custom_root("list");
+    write_model(&root, "zeta", r#"{"backend": "onnx-nemo"}"#, &[]);
+    write_model(&root, "alpha", r#"{"backend": "onnx-nemo"}"#, &[]);
+    write_model(&root, "broken", "not json", &[]);
+
+    let names: Vec<String> = list_custom_models_in(&root)
+        .into_iter()
+        .map(|m| m.name)
+        .collect();
+    assert_eq!(names, vec!["alpha".to_string(), "zeta".to_string()]);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+/// Models using the default file names load straight from their folder.
+#[test]
+fn stage_nemo_model_default_names_uses_folder() {
+    let root = custom_root("stage_default");
+    write_model(&root, "m", r#"{"backend": "onnx-nemo"}"#, &[]);
+    let dir = root.join("m");
+
+    let metadata = CustomModelMetadata::load(&dir).unwrap();
+    assert_eq!(stage_nemo_model(&dir, &metadata).unwrap(), dir);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+/// Renamed files should be staged under the names ParakeetTDT expects,
+/// alongside the other files in the folder.
+#[test]
+fn stage_nemo_model_links_renamed_files() {
+    let root = custom_root("stage_renamed");
+    let metadata = r#"{"backend": "onnx-nemo", "encoder": "encoder-tuned.onnx",
+        "decoder": "decoder_joint-tuned.onnx", "vocab": "tokens.txt"}"#;
+    write_model(
+        &root,
+        "m",
+        metadata,
+        &[
+            "encoder-tuned.onnx",
+            "encoder-tuned.onnx.data",
+            "decoder_joint-tuned.onnx",
+            "tokens.txt",
+        ],
+    );
+    let dir = root.join("m");
+
+    let metadata = CustomModelMetadata::load(&dir).unwrap();
+    let staged = stage_nemo_model(&dir, &metadata).unwrap();
+    assert_ne!(staged, dir);
+    assert_eq!(
+        std::fs::read(staged.join("encoder-model.onnx")).unwrap(),
+        b"encoder-tuned.onnx"
+    );
+    assert_eq!(
+        std::fs::read(staged.join("vocab.txt")).unwrap(),
+        b"tokens.txt"
+    );
+    assert!(staged.join("decoder_joint-model.onnx").exists());
+    assert!(staged.join("encoder-tuned.onnx.data").exists());
+
+    // Staging again replaces the previous links
+    assert_eq!(stage_nemo_model(&dir, &metadata).unwrap(), staged);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+/// model_backend should report registry backends and None for unknown names.
+#[test]
+fn model_backend_registry_and_unknown() {
+    assert_eq!(
+        model_backend("parakeet-tdt-0.6b-v2"),
+        Some(ModelBackend::Parakeet)
+    );
+    assert_eq!(model_backend("whisper-tiny"), Some(ModelBackend::Whisper));
+    assert_eq!(model_backend("nonexistent-model-xyz"), None);
+}
@@ -0,0 +1,532 @@
+// Tests for dikto_core::output — app profiles, the output stage, and
+// CJK/RTL script handling.
+
+use dikto_core::config::{AppProfile, ClipboardFormat, DiktoConfig, OutputSource};
+use dikto_core::output::{
+    find_profile, local_timestamp, match_case_to_context, needs_leading_space, prepare_output,
+    prepare_segment_output, remove_cjk_spaces, route_output, script_for_language,
+    strip_punctuation, to_fullwidth_punctuation, to_markdown, to_rtf, word_chunks, OutputTarget,
+    Script, TranscriptTexts,
+};
+
+/// 2024-03-09 14:05:00 UTC.
+const NOW: u64 = 1_709_993_100;
+
+fn target(bundle_id: &str, app_name: &str) -> OutputTarget {
+    OutputTarget {
+        bundle_id: bundle_id.to_string(),
+        app_name: app_name.to_string(),
+        unix_secs: NOW,
+        utc_offset_secs: 0,
+        preceding_text: String::new(),
+        language: String::new(),
+        dry_run: false,
+    }
+}
+
+fn after(preceding: &str) -> OutputTarget {
+    OutputTarget {
+        preceding_text: preceding.to_string(),
+        ..target("x", "X")
+    }
+}
+
+fn config_with(profiles: Vec<AppProfile>) -> DiktoConfig {
+    let mut config = DiktoConfig::default();
+    config.output.app_profiles = profiles;
+    config
+}
+
+// ---------------------------------------------------------------------------
+// find_profile
+// ---------------------------------------------------------------------------
+
+/// Profiles match the bundle identifier or the app name, ignoring case.
+#[test]
+fn find_profile_by_bundle_id_or_name() {
+    let profiles = vec![
+        AppProfile {
+            app: "com.apple.Terminal".to_string(),
+            ..AppProfile::default()
+        },
+        AppProfile {
+            app: "slack".to_string(),
+            ..AppProfile::default()
+        },
+    ];
+    let found = find_profile(&profiles, &target("com.apple.terminal", "Terminal"));
+    assert_eq!(found.unwrap().app, "com.apple.Terminal");
+    let found = find_profile(&profiles, &target("com.tinyspeck.slackmacgap", "Slack"));
+    assert_eq!(found.unwrap().app, "slack");
+    assert!(find_profile(&profiles, &target("md.obsidian", "Obsidian")).is_none());
+}
+
+/// A blank profile never matches, even an unknown app with no name.
+#[test]
+fn find_profile_ignores_blank_app() {
+    let profiles = vec![AppProfile::default()];
+    assert!(find_profile(&profiles, &target("", "")).is_none());
+}
+
+// ---------------------------------------------------------------------------
+// local_timestamp
+// ---------------------------------------------------------------------------
+
+/// UTC and offset timestamps, including one that crosses midnight.
+#[test]
+fn local_timestamp_applies_offset() {
+    assert_eq!(local_timestamp(NOW, 0), "2024-03-09 14:05");
+    assert_eq!(local_timestamp(NOW, 3600), "2024-03-09 15:05");
+    assert_eq!(local_timestamp(NOW, 11 * 3600), "2024-03-10 01:05");
+    assert_eq!(local_timestamp(NOW, -8 * 3600), "2024-03-09 06:05");
+}
+
+// ---------------------------------------------------------------------------
+// needs_leading_space
+// ---------------------------------------------------------------------------
+
+/// A word or sentence end before the caret needs a space before new text.
+#[test]
+fn leading_space_after_word_or_punctuation() {
+    assert!(needs_leading_space("Hello", "world"));
+    assert!(needs_leading_space("Done.", "Next"));
+    assert!(needs_leading_space("a,", "b"));
+}
+
+/// No space at the start of a field, after whitespace, or after an opening mark.
+#[test]
+fn no_leading_space_after_space_or_opening() {
+    assert!(!needs_leading_space("", "Hello"));
+    assert!(!needs_leading_space("Hello ", "world"));
+    assert!(!needs_leading_space("line\n", "next"));
+    assert!(!needs_leading_space("(", "aside"));
+    assert!(!needs_leading_space("“", "quoted"));
+}
+
+/// Text starting with attaching punctuation goes right after the word.
+#[test]
+fn no_leading_space_before_punctuation() {
+    assert!(!needs_leading_space("Hello", ", world"));
+    assert!(!needs_leading_space("Hello", "."));
+    assert!(!needs_leading_space("Hello", ""));
+}
+
+// ---------------------------------------------------------------------------
+// match_case_to_context
+// ---------------------------------------------------------------------------
+
+/// Mid-sentence insertions start lowercase.
+#[test]
+fn lowercase_mid_sentence() {
+    assert_eq!(
+        match_case_to_context("I met ", "Yesterday we"),
+        "yesterday we"
+    );
+    assert_eq!(match_case_to_context("First,", "Then"), "then");
+    assert_eq!(match_case_to_context("Note:", "The end"), "the end");
+}
+
+/// After a sentence end or line break the first word is capitalized.
+#[test]
+fn capitalize_after_sentence_end() {
+    assert_eq!(match_case_to_context("Done. ", "next step"), "Next step");
+    assert_eq!(match_case_to_context("Really?", "yes"), "Yes");
+    assert_eq!(match_case_to_context("Title\n", "body text"), "Body text");
+}
+
+/// Unknown context, "I", acronyms, and mixed-case names keep their case.
+#[test]
+fn case_kept_when_unsure() {
+    assert_eq!(match_case_to_context("", "Hello"), "Hello");
+    assert_eq!(match_case_to_context("(", "Aside"), "Aside");
+    assert_eq!(match_case_to_context("and ", "I think"), "I think");
+    assert_eq!(match_case_to_context("and ", "I'm sure"), "I'm sure");
+    assert_eq!(match_case_to_context("the ", "NASA team"), "NASA team");
+    assert_eq!(match_case_to_context("my ", "iPhone"), "iPhone");
+}
+
+// ---------------------------------------------------------------------------
+// prepare_output
+// ---------------------------------------------------------------------------
+
+/// Without a matching profile the text is trimmed and auto_paste follows the config.
+#[test]
+fn prepare_output_without_profile() {
+    let mut config = config_with(Vec::new());
+    config.auto_paste = false;
+    let plan = prepare_output("  Hello there. ", &target("x", "X"), &config);
+    assert_eq!(plan.text, "Hello there.");
+    assert!(!plan.auto_paste);
+    assert!(plan.profile.is_none());
+}
+
+/// Chat apps get one line with no trailing newline.
+#[test]
+fn prepare_output_plain_text() {
+    let config = config_with(vec![AppProfile {
+        app: "Slack".to_string(),
+        plain_text: true,
+        ..AppProfile::default()
+    }]);
+    let plan = prepare_output(
+        "First line.\n\nSecond  line.",
+        &target("", "Slack"),
+        &config,
+    );
+    assert_eq!(plan.text, "First line. Second line.");
+    assert_eq!(plan.profile.as_deref(), Some("Slack"));
+}
+
+/// Search boxes can get text without punctuation or capitals.
+#[test]
+fn prepare_output_strips_punctuation_and_case() {
+    let config = config_with(vec![AppProfile {
+        app: "com.apple.Safari".to_string(),
+        strip_punctuation: true,
+        lowercase: true,
+        ..AppProfile::default()
+    }]);
+    let plan = prepare_output(
+        "Weather in Paris, France?",
+        &target("com.apple.Safari", "Safari"),
+        &config,
+    );
+    assert_eq!(plan.text, "weather in paris france");
+}
+
+/// Sentence marks go; marks inside numbers and names stay.
+#[test]
+fn strip_punctuation_keeps_marks_inside_words() {
+    assert_eq!(
+        strip_punctuation("Open example.com at 12:30, then pay 3.50."),
+        "Open example.com at 12:30 then pay 3.50"
+    );
+    assert_eq!(strip_punctuation("¿Qué tal? ¡Bien!"), "Qué tal Bien");
+    assert_eq!(strip_punctuation("wait... what"), "wait what");
+    assert_eq!(strip_punctuation("don't"), "don't");
+}
+
+/// Full-width marks are dropped even between CJK characters.
+#[test]
+fn strip_punctuation_full_width() {
+    assert_eq!(strip_punctuation("你好，世界。"), "你好世界");
+}
+
+/// A profile can turn off auto-paste for one app.
+#[test]
+fn prepare_output_disables_paste() {
+    let config = config_with(vec![AppProfile {
+        app: "com.apple.Terminal".to_string(),
+        auto_paste: Some(false),
+        ..AppProfile::default()
+    }]);
+    let plan = prepare_output("ls", &target("com.apple.Terminal", "Terminal"), &config);
+    assert!(!plan.auto_paste);
+    assert_eq!(plan.text, "ls");
+}
+
+/// Notes apps can get a timestamp prefix and a trailing newline.
+#[test]
+fn prepare_output_timestamp_and_newline() {
+    let config = config_with(vec![AppProfile {
+        app: "md.obsidian".to_string(),
+        trailing_newline: true,
+        timestamp_prefix: true,
+        ..AppProfile::default()
+    }]);
+    let plan = prepare_output("Call Sam.", &target("md.obsidian", "Obsidian"), &config);
+    assert_eq!(plan.text, "2024-03-09 14:05 Call Sam.\n");
+    assert!(plan.auto_paste);
+}
+
+/// Smart spacing separates the transcript from the word before the caret.
+#[test]
+fn prepare_output_smart_spacing() {
+    let mut config = config_with(Vec::new());
+    assert_eq!(
+        prepare_output("world", &after("Hello"), &config).text,
+        " world"
+    );
+    assert_eq!(
+        prepare_output("world", &after("Hello "), &config).text,
+        "world"
+    );
+    config.output.smart_spacing = false;
+    assert_eq!(
+        prepare_output("world", &after("Hello"), &config).text,
+        "world"
+    );
+}
+
+/// append_space and append_newline end the text, without doubling up.
+#[test]
+fn prepare_output_appends() {
+    let mut config = config_with(Vec::new());
+    config.output.append_space = true;
+    assert_eq!(prepare_output("Hi.", &after(""), &config).text, "Hi. ");
+    config.output.append_newline = true;
+    assert_eq!(prepare_output("Hi.", &after(""), &config).text, "Hi.\n");
+
+    config.output.app_profiles = vec![AppProfile {
+        app: "X".to_string(),
+        trailing_newline: true,
+        ..AppProfile::default()
+    }];
+    assert_eq!(prepare_output("Hi.", &after(""), &config).text, "Hi.\n");
+}
+
+/// Smart capitalization runs before the timestamp prefix and spacing.
+#[test]
+fn prepare_output_smart_capitalization() {
+    let mut config = config_with(Vec::new());
+    assert_eq!(
+        prepare_output("Then stop.", &after("go"), &config).text,
+        " then stop."
+    );
+    config.output.smart_capitalization = false;
+    assert_eq!(
+        prepare_output("Then stop.", &after("go"), &config).text,
+        " Then stop."
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Scripts
+// ---------------------------------------------------------------------------
+
+fn in_language(language: &str, preceding: &str) -> OutputTarget {
+    OutputTarget {
+        language: language.to_string(),
+        ..after(preceding)
+    }
+}
+
+/// Chinese and Japanese are CJK, right-to-left languages are RTL, and
+/// Korean is spaced like English.
+#[test]
+fn script_by_language() {
+    assert_eq!(script_for_language("zh"), Script::Cjk);
+    assert_eq!(script_for_language("ja"), Script::Cjk);
+    assert_eq!(script_for_language("ar"), Script::Rtl);
+    assert_eq!(script_for_language("he"), Script::Rtl);
+    assert_eq!(script_for_language("ko"), Script::Spaced);
+    assert_eq!(script_for_language("auto"), Script::Spaced);
+}
+
+/// Punctuation becomes full-width, except inside numbers and words.
+#[test]
+fn fullwidth_punctuation_skips_numbers() {
+    assert_eq!(
+        to_fullwidth_punctuation("你好, 世界. 3.14 12:30 (ok)?"),
+        "你好， 世界。 3.14 12:30 （ok）？"
+    );
+}
+
+/// Spaces between CJK characters go; spaces around Latin words stay.
+#[test]
+fn cjk_spaces_removed_between_cjk_only() {
+    assert_eq!(
+        remove_cjk_spaces("你好。 我是 Alex 的  朋友\n再见"),
+        "你好。我是 Alex 的朋友\n再见"
+    );
+}
+
+/// A CJK transcript gets full-width punctuation and no added spaces.
+#[test]
+fn prepare_output_cjk() {
+    let mut config = DiktoConfig::default();
+    config.output.append_space = true;
+    let plan = prepare_output("我们走吧. 好的!", &in_language("zh", "你好"), &config);
+    assert_eq!(plan.text, "我们走吧。好的！");
+
+    config.output.fullwidth_punctuation = false;
+    let plan = prepare_output("我们走吧. 好的!", &in_language("zh", ""), &config);
+    assert_eq!(plan.text, "我们走吧. 好的!");
+}
+
+/// Without a language on the target, the configured one decides.
+#[test]
+fn prepare_output_uses_configured_language() {
+    let mut config = DiktoConfig::default();
+    config.language = "ja".to_string();
+    let plan = prepare_output("はい, 分かりました.", &target("x", "X"), &config);
+    assert_eq!(plan.text, "はい，分かりました。");
+}
+
+/// RTL text is wrapped in right-to-left marks, unless turned off.
+#[test]
+fn prepare_output_rtl_marks() {
+    let mut config = DiktoConfig::default();
+    let plan = prepare_output("שלום, מה נשמע?", &in_language("he", ""), &config);
+    assert_eq!(plan.text, "\u{200F}שלום, מה נשמע?\u{200F}");
+
+    config.output.rtl_marks = false;
+    let plan = prepare_output("שלום, מה נשמע?", &in_language("he", ""), &config);
+    assert_eq!(plan.text, "שלום, מה נשמע?");
+}
+
+// ---------------------------------------------------------------------------
+// Clipboard formats
+// ---------------------------------------------------------------------------
+
+/// Plain is the default and carries no rich text.
+#[test]
+fn prepare_output_plain_clipboard() {
+    let plan = prepare_output("Hi.", &after(""), &config_with(Vec::new()));
+    assert_eq!(plan.clipboard_format, ClipboardFormat::Plain);
+    assert!(plan.rich_text.is_none());
+}
+
+/// The global format applies unless the app's profile picks another.
+#[test]
+fn prepare_output_clipboard_format_per_app() {
+    let mut config = config_with(vec![AppProfile {
+        app: "md.obsidian".to_string(),
+        clipboard_format: Some(ClipboardFormat::Markdown),
+        ..AppProfile::default()
+    }]);
+    config.output.clipboard_format = ClipboardFormat::Rtf;
+
+    let notes = prepare_output("One.\n\nTwo.", &target("md.obsidian", "Obsidian"), &config);
+    assert_eq!(notes.clipboard_format, ClipboardFormat::Markdown);
+    assert_eq!(notes.rich_text.as_deref(), Some("One.\n\nTwo."));
+    assert_eq!(notes.text, "One.\n\nTwo.");
+
+    let other = prepare_output("Hi.", &target("x", "X"), &config);
+    assert_eq!(other.clipboard_format, ClipboardFormat::Rtf);
+    assert!(other.rich_text.unwrap().contains("Hi."));
+}
+
+// ---------------------------------------------------------------------------
+// route_output
+// ---------------------------------------------------------------------------
+
+fn texts(transcript: &str, summary: Option<&str>) -> TranscriptTexts {
+    TranscriptTexts {
+        transcript: transcript.to_string(),
+        summary: summary.map(str::to_string),
+    }
+}
+
+/// By default both sinks get the transcript, as with prepare_output.
+#[test]
+fn route_output_defaults_to_transcript() {
+    let config = DiktoConfig::default();
+    let plan = route_output(
+        &texts("Full text.", Some("Short.")),
+        &target("x", "X"),
+        &config,
+    );
+    assert_eq!(plan.text, "Full text.");
+    assert_eq!(plan.clipboard_text, "Full text.");
+    assert_eq!(
+        prepare_output("Full text.", &target("x", "X"), &config),
+        plan
+    );
+}
+
+/// Split output: the clipboard keeps the transcript while the summary is
+/// pasted, each formatted for the target, with rich text from the clipboard's.
+#[test]
+fn route_output_pastes_summary_copies_transcript() {
+    let mut config = DiktoConfig::default();
+    config.output.routes.paste = OutputSource::Summary;
+    config.output.clipboard_format = ClipboardFormat::Markdown;
+    config.output.append_space = true;
+    let plan = route_output(
+        &texts("- full text", Some("short")),
+        &after("Notes."),
+        &config,
+    );
+    assert_eq!(plan.text, " Short ");
+    assert_eq!(plan.clipboard_text, " - full text ");
+    assert_eq!(plan.rich_text.as_deref(), Some(" \\- full text "));
+}
+
+/// The summary route falls back to the transcript when there is no summary.
+#[test]
+fn route_output_summary_falls_back_to_transcript() {
+    let mut config = DiktoConfig::default();
+    config.output.routes.clipboard = OutputSource::Summary;
+    let plan = route_output(&texts("Full text.", Some("  ")), &target("x", "X"), &config);
+    assert_eq!(plan.clipboard_text, "Full text.");
+    let plan = route_output(&texts("Full text.", None), &target("x", "X"), &config);
+    assert_eq!(plan.clipboard_text, "Full text.");
+}
+
+/// A dry-run session still gets its text formatted, in a plan marked so
+/// hosts don't paste or copy it.
+#[test]
+fn route_output_dry_run() {
+    let config = DiktoConfig::default();
+    let plan = route_output(&texts("Full text.", None), &target("x", "X"), &config);
+    assert!(!plan.dry_run);
+    let dry = OutputTarget {
+        dry_run: true,
+        ..target("x", "X")
+    };
+    let plan = route_output(&texts("Full text.", None), &dry, &config);
+    assert!(plan.dry_run);
+    assert_eq!(plan.text, "Full text.");
+}
+
+// ---------------------------------------------------------------------------
+// Incremental paste
+// ---------------------------------------------------------------------------
+
+/// Chunks hold up to the given number of words and join back into the
+/// exact text, whitespace and all.
+#[test]
+fn word_chunks_rejoin_exactly() {
+    let text = " one two  three\nfour five ";
+    let chunks = word_chunks(text, 2);
+    assert_eq!(chunks, vec![" one two  ", "three\nfour ", "five "]);
+    assert_eq!(chunks.concat(), text);
+    assert_eq!(word_chunks("one", 0), vec!["one"]);
+    assert!(word_chunks("", 3).is_empty());
+}
+
+/// A segment is formatted against the text before the caret, then chunked.
+#[test]
+fn segment_output_formats_then_chunks() {
+    let mut config = DiktoConfig::default();
+    config.output.paste_chunk_words = 3;
+    let chunks = prepare_segment_output("and then we left early.", &after("We ate."), &config);
+    assert_eq!(chunks, vec![" And then we ", "left early."]);
+}
+
+/// A dry run has no chunks to paste.
+#[test]
+fn segment_output_empty_in_dry_run() {
+    let dry = OutputTarget {
+        dry_run: true,
+        ..after("We ate.")
+    };
+    let config = DiktoConfig::default();
+    assert!(prepare_segment_output("and then we left.", &dry, &config).is_empty());
+}
+
+/// Paragraphs survive, and line starts that Markdown would read as
+/// structure are escaped.
+#[test]
+fn markdown_keeps_paragraphs_and_escapes_markers() {
+    assert_eq!(
+        to_markdown("First point.\n\n\n# tag and - dash"),
+        "First point.\n\n\\# tag and - dash"
+    );
+    assert_eq!(to_markdown("- item\n1. step"), "\\- item  \n1\\. step");
+    assert_eq!(to_markdown("x_1 * y"), "x_1 * y");
+    assert_eq!(to_markdown(" spaced\n"), " spaced\n");
+}
+
+/// RTF escapes its control characters and non-ASCII text.
+#[test]
+fn rtf_escapes_text() {
+    let rtf = to_rtf("a{b}\\c\n\nCafé 😀");
+    assert!(rtf.starts_with("{\\rtf1"));
+    assert!(rtf.ends_with('}'));
+    assert!(rtf.contains("a\\{b\\}\\\\c\\par\n\\par\n"));
+    assert!(rtf.contains("Caf\\u233?"));
+    // Astral characters become a surrogate pair
+    assert!(rtf.contains("\\u-10179?\\u-8704?"));
+}
@@ -0,0 +1,44 @@
+// Tests for dikto_core::paste — platform support reporting. Sending real
+// keystrokes would type into whatever window has focus, so only the
+// portable paths are tested.
+
+use dikto_core::config::OutputConfig;
+use dikto_core::paste::{deliver, is_supported, PasteError};
+
+/// Empty text succeeds without touching the clipboard or keyboard.
+#[test]
+fn empty_text_is_a_no_op() {
+    assert!(deliver("", &OutputConfig::default()).is_ok());
+}
+
+/// A dry run succeeds without pasting, on any platform.
+#[test]
+fn dry_run_is_a_no_op() {
+    let output = OutputConfig {
+        dry_run: true,
+        ..OutputConfig::default()
+    };
+    assert!(deliver("hello", &output).is_ok());
+}
+
+/// Only Windows pastes from the engine; elsewhere the host does it.
+#[test]
+fn supported_only_on_windows() {
+    assert_eq!(is_supported(), cfg!(windows));
+}
+
+/// Off Windows, delivering text fails with Unsupported in both modes.
+#[cfg(not(windows))]
+#[test]
+fn deliver_is_unsupported_off_windows() {
+    let mut output = OutputConfig::default();
+    assert!(matches!(
+        deliver("hello", &output),
+        Err(PasteError::Unsupported)
+    ));
+    output.typing_mode = true;
+    assert!(matches!(
+        deliver("hello", &output),
+        Err(PasteError::Unsupported)
+    ));
+}
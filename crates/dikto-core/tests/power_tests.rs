@@ -0,0 +1,97 @@
+// Tests for dikto_core::power — low-power mode resolution, relaxed pipeline
+// timings, and low-power model selection. Battery detection is hardware-specific
+// and only exercised through the explicit On/Off modes.
+
+use dikto_core::config::{AdvancedConfig, DiktoConfig, LowPowerMode};
+use dikto_core::power::{effective_advanced, effective_model_name, is_low_power};
+
+// ---------------------------------------------------------------------------
+// LowPowerMode
+// ---------------------------------------------------------------------------
+
+/// LowPowerMode default should be Auto.
+#[test]
+fn low_power_mode_default_is_auto() {
+    assert_eq!(LowPowerMode::default(), LowPowerMode::Auto);
+    assert_eq!(DiktoConfig::default().low_power_mode, LowPowerMode::Auto);
+}
+
+/// Explicit On/Off modes should not depend on the power source.
+#[test]
+fn explicit_modes_resolve_directly() {
+    assert!(is_low_power(&LowPowerMode::On));
+    assert!(!is_low_power(&LowPowerMode::Off));
+}
+
+/// low_power_mode should deserialize from lowercase strings.
+#[test]
+fn low_power_mode_deserializes_lowercase() {
+    let config: DiktoConfig = serde_json::from_str(r#"{"low_power_mode":"on"}"#).unwrap();
+    assert_eq!(config.low_power_mode, LowPowerMode::On);
+}
+
+// ---------------------------------------------------------------------------
+// effective_advanced
+// ---------------------------------------------------------------------------
+
+/// Without low-power mode the advanced config should be unchanged.
+#[test]
+fn effective_advanced_passthrough() {
+    let advanced = AdvancedConfig::default();
+    assert_eq!(effective_advanced(&advanced, false), advanced);
+}
+
+/// Low-power mode should poll less often and throttle partials to >= 1s.
+#[test]
+fn effective_advanced_relaxes_timings() {
+    let advanced = AdvancedConfig::default();
+    let relaxed = effective_advanced(&advanced, true);
+    assert!(relaxed.poll_interval_ms > advanced.poll_interval_ms);
+    assert!(relaxed.partial_interval_ms >= 1000);
+}
+
+/// Relaxed timings should still respect the validated ceilings.
+#[test]
+fn effective_advanced_stays_clamped() {
+    let advanced = AdvancedConfig {
+        poll_interval_ms: 200,
+        ..AdvancedConfig::default()
+    };
+    let relaxed = effective_advanced(&advanced, true);
+    assert_eq!(relaxed.poll_interval_ms, 200);
+}
+
+/// Low-power mode should decode greedily instead of running extra passes.
+#[test]
+fn effective_advanced_disables_beam_search() {
+    let advanced = AdvancedConfig {
+        beam_size: 5,
+        ..AdvancedConfig::default()
+    };
+    assert_eq!(effective_advanced(&advanced, false).beam_size, 5);
+    assert_eq!(effective_advanced(&advanced, true).beam_size, 0);
+}
+
+// ---------------------------------------------------------------------------
+// effective_model_name
+// ---------------------------------------------------------------------------
+
+/// Without low-power mode the configured model should be used.
+#[test]
+fn effective_model_uses_configured_model() {
+    let config = DiktoConfig {
+        low_power_model: Some("whisper-tiny".to_string()),
+        ..DiktoConfig::default()
+    };
+    assert_eq!(effective_model_name(&config, false), config.model_name);
+}
+
+/// A low-power model that isn't downloaded should fall back to the configured model.
+#[test]
+fn effective_model_falls_back_when_not_downloaded() {
+    let config = DiktoConfig {
+        low_power_model: Some("nonexistent-model-xyz".to_string()),
+        ..DiktoConfig::default()
+    };
+    assert_eq!(effective_model_name(&config, true), config.model_name);
+}
@@ -0,0 +1,46 @@
+// Tests for dikto_core::rate_limit — minimum spacing and per-minute caps on
+// session starts.
+
+use dikto_core::rate_limit::SessionRateLimiter;
+use std::time::{Duration, Instant};
+
+const MS: Duration = Duration::from_millis(1);
+
+/// The first session always starts.
+#[test]
+fn first_start_allowed() {
+    let mut limiter = SessionRateLimiter::default();
+    assert_eq!(limiter.check(Instant::now(), 250 * MS, 1), Ok(()));
+}
+
+/// A start inside the minimum interval reports the time left.
+#[test]
+fn min_interval_enforced() {
+    let mut limiter = SessionRateLimiter::default();
+    let t0 = Instant::now();
+    limiter.record(t0);
+    assert_eq!(limiter.check(t0 + 100 * MS, 250 * MS, 0), Err(150 * MS));
+    assert_eq!(limiter.check(t0 + 250 * MS, 250 * MS, 0), Ok(()));
+}
+
+/// Once the per-minute cap is reached, the wait lasts until the oldest
+/// counted start is a minute old.
+#[test]
+fn per_minute_cap_enforced() {
+    let mut limiter = SessionRateLimiter::default();
+    let t0 = Instant::now();
+    for i in 0..3 {
+        limiter.record(t0 + Duration::from_secs(i));
+    }
+    let now = t0 + Duration::from_secs(10);
+    assert_eq!(
+        limiter.check(now, Duration::ZERO, 3),
+        Err(Duration::from_secs(50))
+    );
+    assert_eq!(limiter.check(now, Duration::ZERO, 4), Ok(()));
+    assert_eq!(limiter.check(now, Duration::ZERO, 0), Ok(()));
+    assert_eq!(
+        limiter.check(t0 + Duration::from_secs(60), Duration::ZERO, 3),
+        Ok(())
+    );
+}
@@ -0,0 +1,78 @@
+// Tests for dikto_core::redact — masking configured keywords in final text.
+
+use dikto_core::config::RedactionConfig;
+use dikto_core::redact::Redactor;
+use dikto_core::transcribe::WordConfidence;
+
+fn redactor(keywords: &[&str]) -> Redactor {
+    Redactor::new(&RedactionConfig {
+        keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        ..RedactionConfig::default()
+    })
+}
+
+/// Without keywords the text is returned unchanged.
+#[test]
+fn no_keywords_is_inactive() {
+    let r = redactor(&[]);
+    assert!(!r.is_active());
+    assert_eq!(r.redact("Nothing to hide."), "Nothing to hide.");
+    assert!(!redactor(&["  ", "-"]).is_active());
+}
+
+/// Keywords match whole words, ignoring case, and keep the punctuation.
+#[test]
+fn masks_whole_words_ignoring_case() {
+    let r = redactor(&["falcon"]);
+    assert_eq!(
+        r.redact("Falcon ships Friday, FALCON!"),
+        "[redacted] ships Friday, [redacted]!"
+    );
+    assert_eq!(r.redact("Falconry is fine."), "Falconry is fine.");
+}
+
+/// A phrase matches its words in a row, whatever separates them.
+#[test]
+fn masks_phrases() {
+    let r = redactor(&["project falcon"]);
+    assert_eq!(
+        r.redact("About Project-Falcon's budget."),
+        "About [redacted]'s budget."
+    );
+    assert_eq!(r.redact("A project on falcon."), "A project on falcon.");
+}
+
+/// The longest keyword wins where several start at the same word.
+#[test]
+fn longest_keyword_wins() {
+    let r = redactor(&["acme", "acme corp"]);
+    assert_eq!(r.redact("Call acme corp today"), "Call [redacted] today");
+    assert_eq!(r.redact("Call acme today"), "Call [redacted] today");
+}
+
+/// The mask is configurable and non-ASCII words match.
+#[test]
+fn custom_mask_and_unicode() {
+    let r = Redactor::new(&RedactionConfig {
+        keywords: vec!["Müller".to_string()],
+        mask: "***".to_string(),
+    });
+    assert_eq!(r.redact("Herr MÜLLER kommt."), "Herr *** kommt.");
+}
+
+/// Words that make up a keyword are masked one by one, keeping their
+/// confidence, so `on_word_confidence` can't leak them.
+#[test]
+fn masks_words_of_a_keyword() {
+    let r = redactor(&["project falcon"]);
+    let words: Vec<WordConfidence> = ["About", "Project", "Falcon's", "budget"]
+        .iter()
+        .map(|w| WordConfidence {
+            word: w.to_string(),
+            confidence: 0.5,
+        })
+        .collect();
+    let masked: Vec<String> = r.redact_words(&words).into_iter().map(|w| w.word).collect();
+    assert_eq!(masked, ["About", "[redacted]", "[redacted]", "budget"]);
+    assert_eq!(redactor(&[]).redact_words(&words), words);
+}
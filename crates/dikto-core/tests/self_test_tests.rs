@@ -0,0 +1,36 @@
+// Tests for dikto_core::self_test — the synthetic voice used when no speech
+// sample is given.
+
+use dikto_core::self_test::synthetic_voice;
+use dikto_core::wav::TARGET_SAMPLE_RATE;
+
+// ---------------------------------------------------------------------------
+// synthetic_voice
+// ---------------------------------------------------------------------------
+
+/// The signal is lead silence, voice, then trailing silence.
+#[test]
+fn synthetic_voice_layout() {
+    let rate = TARGET_SAMPLE_RATE as usize;
+    let samples = synthetic_voice(0.5, 2.0);
+    assert_eq!(samples.len(), 3 * rate);
+    assert!(samples[..rate / 2].iter().all(|&s| s == 0.0));
+    assert!(samples[rate * 5 / 2..].iter().all(|&s| s == 0.0));
+    assert!(samples[rate / 2..rate * 5 / 2].iter().any(|&s| s != 0.0));
+}
+
+/// The voiced part is normalized to half of full scale.
+#[test]
+fn synthetic_voice_peak_level() {
+    let peak = synthetic_voice(0.0, 1.0)
+        .iter()
+        .fold(0.0f32, |m, s| m.max(s.abs()));
+    assert!((peak - 0.5).abs() < 1e-4);
+}
+
+/// Zero-length voice is only silence.
+#[test]
+fn synthetic_voice_empty() {
+    assert!(synthetic_voice(0.0, 0.0).is_empty());
+    assert_eq!(synthetic_voice(0.1, 0.0).len(), 3200);
+}
@@ -0,0 +1,211 @@
+// Tests for dikto_core::sessions — saved-session file names and folders.
+
+use dikto_core::actions::ActionItem;
+use dikto_core::config::{DiktoConfig, SavedSessionsConfig};
+use dikto_core::sessions::{
+    read_snapshot, render_file_name, save_session, session_dir, snapshot_path, transcript_markdown,
+    unique_stem, SessionInfo,
+};
+use dikto_core::spool::AudioSpool;
+use dikto_core::TranscriptionResult;
+use std::path::Path;
+
+/// 2026-03-05 14:07 UTC.
+const MARCH_5: u64 = 1_772_719_620;
+
+fn info(text: &str) -> SessionInfo {
+    SessionInfo {
+        unix_secs: MARCH_5,
+        utc_offset_secs: 0,
+        app: "Obsidian".to_string(),
+        text: text.to_string(),
+    }
+}
+
+fn result(text: &str) -> TranscriptionResult {
+    TranscriptionResult {
+        text: text.to_string(),
+        raw_text: text.to_string(),
+        speech_duration_secs: 1.0,
+        alternatives: Vec::new(),
+        summary: None,
+        action_items: Vec::new(),
+    }
+}
+
+/// Every placeholder is filled in, with the first words lowercased and
+/// stripped of punctuation.
+#[test]
+fn renders_placeholders() {
+    let info = info("Hello, World! This is the weekly sync.");
+    assert_eq!(
+        render_file_name("{date}-{time}-{app}-{first_words}", &info),
+        "2026-03-05-14-07-Obsidian-hello-world-this-is-the"
+    );
+    assert_eq!(
+        render_file_name("{year}/{month} notes", &info),
+        "2026-03 notes"
+    );
+}
+
+/// Local time comes from the UTC offset, including across midnight.
+#[test]
+fn uses_local_time() {
+    let info = SessionInfo {
+        utc_offset_secs: 11 * 3600,
+        ..info("")
+    };
+    assert_eq!(render_file_name("{date} {time}", &info), "2026-03-06 01-07");
+}
+
+/// Characters file systems reject become dashes, and an empty name falls
+/// back to "session".
+#[test]
+fn sanitizes_file_names() {
+    let info = SessionInfo {
+        app: "a:b*c".to_string(),
+        ..info("")
+    };
+    assert_eq!(render_file_name("{app}--{first_words}", &info), "a-b-c");
+    assert_eq!(render_file_name("{first_words}", &info), "session");
+    assert_eq!(render_file_name("../..", &info), "session");
+}
+
+/// Sessions are filed in a YYYY-MM folder unless that's turned off.
+#[test]
+fn month_folders() {
+    let root = Path::new("/notes");
+    assert_eq!(session_dir(root, &info(""), true), root.join("2026-03"));
+    assert_eq!(session_dir(root, &info(""), false), root);
+}
+
+/// A name already taken by any of the extensions gets a number.
+#[test]
+fn unique_stems() {
+    let dir = std::env::temp_dir().join("dikto_test_sessions_unique");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    assert_eq!(unique_stem(&dir, "note", &["md", "wav"]), "note");
+    std::fs::write(dir.join("note.wav"), b"").unwrap();
+    std::fs::write(dir.join("note-2.md"), b"").unwrap();
+    assert_eq!(unique_stem(&dir, "note", &["md", "wav"]), "note-3");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// The Markdown file includes the summary and action items when present.
+#[test]
+fn markdown_sections() {
+    assert_eq!(transcript_markdown(&result(" Just text. ")), "Just text.\n");
+    let full = TranscriptionResult {
+        summary: Some("Short.".to_string()),
+        action_items: vec![ActionItem {
+            text: "Call Bo".to_string(),
+            trigger: "remind me to".to_string(),
+        }],
+        ..result("Remind me to call Bo.")
+    };
+    assert_eq!(
+        transcript_markdown(&full),
+        "Remind me to call Bo.\n\n## Summary\n\nShort.\n\n## Action items\n\n- [ ] Call Bo\n"
+    );
+}
+
+/// A saved session writes its transcript and audio side by side in the
+/// month folder.
+#[test]
+fn saves_transcript_and_audio() {
+    let root = std::env::temp_dir().join("dikto_test_sessions_save");
+    let _ = std::fs::remove_dir_all(&root);
+    let config = SavedSessionsConfig {
+        save_transcripts: true,
+        save_audio: true,
+        ..SavedSessionsConfig::default()
+    };
+    let mut audio = AudioSpool::create(&root.join("spool")).unwrap();
+    audio.append(&[0.0, 0.5, -0.5, 0.25]).unwrap();
+
+    let text = "Quick note about the launch";
+    let paths = save_session(
+        &root,
+        &config,
+        &info(text),
+        &result(text),
+        Some(&mut audio),
+        None,
+    )
+    .unwrap();
+    let dir = root.join("2026-03");
+    let stem = "2026-03-05-14-07-quick-note-about-the-launch";
+    assert_eq!(
+        paths,
+        vec![
+            dir.join(format!("{stem}.md")),
+            dir.join(format!("{stem}.wav"))
+        ]
+    );
+    assert_eq!(
+        std::fs::read_to_string(&paths[0]).unwrap(),
+        "Quick note about the launch\n"
+    );
+    let samples = dikto_core::wav::read_wav(&paths[1]).unwrap();
+    assert_eq!(samples.len(), 4);
+    assert!((samples[1] - 0.5).abs() < 0.001);
+
+    // Same minute, same words: the next session gets its own name
+    let again = save_session(&root, &config, &info(text), &result(text), None, None).unwrap();
+    assert_eq!(again, vec![dir.join(format!("{stem}-2.md"))]);
+    drop(audio);
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+/// Saved audio should get the session's settings next to it, and reading
+/// them back should give the same config.
+#[test]
+fn saves_settings_snapshot_with_audio() {
+    let root = std::env::temp_dir().join("dikto_test_sessions_snapshot");
+    let _ = std::fs::remove_dir_all(&root);
+    let config = SavedSessionsConfig {
+        save_audio: true,
+        ..SavedSessionsConfig::default()
+    };
+    let mut audio = AudioSpool::create(&root.join("spool")).unwrap();
+    audio.append(&[0.0, 0.5]).unwrap();
+    let snapshot = DiktoConfig {
+        language: "de".to_string(),
+        silence_duration_ms: 900,
+        ..DiktoConfig::default()
+    };
+
+    let text = "Kurze Notiz";
+    let paths = save_session(
+        &root,
+        &config,
+        &info(text),
+        &result(text),
+        Some(&mut audio),
+        Some(&snapshot),
+    )
+    .unwrap();
+    assert_eq!(paths.len(), 2);
+    assert_eq!(paths[1], snapshot_path(&paths[0]));
+    let read = read_snapshot(&paths[0]).unwrap().unwrap();
+    assert_eq!(read.language, "de");
+    assert_eq!(read.silence_duration_ms, 900);
+    drop(audio);
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+/// A recording without saved settings should read as None, and unreadable
+/// settings as an error.
+#[test]
+fn read_snapshot_missing_or_corrupt() {
+    let dir = std::env::temp_dir().join("dikto_test_sessions_no_snapshot");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let wav = dir.join("note.wav");
+    assert!(read_snapshot(&wav).unwrap().is_none());
+
+    std::fs::write(dir.join("note.json"), "not json").unwrap();
+    assert!(read_snapshot(&wav).is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,90 @@
+// Tests for dikto_core::spelling — letter-by-letter input and the
+// "spell out" voice command.
+
+use dikto_core::config::TextProcConfig;
+use dikto_core::spelling::{strip_command, Spelling};
+use dikto_core::textproc::{PostProcessor, TextPipeline};
+
+fn spell(text: &str) -> String {
+    Spelling.process(text)
+}
+
+// ---------------------------------------------------------------------------
+// Spelling stage
+// ---------------------------------------------------------------------------
+
+/// NATO words and digit names concatenate into a literal string.
+#[test]
+fn nato_alphabet_and_digits() {
+    assert_eq!(spell("alpha bravo seven"), "ab7");
+    assert_eq!(spell("x-ray yankee zulu zero"), "xyz0");
+}
+
+/// Letter names and recognizer-emitted single letters are accepted.
+#[test]
+fn letter_names_and_single_letters() {
+    assert_eq!(spell("bee double u zed"), "bwz");
+    assert_eq!(spell("A, B, 7."), "ab7");
+}
+
+/// "capital" uppercases only the next character.
+#[test]
+fn capital_applies_to_next_character() {
+    assert_eq!(spell("capital alpha bravo"), "Ab");
+}
+
+/// Symbol words support email-style input; unknown words pass through.
+#[test]
+fn symbols_and_unknown_words() {
+    assert_eq!(
+        spell("john underscore doe at example dot com"),
+        "john_doe@example.com"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Voice command
+// ---------------------------------------------------------------------------
+
+/// "spell out" at the start of an utterance is stripped; elsewhere it is not.
+#[test]
+fn strip_command_detects_prefix() {
+    assert_eq!(
+        strip_command("Spell out, alpha bravo").as_deref(),
+        Some("alpha bravo")
+    );
+    assert_eq!(strip_command("please spell out alpha"), None);
+    assert_eq!(strip_command("spell out"), None);
+}
+
+/// The default pipeline spells a commanded utterance and leaves prose alone.
+#[test]
+fn pipeline_spells_commanded_utterance() {
+    let pipeline = TextPipeline::for_language("en", &TextProcConfig::default());
+    assert_eq!(pipeline.process("spell out kilo nine"), "k9");
+    assert_eq!(pipeline.process("hello there"), "hello there");
+}
+
+/// Disabling the command leaves "spell out" utterances as prose.
+#[test]
+fn pipeline_command_can_be_disabled() {
+    let config = TextProcConfig {
+        spelling_command: false,
+        prose_rules: true,
+        ..TextProcConfig::default()
+    };
+    let pipeline = TextPipeline::for_language("en", &config);
+    assert_eq!(pipeline.process("spell out kilo"), "Spell out kilo");
+}
+
+/// Spelling mode spells every utterance.
+#[test]
+fn pipeline_spelling_mode() {
+    let config = TextProcConfig {
+        spelling_mode: true,
+        ..TextProcConfig::default()
+    };
+    let pipeline = TextPipeline::for_language("en", &config);
+    assert_eq!(pipeline.stage_names(), vec!["spelling"]);
+    assert_eq!(pipeline.process("tango echo sierra tango"), "test");
+}
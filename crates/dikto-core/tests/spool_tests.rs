@@ -0,0 +1,66 @@
+// Tests for dikto_core::spool — appending audio, reading it back in chunks,
+// clearing, and removing the file on drop.
+
+use dikto_core::spool::AudioSpool;
+
+fn spool_in(name: &str) -> (std::path::PathBuf, AudioSpool) {
+    let dir = std::env::temp_dir().join(name);
+    let spool = AudioSpool::create(&dir).unwrap();
+    (dir, spool)
+}
+
+/// Samples come back in order, split into chunks of at most the given size.
+#[test]
+fn spool_reads_back_in_chunks() {
+    let (dir, mut spool) = spool_in("dikto_test_spool_chunks");
+    spool.append(&[0.1, 0.2, 0.3]).unwrap();
+    spool.append(&[0.4, -0.5]).unwrap();
+    assert_eq!(spool.len(), 5);
+
+    let chunks: Vec<Vec<f32>> = spool.chunks(2).unwrap().map(Result::unwrap).collect();
+    assert_eq!(chunks, vec![vec![0.1, 0.2], vec![0.3, 0.4], vec![-0.5]]);
+    drop(spool);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Appending after reading continues at the end of the spool.
+#[test]
+fn spool_appends_after_read() {
+    let (dir, mut spool) = spool_in("dikto_test_spool_append_after_read");
+    spool.append(&[1.0]).unwrap();
+    assert_eq!(spool.chunks(8).unwrap().count(), 1);
+    spool.append(&[2.0]).unwrap();
+
+    let chunks: Vec<Vec<f32>> = spool.chunks(8).unwrap().map(Result::unwrap).collect();
+    assert_eq!(chunks, vec![vec![1.0, 2.0]]);
+    drop(spool);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// clear() empties the spool for reuse.
+#[test]
+fn spool_clear() {
+    let (dir, mut spool) = spool_in("dikto_test_spool_clear");
+    spool.append(&[1.0, 2.0]).unwrap();
+    spool.clear().unwrap();
+    assert!(spool.is_empty());
+    assert_eq!(spool.chunks(8).unwrap().count(), 0);
+
+    spool.append(&[3.0]).unwrap();
+    let chunks: Vec<Vec<f32>> = spool.chunks(8).unwrap().map(Result::unwrap).collect();
+    assert_eq!(chunks, vec![vec![3.0]]);
+    drop(spool);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Dropping the spool deletes its file.
+#[test]
+fn spool_removed_on_drop() {
+    let (dir, mut spool) = spool_in("dikto_test_spool_drop");
+    spool.append(&[1.0]).unwrap();
+    let path = spool.path().to_path_buf();
+    assert!(path.exists());
+    drop(spool);
+    assert!(!path.exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,97 @@
+// Tests for dikto_core::state — per-app languages, pending downloads, and
+// the state.json roundtrip.
+
+use dikto_core::state::EngineState;
+
+// ---------------------------------------------------------------------------
+// App languages
+// ---------------------------------------------------------------------------
+
+/// A language other than the configured one is remembered for the app.
+#[test]
+fn record_app_language_remembers_override() {
+    let mut state = EngineState::default();
+    state.record_app_language("com.apple.mail", "de", "en");
+    assert_eq!(state.language_for_app("com.apple.mail"), Some("de"));
+    assert_eq!(state.language_for_app("com.apple.Notes"), None);
+}
+
+/// Going back to the configured language forgets the app.
+#[test]
+fn record_app_language_forgets_configured_language() {
+    let mut state = EngineState::default();
+    state.record_app_language("com.apple.mail", "de", "en");
+    state.record_app_language("com.apple.mail", "en", "en");
+    assert!(state.app_languages.is_empty());
+}
+
+/// Sessions without a known app are not remembered.
+#[test]
+fn record_app_language_ignores_unknown_app() {
+    let mut state = EngineState::default();
+    state.record_app_language("", "de", "en");
+    assert!(state.app_languages.is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// Pending downloads
+// ---------------------------------------------------------------------------
+
+/// Starting a download lists it once; finishing it removes it.
+#[test]
+fn set_download_pending_tracks_downloads() {
+    let mut state = EngineState::default();
+    state.set_download_pending("whisper-tiny", true);
+    state.set_download_pending("whisper-tiny", true);
+    state.set_download_pending("parakeet-tdt-0.6b-v2", true);
+    assert_eq!(
+        state.pending_downloads,
+        vec!["whisper-tiny", "parakeet-tdt-0.6b-v2"]
+    );
+
+    state.set_download_pending("whisper-tiny", false);
+    assert_eq!(state.pending_downloads, vec!["parakeet-tdt-0.6b-v2"]);
+}
+
+// ---------------------------------------------------------------------------
+// Persistence
+// ---------------------------------------------------------------------------
+
+/// Saving and loading state should preserve it.
+#[test]
+fn save_load_roundtrip() {
+    let tmp = std::env::temp_dir().join("dikto_test_state_roundtrip");
+    let _ = std::fs::remove_dir_all(&tmp);
+    let path = tmp.join("state.json");
+
+    let mut state = EngineState {
+        last_model: Some("whisper-tiny".to_string()),
+        ..EngineState::default()
+    };
+    state.record_app_language("Slack", "fr", "en");
+    state.set_download_pending("whisper-tiny", true);
+    state.save_to(&path).unwrap();
+
+    assert_eq!(EngineState::load_from(&path), state);
+    let _ = std::fs::remove_dir_all(&tmp);
+}
+
+/// A missing file should load as empty state.
+#[test]
+fn load_missing_file_is_empty() {
+    let path = std::env::temp_dir().join("dikto_test_state_missing/state.json");
+    assert_eq!(EngineState::load_from(&path), EngineState::default());
+}
+
+/// A corrupt file should load as empty state rather than fail.
+#[test]
+fn load_corrupt_file_is_empty() {
+    let tmp = std::env::temp_dir().join("dikto_test_state_corrupt");
+    let _ = std::fs::remove_dir_all(&tmp);
+    std::fs::create_dir_all(&tmp).unwrap();
+    let path = tmp.join("state.json");
+    std::fs::write(&path, "{not json").unwrap();
+
+    assert_eq!(EngineState::load_from(&path), EngineState::default());
+    let _ = std::fs::remove_dir_all(&tmp);
+}
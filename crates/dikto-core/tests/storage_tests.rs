@@ -0,0 +1,107 @@
+// Tests for dikto_core::storage — atomic writes, locked appends, and the
+// data directory override.
+
+use dikto_core::config::{data_dir, set_data_dir_override};
+use dikto_core::storage::{append_locked, write_atomic, write_atomic_with, FileLock};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn file_names(dir: &std::path::Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+/// An atomic write creates the folder, replaces the old contents, and
+/// leaves no temporary file behind.
+#[test]
+fn write_atomic_replaces_file() {
+    let dir = test_dir("dikto_test_storage_atomic");
+    let path = dir.join("usage.json");
+    write_atomic(&path, b"old").unwrap();
+    write_atomic(&path, b"new").unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+    assert_eq!(file_names(&dir), vec!["usage.json"]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// A failed streaming write keeps the old file and cleans up.
+#[test]
+fn failed_write_keeps_old_file() {
+    let dir = test_dir("dikto_test_storage_failed");
+    let path = dir.join("note.md");
+    write_atomic(&path, b"kept").unwrap();
+    let result = write_atomic_with(&path, |out| {
+        out.write_all(b"half")?;
+        Err(std::io::Error::other("disk full"))
+    });
+    assert!(result.is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "kept");
+    assert_eq!(file_names(&dir), vec!["note.md"]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// Locked appends add to the end and release their lock.
+#[test]
+fn append_locked_appends() {
+    let dir = test_dir("dikto_test_storage_append");
+    let path = dir.join("history.jsonl");
+    append_locked(&path, b"one\n").unwrap();
+    append_locked(&path, b"two\n").unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+    assert_eq!(file_names(&dir), vec!["history.jsonl"]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// A held lock makes others wait and time out; dropping it frees the file.
+#[test]
+fn lock_is_exclusive() {
+    let dir = test_dir("dikto_test_storage_lock");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("lexicon.json");
+    let lock = FileLock::acquire(&path).unwrap();
+    let err = FileLock::acquire(&path).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    drop(lock);
+    assert!(FileLock::acquire(&path).is_ok());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// A lock file left by a crashed process is broken.
+#[test]
+fn stale_lock_is_broken() {
+    let dir = test_dir("dikto_test_storage_stale");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("usage.json");
+    let stale = std::fs::File::create(dir.join("usage.json.lock")).unwrap();
+    stale
+        .set_modified(SystemTime::now() - Duration::from_secs(120))
+        .unwrap();
+    drop(stale);
+    assert!(FileLock::acquire(&path).is_ok());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// An absolute data_dir moves the data directory; relative paths and None
+/// are ignored.
+#[test]
+fn data_dir_override() {
+    let moved = std::env::temp_dir().join("dikto_test_storage_data");
+    set_data_dir_override(Some(&moved.to_string_lossy()));
+    assert_eq!(data_dir().unwrap(), moved);
+    set_data_dir_override(Some("relative/dir"));
+    assert_ne!(data_dir().unwrap(), PathBuf::from("relative/dir"));
+    assert_ne!(data_dir().unwrap(), moved);
+    set_data_dir_override(None);
+    assert_ne!(data_dir().unwrap(), moved);
+}
@@ -0,0 +1,63 @@
+// Tests for dikto_core::summarize — when to summarize and the command runner.
+
+use dikto_core::config::SummaryConfig;
+use dikto_core::summarize::{should_summarize, summarize, SummarizeError};
+
+fn with_command(command: &str) -> SummaryConfig {
+    SummaryConfig {
+        min_words: 3,
+        command: command.to_string(),
+        timeout_secs: 5,
+        ..SummaryConfig::default()
+    }
+}
+
+/// Nothing is summarized without a summarizer or below the word count.
+#[test]
+fn should_summarize_needs_summarizer_and_length() {
+    assert!(!should_summarize(
+        "one two three four",
+        &SummaryConfig::default()
+    ));
+    let config = with_command("cat");
+    assert!(!should_summarize("one two", &config));
+    assert!(should_summarize("one two three", &config));
+}
+
+/// The command gets the transcript on stdin; its trimmed stdout is the summary.
+#[cfg(unix)]
+#[test]
+fn summarize_with_command() {
+    let summary = summarize("call sam about the budget", &with_command("tr a-z A-Z")).unwrap();
+    assert_eq!(summary, "CALL SAM ABOUT THE BUDGET");
+}
+
+/// A failing or silent command gives no summary.
+#[cfg(unix)]
+#[test]
+fn summarize_command_failures() {
+    assert!(matches!(
+        summarize("text", &with_command("exit 3")),
+        Err(SummarizeError::Command(_))
+    ));
+    assert!(matches!(
+        summarize("text", &with_command("cat > /dev/null")),
+        Err(SummarizeError::Command(_))
+    ));
+}
+
+/// A command that runs past the timeout is stopped.
+#[cfg(unix)]
+#[test]
+fn summarize_command_timeout() {
+    let config = SummaryConfig {
+        timeout_secs: 1,
+        ..with_command("sleep 10")
+    };
+    let started = std::time::Instant::now();
+    assert!(matches!(
+        summarize("text", &config),
+        Err(SummarizeError::Timeout(1))
+    ));
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+}
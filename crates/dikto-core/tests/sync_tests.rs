@@ -0,0 +1,129 @@
+// Tests for dikto_core::sync — the settings sync document.
+
+use dikto_core::config::{ActivationMode, DiktoConfig};
+use dikto_core::sync::{apply_settings, export_settings, merge_documents, SyncError};
+
+fn laptop() -> DiktoConfig {
+    let mut config = DiktoConfig {
+        language: "de".to_string(),
+        activation_mode: ActivationMode::Toggle,
+        model_name: "whisper-large-v3".to_string(),
+        model_path_override: Some("/Volumes/models/ggml.bin".to_string()),
+        ..DiktoConfig::default()
+    };
+    config.summary.min_words = 500;
+    config.summary.command = "/opt/homebrew/bin/llm".to_string();
+    config
+}
+
+/// Exported documents carry preferences but not device-local fields.
+#[test]
+fn export_leaves_out_device_fields() {
+    let blob = export_settings(&laptop(), 1_000);
+    let doc: serde_json::Value = serde_json::from_str(&blob).unwrap();
+    assert_eq!(doc["format"], "dikto-settings");
+    assert_eq!(doc["version"], 1);
+    assert_eq!(doc["updated_at"], 1_000);
+    let settings = &doc["settings"];
+    assert_eq!(settings["language"], "de");
+    assert_eq!(settings["summary"]["min_words"], 500);
+    assert!(settings.get("model_name").is_none());
+    assert!(settings.get("model_path_override").is_none());
+    assert!(settings.get("advanced").is_none());
+    assert!(settings["summary"].get("command").is_none());
+}
+
+/// API keys and the services transcripts go to are never exported, and
+/// a document can't change them here.
+#[test]
+fn secrets_and_endpoints_stay_local() {
+    let mut config = laptop();
+    config.translation.api_url = "https://translate.example.com".to_string();
+    config.translation.api_key = "translate-secret".to_string();
+    config.summary.api_url = "https://summary.example.com".to_string();
+    config.summary.api_key = "summary-secret".to_string();
+    let blob = export_settings(&config, 1_000);
+    assert!(!blob.contains("secret"));
+    assert!(!blob.contains("example.com"));
+
+    let smuggled = r#"{"format":"dikto-settings","version":1,"updated_at":5,
+        "settings":{"translation":{"api_url":"https://evil.example","api_key":"x"},
+        "summary":{"api_url":"https://evil.example","api_key":"y"}}}"#;
+    let applied = apply_settings(&config, smuggled).unwrap();
+    assert_eq!(applied.translation.api_url, "https://translate.example.com");
+    assert_eq!(applied.translation.api_key, "translate-secret");
+    assert_eq!(applied.summary.api_url, "https://summary.example.com");
+    assert_eq!(applied.summary.api_key, "summary-secret");
+}
+
+/// Applying a document changes preferences and keeps this machine's
+/// models and paths.
+#[test]
+fn apply_keeps_device_fields() {
+    let desktop = DiktoConfig {
+        model_name: "parakeet-tdt-0.6b-v2".to_string(),
+        ..DiktoConfig::default()
+    };
+    let applied = apply_settings(&desktop, &export_settings(&laptop(), 1_000)).unwrap();
+    assert_eq!(applied.language, "de");
+    assert_eq!(applied.activation_mode, ActivationMode::Toggle);
+    assert_eq!(applied.summary.min_words, 500);
+    assert_eq!(applied.model_name, "parakeet-tdt-0.6b-v2");
+    assert_eq!(applied.model_path_override, None);
+    assert!(applied.summary.command.is_empty());
+}
+
+/// Fields a document doesn't have keep their local values, and device
+/// fields smuggled into one are ignored.
+#[test]
+fn partial_documents() {
+    let blob = r#"{"format":"dikto-settings","version":1,"updated_at":5,
+        "settings":{"auto_paste":false,"summary":{"min_words":50},
+        "model_name":"whisper-tiny","future_field":true}}"#;
+    let local = laptop();
+    let applied = apply_settings(&local, blob).unwrap();
+    assert!(!applied.auto_paste);
+    assert_eq!(applied.summary.min_words, 50);
+    assert_eq!(applied.summary.timeout_secs, local.summary.timeout_secs);
+    assert_eq!(applied.language, "de");
+    assert_eq!(applied.model_name, "whisper-large-v3");
+}
+
+/// Documents from newer versions and other JSON are refused.
+#[test]
+fn rejects_unknown_documents() {
+    let newer = r#"{"format":"dikto-settings","version":2,"updated_at":0,"settings":{}}"#;
+    assert!(matches!(
+        apply_settings(&laptop(), newer),
+        Err(SyncError::UnsupportedVersion(2))
+    ));
+    let other = r#"{"format":"other","version":1,"updated_at":0,"settings":{}}"#;
+    assert!(matches!(
+        apply_settings(&laptop(), other),
+        Err(SyncError::NotSettings)
+    ));
+    assert!(matches!(
+        apply_settings(&laptop(), "not json"),
+        Err(SyncError::Json(_))
+    ));
+}
+
+/// Merging takes shared fields from the newer document and keeps the rest.
+#[test]
+fn merge_prefers_newer_document() {
+    let older = r#"{"format":"dikto-settings","version":1,"updated_at":10,
+        "settings":{"language":"fr","auto_copy":false,"summary":{"min_words":100}}}"#;
+    let newer = r#"{"format":"dikto-settings","version":1,"updated_at":20,
+        "settings":{"language":"es","summary":{"timeout_secs":30}}}"#;
+    for merged in [
+        merge_documents(older, newer).unwrap(),
+        merge_documents(newer, older).unwrap(),
+    ] {
+        let doc: serde_json::Value = serde_json::from_str(&merged).unwrap();
+        assert_eq!(doc["updated_at"], 20);
+        assert_eq!(doc["settings"]["language"], "es");
+        assert_eq!(doc["settings"]["auto_copy"], false);
+        assert_eq!(doc["settings"]["summary"]["min_words"], 100);
+        assert_eq!(doc["settings"]["summary"]["timeout_secs"], 30);
+    }
+}
@@ -0,0 +1,124 @@
+// Tests for dikto_core::templates — placeholders, spoken commands, filling,
+// and the templates file.
+
+use dikto_core::templates::{
+    find_template, load_templates, parse_command, placeholders, save_templates, Template,
+    TemplateFill,
+};
+
+fn template(name: &str, body: &str) -> Template {
+    Template {
+        name: name.to_string(),
+        body: body.to_string(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// placeholders
+// ---------------------------------------------------------------------------
+
+/// Placeholders are listed once each, in order, with spaces trimmed.
+#[test]
+fn placeholders_in_order() {
+    let body = "To: {{ who }}\nRe: {{topic}}\nCc: {{who}}\n{{}} {{unclosed";
+    assert_eq!(placeholders(body), ["who", "topic"]);
+    assert!(placeholders("No fields").is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// parse_command / find_template
+// ---------------------------------------------------------------------------
+
+/// The command is "insert <name> template", whatever the case and punctuation.
+#[test]
+fn parse_command_extracts_name() {
+    assert_eq!(
+        parse_command("Insert meeting notes template.").as_deref(),
+        Some("meeting notes")
+    );
+    assert_eq!(
+        parse_command("insert bug-report template").as_deref(),
+        Some("bugreport")
+    );
+    assert_eq!(parse_command("Insert template."), None);
+    assert_eq!(parse_command("Please insert the template now"), None);
+    assert_eq!(parse_command("Meeting notes"), None);
+}
+
+/// Spoken names match template names ignoring case, spaces, and punctuation.
+#[test]
+fn find_template_by_spoken_name() {
+    let templates = vec![
+        template("Meeting Notes", "{{notes}}"),
+        template("Bug-Report", "{{steps}}"),
+    ];
+    let found = find_template(&templates, "meeting notes").unwrap();
+    assert_eq!(found.name, "Meeting Notes");
+    let spoken = parse_command("Insert bug report template.").unwrap();
+    assert_eq!(
+        find_template(&templates, &spoken).unwrap().name,
+        "Bug-Report"
+    );
+    assert!(find_template(&templates, "standup").is_none());
+    assert!(find_template(&templates, "").is_none());
+}
+
+// ---------------------------------------------------------------------------
+// TemplateFill
+// ---------------------------------------------------------------------------
+
+/// Each utterance fills the next placeholder; repeats get the same value.
+#[test]
+fn fill_placeholders_in_order() {
+    let mut fill = TemplateFill::new(template("m", "Who: {{who}}\nWhat: {{what}}\nBy {{who}}"));
+    assert_eq!(fill.next_placeholder(), Some("who"));
+    assert_eq!(fill.render(), "Who: {{who}}\nWhat: {{what}}\nBy {{who}}");
+
+    fill.fill(" Ana and Sam. ");
+    assert_eq!(fill.next_placeholder(), Some("what"));
+    assert_eq!(
+        fill.render(),
+        "Who: Ana and Sam.\nWhat: {{what}}\nBy Ana and Sam."
+    );
+    assert!(!fill.is_complete());
+
+    fill.fill("Budget review.");
+    assert!(fill.is_complete());
+    assert_eq!(fill.next_placeholder(), None);
+    assert_eq!(
+        fill.render(),
+        "Who: Ana and Sam.\nWhat: Budget review.\nBy Ana and Sam."
+    );
+
+    fill.fill("ignored");
+    assert!(fill.render().ends_with("By Ana and Sam."));
+}
+
+/// A template without placeholders is complete right away.
+#[test]
+fn fill_without_placeholders() {
+    let fill = TemplateFill::new(template("sig", "Best,\nAna"));
+    assert!(fill.is_complete());
+    assert_eq!(fill.render(), "Best,\nAna");
+}
+
+// ---------------------------------------------------------------------------
+// load_templates / save_templates
+// ---------------------------------------------------------------------------
+
+/// Templates survive a save and load; missing or corrupt files give none.
+#[test]
+fn templates_roundtrip() {
+    let tmp = std::env::temp_dir().join("dikto_test_templates_roundtrip");
+    let _ = std::fs::remove_dir_all(&tmp);
+    let path = tmp.join("templates.json");
+    assert!(load_templates(&path).is_empty());
+
+    let templates = vec![template("Meeting notes", "Attendees: {{attendees}}")];
+    save_templates(&path, &templates).unwrap();
+    assert_eq!(load_templates(&path), templates);
+
+    std::fs::write(&path, "not json").unwrap();
+    assert!(load_templates(&path).is_empty());
+    let _ = std::fs::remove_dir_all(&tmp);
+}
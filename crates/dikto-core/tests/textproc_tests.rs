@@ -0,0 +1,473 @@
+// Tests for dikto_core::textproc — per-language rule tables, the built-in
+// post-processing stages, config overrides, and grapheme safety on
+// non-ASCII transcripts.
+
+use dikto_core::config::{LanguageRuleOverride, TextProcConfig, TextProcPreset};
+use dikto_core::textproc::{
+    is_hallucination, join_utterances, tail_graphemes, truncate_graphemes, LanguageRules,
+    NumberGrouping, PostProcessor, PunctuationSpacing, SentenceCase, SpokenPunctuation,
+    TextPipeline,
+};
+use proptest::prelude::*;
+use unicode_segmentation::UnicodeSegmentation;
+
+fn spoken(language: &str) -> SpokenPunctuation {
+    SpokenPunctuation::new(&LanguageRules::for_language(language).spoken_punctuation)
+}
+
+// ---------------------------------------------------------------------------
+// Rule tables
+// ---------------------------------------------------------------------------
+
+/// Built-in tables should differ by language.
+#[test]
+fn rules_differ_by_language() {
+    let en = LanguageRules::for_language("en");
+    let de = LanguageRules::for_language("de");
+    let fr = LanguageRules::for_language("fr");
+    assert_eq!(en.thousands_separator, ",");
+    assert_eq!(de.thousands_separator, ".");
+    assert!(fr.space_before_punctuation);
+    assert!(!en.space_before_punctuation);
+    assert!(de.spoken_punctuation.iter().any(|(p, _)| p == "komma"));
+}
+
+/// Unknown languages fall back to English words without number grouping.
+#[test]
+fn unknown_language_uses_fallback() {
+    let rules = LanguageRules::for_language("auto");
+    assert!(rules.spoken_punctuation.iter().any(|(p, _)| p == "comma"));
+    assert!(rules.thousands_separator.is_empty());
+}
+
+/// Overrides replace, add, and remove individual rules.
+#[test]
+fn override_adjusts_rules() {
+    let mut rules = LanguageRules::for_language("en");
+    let ov = LanguageRuleOverride {
+        spoken_punctuation: [
+            ("Dash".to_string(), " -".to_string()),
+            ("colon".to_string(), String::new()),
+        ]
+        .into_iter()
+        .collect(),
+        capitalize_sentences: Some(false),
+        thousands_separator: Some("'".to_string()),
+        space_before_punctuation: None,
+    };
+    rules.apply_override(&ov);
+    assert!(rules
+        .spoken_punctuation
+        .contains(&("dash".to_string(), " -".to_string())));
+    assert!(!rules.spoken_punctuation.iter().any(|(p, _)| p == "colon"));
+    assert!(!rules.capitalize_sentences);
+    assert_eq!(rules.thousands_separator, "'");
+    assert!(!rules.space_before_punctuation);
+}
+
+// ---------------------------------------------------------------------------
+// Stages
+// ---------------------------------------------------------------------------
+
+/// Spoken punctuation words become marks attached to the previous word.
+#[test]
+fn spoken_punctuation_replaces_words() {
+    let stage = spoken("en");
+    assert_eq!(stage.process("hello comma world period"), "hello, world.");
+    assert_eq!(
+        stage.process("first line new line second line"),
+        "first line\nsecond line"
+    );
+}
+
+/// A mark the recognizer already emitted is not doubled.
+#[test]
+fn spoken_punctuation_drops_recognizer_mark() {
+    assert_eq!(spoken("en").process("hello, comma, world"), "hello, world");
+}
+
+/// Multi-word phrases win over their single-word prefixes.
+#[test]
+fn spoken_punctuation_prefers_longest_phrase() {
+    assert_eq!(
+        spoken("fr").process("bonjour point d'interrogation"),
+        "bonjour?"
+    );
+    assert_eq!(spoken("es").process("hola punto y coma"), "hola;");
+}
+
+/// Sentence case uppercases the first letter of each sentence only.
+#[test]
+fn sentence_case_capitalizes_sentences() {
+    let stage = SentenceCase::new(false);
+    assert_eq!(
+        stage.process("hello. world? yes! ok"),
+        "Hello. World? Yes! Ok"
+    );
+    assert_eq!(stage.process("3 apples"), "3 apples");
+}
+
+/// Turkic casing maps a leading i to dotted capital İ.
+#[test]
+fn sentence_case_turkic_dotted_i() {
+    assert_eq!(SentenceCase::new(true).process("istanbul"), "İstanbul");
+    assert_eq!(SentenceCase::new(false).process("istanbul"), "Istanbul");
+}
+
+/// Long standalone digit runs are grouped; years, decimals, and grouped numbers are not.
+#[test]
+fn number_grouping_groups_long_runs() {
+    let stage = NumberGrouping::new(",");
+    assert_eq!(
+        stage.process("about 1234567 people"),
+        "about 1,234,567 people"
+    );
+    assert_eq!(stage.process("total 12345."), "total 12,345.");
+    assert_eq!(stage.process("in 2024"), "in 2024");
+    assert_eq!(stage.process("pi is 3.14159265"), "pi is 3.14159265");
+    assert_eq!(stage.process("x 123456.5"), "x 123456.5");
+    assert_eq!(stage.process("id abc12345"), "id abc12345");
+    assert_eq!(stage.process("1,234,567"), "1,234,567");
+}
+
+/// ZIP codes, phone numbers, and IDs are not quantities and stay as spoken.
+#[test]
+fn number_grouping_skips_identifiers() {
+    let stage = NumberGrouping::new(",");
+    assert_eq!(stage.process("zip code 94103"), "zip code 94103");
+    assert_eq!(
+        stage.process("call me at 5551234567"),
+        "call me at 5551234567"
+    );
+    assert_eq!(
+        stage.process("order number: 123456"),
+        "order number: 123456"
+    );
+    assert_eq!(stage.process("ticket #48213"), "ticket #48213");
+    assert_eq!(stage.process("account 012345"), "account 012345");
+    assert_eq!(
+        stage.process("raised 250000 dollars"),
+        "raised 250,000 dollars"
+    );
+}
+
+/// French spacing inserts a space before high punctuation, once.
+#[test]
+fn punctuation_spacing_french() {
+    assert_eq!(
+        PunctuationSpacing.process("Bonjour! Ça va?"),
+        "Bonjour ! Ça va ?"
+    );
+    assert_eq!(PunctuationSpacing.process("Bonjour !"), "Bonjour !");
+}
+
+// ---------------------------------------------------------------------------
+// Pipeline
+// ---------------------------------------------------------------------------
+
+/// Default config: the recognizer's text as is, apart from spelling
+/// commands.
+#[test]
+fn pipeline_default_stages() {
+    let pipeline = TextPipeline::for_language("en", &TextProcConfig::default());
+    assert!(pipeline.stage_names().is_empty());
+    assert_eq!(pipeline.process("zip code 94103"), "zip code 94103");
+    assert_eq!(pipeline.process("call 5551234567"), "call 5551234567");
+}
+
+/// Prose rules add the language stages; numbers are grouped only on
+/// request.
+#[test]
+fn pipeline_prose_stages() {
+    let mut config = TextProcConfig {
+        prose_rules: true,
+        ..TextProcConfig::default()
+    };
+    let en = TextPipeline::for_language("en", &config);
+    assert_eq!(en.stage_names(), vec!["sentence_case"]);
+    assert_eq!(en.process("about 1234567 people"), "About 1234567 people");
+    config.group_numbers = true;
+    let fr = TextPipeline::for_language("fr", &config);
+    assert_eq!(
+        fr.stage_names(),
+        vec!["number_grouping", "sentence_case", "punctuation_spacing"]
+    );
+}
+
+/// A disabled pipeline returns text unchanged.
+#[test]
+fn pipeline_disabled_is_identity() {
+    let config = TextProcConfig {
+        enabled: false,
+        ..TextProcConfig::default()
+    };
+    let pipeline = TextPipeline::for_language("en", &config);
+    assert!(pipeline.stage_names().is_empty());
+    assert_eq!(pipeline.process("hello comma world"), "hello comma world");
+}
+
+/// Session presets pick the stages regardless of the user's settings.
+#[test]
+fn pipeline_presets() {
+    let user = TextProcConfig {
+        math_mode: true,
+        ..TextProcConfig::default()
+    };
+    let stages = |preset| {
+        TextPipeline::for_language("en", &user.with_preset(preset))
+            .stage_names()
+            .join(",")
+    };
+    assert_eq!(stages(TextProcPreset::Config), "math_latex");
+    assert_eq!(stages(TextProcPreset::Raw), "");
+    assert_eq!(stages(TextProcPreset::Prose), "sentence_case");
+    assert_eq!(stages(TextProcPreset::Code), "spoken_punctuation");
+}
+
+/// The code preset never adds periods or paragraphs between utterances.
+#[test]
+fn code_preset_skips_breaks() {
+    let config = TextProcConfig::default().with_preset(TextProcPreset::Code);
+    let text = join_utterances(&parts(&[(0, "let x"), (9000, "x + 1")]), &config);
+    assert_eq!(text, "let x x + 1");
+}
+
+/// Presets parse by name, case-insensitively.
+#[test]
+fn preset_from_str() {
+    assert_eq!("RAW".parse::<TextProcPreset>(), Ok(TextProcPreset::Raw));
+    assert_eq!("code".parse::<TextProcPreset>(), Ok(TextProcPreset::Code));
+    assert!("fancy".parse::<TextProcPreset>().is_err());
+}
+
+/// Full French pipeline with spoken punctuation enabled.
+#[test]
+fn pipeline_french_spoken_punctuation() {
+    let config = TextProcConfig {
+        spoken_punctuation: true,
+        prose_rules: true,
+        ..TextProcConfig::default()
+    };
+    let pipeline = TextPipeline::for_language("fr", &config);
+    assert_eq!(
+        pipeline.process("bonjour point d'exclamation ça va point d'interrogation"),
+        "Bonjour ! Ça va ?"
+    );
+}
+
+/// Overrides are looked up by the session language only.
+#[test]
+fn pipeline_applies_matching_override() {
+    let mut config = TextProcConfig {
+        prose_rules: true,
+        group_numbers: true,
+        ..TextProcConfig::default()
+    };
+    config.language_overrides.insert(
+        "de".to_string(),
+        LanguageRuleOverride {
+            capitalize_sentences: Some(false),
+            ..LanguageRuleOverride::default()
+        },
+    );
+    let de = TextPipeline::for_language("de", &config);
+    assert_eq!(de.stage_names(), vec!["number_grouping"]);
+    let en = TextPipeline::for_language("en", &config);
+    assert!(en.stage_names().contains(&"sentence_case"));
+}
+
+// ---------------------------------------------------------------------------
+// is_hallucination
+// ---------------------------------------------------------------------------
+
+/// The filter lives in textproc so it builds without the native feature;
+/// engine re-exports it.
+#[test]
+fn hallucination_filter_in_textproc() {
+    assert!(is_hallucination(" (Music) "));
+    assert!(!is_hallucination("music"));
+    assert!(dikto_core::engine::is_hallucination("[BLANK_AUDIO]"));
+}
+
+// ---------------------------------------------------------------------------
+// join_utterances
+// ---------------------------------------------------------------------------
+
+fn parts(items: &[(u32, &str)]) -> Vec<(u32, String)> {
+    items.iter().map(|(g, t)| (*g, t.to_string())).collect()
+}
+
+/// Short pauses join with a space.
+#[test]
+fn join_short_pause_uses_space() {
+    let text = join_utterances(
+        &parts(&[(0, "Hello there"), (1600, "how are you")]),
+        &TextProcConfig::default(),
+    );
+    assert_eq!(text, "Hello there how are you");
+}
+
+/// A pause past sentence_break_ms closes the previous sentence.
+#[test]
+fn join_sentence_break_adds_period() {
+    let text = join_utterances(
+        &parts(&[(0, "First point"), (2500, "Second point.")]),
+        &TextProcConfig::default(),
+    );
+    assert_eq!(text, "First point. Second point.");
+}
+
+/// A pause past paragraph_break_ms starts a new paragraph without doubling
+/// existing punctuation.
+#[test]
+fn join_paragraph_break() {
+    let text = join_utterances(
+        &parts(&[(0, "Done?"), (5000, "Next topic")]),
+        &TextProcConfig::default(),
+    );
+    assert_eq!(text, "Done?\n\nNext topic");
+}
+
+/// Zero thresholds and a disabled pipeline turn breaks off.
+#[test]
+fn join_breaks_can_be_disabled() {
+    let segments = parts(&[(0, "a"), (9000, "b")]);
+    let off = TextProcConfig {
+        sentence_break_ms: 0,
+        paragraph_break_ms: 0,
+        ..TextProcConfig::default()
+    };
+    assert_eq!(join_utterances(&segments, &off), "a b");
+    let disabled = TextProcConfig {
+        enabled: false,
+        ..TextProcConfig::default()
+    };
+    assert_eq!(join_utterances(&segments, &disabled), "a b");
+}
+
+/// Math mode keeps paragraph breaks but never adds periods.
+#[test]
+fn join_math_mode_skips_period() {
+    let config = TextProcConfig {
+        math_mode: true,
+        ..TextProcConfig::default()
+    };
+    let text = join_utterances(&parts(&[(0, "x^2"), (5000, "y^2")]), &config);
+    assert_eq!(text, "x^2\n\ny^2");
+}
+
+// ---------------------------------------------------------------------------
+// Graphemes
+// ---------------------------------------------------------------------------
+
+/// Ukrainian and Greek letters, a decomposed accent, emoji with a skin tone
+/// and a ZWJ sequence, digits, and sentence punctuation.
+const NON_ASCII: &str =
+    "([а-щьюяґєії]|[α-ρσ-ω]|e\u{301}|\u{1F44D}\u{1F3FD}|\u{1F469}\u{200D}\u{1F4BB}|[0-9]| |[.?!,]){0,80}";
+
+fn graphemes(text: &str) -> Vec<&str> {
+    text.graphemes(true).collect()
+}
+
+/// Letter graphemes, lowercased one by one.
+fn letters(text: &str) -> Vec<String> {
+    text.graphemes(true)
+        .filter(|g| g.starts_with(char::is_alphabetic))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Capitalizing a decomposed letter keeps its accent on it.
+#[test]
+fn sentence_case_keeps_combining_marks() {
+    assert_eq!(
+        SentenceCase::new(false).process("e\u{301}cole. i\u{308}ржа"),
+        "E\u{301}cole. I\u{308}ржа"
+    );
+}
+
+/// A CRLF line break starts a sentence like a bare newline.
+#[test]
+fn sentence_case_after_crlf() {
+    assert_eq!(
+        SentenceCase::new(false).process("привіт\r\nсвіте"),
+        "Привіт\r\nСвіте"
+    );
+}
+
+/// Keycap digits ("1️⃣") are emoji, not part of a number.
+#[test]
+fn number_grouping_skips_keycaps() {
+    let stage = NumberGrouping::new(",");
+    assert_eq!(
+        stage.process("1234\u{FE0F}\u{20E3}5"),
+        "1234\u{FE0F}\u{20E3}5"
+    );
+    assert_eq!(stage.process("12345\u{20E3}"), "12345\u{20E3}");
+}
+
+/// A spoken mark after an emoji keeps the emoji whole.
+#[test]
+fn spoken_punctuation_after_emoji() {
+    assert_eq!(
+        spoken("en").process("thanks \u{1F44D}\u{1F3FD} period"),
+        "thanks \u{1F44D}\u{1F3FD}."
+    );
+}
+
+/// Truncation counts an emoji sequence as one character.
+#[test]
+fn truncate_keeps_emoji_sequences() {
+    let text = "\u{1F469}\u{200D}\u{1F4BB} ok";
+    assert_eq!(truncate_graphemes(text, 1), "\u{1F469}\u{200D}\u{1F4BB}");
+    assert_eq!(tail_graphemes(text, 2), "ok");
+    assert_eq!(tail_graphemes(text, 10), text);
+    assert_eq!(tail_graphemes(text, 0), "");
+}
+
+proptest! {
+    /// Sentence case changes the case of letters and nothing else.
+    #[test]
+    fn sentence_case_keeps_graphemes(text in NON_ASCII) {
+        let out = SentenceCase::new(false).process(&text);
+        let before: Vec<String> = graphemes(&text).iter().map(|g| g.to_lowercase()).collect();
+        let after: Vec<String> = graphemes(&out).iter().map(|g| g.to_lowercase()).collect();
+        prop_assert_eq!(after, before);
+    }
+
+    /// Number grouping only inserts separators between digits.
+    #[test]
+    fn number_grouping_keeps_graphemes(text in NON_ASCII) {
+        let out = NumberGrouping::new("\u{202F}").process(&text);
+        let kept: Vec<&str> = graphemes(&out).into_iter().filter(|g| *g != "\u{202F}").collect();
+        prop_assert_eq!(kept, graphemes(&text));
+    }
+
+    /// French spacing only inserts spaces.
+    #[test]
+    fn punctuation_spacing_keeps_graphemes(text in NON_ASCII) {
+        let out = PunctuationSpacing.process(&text);
+        let kept: Vec<&str> = graphemes(&out).into_iter().filter(|g| *g != " ").collect();
+        let original: Vec<&str> = graphemes(&text).into_iter().filter(|g| *g != " ").collect();
+        prop_assert_eq!(kept, original);
+    }
+
+    /// The full pipeline keeps every letter, accents and all.
+    #[test]
+    fn pipeline_keeps_letters(text in NON_ASCII) {
+        for language in ["uk", "el", "fr"] {
+            let out = TextPipeline::for_language(language, &TextProcConfig::default()).process(&text);
+            prop_assert_eq!(letters(&out), letters(&text));
+        }
+    }
+
+    /// Truncating from either end cuts between graphemes.
+    #[test]
+    fn truncation_cuts_between_graphemes(text in NON_ASCII, max in 0usize..100) {
+        let all = graphemes(&text);
+        let head = truncate_graphemes(&text, max);
+        prop_assert_eq!(graphemes(head), all[..max.min(all.len())].to_vec());
+        let tail = tail_graphemes(&text, max);
+        prop_assert_eq!(graphemes(tail), all[all.len() - max.min(all.len())..].to_vec());
+    }
+}
@@ -1,7 +1,11 @@
 // Tests for dikto_core::transcribe — TranscribeConfig defaults, TranscriptSegment
-// construction, and TranscribeError display messages.
+// construction, TranscribeError display messages, and alternative ranking.
 
-use dikto_core::transcribe::{TranscribeConfig, TranscribeError, TranscriptSegment};
+use dikto_core::transcribe::{
+    group_word_confidence, hypothesis_score, is_whisper_model_file, rank_alternatives,
+    segments_from_timed_tokens, Hypothesis, TranscribeConfig, TranscribeError, TranscriptSegment,
+    WordConfidence, MAX_ALTERNATIVES,
+};
 
 // ---------------------------------------------------------------------------
 // TranscribeConfig
@@ -27,15 +31,22 @@ fn transcribe_config_custom_language() {
 // TranscriptSegment
 // ---------------------------------------------------------------------------
 
-/// A TranscriptSegment should store text and is_final fields.
+/// A TranscriptSegment should store text, is_final, and language fields.
 #[test]
 fn transcript_segment_construction() {
     let seg = TranscriptSegment {
         text: "hello world".to_string(),
         is_final: true,
+        language: Some("de".to_string()),
+        words: Vec::new(),
+        start_secs: None,
+        end_secs: None,
+        possible_overlap: false,
+        no_speech_prob: None,
     };
     assert_eq!(seg.text, "hello world");
     assert!(seg.is_final);
+    assert_eq!(seg.language.as_deref(), Some("de"));
 }
 
 /// TranscriptSegment should be clonable.
@@ -44,6 +55,12 @@ fn transcript_segment_clone() {
     let seg = TranscriptSegment {
         text: "test".to_string(),
         is_final: false,
+        language: None,
+        words: Vec::new(),
+        start_secs: None,
+        end_secs: None,
+        possible_overlap: false,
+        no_speech_prob: None,
     };
     let cloned = seg.clone();
     assert_eq!(cloned.text, "test");
@@ -56,6 +73,12 @@ fn transcript_segment_debug() {
     let seg = TranscriptSegment {
         text: "debug".to_string(),
         is_final: true,
+        language: None,
+        words: Vec::new(),
+        start_secs: None,
+        end_secs: None,
+        possible_overlap: false,
+        no_speech_prob: None,
     };
     let debug_str = format!("{seg:?}");
     assert!(debug_str.contains("debug"));
@@ -88,3 +111,193 @@ fn transcribe_error_not_loaded_display() {
     let err = TranscribeError::NotLoaded;
     assert!(err.to_string().contains("not loaded"));
 }
+
+// ---------------------------------------------------------------------------
+// Word confidence
+// ---------------------------------------------------------------------------
+
+fn tokens(list: &[(&str, f32)]) -> Vec<(String, f32)> {
+    list.iter().map(|(t, p)| (t.to_string(), *p)).collect()
+}
+
+/// Sub-word tokens merge into words, keeping the lowest probability.
+#[test]
+fn word_confidence_groups_subwords() {
+    let words = group_word_confidence(&tokens(&[
+        (" Hello", 0.98),
+        (" Ok", 0.9),
+        ("af", 0.4),
+        ("or", 0.7),
+    ]));
+    assert_eq!(
+        words,
+        vec![
+            WordConfidence {
+                word: "Hello".to_string(),
+                confidence: 0.98
+            },
+            WordConfidence {
+                word: "Okafor".to_string(),
+                confidence: 0.4
+            },
+        ]
+    );
+}
+
+/// Special tokens are skipped and a leading token without a space starts a word.
+#[test]
+fn word_confidence_skips_special_tokens() {
+    let words = group_word_confidence(&tokens(&[
+        ("[_BEG_]", 0.1),
+        ("Hi", 0.8),
+        ("<|endoftext|>", 0.2),
+    ]));
+    assert_eq!(words.len(), 1);
+    assert_eq!(words[0].word, "Hi");
+    assert!((words[0].confidence - 0.8).abs() < f32::EPSILON);
+}
+
+// ---------------------------------------------------------------------------
+// Timed segments
+// ---------------------------------------------------------------------------
+
+fn timed(list: &[(&str, f32, f32)]) -> Vec<(String, f32, f32)> {
+    list.iter()
+        .map(|(t, s, e)| (t.to_string(), *s, *e))
+        .collect()
+}
+
+/// Tokens group into sentences spanning first start to last end.
+#[test]
+fn timed_tokens_split_into_sentences() {
+    let segments = segments_from_timed_tokens(&timed(&[
+        ("▁Hel", 0.0, 0.2),
+        ("lo", 0.2, 0.4),
+        ("▁there.", 0.4, 0.8),
+        ("▁How", 1.2, 1.4),
+        (" are", 1.4, 1.5),
+        (" you?", 1.5, 1.9),
+    ]));
+    assert_eq!(segments.len(), 2);
+    assert_eq!(segments[0].text, "Hello there.");
+    assert!((segments[0].start_secs - 0.0).abs() < f32::EPSILON);
+    assert!((segments[0].end_secs - 0.8).abs() < f32::EPSILON);
+    assert_eq!(segments[1].text, "How are you?");
+    assert!((segments[1].start_secs - 1.2).abs() < f32::EPSILON);
+    assert!((segments[1].end_secs - 1.9).abs() < f32::EPSILON);
+}
+
+/// Trailing text without sentence punctuation still forms a segment.
+#[test]
+fn timed_tokens_trailing_segment() {
+    let segments = segments_from_timed_tokens(&timed(&[(" so", 0.0, 0.3), (" um", 0.3, 0.6)]));
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].text, "so um");
+}
+
+/// No tokens means no segments.
+#[test]
+fn timed_tokens_empty() {
+    assert!(segments_from_timed_tokens(&[]).is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// Whisper model files
+// ---------------------------------------------------------------------------
+
+/// Both legacy ggml-*.bin files and GGUF files are loadable.
+#[test]
+fn whisper_model_file_accepts_ggml_and_gguf() {
+    assert!(is_whisper_model_file("ggml-small.bin"));
+    assert!(is_whisper_model_file("ggml-large-v3-turbo-q8_0.bin"));
+    assert!(is_whisper_model_file("whisper-small-q5_k.gguf"));
+}
+
+/// Unrelated files in a model folder are ignored.
+#[test]
+fn whisper_model_file_rejects_other_files() {
+    assert!(!is_whisper_model_file("model.bin"));
+    assert!(!is_whisper_model_file("ggml-small.bin.downloading"));
+    assert!(!is_whisper_model_file("encoder-model.onnx"));
+}
+
+// ---------------------------------------------------------------------------
+// Alternatives
+// ---------------------------------------------------------------------------
+
+fn hyps(list: &[(&str, f32)]) -> Vec<Hypothesis> {
+    list.iter()
+        .map(|(text, score)| Hypothesis {
+            text: text.to_string(),
+            score: *score,
+        })
+        .collect()
+}
+
+/// Alternatives come back best first, trimmed.
+#[test]
+fn alternatives_sorted_by_score() {
+    let ranked = rank_alternatives(
+        "recognize speech",
+        hyps(&[(" wreck a nice beach", 0.2), (" recognise speech ", 0.6)]),
+    );
+    assert_eq!(
+        ranked,
+        hyps(&[("recognise speech", 0.6), ("wreck a nice beach", 0.2)])
+    );
+}
+
+/// Readings that only differ from the best one or each other in case and
+/// punctuation are dropped, as are empty ones.
+#[test]
+fn alternatives_drop_duplicates_and_empty() {
+    let ranked = rank_alternatives(
+        "Hello, world.",
+        hyps(&[
+            ("hello world", 0.9),
+            ("Hello there.", 0.5),
+            ("hello there", 0.4),
+            (" ... ", 0.3),
+        ]),
+    );
+    assert_eq!(ranked, hyps(&[("Hello there.", 0.5)]));
+}
+
+/// At most MAX_ALTERNATIVES are kept.
+#[test]
+fn alternatives_capped() {
+    let list: Vec<(String, f32)> = (0..10)
+        .map(|i| (format!("take {i}"), i as f32 / 10.0))
+        .collect();
+    let list: Vec<(&str, f32)> = list.iter().map(|(t, s)| (t.as_str(), *s)).collect();
+    let ranked = rank_alternatives("best", hyps(&list));
+    assert_eq!(ranked.len(), MAX_ALTERNATIVES);
+    assert_eq!(ranked[0].text, "take 9");
+}
+
+/// A hypothesis scores the geometric mean of its token probabilities,
+/// ignoring special tokens.
+#[test]
+fn hypothesis_score_is_geometric_mean() {
+    let score = hypothesis_score(&tokens(&[
+        ("[_BEG_]", 0.01),
+        (" hello", 0.5),
+        (" world", 0.5),
+        ("<|endoftext|>", 0.01),
+    ]));
+    assert!((score - 0.5).abs() < 1e-5);
+}
+
+/// One unlikely token pulls the score down.
+#[test]
+fn hypothesis_score_penalizes_unlikely_tokens() {
+    let sure = hypothesis_score(&tokens(&[(" a", 0.9), (" b", 0.9)]));
+    let unsure = hypothesis_score(&tokens(&[(" a", 0.9), (" b", 0.1)]));
+    assert!(unsure < sure);
+}
+
+/// No tokens scores zero.
+#[test]
+fn hypothesis_score_empty() {
+    assert_eq!(hypothesis_score(&[]), 0.0);
+}
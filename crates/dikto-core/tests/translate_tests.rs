@@ -0,0 +1,68 @@
+// Tests for dikto_core::translate — language matching, model lookup, and
+// translator setup errors.
+
+use dikto_core::config::{TranslationConfig, TranslationEngine};
+use dikto_core::translate::{
+    model_path, needs_translation, nllb_language, translator_for, ApiTranslator, OnnxTranslator,
+    TranslateError,
+};
+
+/// Same-language segments and an empty target are left alone.
+#[test]
+fn needs_translation_compares_base_languages() {
+    assert!(needs_translation("de", "en"));
+    assert!(!needs_translation("en", "en"));
+    assert!(!needs_translation("en-US", "EN"));
+    assert!(!needs_translation("de", ""));
+}
+
+/// ISO codes map to NLLB's script-tagged codes.
+#[test]
+fn nllb_codes() {
+    assert_eq!(nllb_language("fr"), Some("fra_Latn"));
+    assert_eq!(nllb_language("zh-CN"), Some("zho_Hans"));
+    assert_eq!(nllb_language("xx"), None);
+}
+
+/// Model names resolve under the models directory; absolute paths are kept.
+#[test]
+fn model_path_name_or_absolute() {
+    let named = TranslationConfig {
+        model: "opus-mt-de-en".to_string(),
+        ..TranslationConfig::default()
+    };
+    assert!(model_path(&named).ends_with("translation/opus-mt-de-en"));
+
+    let dir = std::env::temp_dir().join("dikto_test_translate_model");
+    let absolute = TranslationConfig {
+        model: dir.to_string_lossy().into_owned(),
+        ..TranslationConfig::default()
+    };
+    assert_eq!(model_path(&absolute), dir);
+}
+
+/// A directory without an ONNX export is reported as missing.
+#[test]
+fn local_model_not_found() {
+    let dir = std::env::temp_dir().join("dikto_test_translate_missing");
+    match OnnxTranslator::load(&dir) {
+        Err(TranslateError::ModelNotFound(path)) => assert_eq!(path, dir),
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("loaded a model from an empty directory"),
+    }
+}
+
+/// The API translator needs a URL.
+#[test]
+fn api_translator_requires_url() {
+    assert!(matches!(
+        ApiTranslator::new("  ", ""),
+        Err(TranslateError::Api(_))
+    ));
+    let config = TranslationConfig {
+        target_language: "en".to_string(),
+        engine: TranslationEngine::Api,
+        ..TranslationConfig::default()
+    };
+    assert!(translator_for(&config).is_err());
+}
@@ -0,0 +1,105 @@
+// Tests for dikto_core::usage — local usage counters, summary statistics,
+// file roundtrip, and UTC date formatting.
+
+use dikto_core::usage::{utc_date, UsageCounters};
+
+// ---------------------------------------------------------------------------
+// utc_date
+// ---------------------------------------------------------------------------
+
+/// The Unix epoch should format as 1970-01-01.
+#[test]
+fn utc_date_epoch() {
+    assert_eq!(utc_date(0), "1970-01-01");
+}
+
+/// A known timestamp should format to the right calendar date.
+#[test]
+fn utc_date_known_timestamp() {
+    assert_eq!(utc_date(1_700_000_000), "2023-11-14");
+}
+
+/// Leap days should be handled.
+#[test]
+fn utc_date_leap_day() {
+    assert_eq!(utc_date(951_782_400), "2000-02-29");
+}
+
+// ---------------------------------------------------------------------------
+// UsageCounters — record / stats
+// ---------------------------------------------------------------------------
+
+/// Empty counters should produce zeroed stats.
+#[test]
+fn empty_counters_stats() {
+    let stats = UsageCounters::default().stats("2026-01-01");
+    assert_eq!(stats.total_sessions, 0);
+    assert_eq!(stats.sessions_today, 0);
+    assert!((stats.average_duration_secs - 0.0).abs() < f64::EPSILON);
+    assert!(stats.model_usage.is_empty());
+    assert!(stats.daily.is_empty());
+}
+
+/// Recorded sessions should be counted per day and averaged.
+#[test]
+fn record_counts_sessions_and_average() {
+    let mut counters = UsageCounters::default();
+    counters.record("2026-01-01", "whisper-tiny", 10.0);
+    counters.record("2026-01-02", "whisper-tiny", 20.0);
+    counters.record("2026-01-02", "parakeet-tdt-0.6b-v2", 30.0);
+
+    let stats = counters.stats("2026-01-02");
+    assert_eq!(stats.total_sessions, 3);
+    assert_eq!(stats.sessions_today, 2);
+    assert!((stats.average_duration_secs - 20.0).abs() < 1e-9);
+    assert_eq!(stats.daily.len(), 2);
+    assert_eq!(stats.daily[0].date, "2026-01-01");
+}
+
+/// Model usage should be sorted by session count, most used first.
+#[test]
+fn model_usage_sorted_by_sessions() {
+    let mut counters = UsageCounters::default();
+    counters.record("2026-01-01", "a", 1.0);
+    counters.record("2026-01-01", "b", 1.0);
+    counters.record("2026-01-01", "b", 1.0);
+
+    let stats = counters.stats("2026-01-01");
+    assert_eq!(stats.model_usage[0].model_name, "b");
+    assert_eq!(stats.model_usage[0].sessions, 2);
+}
+
+/// Negative durations should not reduce the total.
+#[test]
+fn record_ignores_negative_duration() {
+    let mut counters = UsageCounters::default();
+    counters.record("2026-01-01", "a", -5.0);
+    let stats = counters.stats("2026-01-01");
+    assert!((stats.average_duration_secs - 0.0).abs() < f64::EPSILON);
+}
+
+// ---------------------------------------------------------------------------
+// UsageCounters — persistence
+// ---------------------------------------------------------------------------
+
+/// Saving and loading counters should preserve them.
+#[test]
+fn save_load_roundtrip() {
+    let tmp = std::env::temp_dir().join("dikto_test_usage_roundtrip");
+    let _ = std::fs::remove_dir_all(&tmp);
+    let path = tmp.join("usage.json");
+
+    let mut counters = UsageCounters::default();
+    counters.record("2026-01-01", "whisper-tiny", 12.5);
+    counters.save_to(&path).unwrap();
+
+    assert_eq!(UsageCounters::load_from(&path), counters);
+    let _ = std::fs::remove_dir_all(&tmp);
+}
+
+/// A missing file should load as empty counters.
+#[test]
+fn load_missing_file_is_empty() {
+    let path = std::env::temp_dir().join("dikto_test_usage_missing/usage.json");
+    assert_eq!(UsageCounters::load_from(&path), UsageCounters::default());
+}
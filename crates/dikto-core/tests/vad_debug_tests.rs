@@ -0,0 +1,140 @@
+// Tests for dikto_core::vad_debug — trace files and offline VAD replay.
+
+use dikto_core::vad::{VadConfig, VadEvent, VadState};
+use dikto_core::vad_debug::{
+    replay, replay_probabilities, speech_regions, ReplayResult, SpeechSegment, VadTrace,
+    VadTraceEntry,
+};
+use dikto_core::wav::read_wav;
+
+fn config(threshold: f32) -> VadConfig {
+    VadConfig {
+        speech_threshold: threshold,
+        speech_activation_frames: 2,
+        silence_duration_ms: 64,
+        min_speech_duration_ms: 0,
+        ..VadConfig::default()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// VadTrace
+// ---------------------------------------------------------------------------
+
+/// A trace writes one JSONL line per chunk and the audio on drop.
+#[test]
+fn trace_writes_jsonl_and_wav() {
+    let dir = std::env::temp_dir().join("dikto_vad_trace_test");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let mut trace = VadTrace::create(&dir).unwrap();
+    let wav_path = trace.wav_path().to_path_buf();
+    trace.record(&[0.0; 512], 0.1, &VadEvent::Silence, VadState::Idle);
+    trace.record(&[0.0; 512], 0.8, &VadEvent::Silence, VadState::Pending);
+    drop(trace);
+
+    assert_eq!(read_wav(&wav_path).unwrap().len(), 1024);
+    let log = std::fs::read_to_string(wav_path.with_extension("jsonl")).unwrap();
+    let entries: Vec<VadTraceEntry> = log
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].offset_ms, 32);
+    assert_eq!(entries[1].state, "Pending");
+    assert_eq!(entries[0].event, "Silence");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// ---------------------------------------------------------------------------
+// Replay
+// ---------------------------------------------------------------------------
+
+/// A burst of speech between silences becomes one segment.
+#[test]
+fn replay_finds_segment() {
+    let probs = [0.1, 0.9, 0.9, 0.9, 0.1, 0.1, 0.1, 0.1];
+    let segments = replay_probabilities(&probs, config(0.5)).unwrap();
+    assert_eq!(
+        segments,
+        vec![SpeechSegment {
+            start_ms: 64,
+            end_ms: 192
+        }]
+    );
+}
+
+/// A higher threshold ignores speech the lower one accepted.
+#[test]
+fn replay_threshold_changes_result() {
+    let probs = [0.6, 0.6, 0.6, 0.1, 0.1];
+    assert_eq!(replay_probabilities(&probs, config(0.5)).unwrap().len(), 1);
+    assert!(replay_probabilities(&probs, config(0.7))
+        .unwrap()
+        .is_empty());
+}
+
+/// Speech still going at the end of the audio is closed at the end.
+#[test]
+fn replay_closes_open_segment() {
+    let probs = [0.9, 0.9, 0.9];
+    let segments = replay_probabilities(&probs, config(0.5)).unwrap();
+    assert_eq!(segments[0].end_ms, 96);
+}
+
+/// Speech regions run from the first to the last speech chunk, without
+/// the silence that ended them.
+#[test]
+fn speech_regions_have_tight_edges() {
+    let probs = [0.1, 0.9, 0.9, 0.9, 0.1, 0.1, 0.1, 0.1, 0.9, 0.9];
+    let regions = speech_regions(&probs, config(0.5)).unwrap();
+    assert_eq!(
+        regions,
+        vec![
+            SpeechSegment {
+                start_ms: 32,
+                end_ms: 128
+            },
+            SpeechSegment {
+                start_ms: 256,
+                end_ms: 320
+            }
+        ]
+    );
+}
+
+/// Speech too short to confirm leaves no region.
+#[test]
+fn speech_regions_skip_unconfirmed_blips() {
+    let probs = [0.1, 0.9, 0.1, 0.1, 0.1];
+    assert!(speech_regions(&probs, config(0.5)).unwrap().is_empty());
+}
+
+/// replay returns one result per threshold; silence has no speech.
+#[test]
+fn replay_silence_per_threshold() {
+    let results = replay(&[0.0; 16000], &[0.2, 0.5], 1500).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[1].speech_threshold, 0.5);
+    assert!(results.iter().all(|r| r.segments.is_empty()));
+}
+
+/// speech_ms sums the segment lengths.
+#[test]
+fn replay_result_speech_ms() {
+    let result = ReplayResult {
+        speech_threshold: 0.35,
+        segments: vec![
+            SpeechSegment {
+                start_ms: 0,
+                end_ms: 100,
+            },
+            SpeechSegment {
+                start_ms: 500,
+                end_ms: 750,
+            },
+        ],
+    };
+    assert_eq!(result.speech_ms(), 350);
+}
@@ -107,6 +107,93 @@ fn pending_no_false_trigger() {
     assert_eq!(vad.state(), VadState::Idle);
 }
 
+// ---------------------------------------------------------------------------
+// State machine — probabilities
+// ---------------------------------------------------------------------------
+
+/// Enough consecutive speech probabilities confirm speech, and enough
+/// silence afterwards ends it.
+#[test]
+fn process_probability_start_and_end() {
+    let config = VadConfig {
+        speech_activation_frames: 3,
+        silence_duration_ms: 96,
+        min_speech_duration_ms: 0,
+        ..VadConfig::default()
+    };
+    let mut vad = VadProcessor::new(config).unwrap();
+    assert_eq!(vad.process_probability(0.9), VadEvent::Silence);
+    assert_eq!(vad.process_probability(0.9), VadEvent::Silence);
+    assert_eq!(vad.process_probability(0.9), VadEvent::SpeechStart);
+    assert_eq!(vad.state(), VadState::Speaking);
+    assert_eq!(vad.process_probability(0.1), VadEvent::SpeechContinue);
+    assert_eq!(vad.process_probability(0.1), VadEvent::SpeechContinue);
+    assert_eq!(vad.process_probability(0.1), VadEvent::SpeechEnd);
+    assert_eq!(vad.state(), VadState::Idle);
+}
+
+/// last_speech_end_sample marks the end of the last speech chunk, not the
+/// silence after it.
+#[test]
+fn last_speech_end_excludes_trailing_silence() {
+    let mut vad = VadProcessor::new(VadConfig::default()).unwrap();
+    assert_eq!(vad.last_speech_end_sample(), 0);
+    vad.process_probability(0.9);
+    vad.process_probability(0.9);
+    vad.process_probability(0.1);
+    vad.process_probability(0.1);
+    assert_eq!(vad.samples_processed(), 4 * 512);
+    assert_eq!(vad.last_speech_end_sample(), 2 * 512);
+    assert_eq!(vad.speech_start_sample(), 0);
+    vad.reset();
+    assert_eq!(vad.samples_processed(), 0);
+    assert_eq!(vad.last_speech_end_sample(), 0);
+}
+
+/// speech_start_sample points at the chunk where the speech run began.
+#[test]
+fn speech_start_sample_marks_run_start() {
+    let mut vad = VadProcessor::new(VadConfig::default()).unwrap();
+    vad.process_probability(0.1);
+    vad.process_probability(0.1);
+    vad.process_probability(0.9);
+    vad.process_probability(0.9);
+    assert_eq!(vad.speech_start_sample(), 2 * 512);
+}
+
+/// process_chunk remembers the probability it computed.
+#[test]
+fn process_chunk_records_last_probability() {
+    let mut vad = VadProcessor::new(VadConfig::default()).unwrap();
+    vad.process_chunk(&[0.0f32; 512]).unwrap();
+    let silence_probability = vad.last_probability();
+    assert!((0.0..0.35).contains(&silence_probability));
+}
+
+// ---------------------------------------------------------------------------
+// process_stream
+// ---------------------------------------------------------------------------
+
+/// Each full chunk yields one event with its range in the stream.
+#[test]
+fn process_stream_yields_chunk_ranges() {
+    let mut vad = VadProcessor::new(VadConfig::default()).unwrap();
+    let events: Vec<_> = vad
+        .process_stream(std::iter::repeat(0.0f32).take(512 * 3))
+        .collect();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0], (VadEvent::Silence, 0..512));
+    assert_eq!(events[2].1, 1024..1536);
+}
+
+/// A trailing partial chunk is dropped.
+#[test]
+fn process_stream_drops_partial_chunk() {
+    let mut vad = VadProcessor::new(VadConfig::default()).unwrap();
+    assert_eq!(vad.process_stream(vec![0.0f32; 700]).count(), 1);
+    assert_eq!(vad.samples_processed(), 512);
+}
+
 // ---------------------------------------------------------------------------
 // Reset
 // ---------------------------------------------------------------------------
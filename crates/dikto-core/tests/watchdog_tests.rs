@@ -0,0 +1,133 @@
+// Tests for dikto_core::watchdog — session heartbeats and stuck-session
+// recovery.
+
+use dikto_core::watchdog::{self, check, Liveness, RecordingFlag, Stall};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+/// Beats reset the time since the last beat.
+#[test]
+fn beats_reset_the_clock() {
+    let liveness = Liveness::new();
+    std::thread::sleep(Duration::from_millis(30));
+    assert!(liveness.since_last_beat() >= Duration::from_millis(30));
+    liveness.beat();
+    assert!(liveness.since_last_beat() < Duration::from_millis(30));
+}
+
+/// A session is abandoned only once.
+#[test]
+fn abandon_once() {
+    let liveness = Liveness::new();
+    assert!(!liveness.is_abandoned());
+    assert!(liveness.abandon());
+    assert!(!liveness.abandon());
+    assert!(liveness.is_abandoned());
+}
+
+/// An exited thread is always a stall; missing beats only past a nonzero
+/// timeout.
+#[test]
+fn stall_detection() {
+    let liveness = Liveness::new();
+    assert_eq!(
+        check(&liveness, true, Duration::ZERO),
+        Some(Stall::ThreadExited)
+    );
+    assert_eq!(check(&liveness, false, Duration::from_secs(60)), None);
+    std::thread::sleep(Duration::from_millis(20));
+    assert_eq!(
+        check(&liveness, false, Duration::from_millis(10)),
+        Some(Stall::Unresponsive)
+    );
+    assert_eq!(check(&liveness, false, Duration::ZERO), None);
+}
+
+/// A pipeline thread that dies holding the recording flag is caught: the
+/// flag is cleared, the session stopped, and the host told.
+#[test]
+fn watchdog_clears_flag_of_dead_thread() {
+    let liveness = Liveness::new();
+    let recording = Arc::new(RecordingFlag::default());
+    assert!(recording.claim(1));
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = mpsc::channel();
+    let pipeline = std::thread::spawn(|| {});
+    watchdog::spawn(
+        liveness.clone(),
+        pipeline,
+        recording.clone(),
+        1,
+        stop_flag.clone(),
+        Duration::from_secs(300),
+        move |stall| tx.send(stall).unwrap(),
+    );
+    let stall = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(stall, Stall::ThreadExited);
+    assert!(!recording.is_set());
+    assert!(stop_flag.load(Ordering::Acquire));
+    assert!(liveness.is_abandoned());
+}
+
+/// A session that finished normally is left alone.
+#[test]
+fn watchdog_ignores_finished_session() {
+    let liveness = Liveness::new();
+    let recording = Arc::new(RecordingFlag::default());
+    let (tx, rx) = mpsc::channel();
+    watchdog::spawn(
+        liveness.clone(),
+        std::thread::spawn(|| {}),
+        recording,
+        1,
+        Arc::new(AtomicBool::new(false)),
+        Duration::from_secs(300),
+        move |stall| tx.send(stall).unwrap(),
+    );
+    assert!(rx.recv_timeout(Duration::from_secs(2)).is_err());
+    assert!(!liveness.is_abandoned());
+}
+
+/// A session that finishes and is followed at once by the next one, before
+/// its watchdog next checks, doesn't get the new session's flag cleared.
+#[test]
+fn watchdog_leaves_next_session_alone() {
+    let liveness = Liveness::new();
+    let recording = Arc::new(RecordingFlag::default());
+    assert!(recording.claim(1));
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = mpsc::channel();
+    let pipeline = std::thread::spawn(|| {});
+    watchdog::spawn(
+        liveness.clone(),
+        pipeline,
+        recording.clone(),
+        1,
+        stop_flag.clone(),
+        Duration::from_secs(300),
+        move |stall| tx.send(stall).unwrap(),
+    );
+    // Session 1 finishes and session 2 starts within one check interval
+    assert!(recording.release(1));
+    assert!(recording.claim(2));
+    assert!(rx.recv_timeout(Duration::from_secs(2)).is_err());
+    assert!(recording.is_owned_by(2));
+    assert!(!stop_flag.load(Ordering::Acquire));
+    assert!(!liveness.is_abandoned());
+}
+
+/// Only the session holding the flag can clear it, and only one session
+/// can hold it.
+#[test]
+fn recording_flag_is_owned() {
+    let flag = RecordingFlag::default();
+    assert!(!flag.is_set());
+    assert!(flag.claim(3));
+    assert!(!flag.claim(4));
+    assert!(!flag.release(4));
+    assert!(flag.is_owned_by(3));
+    assert!(flag.release(3));
+    assert!(!flag.is_set());
+    assert!(!flag.claim(0));
+}
@@ -0,0 +1,220 @@
+// Tests for dikto_core::wav — WAV decoding, downmixing, resampling, and
+// rejection of malformed or unsupported files.
+
+use dikto_core::wav::{
+    decode_wav, encode_wav, fingerprint, read_wav, resample, write_wav, WavError,
+    TARGET_SAMPLE_RATE,
+};
+
+/// Build a WAV file from raw sample bytes.
+fn wav_bytes(format_tag: u16, channels: u16, rate: u32, bits: u16, data: &[u8]) -> Vec<u8> {
+    let block_align = channels * bits / 8;
+    let mut out = Vec::new();
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&format_tag.to_le_bytes());
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&rate.to_le_bytes());
+    out.extend_from_slice(&(rate * block_align as u32).to_le_bytes());
+    out.extend_from_slice(&block_align.to_le_bytes());
+    out.extend_from_slice(&bits.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    out
+}
+
+fn i16_bytes(samples: &[i16]) -> Vec<u8> {
+    samples.iter().flat_map(|s| s.to_le_bytes()).collect()
+}
+
+// ---------------------------------------------------------------------------
+// Decoding
+// ---------------------------------------------------------------------------
+
+/// 16-bit mono at 16kHz should decode sample-for-sample.
+#[test]
+fn decode_pcm16_mono_16k() {
+    let bytes = wav_bytes(1, 1, 16000, 16, &i16_bytes(&[0, 16384, -16384, 32767]));
+    let samples = decode_wav(&bytes).unwrap();
+    assert_eq!(samples.len(), 4);
+    assert!((samples[1] - 0.5).abs() < 1e-4);
+    assert!((samples[2] + 0.5).abs() < 1e-4);
+    assert!(samples[3] > 0.99);
+}
+
+/// Stereo frames should be averaged into one mono sample.
+#[test]
+fn decode_stereo_downmixes() {
+    let bytes = wav_bytes(1, 2, 16000, 16, &i16_bytes(&[16384, 0, -16384, -16384]));
+    let samples = decode_wav(&bytes).unwrap();
+    assert_eq!(samples.len(), 2);
+    assert!((samples[0] - 0.25).abs() < 1e-4);
+    assert!((samples[1] + 0.5).abs() < 1e-4);
+}
+
+/// 32-bit float samples should pass through unchanged.
+#[test]
+fn decode_float32() {
+    let data: Vec<u8> = [0.25f32, -0.75]
+        .iter()
+        .flat_map(|s| s.to_le_bytes())
+        .collect();
+    let samples = decode_wav(&wav_bytes(3, 1, 16000, 32, &data)).unwrap();
+    assert_eq!(samples, vec![0.25, -0.75]);
+}
+
+/// 24-bit samples should keep their sign.
+#[test]
+fn decode_pcm24_sign_extends() {
+    // -0.5 as 24-bit little endian: 0xC00000
+    let samples = decode_wav(&wav_bytes(1, 1, 16000, 24, &[0x00, 0x00, 0xC0])).unwrap();
+    assert!((samples[0] + 0.5).abs() < 1e-4);
+}
+
+/// Audio at other rates should come out at 16kHz.
+#[test]
+fn decode_resamples_to_16k() {
+    let bytes = wav_bytes(1, 1, 48000, 16, &i16_bytes(&[0; 4800]));
+    let samples = decode_wav(&bytes).unwrap();
+    assert_eq!(samples.len(), 1600);
+    assert_eq!(TARGET_SAMPLE_RATE, 16000);
+}
+
+/// read_wav should decode a file on disk.
+#[test]
+fn read_wav_from_file() {
+    let path = std::env::temp_dir().join("dikto_wav_test.wav");
+    std::fs::write(&path, wav_bytes(1, 1, 16000, 16, &i16_bytes(&[0, 0, 0]))).unwrap();
+    assert_eq!(read_wav(&path).unwrap().len(), 3);
+    let _ = std::fs::remove_file(&path);
+}
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+/// Files without a RIFF/WAVE header are rejected.
+#[test]
+fn decode_rejects_non_wav() {
+    assert!(matches!(
+        decode_wav(b"ID3\x04 not a wav file"),
+        Err(WavError::Invalid(_))
+    ));
+}
+
+/// Compressed formats (e.g. tag 2, ADPCM) are unsupported.
+#[test]
+fn decode_rejects_compressed() {
+    let bytes = wav_bytes(2, 1, 16000, 16, &[0; 4]);
+    assert!(matches!(decode_wav(&bytes), Err(WavError::Unsupported(_))));
+}
+
+/// 8-bit PCM is unsupported.
+#[test]
+fn decode_rejects_8bit() {
+    let bytes = wav_bytes(1, 1, 16000, 8, &[0; 4]);
+    assert!(matches!(decode_wav(&bytes), Err(WavError::Unsupported(_))));
+}
+
+/// A missing file reports an IO error.
+#[test]
+fn read_wav_missing_file() {
+    let path = std::path::Path::new("/tmp/dikto_wav_missing_xyz.wav");
+    assert!(matches!(read_wav(path), Err(WavError::Io(_))));
+}
+
+// ---------------------------------------------------------------------------
+// resample
+// ---------------------------------------------------------------------------
+
+/// Same-rate resampling is a copy.
+#[test]
+fn resample_same_rate_is_identity() {
+    assert_eq!(resample(&[0.1, 0.2], 16000, 16000), vec![0.1, 0.2]);
+}
+
+/// Upsampling interpolates between neighbors.
+#[test]
+fn resample_upsample_interpolates() {
+    let out = resample(&[0.0, 1.0], 8000, 16000);
+    assert_eq!(out.len(), 4);
+    assert!((out[1] - 0.5).abs() < 1e-6);
+}
+
+// ---------------------------------------------------------------------------
+// Encoding
+// ---------------------------------------------------------------------------
+
+/// encode_wav output decodes back to the same samples, within 16-bit precision.
+#[test]
+fn encode_round_trips() {
+    let samples = vec![0.0, 0.5, -0.5, 1.0];
+    let decoded = decode_wav(&encode_wav(&samples, TARGET_SAMPLE_RATE)).unwrap();
+    assert_eq!(decoded.len(), samples.len());
+    for (a, b) in samples.iter().zip(&decoded) {
+        assert!((a - b).abs() < 1e-3);
+    }
+}
+
+/// Out-of-range samples are clipped rather than wrapping around.
+#[test]
+fn encode_clips() {
+    let decoded = decode_wav(&encode_wav(&[2.0, -2.0], TARGET_SAMPLE_RATE)).unwrap();
+    assert!(decoded[0] > 0.99);
+    assert!(decoded[1] < -0.99);
+}
+
+/// write_wav writes a file read_wav can load.
+#[test]
+fn write_wav_to_file() {
+    let path = std::env::temp_dir().join("dikto_wav_write_test.wav");
+    write_wav(&path, &[0.25; 160], TARGET_SAMPLE_RATE).unwrap();
+    assert_eq!(read_wav(&path).unwrap().len(), 160);
+    let _ = std::fs::remove_file(&path);
+}
+
+// ---------------------------------------------------------------------------
+// fingerprint
+// ---------------------------------------------------------------------------
+
+/// Two seconds of a tone whose loudness steps every 100 ms, like syllables.
+fn take(levels: &[f32]) -> Vec<f32> {
+    levels
+        .iter()
+        .flat_map(|level| {
+            (0..1600)
+                .map(move |i| level * (i as f32 * 440.0 * std::f32::consts::TAU / 16000.0).sin())
+        })
+        .collect()
+}
+
+const LEVELS: [f32; 20] = [
+    0.1, 0.5, 0.2, 0.8, 0.3, 0.3, 0.7, 0.1, 0.0, 0.0, 0.4, 0.9, 0.2, 0.6, 0.1, 0.5, 0.8, 0.2, 0.4,
+    0.1,
+];
+
+/// Re-exporting the same audio at another bit depth or volume keeps its fingerprint.
+#[test]
+fn fingerprint_survives_reexport() {
+    let original = take(&LEVELS);
+    let reencoded = decode_wav(&encode_wav(&original, TARGET_SAMPLE_RATE)).unwrap();
+    let quieter: Vec<f32> = original.iter().map(|s| s * 0.5).collect();
+
+    assert_eq!(fingerprint(&reencoded), fingerprint(&original));
+    assert_eq!(fingerprint(&quieter), fingerprint(&original));
+}
+
+/// A different take, or the same one cut shorter, gets another fingerprint.
+#[test]
+fn fingerprint_tells_recordings_apart() {
+    let original = take(&LEVELS);
+    let mut reversed = LEVELS;
+    reversed.reverse();
+
+    assert_ne!(fingerprint(&take(&reversed)), fingerprint(&original));
+    assert_ne!(fingerprint(&original[..16000]), fingerprint(&original));
+}
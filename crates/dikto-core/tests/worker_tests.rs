@@ -0,0 +1,74 @@
+// Tests for dikto_core::worker — job submission to the inference actor without
+// a loaded model, load failures, and thread-safety of the handle. Real inference
+// requires downloaded models.
+
+use dikto_core::models::ModelBackend;
+use dikto_core::transcribe::TranscribeError;
+use dikto_core::worker::InferenceWorker;
+
+// ---------------------------------------------------------------------------
+// InferenceWorker — no model loaded
+// ---------------------------------------------------------------------------
+
+/// A transcribe job with no model loaded should fail with NotLoaded.
+#[test]
+fn transcribe_without_model_returns_not_loaded() {
+    let worker = InferenceWorker::spawn();
+    let result = worker.transcribe(vec![0.0f32; 1600], "en");
+    assert!(matches!(result, Err(TranscribeError::NotLoaded)));
+}
+
+/// The worker should keep serving jobs after a failed one.
+#[test]
+fn worker_survives_failed_jobs() {
+    let worker = InferenceWorker::spawn();
+    for _ in 0..3 {
+        assert!(worker.transcribe(vec![0.0f32; 160], "en").is_err());
+    }
+}
+
+/// Dropping the worker should shut the thread down without hanging.
+#[test]
+fn worker_drop_shuts_down() {
+    let worker = InferenceWorker::spawn();
+    drop(worker);
+}
+
+// ---------------------------------------------------------------------------
+// InferenceWorker — load / unload
+// ---------------------------------------------------------------------------
+
+/// A fresh worker should report no loaded model.
+#[test]
+fn fresh_worker_has_no_model() {
+    let worker = InferenceWorker::spawn();
+    assert!(worker.loaded_model().is_none());
+}
+
+/// Unloading with nothing loaded should report false.
+#[test]
+fn unload_without_model_returns_false() {
+    let worker = InferenceWorker::spawn();
+    assert!(!worker.unload());
+}
+
+/// Loading from a nonexistent directory should fail and leave nothing loaded.
+#[test]
+fn load_missing_model_dir_fails() {
+    let worker = InferenceWorker::spawn();
+    let dir = std::env::temp_dir().join("dikto_worker_test_missing_model");
+    let result = worker.load("whisper-tiny", ModelBackend::Whisper, &dir);
+    assert!(result.is_err());
+    assert!(worker.loaded_model().is_none());
+}
+
+// ---------------------------------------------------------------------------
+// Thread safety
+// ---------------------------------------------------------------------------
+
+/// The worker handle must be shareable across threads without unsafe impls.
+#[test]
+fn worker_handle_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<InferenceWorker>();
+}
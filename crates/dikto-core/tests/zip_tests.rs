@@ -0,0 +1,78 @@
+// Tests for dikto_core::zip — CRC-32 and the stored-only archive layout.
+
+use dikto_core::zip::{crc32, ZipWriter};
+
+fn u16_at(data: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([data[at], data[at + 1]])
+}
+
+fn u32_at(data: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(data[at..at + 4].try_into().unwrap())
+}
+
+// ---------------------------------------------------------------------------
+// crc32
+// ---------------------------------------------------------------------------
+
+/// The standard CRC-32 check value.
+#[test]
+fn crc32_check_value() {
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+}
+
+/// CRC of no data is zero.
+#[test]
+fn crc32_empty() {
+    assert_eq!(crc32(b""), 0);
+}
+
+// ---------------------------------------------------------------------------
+// ZipWriter
+// ---------------------------------------------------------------------------
+
+/// An empty archive is just the end-of-central-directory record.
+#[test]
+fn empty_archive() {
+    let data = ZipWriter::new(Vec::new()).finish().unwrap();
+    assert_eq!(data.len(), 22);
+    assert_eq!(u32_at(&data, 0), 0x0605_4b50);
+    assert_eq!(u16_at(&data, 10), 0);
+}
+
+/// Entries are stored uncompressed after their local headers, and the end
+/// record points at a central directory listing each of them.
+#[test]
+fn archive_layout() {
+    let mut zip = ZipWriter::new(Vec::new());
+    zip.add("a.txt", b"hello").unwrap();
+    zip.add("logs/b.jsonl", b"{}\n").unwrap();
+    let data = zip.finish().unwrap();
+
+    // First local header and its data
+    assert_eq!(u32_at(&data, 0), 0x0403_4b50);
+    assert_eq!(u32_at(&data, 14), crc32(b"hello"));
+    assert_eq!(u32_at(&data, 18), 5);
+    assert_eq!(u16_at(&data, 26), 5);
+    assert_eq!(&data[30..35], b"a.txt");
+    assert_eq!(&data[35..40], b"hello");
+
+    // End record
+    let eocd = data.len() - 22;
+    assert_eq!(u32_at(&data, eocd), 0x0605_4b50);
+    assert_eq!(u16_at(&data, eocd + 10), 2);
+    let dir_size = u32_at(&data, eocd + 12) as usize;
+    let dir_offset = u32_at(&data, eocd + 16) as usize;
+    assert_eq!(dir_offset + dir_size, eocd);
+
+    // Second central directory entry points at the second local header
+    let first = dir_offset;
+    assert_eq!(u32_at(&data, first), 0x0201_4b50);
+    let second = first + 46 + u16_at(&data, first + 28) as usize;
+    assert_eq!(u32_at(&data, second), 0x0201_4b50);
+    assert_eq!(&data[second + 46..second + 58], b"logs/b.jsonl");
+    let local = u32_at(&data, second + 42) as usize;
+    assert_eq!(local, 40);
+    assert_eq!(u32_at(&data, local), 0x0403_4b50);
+    assert_eq!(&data[local + 30..local + 42], b"logs/b.jsonl");
+    assert_eq!(&data[local + 42..local + 45], b"{}\n");
+}
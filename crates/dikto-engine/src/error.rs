@@ -0,0 +1,57 @@
+use dikto_core::DiktoError;
+use thiserror::Error;
+
+/// Errors from the engine.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Audio error: {0}")]
+    Audio(String),
+    #[error("VAD error: {0}")]
+    Vad(String),
+    #[error("Transcription error: {0}")]
+    Transcribe(String),
+    #[error("Model error: {0}")]
+    Model(String),
+    #[error("No model loaded. Run: dikto --setup")]
+    NoModel,
+    #[error("Microphone access denied")]
+    PermissionDenied,
+    #[error("Already recording")]
+    AlreadyRecording,
+    /// A do-not-disturb rule kept the session from starting.
+    #[error("Do not disturb: {0}")]
+    DoNotDisturb(String),
+    /// Sessions were started faster than the configured limits allow.
+    #[error("Too many sessions, try again in {retry_after_ms} ms")]
+    RateLimited { retry_after_ms: u64 },
+    #[error("Config error: {0}")]
+    Config(String),
+    /// The session ended without reporting a result.
+    #[error("Session ended unexpectedly")]
+    SessionLost,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl From<DiktoError> for Error {
+    fn from(e: DiktoError) -> Self {
+        match e {
+            DiktoError::Audio(msg) => Error::Audio(msg),
+            DiktoError::Vad(msg) => Error::Vad(msg),
+            DiktoError::Transcribe(msg) => Error::Transcribe(msg),
+            DiktoError::Model(msg) => Error::Model(msg),
+            DiktoError::NoModel => Error::NoModel,
+            DiktoError::PermissionDenied => Error::PermissionDenied,
+            DiktoError::AlreadyRecording => Error::AlreadyRecording,
+            DiktoError::DoNotDisturb(reason) => Error::DoNotDisturb(reason),
+            DiktoError::RateLimited { retry_after_ms } => Error::RateLimited { retry_after_ms },
+            DiktoError::Config(msg) => Error::Config(msg),
+        }
+    }
+}
+
+impl From<dikto_core::models::ModelError> for Error {
+    fn from(e: dikto_core::models::ModelError) -> Self {
+        Error::Model(e.to_string())
+    }
+}
@@ -0,0 +1,232 @@
+//! Plain-Rust API for Dikto, for servers, CLIs, and other Rust apps.
+//!
+//! `dikto-core` is shaped for the Swift, Kotlin, and C hosts: strings for
+//! paths, callback traits for events, and `Arc<Self>` receivers. This crate
+//! wraps it with borrowed arguments, `Result<T, Error>`, and sessions that
+//! hand out events as an enum.
+//!
+//! This is a facade, not a split: the engine itself still lives in
+//! `dikto-core` alongside its UniFFI exports, and this crate depends on it.
+//! Records such as `DiktoConfig` are re-exported as they are; they are
+//! plain structs in Rust, though they carry UniFFI derives.
+
+mod error;
+mod session;
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use dikto_core::{DiktoEngine, DiktoEngineBuilder};
+
+pub use dikto_core::audio::AudioSource;
+pub use dikto_core::clock::{Clock, MockClock, SystemClock};
+pub use dikto_core::config::{ConfigWarning, DiktoConfig, TextProcPreset};
+pub use dikto_core::output::OutputAction;
+pub use dikto_core::transcribe::WordConfidence;
+pub use dikto_core::{
+    FileTranscription, ListenConfig, OverlapWarning, SelfTestReport, SessionSource, SessionState,
+    SessionSummary, TranscriptAlternative, TranscriptionResult,
+};
+pub use error::{Error, Result};
+pub use session::{Event, Session};
+
+/// Speech-to-text engine: one loaded model, one session at a time.
+pub struct Engine {
+    core: DiktoEngine,
+}
+
+impl Engine {
+    /// Engine using the user's config.json, models, and microphone, like
+    /// the desktop app.
+    pub fn new() -> Self {
+        Self {
+            core: DiktoEngine::new(),
+        }
+    }
+
+    pub fn builder() -> EngineBuilder {
+        EngineBuilder::default()
+    }
+
+    pub fn config(&self) -> DiktoConfig {
+        self.core.get_config()
+    }
+
+    /// Replace the config, saving it unless the engine was built
+    /// `without_config_file`. Conflicting settings are saved as given; the
+    /// returned warnings say which of them won't take effect.
+    pub fn set_config(&self, config: DiktoConfig) -> Result<Vec<ConfigWarning>> {
+        let warnings = config.lint();
+        self.core.update_config(config)?;
+        Ok(warnings)
+    }
+
+    /// Preferences to share with the user's other machines, without
+    /// device-local fields such as models and paths.
+    pub fn export_sync_blob(&self) -> String {
+        self.core.export_sync_blob()
+    }
+
+    /// Apply preferences from another machine's `export_sync_blob`.
+    pub fn apply_sync_blob(&self, blob: &str) -> Result<()> {
+        Ok(self.core.apply_sync_blob(blob.to_string())?)
+    }
+
+    /// Session settings taken from the current config.
+    pub fn listen_config(&self) -> ListenConfig {
+        self.core.listen_config()
+    }
+
+    /// Load the configured model now instead of on first use.
+    pub fn load_model(&self) -> Result<()> {
+        Ok(self.core.load_model()?)
+    }
+
+    pub fn unload_model(&self) {
+        self.core.unload_model();
+    }
+
+    /// Switch to a downloaded model and load it.
+    pub fn switch_model(&self, name: &str) -> Result<()> {
+        Ok(self.core.switch_model(name.to_string())?)
+    }
+
+    pub fn is_model_available(&self) -> bool {
+        self.core.is_model_available()
+    }
+
+    pub fn is_model_loaded(&self) -> bool {
+        self.core.is_model_loaded()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.core.is_recording()
+    }
+
+    /// Sessions that are queued, recording, or finishing, oldest first.
+    pub fn active_sessions(&self) -> Vec<SessionSummary> {
+        self.core.active_sessions()
+    }
+
+    /// Stop every session and close the microphone before returning; false
+    /// if it was still open after the timeout.
+    pub fn stop_all(&self) -> bool {
+        self.core.stop_all()
+    }
+
+    /// Download a model by name or alias into the models directory,
+    /// reporting (bytes downloaded, total bytes) as it goes.
+    pub async fn download_model<F>(&self, name: &str, on_progress: F) -> Result<PathBuf>
+    where
+        F: Fn(u64, u64) + Send + 'static,
+    {
+        Ok(dikto_core::models::download_model(name, None, on_progress).await?)
+    }
+
+    /// Models whose download was cut short, e.g. by quitting. Passing one to
+    /// `download_model` continues from its last checkpoint.
+    pub fn interrupted_downloads(&self) -> Vec<String> {
+        self.core.interrupted_downloads()
+    }
+
+    /// Start a listening session. The model is loaded first if needed.
+    pub fn listen(&self, config: ListenConfig) -> Result<Session> {
+        let (callback, rx) = Session::channel();
+        let handle = self.core.start_listening(config, callback)?;
+        Ok(Session::new(handle, rx))
+    }
+
+    /// Transcribe a WAV file with the configured model. `language` is an
+    /// ISO-639-1 code or "auto".
+    pub fn transcribe_file(&self, path: &Path, language: &str) -> Result<TranscriptionResult> {
+        Ok(self
+            .core
+            .transcribe_file(path.to_string_lossy().into_owned(), language.to_string())?)
+    }
+
+    /// Transcribe a batch of WAV files, skipping audio already transcribed;
+    /// see `DiktoEngine::transcribe_files`.
+    pub fn transcribe_files(
+        &self,
+        paths: &[PathBuf],
+        language: &str,
+    ) -> Result<Vec<FileTranscription>> {
+        let paths = paths
+            .iter()
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect();
+        Ok(self.core.transcribe_files(paths, language.to_string())?)
+    }
+
+    /// Run a saved session's recording through the live pipeline again
+    /// with the settings it was recorded with; see
+    /// `DiktoEngine::replay_session`.
+    pub fn replay_session(&self, path: &Path) -> Result<TranscriptionResult> {
+        Ok(self
+            .core
+            .replay_session(path.to_string_lossy().into_owned())?)
+    }
+
+    /// Check VAD and the configured model end to end; see
+    /// `DiktoEngine::self_test`.
+    pub fn self_test(&self, sample: Option<&Path>) -> Result<SelfTestReport> {
+        Ok(self
+            .core
+            .self_test(sample.map(|p| p.to_string_lossy().into_owned()))?)
+    }
+
+    /// The FFI engine underneath, for calls this crate doesn't wrap.
+    pub fn core(&self) -> &DiktoEngine {
+        &self.core
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Configures an `Engine`; see `DiktoEngineBuilder` for what each option does.
+#[derive(Default)]
+pub struct EngineBuilder {
+    inner: DiktoEngineBuilder,
+}
+
+impl EngineBuilder {
+    pub fn config(mut self, config: DiktoConfig) -> Self {
+        self.inner = self.inner.config(config);
+        self
+    }
+
+    /// Process-wide, like `DiktoEngineBuilder::models_dir`: every engine
+    /// in the process uses the last directory set.
+    pub fn models_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.inner = self.inner.models_dir(dir);
+        self
+    }
+
+    pub fn audio_source<F>(mut self, make_source: F) -> Self
+    where
+        F: Fn() -> Box<dyn AudioSource> + Send + Sync + 'static,
+    {
+        self.inner = self.inner.audio_source(make_source);
+        self
+    }
+
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.inner = self.inner.clock(clock);
+        self
+    }
+
+    pub fn without_config_file(mut self) -> Self {
+        self.inner = self.inner.without_config_file();
+        self
+    }
+
+    pub fn build(self) -> Engine {
+        Engine {
+            core: self.inner.build(),
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+
+use dikto_core::output::OutputAction;
+use dikto_core::transcribe::WordConfidence;
+use dikto_core::{
+    OverlapWarning, RecordingState, SessionHandle, SessionSummary, TranscriptionCallback,
+};
+
+use crate::error::{Error, Result};
+
+/// Something that happened during a session.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// Listening for speech, at the start and again after an audio interruption.
+    Listening,
+    /// The model is loaded and speech is being transcribed. Audio from
+    /// before this, while the model loaded, was buffered.
+    Ready,
+    /// Speech ended and is being transcribed.
+    Processing,
+    /// Text so far for the current utterance; may still change.
+    Partial(String),
+    /// A finished utterance.
+    Segment(String),
+    /// A pause with nothing to transcribe.
+    Silence,
+    /// The recognized language changed (auto-detect only).
+    LanguageChanged(String),
+    /// Per-word confidence for the recognizer's raw output.
+    WordConfidence(Vec<WordConfidence>),
+    /// A segment that may be crosstalk.
+    Overlap(OverlapWarning),
+    /// The microphone failed; the session resumes with `Listening` if it
+    /// comes back.
+    AudioInterrupted(String),
+    /// A summary of the transcript, sent just before the session finishes.
+    Summary(String),
+    /// The outputs a dry run skipped, sent just before the session finishes.
+    DryRun(Vec<OutputAction>),
+    /// The microphone is sending only silence and is probably muted. The
+    /// session keeps listening.
+    NoAudioDetected,
+    /// Seconds left before `max_duration` ends the session.
+    TimeRemaining(u32),
+    /// A problem that did not end the session.
+    Warning(String),
+}
+
+pub(crate) enum Message {
+    Event(Event),
+    Finished(Result<String>),
+}
+
+/// Forwards engine callbacks into the session's channel.
+pub(crate) struct ChannelCallback {
+    tx: Sender<Message>,
+}
+
+impl ChannelCallback {
+    fn send(&self, message: Message) {
+        // The session may have been dropped; nobody is listening then
+        let _ = self.tx.send(message);
+    }
+
+    fn event(&self, event: Event) {
+        self.send(Message::Event(event));
+    }
+}
+
+impl TranscriptionCallback for ChannelCallback {
+    fn on_partial(&self, text: String) {
+        self.event(Event::Partial(text));
+    }
+
+    fn on_final_segment(&self, text: String) {
+        self.event(Event::Segment(text));
+    }
+
+    fn on_silence(&self) {
+        self.event(Event::Silence);
+    }
+
+    fn on_error(&self, error: String) {
+        self.event(Event::Warning(error));
+    }
+
+    fn on_state_change(&self, state: RecordingState) {
+        match state {
+            RecordingState::Listening => self.event(Event::Listening),
+            RecordingState::Processing => self.event(Event::Processing),
+            RecordingState::Done { text } => self.send(Message::Finished(Ok(text))),
+            RecordingState::Error { message, .. } => {
+                self.send(Message::Finished(Err(Error::Transcribe(message))))
+            }
+        }
+    }
+
+    fn on_language_change(&self, language: String) {
+        self.event(Event::LanguageChanged(language));
+    }
+
+    fn on_word_confidence(&self, words: Vec<WordConfidence>) {
+        self.event(Event::WordConfidence(words));
+    }
+
+    fn on_overlap_warning(&self, warning: OverlapWarning) {
+        self.event(Event::Overlap(warning));
+    }
+
+    fn on_audio_interrupted(&self, reason: String) {
+        self.event(Event::AudioInterrupted(reason));
+    }
+
+    fn on_summary(&self, summary: String) {
+        self.event(Event::Summary(summary));
+    }
+
+    fn on_no_audio_detected(&self) {
+        self.event(Event::NoAudioDetected);
+    }
+
+    fn on_time_remaining(&self, seconds: u32) {
+        self.event(Event::TimeRemaining(seconds));
+    }
+
+    fn on_ready(&self) {
+        self.event(Event::Ready);
+    }
+
+    fn on_dry_run(&self, actions: Vec<OutputAction>) {
+        self.event(Event::DryRun(actions));
+    }
+}
+
+/// A running listening session. Read its events with `next_event` or
+/// `events`, and get the final text from `wait`. Dropping it does not stop
+/// the session; call `stop` first.
+pub struct Session {
+    handle: Arc<SessionHandle>,
+    rx: Receiver<Message>,
+    outcome: Option<Result<String>>,
+}
+
+impl Session {
+    pub(crate) fn channel() -> (Arc<ChannelCallback>, Receiver<Message>) {
+        let (tx, rx) = mpsc::channel();
+        (Arc::new(ChannelCallback { tx }), rx)
+    }
+
+    pub(crate) fn new(handle: Arc<SessionHandle>, rx: Receiver<Message>) -> Self {
+        Self {
+            handle,
+            rx,
+            outcome: None,
+        }
+    }
+
+    /// Ask the session to finish: speech heard so far is transcribed and
+    /// returned from `wait`.
+    pub fn stop(&self) {
+        self.handle.stop();
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.handle.is_active()
+    }
+
+    /// Push 16 kHz mono samples, for sessions started with `host_audio`.
+    pub fn feed_audio(&self, samples: &[f32]) {
+        self.handle.feed_audio(samples.to_vec());
+    }
+
+    /// Seconds since the session started listening.
+    pub fn elapsed_secs(&self) -> f64 {
+        self.handle.elapsed_secs()
+    }
+
+    /// Seconds of speech recorded and not yet transcribed.
+    pub fn buffered_audio_secs(&self) -> f32 {
+        self.handle.buffered_audio_secs()
+    }
+
+    /// Whether the current utterance has begun, as opposed to waiting for
+    /// speech.
+    pub fn speech_detected(&self) -> bool {
+        self.handle.speech_detected()
+    }
+
+    /// The session's id, state, running time, and audio source, as listed
+    /// by `Engine::active_sessions`.
+    pub fn summary(&self) -> SessionSummary {
+        self.handle.summary()
+    }
+
+    /// Block until the next event, or None once the session has finished.
+    pub fn next_event(&mut self) -> Option<Event> {
+        if self.outcome.is_some() {
+            return None;
+        }
+        match self.rx.recv() {
+            Ok(Message::Event(event)) => Some(event),
+            Ok(Message::Finished(outcome)) => {
+                self.outcome = Some(outcome);
+                None
+            }
+            Err(_) => {
+                self.outcome = Some(Err(Error::SessionLost));
+                None
+            }
+        }
+    }
+
+    /// Iterate over events until the session finishes.
+    pub fn events(&mut self) -> impl Iterator<Item = Event> + '_ {
+        std::iter::from_fn(move || self.next_event())
+    }
+
+    /// Wait for the session to finish and return its text, skipping any
+    /// events not yet read.
+    pub fn wait(mut self) -> Result<String> {
+        while self.next_event().is_some() {}
+        self.outcome.take().unwrap_or(Err(Error::SessionLost))
+    }
+}
@@ -0,0 +1,59 @@
+// Tests for the dikto-engine facade — builder, config, and error mapping.
+
+use dikto_core::DiktoError;
+use dikto_engine::{DiktoConfig, Engine, Error};
+
+/// The builder's injected config is what the engine reports.
+#[test]
+fn builder_injected_config() {
+    let engine = Engine::builder()
+        .config(DiktoConfig {
+            language: "es".to_string(),
+            ..DiktoConfig::default()
+        })
+        .without_config_file()
+        .build();
+    assert_eq!(engine.config().language, "es");
+    assert_eq!(engine.listen_config().language, "es");
+}
+
+/// set_config replaces the config in memory.
+#[test]
+fn set_config_round_trip() {
+    let engine = Engine::builder().without_config_file().build();
+    engine
+        .set_config(DiktoConfig {
+            language: "it".to_string(),
+            ..DiktoConfig::default()
+        })
+        .unwrap();
+    assert_eq!(engine.config().language, "it");
+    assert!(!engine.is_recording());
+}
+
+/// set_config saves conflicting settings and says which ones conflict.
+#[test]
+fn set_config_returns_lint_warnings() {
+    let engine = Engine::builder().without_config_file().build();
+    let warnings = engine
+        .set_config(DiktoConfig {
+            continuous_mode: true,
+            ..DiktoConfig::default()
+        })
+        .unwrap();
+    assert!(engine.config().continuous_mode);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].field, "continuous_mode");
+}
+
+/// Core errors keep their variant and message.
+#[test]
+fn error_from_core_error() {
+    assert!(matches!(Error::from(DiktoError::NoModel), Error::NoModel));
+    assert!(matches!(
+        Error::from(DiktoError::AlreadyRecording),
+        Error::AlreadyRecording
+    ));
+    let err = Error::from(DiktoError::Audio("no device".to_string()));
+    assert_eq!(err.to_string(), "Audio error: no device");
+}
@@ -4,6 +4,7 @@ set -euo pipefail
 # ── Configuration ───────────────────────────────────────────────
 CARGO_TOML="Cargo.toml"
 INFO_PLIST="DiktoApp/Resources/Info.plist"
+PYPROJECT="python/pyproject.toml"
 GITHUB_REPO="diktoapp/dikto"
 
 # ── Helpers ─────────────────────────────────────────────────────
@@ -85,6 +86,7 @@ echo ""
 echo "  Files to update:"
 echo "    $CARGO_TOML          (workspace.package.version)"
 echo "    $INFO_PLIST           (CFBundleShortVersionString)"
+echo "    $PYPROJECT     (project.version)"
 echo "    Cargo.lock             (regenerated)"
 echo ""
 echo "  Git operations:"
@@ -113,13 +115,17 @@ sed -i '' "/\[workspace\.package\]/,/^\[/ s/^version = \".*\"/version = \"$NEW_V
 info "Updating $INFO_PLIST"
 /usr/libexec/PlistBuddy -c "Set :CFBundleShortVersionString $NEW_VERSION" "$INFO_PLIST"
 
+# ── Update pyproject.toml ───────────────────────────────────────
+info "Updating $PYPROJECT"
+sed -i '' "s/^version = \".*\"/version = \"$NEW_VERSION\"/" "$PYPROJECT"
+
 # ── Regenerate Cargo.lock ───────────────────────────────────────
 info "Regenerating Cargo.lock"
 cargo generate-lockfile
 
 # ── Commit, tag, push ──────────────────────────────────────────
 info "Committing changes"
-git add "$CARGO_TOML" "$INFO_PLIST" Cargo.lock
+git add "$CARGO_TOML" "$INFO_PLIST" "$PYPROJECT" Cargo.lock
 git commit -m "Bump version to $NEW_VERSION"
 
 info "Tagging v$NEW_VERSION"